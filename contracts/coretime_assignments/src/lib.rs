@@ -0,0 +1,305 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime assignments
+//!
+//! This contract allows xcRegion holders to assign their regions to a task, i.e. the para id of a
+//! parachain, and keeps track of the assignment state of every region.
+//!
+//! The holder of an xcRegion records the assignment in the contract, where it is queued until it
+//! gets dispatched to the Coretime chain. The assignment is dispatched through the xcRegions
+//! contract, which owns the region on the RegionX chain and checks that the holder approved this
+//! contract for the xcRegion. The xcRegions contract relies on the regions chain extension, which
+//! can later be swapped for sending the broker `assign` call over XCM once `Transact` is allowed.
+//! Anyone can trigger the dispatch of a queued assignment, as long as the xcRegion didn't change
+//! hands since the assignment was requested.
+//!
+//! Provisional assignments can be changed at any time by requesting a new assignment, whereas a
+//! region that was finally assigned can no longer be reassigned.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+mod types;
+
+#[openbrush::contract(env = environment::ExtendedEnvironment)]
+pub mod coretime_assignments {
+	use crate::types::{Assignment, AssignmentError, AssignmentStatus, Config};
+	use block_number_extension::BlockNumberProviderExtension;
+	use ink::{
+		codegen::{EmitEvent, Env},
+		env::call::FromAccountId,
+	};
+	use openbrush::{
+		contracts::traits::psp34::{Id, PSP34Ref},
+		storage::Mapping,
+		traits::Storage,
+	};
+	use primitives::{
+		coretime::{Finality, RawRegionId, TaskId, Timeslice},
+		ensure,
+	};
+	use sp_arithmetic::traits::SaturatedConversion;
+	use xc_regions::xc_regions::XcRegionsRef;
+	use xc_regions_traits::RegionMetadataRef;
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimeAssignments {
+		/// The configuration of the assignments contract.
+		pub config: Config,
+		/// A mapping that holds the latest assignment of each region.
+		pub assignments: Mapping<RawRegionId, Assignment>,
+	}
+
+	#[ink(event)]
+	pub struct AssignmentRequested {
+		/// The identifier of the region to be assigned.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The holder of the xcRegion.
+		#[ink(topic)]
+		pub(crate) holder: AccountId,
+		/// The task the region is assigned to.
+		pub(crate) task: TaskId,
+		/// Whether the assignment can later be changed.
+		pub(crate) finality: Finality,
+	}
+
+	#[ink(event)]
+	pub struct AssignmentCancelled {
+		/// The identifier of the region whose pending assignment got cancelled.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+	}
+
+	#[ink(event)]
+	pub struct AssignmentDispatched {
+		/// The identifier of the assigned region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The task the region is assigned to.
+		pub(crate) task: TaskId,
+		/// Whether the assignment can later be changed.
+		pub(crate) finality: Finality,
+	}
+
+	impl CoretimeAssignments {
+		/// ## Arguments:
+		/// - `xc_regions_contract` - The `AccountId` of the xcRegions contract.
+		/// - `timeslice_period` - The number of relay chain blocks in a timeslice.
+		#[ink(constructor)]
+		pub fn new(xc_regions_contract: AccountId, timeslice_period: BlockNumber) -> Self {
+			assert!(timeslice_period > 0, "Timeslice period must be greater than zero");
+
+			Self {
+				config: Config { xc_regions_contract, timeslice_period },
+				assignments: Default::default(),
+			}
+		}
+
+		#[ink(message)]
+		pub fn config(&self) -> Config {
+			self.config.clone()
+		}
+
+		#[ink(message)]
+		pub fn assignment(&self, id: Id) -> Option<Assignment> {
+			let Id::U128(region_id) = id else { return None };
+			self.assignments.get(region_id)
+		}
+
+		/// A function for requesting the assignment of a region to a task.
+		///
+		/// The assignment is queued until it gets dispatched. Any pending assignment of the region
+		/// is replaced.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region. The caller must be the
+		///   holder of the xcRegion and approve it to the contract before the assignment gets
+		///   dispatched.
+		/// - `task`: The task the region is assigned to.
+		/// - `finality`: Whether the assignment can later be changed.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `AssignmentRequested` event.
+		#[ink(message)]
+		pub fn request_assignment(
+			&mut self,
+			id: Id,
+			task: TaskId,
+			finality: Finality,
+		) -> Result<(), AssignmentError> {
+			let Id::U128(region_id) = id else { return Err(AssignmentError::InvalidRegionId) };
+			let caller = self.env().caller();
+
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id.clone()) == Some(caller),
+				AssignmentError::NotAllowed
+			);
+
+			let metadata = RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id)
+				.map_err(|_| AssignmentError::MetadataNotFound)?;
+			ensure!(
+				metadata.region().end > self.current_timeslice(),
+				AssignmentError::RegionExpired
+			);
+
+			self.record_assignment(caller, region_id, task, finality)?;
+
+			self.env().emit_event(AssignmentRequested {
+				region_id,
+				holder: caller,
+				task,
+				finality,
+			});
+			Ok(())
+		}
+
+		/// A function for cancelling a pending assignment.
+		///
+		/// Only callable by the account that requested the assignment.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `AssignmentCancelled` event.
+		#[ink(message)]
+		pub fn cancel_assignment(&mut self, id: Id) -> Result<(), AssignmentError> {
+			let Id::U128(region_id) = id else { return Err(AssignmentError::InvalidRegionId) };
+			let caller = self.env().caller();
+
+			self.remove_pending(caller, region_id)?;
+
+			self.env().emit_event(AssignmentCancelled { region_id });
+			Ok(())
+		}
+
+		/// A function for dispatching a pending assignment to the Coretime chain.
+		///
+		/// Callable by anyone, as long as the xcRegion is still held by the account that
+		/// requested the assignment and the holder approved the contract for the xcRegion.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `AssignmentDispatched` event.
+		#[ink(message)]
+		pub fn dispatch_assignment(&mut self, id: Id) -> Result<(), AssignmentError> {
+			let Id::U128(region_id) = id else { return Err(AssignmentError::InvalidRegionId) };
+
+			let assignment = self.mark_dispatched(region_id)?;
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id) == Some(assignment.holder),
+				AssignmentError::HolderChanged
+			);
+
+			let mut xc_regions: XcRegionsRef =
+				FromAccountId::from_account_id(self.config.xc_regions_contract);
+			xc_regions
+				.assign(Id::U128(region_id), assignment.task, assignment.finality)
+				.map_err(AssignmentError::XcRegionsError)?;
+
+			self.env().emit_event(AssignmentDispatched {
+				region_id,
+				task: assignment.task,
+				finality: assignment.finality,
+			});
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimeAssignments {
+		// Queues the assignment of the region, unless the region was already finally assigned.
+		pub(crate) fn record_assignment(
+			&mut self,
+			holder: AccountId,
+			region_id: RawRegionId,
+			task: TaskId,
+			finality: Finality,
+		) -> Result<(), AssignmentError> {
+			if let Some(assignment) = self.assignments.get(region_id) {
+				ensure!(
+					assignment.status == AssignmentStatus::Pending ||
+						assignment.finality == Finality::Provisional,
+					AssignmentError::AlreadyFinalized
+				);
+			}
+
+			self.assignments.insert(
+				region_id,
+				&Assignment { holder, task, finality, status: AssignmentStatus::Pending },
+			);
+
+			Ok(())
+		}
+
+		// Removes the pending assignment of the region requested by `holder`.
+		pub(crate) fn remove_pending(
+			&mut self,
+			holder: AccountId,
+			region_id: RawRegionId,
+		) -> Result<(), AssignmentError> {
+			let assignment =
+				self.assignments.get(region_id).ok_or(AssignmentError::AssignmentNotFound)?;
+			ensure!(assignment.holder == holder, AssignmentError::NotAllowed);
+			ensure!(
+				assignment.status == AssignmentStatus::Pending,
+				AssignmentError::AlreadyDispatched
+			);
+
+			self.assignments.remove(region_id);
+			Ok(())
+		}
+
+		// Marks the pending assignment of the region as dispatched and returns it.
+		pub(crate) fn mark_dispatched(
+			&mut self,
+			region_id: RawRegionId,
+		) -> Result<Assignment, AssignmentError> {
+			let mut assignment =
+				self.assignments.get(region_id).ok_or(AssignmentError::AssignmentNotFound)?;
+			ensure!(
+				assignment.status == AssignmentStatus::Pending,
+				AssignmentError::AlreadyDispatched
+			);
+
+			assignment.status = AssignmentStatus::Dispatched;
+			self.assignments.insert(region_id, &assignment);
+
+			Ok(assignment)
+		}
+
+		pub(crate) fn current_timeslice(&self) -> Timeslice {
+			(self.relay_block_number() / self.config.timeslice_period).saturated_into()
+		}
+
+		#[cfg(not(test))]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().extension().relay_chain_block_number().unwrap_or_default()
+		}
+
+		#[cfg(test)]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().block_number()
+		}
+	}
+}
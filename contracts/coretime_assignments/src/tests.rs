@@ -0,0 +1,119 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_assignments::CoretimeAssignments,
+	types::{Assignment, AssignmentError, AssignmentStatus},
+};
+use ink::env::{
+	test::{default_accounts, DefaultAccounts},
+	DefaultEnvironment,
+};
+use openbrush::contracts::psp34::Id;
+use primitives::{assert_ok, coretime::Finality};
+
+const TIMESLICE_PERIOD: u32 = 80;
+
+#[ink::test]
+fn record_assignment_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut assignments = new_assignments();
+
+	assert_ok!(assignments.record_assignment(bob, 0, 2000, Finality::Provisional));
+	assert_eq!(
+		assignments.assignment(Id::U128(0)),
+		Some(Assignment {
+			holder: bob,
+			task: 2000,
+			finality: Finality::Provisional,
+			status: AssignmentStatus::Pending,
+		})
+	);
+
+	// A pending assignment can be replaced, even by a new holder:
+	assert_ok!(assignments.record_assignment(charlie, 0, 2001, Finality::Final));
+	assert_eq!(
+		assignments
+			.assignment(Id::U128(0))
+			.map(|assignment| (assignment.holder, assignment.task)),
+		Some((charlie, 2001))
+	);
+
+	// Provisional assignments can be changed after being dispatched:
+	assert_ok!(assignments.record_assignment(bob, 1, 2000, Finality::Provisional));
+	assert_ok!(assignments.mark_dispatched(1));
+	assert_ok!(assignments.record_assignment(bob, 1, 2001, Finality::Provisional));
+	assert_eq!(
+		assignments.assignment(Id::U128(1)).map(|assignment| assignment.status),
+		Some(AssignmentStatus::Pending)
+	);
+
+	// Final assignments can't be changed once dispatched:
+	assert_ok!(assignments.mark_dispatched(0));
+	assert_eq!(
+		assignments.record_assignment(charlie, 0, 2002, Finality::Provisional),
+		Err(AssignmentError::AlreadyFinalized)
+	);
+}
+
+#[ink::test]
+fn remove_pending_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut assignments = new_assignments();
+
+	assert_eq!(assignments.remove_pending(bob, 0), Err(AssignmentError::AssignmentNotFound));
+
+	assert_ok!(assignments.record_assignment(bob, 0, 2000, Finality::Provisional));
+	// Only the account that requested the assignment can cancel it:
+	assert_eq!(assignments.remove_pending(charlie, 0), Err(AssignmentError::NotAllowed));
+	assert_ok!(assignments.remove_pending(bob, 0));
+	assert_eq!(assignments.assignment(Id::U128(0)), None);
+
+	// Dispatched assignments can't be cancelled:
+	assert_ok!(assignments.record_assignment(bob, 0, 2000, Finality::Provisional));
+	assert_ok!(assignments.mark_dispatched(0));
+	assert_eq!(assignments.remove_pending(bob, 0), Err(AssignmentError::AlreadyDispatched));
+}
+
+#[ink::test]
+fn mark_dispatched_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, .. } = get_default_accounts();
+	let mut assignments = new_assignments();
+
+	assert_eq!(assignments.mark_dispatched(0), Err(AssignmentError::AssignmentNotFound));
+
+	assert_ok!(assignments.record_assignment(bob, 0, 2000, Finality::Final));
+	assert_eq!(
+		assignments.mark_dispatched(0),
+		Ok(Assignment {
+			holder: bob,
+			task: 2000,
+			finality: Finality::Final,
+			status: AssignmentStatus::Dispatched,
+		})
+	);
+
+	// An assignment can only be dispatched once:
+	assert_eq!(assignments.mark_dispatched(0), Err(AssignmentError::AlreadyDispatched));
+}
+
+fn new_assignments() -> CoretimeAssignments {
+	let DefaultAccounts::<DefaultEnvironment> { alice, .. } = get_default_accounts();
+	CoretimeAssignments::new(alice, TIMESLICE_PERIOD)
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
@@ -0,0 +1,89 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use openbrush::traits::{AccountId, BlockNumber};
+use primitives::coretime::{Finality, TaskId};
+use xc_regions::types::XcRegionsError;
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Config {
+	/// The `AccountId` of the xcRegions contract.
+	pub xc_regions_contract: AccountId,
+	/// The number of relay chain blocks in a timeslice.
+	pub timeslice_period: BlockNumber,
+}
+
+#[derive(scale::Decode, scale::Encode, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum AssignmentStatus {
+	/// The assignment is queued until it gets dispatched.
+	Pending,
+	/// The assignment was dispatched to the Coretime chain.
+	Dispatched,
+}
+
+/// The assignment of a region to a task.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Assignment {
+	/// The holder of the xcRegion at the time the assignment was requested.
+	pub holder: AccountId,
+	/// The task the region is assigned to.
+	pub task: TaskId,
+	/// Whether the assignment can later be changed.
+	pub finality: Finality,
+	/// The status of the assignment.
+	pub status: AssignmentStatus,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum AssignmentError {
+	/// The provided identifier is not a valid region id.
+	InvalidRegionId,
+	/// The caller is not allowed to perform the operation.
+	NotAllowed,
+	/// The metadata of the region couldn't be retrieved from the xcRegions contract.
+	MetadataNotFound,
+	/// The region already ended and can no longer be assigned.
+	RegionExpired,
+	/// The region was finally assigned and its assignment can no longer be changed.
+	AlreadyFinalized,
+	/// There is no assignment recorded for the region.
+	AssignmentNotFound,
+	/// The assignment was already dispatched.
+	AlreadyDispatched,
+	/// The xcRegion changed hands since the assignment was requested.
+	HolderChanged,
+	/// An error occured when dispatching the assignment through the xcRegions contract.
+	XcRegionsError(XcRegionsError),
+}
+
+impl core::fmt::Display for AssignmentError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			AssignmentError::InvalidRegionId => write!(f, "InvalidRegionId"),
+			AssignmentError::NotAllowed => write!(f, "NotAllowed"),
+			AssignmentError::MetadataNotFound => write!(f, "MetadataNotFound"),
+			AssignmentError::RegionExpired => write!(f, "RegionExpired"),
+			AssignmentError::AlreadyFinalized => write!(f, "AlreadyFinalized"),
+			AssignmentError::AssignmentNotFound => write!(f, "AssignmentNotFound"),
+			AssignmentError::AlreadyDispatched => write!(f, "AlreadyDispatched"),
+			AssignmentError::HolderChanged => write!(f, "HolderChanged"),
+			AssignmentError::XcRegionsError(e) => write!(f, "{}", e),
+		}
+	}
+}
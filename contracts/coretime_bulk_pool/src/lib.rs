@@ -0,0 +1,594 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime bulk pool
+//!
+//! This contract pools the contributions of many accounts to participate in the bulk sale of the
+//! Coretime chain, and distributes the purchased region back to the contributors.
+//!
+//! The contributions are made in the relay chain asset and collected until they reach the price
+//! limit of the pool. Once the price limit is raised, the contributions are reserve-transferred to
+//! the Coretime chain over XCM. Until the broker `purchase` call can be sent over XCM, the
+//! contributions are transferred to the operator of the pool, who purchases the region on behalf
+//! of the pool and hands the xcRegion over to the contract. If the purchase fails, the operator
+//! returns the contributions and the contributors can withdraw them.
+//!
+//! The purchased region is distributed in one of two ways:
+//! - Any contributor can buy out the region by refunding the contributions of all the other
+//!   contributors.
+//! - The operator can list the region for resale on the Coretime market, in which case the proceeds
+//!   of the sale are distributed among the contributors pro-rata to their contributions.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+mod types;
+
+#[openbrush::contract(env = environment::ExtendedEnvironment)]
+pub mod coretime_bulk_pool {
+	use crate::types::{BulkPoolError, Config, Phase, Resale};
+	use coretime_market::coretime_market::CoretimeMarketRef;
+	use ink::{
+		codegen::{EmitEvent, Env, TraitCallBuilder},
+		env::call::FromAccountId,
+		prelude::vec::Vec,
+	};
+	use openbrush::{
+		contracts::{
+			reentrancy_guard::*,
+			traits::{
+				psp22::PSP22Ref,
+				psp34::{Id, PSP34Ref},
+			},
+		},
+		modifiers,
+		storage::Mapping,
+		traits::Storage,
+	};
+	use primitives::{coretime::RawRegionId, ensure, polkadot_xcm::PolkadotXcmCall, RuntimeCall};
+	use sp_arithmetic::{helpers_128bit::multiply_by_rational_with_rounding, Rounding};
+	use xc_regions::CORETIME_CHAIN_PARA_ID;
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimeBulkPool {
+		#[storage_field]
+		guard: reentrancy_guard::Data,
+		/// The configuration of the pool.
+		pub config: Config,
+		/// The phase the participation of the pool in the bulk sale is in.
+		pub phase: Phase,
+		/// The amount of the relay chain asset contributed by each account.
+		pub contributions: Mapping<AccountId, Balance>,
+		/// The total amount raised by the pool.
+		pub raised: Balance,
+	}
+
+	#[ink(event)]
+	pub struct Contributed {
+		/// The account that contributed.
+		#[ink(topic)]
+		pub(crate) contributor: AccountId,
+		/// The contributed amount.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct ContributionWithdrawn {
+		/// The account that withdrew its contribution.
+		#[ink(topic)]
+		pub(crate) contributor: AccountId,
+		/// The withdrawn amount.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct PurchaseDispatched {
+		/// The amount sent to the Coretime chain for the purchase.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct PurchaseCompleted {
+		/// The identifier of the purchased region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+	}
+
+	#[ink(event)]
+	pub struct PurchaseFailed {}
+
+	#[ink(event)]
+	pub struct RegionBoughtOut {
+		/// The identifier of the bought out region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The contributor that bought out the region.
+		#[ink(topic)]
+		pub(crate) buyer: AccountId,
+		/// The amount refunded to the other contributors.
+		pub(crate) cost: Balance,
+	}
+
+	#[ink(event)]
+	pub struct RegionListed {
+		/// The identifier of the listed region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The price per a single timeslice.
+		pub(crate) timeslice_price: Balance,
+	}
+
+	#[ink(event)]
+	pub struct ResaleSettled {
+		/// The identifier of the resold region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The proceeds distributed among the contributors.
+		pub(crate) proceeds: Balance,
+	}
+
+	#[ink(event)]
+	pub struct ProceedsClaimed {
+		/// The account that claimed its share of the proceeds.
+		#[ink(topic)]
+		pub(crate) contributor: AccountId,
+		/// The claimed amount.
+		pub(crate) amount: Balance,
+	}
+
+	impl CoretimeBulkPool {
+		/// ## Arguments:
+		/// - `xc_regions_contract` - The `AccountId` of the xcRegions contract.
+		/// - `market_contract` - The `AccountId` of the Coretime market.
+		/// - `relay_asset` - The PSP22 token representing the relay chain asset.
+		/// - `operator` - The account purchasing the region on the Coretime chain on behalf of the
+		///   pool.
+		/// - `price_limit` - The maximum price the pool pays for a region in the bulk sale,
+		///   including the XCM execution fees.
+		/// - `contribution_end` - The block number until which contributions are accepted.
+		#[ink(constructor)]
+		pub fn new(
+			xc_regions_contract: AccountId,
+			market_contract: AccountId,
+			relay_asset: AccountId,
+			operator: AccountId,
+			price_limit: Balance,
+			contribution_end: BlockNumber,
+		) -> Self {
+			assert!(price_limit > 0, "Price limit must be greater than zero");
+
+			Self {
+				guard: Default::default(),
+				config: Config {
+					xc_regions_contract,
+					market_contract,
+					relay_asset,
+					operator,
+					price_limit,
+					contribution_end,
+				},
+				phase: Phase::Collecting,
+				contributions: Default::default(),
+				raised: 0,
+			}
+		}
+
+		#[ink(message)]
+		pub fn config(&self) -> Config {
+			self.config.clone()
+		}
+
+		#[ink(message)]
+		pub fn phase(&self) -> Phase {
+			self.phase.clone()
+		}
+
+		#[ink(message)]
+		pub fn raised(&self) -> Balance {
+			self.raised
+		}
+
+		#[ink(message)]
+		pub fn contribution(&self, contributor: AccountId) -> Balance {
+			self.contributions.get(contributor).unwrap_or_default()
+		}
+
+		/// A function for contributing to the pool.
+		///
+		/// ## Arguments:
+		/// - `amount`: The amount of the relay chain asset to contribute. The caller must approve
+		///   the amount to the contract beforehand.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `Contributed` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn contribute(&mut self, amount: Balance) -> Result<(), BulkPoolError> {
+			let caller = self.env().caller();
+
+			self.record_contribution(caller, amount)?;
+			PSP22Ref::transfer_from(
+				&self.config.relay_asset,
+				caller,
+				self.env().account_id(),
+				amount,
+				Vec::new(),
+			)
+			.map_err(BulkPoolError::RelayAssetError)?;
+
+			self.env().emit_event(Contributed { contributor: caller, amount });
+			Ok(())
+		}
+
+		/// A function for withdrawing the contribution of the caller.
+		///
+		/// Contributions can be withdrawn while they are collected, or once they are refunded.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `ContributionWithdrawn` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn withdraw(&mut self) -> Result<(), BulkPoolError> {
+			let caller = self.env().caller();
+
+			let amount = self.take_contribution(caller)?;
+			PSP22Ref::transfer(&self.config.relay_asset, caller, amount, Vec::new())
+				.map_err(BulkPoolError::RelayAssetError)?;
+
+			self.env().emit_event(ContributionWithdrawn { contributor: caller, amount });
+			Ok(())
+		}
+
+		/// A function for sending the raised contributions to the Coretime chain for purchasing a
+		/// region in the bulk sale.
+		///
+		/// Only callable by the operator once the price limit is raised. The contributions are
+		/// reserve-transferred to the account of the operator on the Coretime chain.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `PurchaseDispatched` event.
+		#[ink(message)]
+		pub fn purchase(&mut self) -> Result<(), BulkPoolError> {
+			let caller = self.env().caller();
+
+			let amount = self.start_purchase(caller)?;
+
+			let call =
+				PolkadotXcmCall::transfer_to_coretime_chain(CORETIME_CHAIN_PARA_ID, caller, amount);
+			self.env()
+				.call_runtime(&RuntimeCall::PolkadotXcm(call))
+				.map_err(|_| BulkPoolError::RuntimeError)?;
+
+			self.env().emit_event(PurchaseDispatched { amount });
+			Ok(())
+		}
+
+		/// A function for handing the purchased region over to the pool.
+		///
+		/// Only callable by the operator.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the purchased region. The caller must
+		///   approve the region to the contract beforehand.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `PurchaseCompleted` event.
+		#[ink(message)]
+		pub fn complete_purchase(&mut self, id: Id) -> Result<(), BulkPoolError> {
+			let Id::U128(region_id) = id else { return Err(BulkPoolError::InvalidRegionId) };
+			let contract = self.env().account_id();
+
+			self.finish_purchase(self.env().caller(), Phase::Acquired(region_id))?;
+
+			PSP34Ref::transfer(&self.config.xc_regions_contract, contract, id.clone(), Vec::new())
+				.map_err(BulkPoolError::XcRegionsPsp34Error)?;
+			// Don't rely on the transfer succeeding, but ensure that the contract actually received
+			// the region.
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id) == Some(contract),
+				BulkPoolError::RegionNotReceived
+			);
+
+			self.env().emit_event(PurchaseCompleted { region_id });
+			Ok(())
+		}
+
+		/// A function for marking the purchase as failed, allowing the contributors to withdraw
+		/// their contributions.
+		///
+		/// Only callable by the operator, after returning the contributions to the contract.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `PurchaseFailed` event.
+		#[ink(message)]
+		pub fn fail_purchase(&mut self) -> Result<(), BulkPoolError> {
+			self.finish_purchase(self.env().caller(), Phase::Refunding)?;
+
+			self.env().emit_event(PurchaseFailed {});
+			Ok(())
+		}
+
+		/// A function for buying out the purchased region.
+		///
+		/// Callable by any contributor, who pays for the contributions of all the other
+		/// contributors. The other contributors can withdraw their contributions afterwards.
+		///
+		/// The caller must approve the cost of the buyout in the relay chain asset to the
+		/// contract beforehand.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionBoughtOut` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn buyout(&mut self) -> Result<(), BulkPoolError> {
+			let caller = self.env().caller();
+
+			let (region_id, cost) = self.take_buyout(caller)?;
+
+			PSP22Ref::transfer_from(
+				&self.config.relay_asset,
+				caller,
+				self.env().account_id(),
+				cost,
+				Vec::new(),
+			)
+			.map_err(BulkPoolError::RelayAssetError)?;
+			PSP34Ref::transfer(
+				&self.config.xc_regions_contract,
+				caller,
+				Id::U128(region_id),
+				Vec::new(),
+			)
+			.map_err(BulkPoolError::XcRegionsPsp34Error)?;
+
+			self.env().emit_event(RegionBoughtOut { region_id, buyer: caller, cost });
+			Ok(())
+		}
+
+		/// A function for listing the purchased region for resale on the Coretime market.
+		///
+		/// Only callable by the operator, who pays the listing deposit required by the market.
+		/// The deposit is returned to the operator once the resale is settled.
+		///
+		/// ## Arguments:
+		/// - `timeslice_price`: The price per a single timeslice.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionListed` event.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn list(&mut self, timeslice_price: Balance) -> Result<(), BulkPoolError> {
+			let caller = self.env().caller();
+			let deposit = self.env().transferred_value();
+
+			ensure!(caller == self.config.operator, BulkPoolError::NotOperator);
+			let Phase::Acquired(region_id) = self.phase else {
+				return Err(BulkPoolError::InvalidPhase)
+			};
+			let id = Id::U128(region_id);
+
+			PSP34Ref::approve(
+				&self.config.xc_regions_contract,
+				self.config.market_contract,
+				Some(id.clone()),
+				true,
+			)
+			.map_err(BulkPoolError::XcRegionsPsp34Error)?;
+
+			let mut market: CoretimeMarketRef =
+				FromAccountId::from_account_id(self.config.market_contract);
+			market
+				.call_mut()
+				.list_region(id, timeslice_price, None)
+				.transferred_value(deposit)
+				.try_invoke()
+				.map_err(|_| BulkPoolError::MarketCallFailed)?
+				.map_err(|_| BulkPoolError::MarketCallFailed)?
+				.map_err(BulkPoolError::MarketError)?;
+
+			self.phase = Phase::Listed(Resale {
+				region_id,
+				lister: caller,
+				deposit,
+				balance: self.env().balance(),
+			});
+
+			self.env().emit_event(RegionListed { region_id, timeslice_price });
+			Ok(())
+		}
+
+		/// A function for settling the resale once the region is no longer listed on the market.
+		///
+		/// Callable by anyone. The listing deposit is returned to the operator, and the rest of
+		/// the sale payment is distributed among the contributors.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `ResaleSettled` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn settle_resale(&mut self) -> Result<(), BulkPoolError> {
+			let Phase::Listed(Resale { region_id, .. }) = self.phase else {
+				return Err(BulkPoolError::InvalidPhase)
+			};
+
+			let market: CoretimeMarketRef =
+				FromAccountId::from_account_id(self.config.market_contract);
+			let listing =
+				market.listed_region(Id::U128(region_id)).map_err(BulkPoolError::MarketError)?;
+			ensure!(listing.is_none(), BulkPoolError::ResaleNotCompleted);
+
+			let (lister, deposit, proceeds) = self.settle(self.env().balance())?;
+			if deposit > 0 {
+				self.env()
+					.transfer(lister, deposit)
+					.map_err(|_| BulkPoolError::TransferFailed)?;
+			}
+
+			self.env().emit_event(ResaleSettled { region_id, proceeds });
+			Ok(())
+		}
+
+		/// A function for claiming the share of the resale proceeds of the caller.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `ProceedsClaimed` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn claim_proceeds(&mut self) -> Result<(), BulkPoolError> {
+			let caller = self.env().caller();
+
+			let amount = self.take_proceeds(caller)?;
+			if amount > 0 {
+				self.env().transfer(caller, amount).map_err(|_| BulkPoolError::TransferFailed)?;
+			}
+
+			self.env().emit_event(ProceedsClaimed { contributor: caller, amount });
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimeBulkPool {
+		pub(crate) fn record_contribution(
+			&mut self,
+			contributor: AccountId,
+			amount: Balance,
+		) -> Result<(), BulkPoolError> {
+			ensure!(self.phase == Phase::Collecting, BulkPoolError::InvalidPhase);
+			ensure!(
+				self.env().block_number() < self.config.contribution_end,
+				BulkPoolError::ContributionsClosed
+			);
+			ensure!(amount > 0, BulkPoolError::InvalidAmount);
+
+			let raised = self.raised.checked_add(amount).ok_or(BulkPoolError::ArithmeticError)?;
+			ensure!(raised <= self.config.price_limit, BulkPoolError::PriceLimitExceeded);
+
+			let contribution = self.contribution(contributor).saturating_add(amount);
+			self.raised = raised;
+			self.contributions.insert(contributor, &contribution);
+			Ok(())
+		}
+
+		// Removes the contribution of the contributor in case contributions can be withdrawn.
+		pub(crate) fn take_contribution(
+			&mut self,
+			contributor: AccountId,
+		) -> Result<Balance, BulkPoolError> {
+			ensure!(
+				matches!(self.phase, Phase::Collecting | Phase::Refunding),
+				BulkPoolError::InvalidPhase
+			);
+
+			let amount =
+				self.contributions.get(contributor).ok_or(BulkPoolError::NoContribution)?;
+			self.contributions.remove(contributor);
+
+			// While collecting, the withdrawn amount has to be raised again. Once refunding, the
+			// raised amount is kept for the record.
+			if self.phase == Phase::Collecting {
+				self.raised = self.raised.saturating_sub(amount);
+			}
+
+			Ok(amount)
+		}
+
+		// Returns the amount to send to the Coretime chain.
+		pub(crate) fn start_purchase(
+			&mut self,
+			caller: AccountId,
+		) -> Result<Balance, BulkPoolError> {
+			ensure!(caller == self.config.operator, BulkPoolError::NotOperator);
+			ensure!(self.phase == Phase::Collecting, BulkPoolError::InvalidPhase);
+			ensure!(self.raised == self.config.price_limit, BulkPoolError::PriceLimitNotReached);
+
+			self.phase = Phase::Purchasing;
+			Ok(self.raised)
+		}
+
+		pub(crate) fn finish_purchase(
+			&mut self,
+			caller: AccountId,
+			outcome: Phase,
+		) -> Result<(), BulkPoolError> {
+			ensure!(caller == self.config.operator, BulkPoolError::NotOperator);
+			ensure!(self.phase == Phase::Purchasing, BulkPoolError::InvalidPhase);
+
+			self.phase = outcome;
+			Ok(())
+		}
+
+		// Returns the region and the cost of buying out all the other contributors.
+		pub(crate) fn take_buyout(
+			&mut self,
+			buyer: AccountId,
+		) -> Result<(RawRegionId, Balance), BulkPoolError> {
+			let Phase::Acquired(region_id) = self.phase else {
+				return Err(BulkPoolError::InvalidPhase)
+			};
+			let contribution =
+				self.contributions.get(buyer).ok_or(BulkPoolError::NoContribution)?;
+
+			self.contributions.remove(buyer);
+			self.phase = Phase::Refunding;
+
+			Ok((region_id, self.raised.saturating_sub(contribution)))
+		}
+
+		// Settles the resale given the current balance of the contract. Returns the lister
+		// together with the deposit to return to it, and the proceeds of the resale.
+		//
+		// Once the region is no longer listed, the market returned the listing deposit to the
+		// contract and paid for the sale. If the region expired without being sold, the listing
+		// was purged, only part of the deposit was returned and there are no proceeds to
+		// distribute.
+		pub(crate) fn settle(
+			&mut self,
+			balance: Balance,
+		) -> Result<(AccountId, Balance, Balance), BulkPoolError> {
+			let Phase::Listed(Resale { lister, deposit, balance: listed_balance, .. }) = self.phase
+			else {
+				return Err(BulkPoolError::InvalidPhase)
+			};
+
+			let received = balance.saturating_sub(listed_balance);
+			let deposit = deposit.min(received);
+			let proceeds = received.saturating_sub(deposit);
+
+			self.phase = Phase::Settled { proceeds };
+			Ok((lister, deposit, proceeds))
+		}
+
+		// Removes the contribution of the contributor and returns its share of the proceeds.
+		pub(crate) fn take_proceeds(
+			&mut self,
+			contributor: AccountId,
+		) -> Result<Balance, BulkPoolError> {
+			let Phase::Settled { proceeds } = self.phase else {
+				return Err(BulkPoolError::InvalidPhase)
+			};
+			let contribution =
+				self.contributions.get(contributor).ok_or(BulkPoolError::NoContribution)?;
+			self.contributions.remove(contributor);
+
+			multiply_by_rational_with_rounding(proceeds, contribution, self.raised, Rounding::Down)
+				.ok_or(BulkPoolError::ArithmeticError)
+		}
+	}
+}
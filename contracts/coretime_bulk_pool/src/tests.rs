@@ -0,0 +1,169 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_bulk_pool::CoretimeBulkPool,
+	types::{BulkPoolError, Phase, Resale},
+};
+use ink::env::{
+	test::{advance_block, default_accounts, DefaultAccounts},
+	DefaultEnvironment,
+};
+use primitives::assert_ok;
+
+const PRICE_LIMIT: u128 = 1000;
+const CONTRIBUTION_END: u32 = 10;
+
+#[ink::test]
+fn record_contribution_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut pool = new_pool();
+
+	assert_eq!(pool.record_contribution(bob, 0), Err(BulkPoolError::InvalidAmount));
+	assert_eq!(
+		pool.record_contribution(bob, PRICE_LIMIT + 1),
+		Err(BulkPoolError::PriceLimitExceeded)
+	);
+
+	assert_ok!(pool.record_contribution(bob, 300));
+	assert_ok!(pool.record_contribution(bob, 200));
+	assert_ok!(pool.record_contribution(charlie, 100));
+	assert_eq!(pool.contribution(bob), 500);
+	assert_eq!(pool.contribution(charlie), 100);
+	assert_eq!(pool.raised(), 600);
+
+	// The raised amount can't exceed the price limit:
+	assert_eq!(pool.record_contribution(charlie, 401), Err(BulkPoolError::PriceLimitExceeded));
+
+	advance_n_blocks(CONTRIBUTION_END);
+	assert_eq!(pool.record_contribution(charlie, 100), Err(BulkPoolError::ContributionsClosed));
+}
+
+#[ink::test]
+fn take_contribution_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	let mut pool = new_pool();
+
+	assert_eq!(pool.take_contribution(bob), Err(BulkPoolError::NoContribution));
+	assert_ok!(pool.record_contribution(bob, 500));
+	assert_ok!(pool.record_contribution(charlie, 100));
+
+	// While collecting, withdrawing reduces the raised amount:
+	assert_eq!(pool.take_contribution(charlie), Ok(100));
+	assert_eq!(pool.raised(), 500);
+	assert_eq!(pool.contribution(charlie), 0);
+
+	assert_ok!(pool.record_contribution(charlie, 500));
+	assert_ok!(pool.start_purchase(alice));
+	// Contributions can't be withdrawn during the purchase:
+	assert_eq!(pool.take_contribution(bob), Err(BulkPoolError::InvalidPhase));
+
+	assert_ok!(pool.finish_purchase(alice, Phase::Refunding));
+	assert_eq!(pool.take_contribution(bob), Ok(500));
+	assert_eq!(pool.raised(), PRICE_LIMIT);
+}
+
+#[ink::test]
+fn purchase_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, .. } = get_default_accounts();
+	let mut pool = new_pool();
+
+	assert_ok!(pool.record_contribution(bob, 500));
+	assert_eq!(pool.start_purchase(bob), Err(BulkPoolError::NotOperator));
+	assert_eq!(pool.start_purchase(alice), Err(BulkPoolError::PriceLimitNotReached));
+	assert_eq!(pool.finish_purchase(alice, Phase::Acquired(0)), Err(BulkPoolError::InvalidPhase));
+
+	assert_ok!(pool.record_contribution(bob, 500));
+	assert_eq!(pool.start_purchase(alice), Ok(PRICE_LIMIT));
+	assert_eq!(pool.phase(), Phase::Purchasing);
+	assert_eq!(pool.record_contribution(bob, 1), Err(BulkPoolError::InvalidPhase));
+
+	assert_eq!(pool.finish_purchase(bob, Phase::Acquired(0)), Err(BulkPoolError::NotOperator));
+	assert_ok!(pool.finish_purchase(alice, Phase::Acquired(0)));
+	assert_eq!(pool.phase(), Phase::Acquired(0));
+}
+
+#[ink::test]
+fn take_buyout_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, django, .. } =
+		get_default_accounts();
+	let mut pool = new_pool();
+
+	assert_ok!(pool.record_contribution(bob, 700));
+	assert_ok!(pool.record_contribution(charlie, 300));
+	assert_eq!(pool.take_buyout(bob), Err(BulkPoolError::InvalidPhase));
+
+	assert_ok!(pool.start_purchase(alice));
+	assert_ok!(pool.finish_purchase(alice, Phase::Acquired(0)));
+
+	assert_eq!(pool.take_buyout(django), Err(BulkPoolError::NoContribution));
+	// The buyer pays for the contributions of the others:
+	assert_eq!(pool.take_buyout(bob), Ok((0, 300)));
+	assert_eq!(pool.phase(), Phase::Refunding);
+
+	assert_eq!(pool.take_contribution(bob), Err(BulkPoolError::NoContribution));
+	assert_eq!(pool.take_contribution(charlie), Ok(300));
+}
+
+#[ink::test]
+fn resale_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, django, .. } =
+		get_default_accounts();
+	let mut pool = new_pool();
+
+	assert_ok!(pool.record_contribution(bob, 700));
+	assert_ok!(pool.record_contribution(charlie, 300));
+	assert_ok!(pool.start_purchase(alice));
+	assert_ok!(pool.finish_purchase(alice, Phase::Acquired(0)));
+	assert_eq!(pool.settle(0), Err(BulkPoolError::InvalidPhase));
+
+	pool.phase = Phase::Listed(Resale { region_id: 0, lister: alice, deposit: 50, balance: 100 });
+	assert_eq!(pool.take_proceeds(bob), Err(BulkPoolError::InvalidPhase));
+
+	// The market returned the deposit and paid 2000 for the region:
+	assert_eq!(pool.settle(2150), Ok((alice, 50, 2000)));
+	assert_eq!(pool.phase(), Phase::Settled { proceeds: 2000 });
+
+	assert_eq!(pool.take_proceeds(bob), Ok(1400));
+	assert_eq!(pool.take_proceeds(bob), Err(BulkPoolError::NoContribution));
+	assert_eq!(pool.take_proceeds(charlie), Ok(600));
+	assert_eq!(pool.take_proceeds(django), Err(BulkPoolError::NoContribution));
+}
+
+#[ink::test]
+fn settle_unsold_region_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, .. } = get_default_accounts();
+	let mut pool = new_pool();
+
+	// The expired listing was purged and only half of the deposit was returned:
+	pool.phase = Phase::Listed(Resale { region_id: 0, lister: alice, deposit: 50, balance: 100 });
+	assert_eq!(pool.settle(125), Ok((alice, 25, 0)));
+	assert_eq!(pool.phase(), Phase::Settled { proceeds: 0 });
+}
+
+fn new_pool() -> CoretimeBulkPool {
+	let DefaultAccounts::<DefaultEnvironment> { alice, frank, eve, .. } = get_default_accounts();
+	CoretimeBulkPool::new(frank, eve, frank, alice, PRICE_LIMIT, CONTRIBUTION_END)
+}
+
+fn advance_n_blocks(n: u32) {
+	for _ in 0..n {
+		advance_block::<DefaultEnvironment>();
+	}
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
@@ -0,0 +1,144 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use coretime_market::types::MarketError;
+use openbrush::{
+	contracts::traits::{errors::ReentrancyGuardError, psp22::PSP22Error, psp34::PSP34Error},
+	traits::{AccountId, BlockNumber},
+};
+use primitives::{coretime::RawRegionId, Balance};
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Config {
+	/// The `AccountId` of the xcRegions contract.
+	pub xc_regions_contract: AccountId,
+	/// The `AccountId` of the Coretime market.
+	pub market_contract: AccountId,
+	/// The PSP22 token representing the relay chain asset in which the contributions are made.
+	pub relay_asset: AccountId,
+	/// The account purchasing the region on the Coretime chain on behalf of the pool.
+	pub operator: AccountId,
+	/// The maximum price the pool pays for a region in the bulk sale. Contributions are collected
+	/// until this amount is raised.
+	pub price_limit: Balance,
+	/// The block number until which contributions are accepted.
+	pub contribution_end: BlockNumber,
+}
+
+/// The phase the participation of the pool in the bulk sale is in.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum Phase {
+	/// The contributions are being collected.
+	Collecting,
+	/// The contributions were sent to the Coretime chain for purchasing a region.
+	Purchasing,
+	/// The purchased region is held by the pool.
+	Acquired(RawRegionId),
+	/// The purchased region is listed for resale on the Coretime market.
+	Listed(Resale),
+	/// The resale was settled, and the proceeds are distributed among the contributors.
+	Settled { proceeds: Balance },
+	/// The contributions are refunded, either because the purchase failed or because the region
+	/// was bought out by one of the contributors.
+	Refunding,
+}
+
+/// The resale of the purchased region on the Coretime market.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Resale {
+	/// The identifier of the listed region.
+	pub region_id: RawRegionId,
+	/// The account that paid the listing deposit.
+	pub lister: AccountId,
+	/// The listing deposit, which is returned to the lister once the resale is settled.
+	pub deposit: Balance,
+	/// The balance of the contract right after the region got listed.
+	pub balance: Balance,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum BulkPoolError {
+	/// An arithmetic error occured.
+	ArithmeticError,
+	/// The provided identifier is not a valid region id.
+	InvalidRegionId,
+	/// The caller is not the operator of the pool.
+	NotOperator,
+	/// The operation isn't possible in the current phase.
+	InvalidPhase,
+	/// The contribution period has ended.
+	ContributionsClosed,
+	/// The contributed amount must be greater than zero.
+	InvalidAmount,
+	/// The contribution would raise more than the price limit.
+	PriceLimitExceeded,
+	/// The contributions didn't reach the price limit yet.
+	PriceLimitNotReached,
+	/// The caller doesn't have any contribution in the pool.
+	NoContribution,
+	/// The region is still listed on the market.
+	ResaleNotCompleted,
+	/// The contract didn't receive the region.
+	RegionNotReceived,
+	/// Failed to transfer the native tokens.
+	TransferFailed,
+	/// Failed to send the contributions to the Coretime chain.
+	RuntimeError,
+	/// The call to the market failed.
+	MarketCallFailed,
+	/// The call would result in reentering the contract.
+	ReentrantCall,
+	/// An error occured when transferring the relay chain asset.
+	RelayAssetError(PSP22Error),
+	/// An error occured when transferring the xcRegion.
+	XcRegionsPsp34Error(PSP34Error),
+	/// An error returned by the market.
+	MarketError(MarketError),
+}
+
+impl core::fmt::Display for BulkPoolError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			BulkPoolError::ArithmeticError => write!(f, "ArithmeticError"),
+			BulkPoolError::InvalidRegionId => write!(f, "InvalidRegionId"),
+			BulkPoolError::NotOperator => write!(f, "NotOperator"),
+			BulkPoolError::InvalidPhase => write!(f, "InvalidPhase"),
+			BulkPoolError::ContributionsClosed => write!(f, "ContributionsClosed"),
+			BulkPoolError::InvalidAmount => write!(f, "InvalidAmount"),
+			BulkPoolError::PriceLimitExceeded => write!(f, "PriceLimitExceeded"),
+			BulkPoolError::PriceLimitNotReached => write!(f, "PriceLimitNotReached"),
+			BulkPoolError::NoContribution => write!(f, "NoContribution"),
+			BulkPoolError::ResaleNotCompleted => write!(f, "ResaleNotCompleted"),
+			BulkPoolError::RegionNotReceived => write!(f, "RegionNotReceived"),
+			BulkPoolError::TransferFailed => write!(f, "TransferFailed"),
+			BulkPoolError::RuntimeError => write!(f, "RuntimeError"),
+			BulkPoolError::MarketCallFailed => write!(f, "MarketCallFailed"),
+			BulkPoolError::ReentrantCall => write!(f, "ReentrantCall"),
+			BulkPoolError::RelayAssetError(e) => write!(f, "{:?}", e),
+			BulkPoolError::XcRegionsPsp34Error(e) => write!(f, "{:?}", e),
+			BulkPoolError::MarketError(e) => write!(f, "{}", e),
+		}
+	}
+}
+
+impl From<ReentrancyGuardError> for BulkPoolError {
+	fn from(_: ReentrancyGuardError) -> Self {
+		BulkPoolError::ReentrantCall
+	}
+}
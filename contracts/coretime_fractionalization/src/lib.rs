@@ -0,0 +1,371 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime fractionalization
+//!
+//! This contract allows an xcRegion to be co-owned by locking it in the contract and minting PSP22
+//! shares which represent the fractional ownership over the region.
+//!
+//! Every instance of the contract fractionalizes a single region, the shares being the PSP22
+//! token implemented by the contract itself. Upon fractionalizing the region, the curator sets
+//! the price for buying all of the shares.
+//!
+//! Anyone can buy out the region by paying for the shares they don't hold, pro-rata to the buyout
+//! price. The shares of the buyer are burned and the region is transferred to them, while the
+//! remaining shareholders can redeem their shares for their part of the buyout payment. An
+//! account holding all of the shares can therefore unlock the region without paying anything.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+mod types;
+
+#[openbrush::implementation(PSP22, PSP22Metadata)]
+#[openbrush::contract]
+pub mod coretime_fractionalization {
+	use crate::types::{Config, FractionalizationError, Vault, VaultStatus};
+	use ink::{
+		codegen::{EmitEvent, Env},
+		prelude::{string::String, vec::Vec},
+	};
+	use openbrush::{
+		contracts::{
+			reentrancy_guard::*,
+			traits::psp34::{Id, PSP34Ref},
+		},
+		modifiers,
+		traits::Storage,
+	};
+	use primitives::{coretime::RawRegionId, ensure};
+	use sp_arithmetic::{helpers_128bit::multiply_by_rational_with_rounding, Rounding};
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimeFractionalization {
+		#[storage_field]
+		psp22: psp22::Data,
+		#[storage_field]
+		metadata: metadata::Data,
+		#[storage_field]
+		guard: reentrancy_guard::Data,
+		/// The configuration of the fractionalization contract.
+		pub config: Config,
+		/// The region locked in the contract.
+		pub vault: Option<Vault>,
+	}
+
+	#[ink(event)]
+	pub struct RegionFractionalized {
+		/// The identifier of the fractionalized region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that fractionalized the region.
+		#[ink(topic)]
+		pub(crate) curator: AccountId,
+		/// The number of minted shares.
+		pub(crate) shares: Balance,
+		/// The price for buying all the shares of the region.
+		pub(crate) buyout_price: Balance,
+	}
+
+	#[ink(event)]
+	pub struct RegionBoughtOut {
+		/// The identifier of the bought out region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that bought out the region.
+		#[ink(topic)]
+		pub(crate) buyer: AccountId,
+		/// The amount paid for the shares not held by the buyer.
+		pub(crate) cost: Balance,
+	}
+
+	#[ink(event)]
+	pub struct SharesRedeemed {
+		/// The identifier of the bought out region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that redeemed the shares.
+		#[ink(topic)]
+		pub(crate) holder: AccountId,
+		/// The number of redeemed shares.
+		pub(crate) shares: Balance,
+		/// The part of the buyout payment paid out to the holder.
+		pub(crate) payout: Balance,
+	}
+
+	impl CoretimeFractionalization {
+		/// ## Arguments:
+		/// - `xc_regions_contract` - The `AccountId` of the xcRegions contract.
+		/// - `name` - The name of the share token.
+		/// - `symbol` - The symbol of the share token.
+		/// - `decimals` - The number of decimals of the share token.
+		#[ink(constructor)]
+		pub fn new(
+			xc_regions_contract: AccountId,
+			name: Option<String>,
+			symbol: Option<String>,
+			decimals: u8,
+		) -> Self {
+			let mut fractionalization = Self {
+				psp22: Default::default(),
+				metadata: Default::default(),
+				guard: Default::default(),
+				config: Config { xc_regions_contract },
+				vault: None,
+			};
+
+			fractionalization.metadata.name.set(&name);
+			fractionalization.metadata.symbol.set(&symbol);
+			fractionalization.metadata.decimals.set(&decimals);
+
+			fractionalization
+		}
+
+		#[ink(message)]
+		pub fn config(&self) -> Config {
+			self.config.clone()
+		}
+
+		#[ink(message)]
+		pub fn vault(&self) -> Option<Vault> {
+			self.vault.clone()
+		}
+
+		/// Returns the amount the account has to pay for buying out the region.
+		///
+		/// ## Arguments:
+		/// - `buyer`: The account buying out the region.
+		#[ink(message)]
+		pub fn buyout_cost(&self, buyer: AccountId) -> Result<Balance, FractionalizationError> {
+			let vault = self.vault.as_ref().ok_or(FractionalizationError::NotFractionalized)?;
+			ensure!(vault.status == VaultStatus::Locked, FractionalizationError::AlreadyBoughtOut);
+
+			let missing_shares = vault.total_shares.saturating_sub(self.shares_of(buyer));
+			multiply_by_rational_with_rounding(
+				vault.buyout_price,
+				missing_shares,
+				vault.total_shares,
+				Rounding::Up,
+			)
+			.ok_or(FractionalizationError::ArithmeticError)
+		}
+
+		/// A function for fractionalizing an xcRegion.
+		///
+		/// The xcRegion is locked in the contract and the shares are minted to the caller.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region. The caller must own the
+		///   region and approve it to the contract beforehand.
+		/// - `shares`: The number of shares to mint.
+		/// - `buyout_price`: The price for buying all the shares of the region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionFractionalized` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn fractionalize(
+			&mut self,
+			id: Id,
+			shares: Balance,
+			buyout_price: Balance,
+		) -> Result<(), FractionalizationError> {
+			let Id::U128(region_id) = id else {
+				return Err(FractionalizationError::InvalidRegionId)
+			};
+			let caller = self.env().caller();
+			let contract = self.env().account_id();
+
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id.clone()) == Some(caller),
+				FractionalizationError::NotAllowed
+			);
+
+			self.lock_region(caller, region_id, shares, buyout_price)?;
+
+			PSP34Ref::transfer(&self.config.xc_regions_contract, contract, id.clone(), Vec::new())
+				.map_err(FractionalizationError::XcRegionsPsp34Error)?;
+			// Don't rely on the transfer succeeding, but ensure that the contract actually received
+			// the region.
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id) == Some(contract),
+				FractionalizationError::RegionNotReceived
+			);
+
+			self.env().emit_event(RegionFractionalized {
+				region_id,
+				curator: caller,
+				shares,
+				buyout_price,
+			});
+			Ok(())
+		}
+
+		/// A function for buying out the fractionalized region.
+		///
+		/// The caller pays for the shares they don't hold, pro-rata to the buyout price. The
+		/// shares of the caller are burned and the region is transferred to them. Any value
+		/// transferred on top of the buyout cost is returned to the caller.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionBoughtOut` event.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn buyout(&mut self) -> Result<(), FractionalizationError> {
+			let caller = self.env().caller();
+			let transferred_value = self.env().transferred_value();
+
+			let (region_id, cost) = self.take_buyout(caller, transferred_value)?;
+
+			PSP34Ref::transfer(
+				&self.config.xc_regions_contract,
+				caller,
+				Id::U128(region_id),
+				Vec::new(),
+			)
+			.map_err(FractionalizationError::XcRegionsPsp34Error)?;
+
+			let surplus = transferred_value.saturating_sub(cost);
+			if surplus > 0 {
+				self.env()
+					.transfer(caller, surplus)
+					.map_err(|_| FractionalizationError::TransferFailed)?;
+			}
+
+			self.env().emit_event(RegionBoughtOut { region_id, buyer: caller, cost });
+			Ok(())
+		}
+
+		/// A function for redeeming shares for their part of the buyout payment.
+		///
+		/// Only callable once the region was bought out.
+		///
+		/// ## Arguments:
+		/// - `shares`: The number of shares to redeem.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `SharesRedeemed` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn redeem(&mut self, shares: Balance) -> Result<(), FractionalizationError> {
+			let caller = self.env().caller();
+
+			let (region_id, payout) = self.redeem_shares(caller, shares)?;
+			if payout > 0 {
+				self.env()
+					.transfer(caller, payout)
+					.map_err(|_| FractionalizationError::TransferFailed)?;
+			}
+
+			self.env()
+				.emit_event(SharesRedeemed { region_id, holder: caller, shares, payout });
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimeFractionalization {
+		// Records the fractionalized region and mints the shares to the curator.
+		pub(crate) fn lock_region(
+			&mut self,
+			curator: AccountId,
+			region_id: RawRegionId,
+			shares: Balance,
+			buyout_price: Balance,
+		) -> Result<(), FractionalizationError> {
+			ensure!(self.vault.is_none(), FractionalizationError::AlreadyFractionalized);
+			ensure!(shares > 0, FractionalizationError::InvalidShares);
+			ensure!(buyout_price > 0, FractionalizationError::InvalidPrice);
+
+			psp22::InternalImpl::_mint_to(self, curator, shares)
+				.map_err(FractionalizationError::SharesError)?;
+
+			self.vault = Some(Vault {
+				region_id,
+				curator,
+				total_shares: shares,
+				buyout_price,
+				status: VaultStatus::Locked,
+				proceeds: 0,
+			});
+
+			Ok(())
+		}
+
+		// Burns the shares of the buyer and marks the region as bought out, in case the payment
+		// covers the buyout cost.
+		//
+		// Returns the identifier of the region together with the buyout cost.
+		pub(crate) fn take_buyout(
+			&mut self,
+			buyer: AccountId,
+			payment: Balance,
+		) -> Result<(RawRegionId, Balance), FractionalizationError> {
+			let cost = self.buyout_cost(buyer)?;
+			ensure!(payment >= cost, FractionalizationError::InsufficientPayment);
+
+			let held_shares = self.shares_of(buyer);
+			if held_shares > 0 {
+				psp22::InternalImpl::_burn_from(self, buyer, held_shares)
+					.map_err(FractionalizationError::SharesError)?;
+			}
+
+			let vault = self.vault.as_mut().ok_or(FractionalizationError::NotFractionalized)?;
+			vault.status = VaultStatus::BoughtOut;
+			vault.proceeds = cost;
+
+			Ok((vault.region_id, cost))
+		}
+
+		// Burns the redeemed shares and deducts their part of the buyout payment from the
+		// proceeds.
+		//
+		// Returns the identifier of the region together with the payout.
+		pub(crate) fn redeem_shares(
+			&mut self,
+			holder: AccountId,
+			shares: Balance,
+		) -> Result<(RawRegionId, Balance), FractionalizationError> {
+			ensure!(shares > 0, FractionalizationError::InvalidAmount);
+			let total_supply = psp22::PSP22Impl::total_supply(self);
+			let vault = self.vault.as_ref().ok_or(FractionalizationError::NotFractionalized)?;
+			ensure!(vault.status == VaultStatus::BoughtOut, FractionalizationError::NotBoughtOut);
+
+			let payout = multiply_by_rational_with_rounding(
+				vault.proceeds,
+				shares,
+				total_supply,
+				Rounding::Down,
+			)
+			.ok_or(FractionalizationError::ArithmeticError)?;
+
+			psp22::InternalImpl::_burn_from(self, holder, shares)
+				.map_err(FractionalizationError::SharesError)?;
+
+			let vault = self.vault.as_mut().ok_or(FractionalizationError::NotFractionalized)?;
+			vault.proceeds = vault.proceeds.saturating_sub(payout);
+
+			Ok((vault.region_id, payout))
+		}
+
+		fn shares_of(&self, account: AccountId) -> Balance {
+			psp22::PSP22Impl::balance_of(self, account)
+		}
+	}
+}
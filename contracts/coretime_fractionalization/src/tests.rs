@@ -0,0 +1,161 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_fractionalization::CoretimeFractionalization,
+	types::{FractionalizationError, Vault, VaultStatus},
+};
+use ink::env::{
+	test::{default_accounts, DefaultAccounts},
+	DefaultEnvironment,
+};
+use openbrush::contracts::psp22::{PSP22Error, PSP22};
+use primitives::assert_ok;
+
+const SHARES: u128 = 100;
+const BUYOUT_PRICE: u128 = 1_000;
+
+#[ink::test]
+fn lock_region_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, .. } = get_default_accounts();
+	let mut fractionalization = new_fractionalization();
+
+	assert_eq!(
+		fractionalization.buyout_cost(alice),
+		Err(FractionalizationError::NotFractionalized)
+	);
+
+	assert_eq!(
+		fractionalization.lock_region(alice, 0, 0, BUYOUT_PRICE),
+		Err(FractionalizationError::InvalidShares)
+	);
+	assert_eq!(
+		fractionalization.lock_region(alice, 0, SHARES, 0),
+		Err(FractionalizationError::InvalidPrice)
+	);
+
+	assert_ok!(fractionalization.lock_region(alice, 0, SHARES, BUYOUT_PRICE));
+	assert_eq!(
+		fractionalization.vault(),
+		Some(Vault {
+			region_id: 0,
+			curator: alice,
+			total_shares: SHARES,
+			buyout_price: BUYOUT_PRICE,
+			status: VaultStatus::Locked,
+			proceeds: 0,
+		})
+	);
+	assert_eq!(fractionalization.balance_of(alice), SHARES);
+	assert_eq!(fractionalization.total_supply(), SHARES);
+
+	// Only a single region can be fractionalized:
+	assert_eq!(
+		fractionalization.lock_region(alice, 1, SHARES, BUYOUT_PRICE),
+		Err(FractionalizationError::AlreadyFractionalized)
+	);
+}
+
+#[ink::test]
+fn buyout_cost_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	let mut fractionalization = new_fractionalization();
+	assert_ok!(fractionalization.lock_region(alice, 0, 3, BUYOUT_PRICE));
+
+	// Holding all the shares allows unlocking the region for free:
+	assert_eq!(fractionalization.buyout_cost(alice), Ok(0));
+	assert_eq!(fractionalization.buyout_cost(charlie), Ok(BUYOUT_PRICE));
+
+	// The cost of the missing shares is rounded up:
+	assert_ok!(fractionalization.transfer(bob, 1, Default::default()));
+	assert_eq!(fractionalization.buyout_cost(bob), Ok(667));
+	assert_eq!(fractionalization.buyout_cost(alice), Ok(334));
+}
+
+#[ink::test]
+fn buyout_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, .. } = get_default_accounts();
+	let mut fractionalization = new_fractionalization();
+
+	assert_eq!(
+		fractionalization.take_buyout(bob, 0),
+		Err(FractionalizationError::NotFractionalized)
+	);
+
+	assert_ok!(fractionalization.lock_region(alice, 0, SHARES, BUYOUT_PRICE));
+	assert_ok!(fractionalization.transfer(bob, 30, Default::default()));
+
+	// Bob has to pay for the 70 shares he doesn't hold:
+	assert_eq!(
+		fractionalization.take_buyout(bob, 699),
+		Err(FractionalizationError::InsufficientPayment)
+	);
+	assert_eq!(fractionalization.take_buyout(bob, 800), Ok((0, 700)));
+
+	// The shares of the buyer are burned:
+	assert_eq!(fractionalization.balance_of(bob), 0);
+	assert_eq!(fractionalization.total_supply(), 70);
+	assert_eq!(
+		fractionalization.vault().map(|vault| (vault.status, vault.proceeds)),
+		Some((VaultStatus::BoughtOut, 700))
+	);
+
+	// The region can only be bought out once:
+	assert_eq!(
+		fractionalization.take_buyout(alice, BUYOUT_PRICE),
+		Err(FractionalizationError::AlreadyBoughtOut)
+	);
+}
+
+#[ink::test]
+fn redeem_shares_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, .. } = get_default_accounts();
+	let mut fractionalization = new_fractionalization();
+
+	assert_ok!(fractionalization.lock_region(alice, 0, SHARES, BUYOUT_PRICE));
+	assert_ok!(fractionalization.transfer(bob, 30, Default::default()));
+
+	// Shares can only be redeemed once the region is bought out:
+	assert_eq!(
+		fractionalization.redeem_shares(alice, 10),
+		Err(FractionalizationError::NotBoughtOut)
+	);
+
+	assert_ok!(fractionalization.take_buyout(bob, 700));
+
+	assert_eq!(
+		fractionalization.redeem_shares(alice, 0),
+		Err(FractionalizationError::InvalidAmount)
+	);
+	assert_eq!(
+		fractionalization.redeem_shares(bob, 10),
+		Err(FractionalizationError::SharesError(PSP22Error::InsufficientBalance))
+	);
+
+	// The shareholders receive their part of the buyout payment:
+	assert_eq!(fractionalization.redeem_shares(alice, 20), Ok((0, 200)));
+	assert_eq!(fractionalization.redeem_shares(alice, 50), Ok((0, 500)));
+	assert_eq!(fractionalization.total_supply(), 0);
+	assert_eq!(fractionalization.vault().map(|vault| vault.proceeds), Some(0));
+}
+
+fn new_fractionalization() -> CoretimeFractionalization {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	CoretimeFractionalization::new(charlie, None, None, 0)
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
@@ -0,0 +1,120 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use openbrush::{
+	contracts::traits::{errors::ReentrancyGuardError, psp22::PSP22Error, psp34::PSP34Error},
+	traits::AccountId,
+};
+use primitives::{coretime::RawRegionId, Balance};
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Config {
+	/// The `AccountId` of the xcRegions contract.
+	pub xc_regions_contract: AccountId,
+}
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum VaultStatus {
+	/// The region is locked in the contract and co-owned by the shareholders.
+	Locked,
+	/// The region was bought out and the shareholders can redeem their shares for the proceeds.
+	BoughtOut,
+}
+
+/// The region locked in the contract.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Vault {
+	/// The identifier of the fractionalized region.
+	pub region_id: RawRegionId,
+	/// The account that fractionalized the region.
+	pub curator: AccountId,
+	/// The number of shares minted upon fractionalizing the region.
+	pub total_shares: Balance,
+	/// The price for buying all the shares of the region.
+	pub buyout_price: Balance,
+	/// The status of the vault.
+	pub status: VaultStatus,
+	/// The buyout payment which wasn't redeemed by the shareholders yet.
+	pub proceeds: Balance,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum FractionalizationError {
+	/// An arithmetic error occured.
+	ArithmeticError,
+	/// The provided identifier is not a valid region id.
+	InvalidRegionId,
+	/// The number of shares must be greater than zero.
+	InvalidShares,
+	/// The buyout price must be greater than zero.
+	InvalidPrice,
+	/// The amount must be greater than zero.
+	InvalidAmount,
+	/// The contract already holds a fractionalized region.
+	AlreadyFractionalized,
+	/// The contract doesn't hold a fractionalized region.
+	NotFractionalized,
+	/// The region was already bought out.
+	AlreadyBoughtOut,
+	/// The region wasn't bought out yet.
+	NotBoughtOut,
+	/// The transferred value doesn't cover the buyout cost.
+	InsufficientPayment,
+	/// The contract didn't receive the region.
+	RegionNotReceived,
+	/// Failed to transfer the native tokens.
+	TransferFailed,
+	/// The call would result in reentering the contract.
+	ReentrantCall,
+	/// An error occured when minting or burning the shares.
+	SharesError(PSP22Error),
+	/// An error occured when transferring the xcRegion.
+	XcRegionsPsp34Error(PSP34Error),
+	/// The caller is not allowed to perform the operation.
+	NotAllowed,
+}
+
+impl core::fmt::Display for FractionalizationError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			FractionalizationError::ArithmeticError => write!(f, "ArithmeticError"),
+			FractionalizationError::InvalidRegionId => write!(f, "InvalidRegionId"),
+			FractionalizationError::InvalidShares => write!(f, "InvalidShares"),
+			FractionalizationError::InvalidPrice => write!(f, "InvalidPrice"),
+			FractionalizationError::InvalidAmount => write!(f, "InvalidAmount"),
+			FractionalizationError::AlreadyFractionalized => write!(f, "AlreadyFractionalized"),
+			FractionalizationError::NotFractionalized => write!(f, "NotFractionalized"),
+			FractionalizationError::AlreadyBoughtOut => write!(f, "AlreadyBoughtOut"),
+			FractionalizationError::NotBoughtOut => write!(f, "NotBoughtOut"),
+			FractionalizationError::InsufficientPayment => write!(f, "InsufficientPayment"),
+			FractionalizationError::RegionNotReceived => write!(f, "RegionNotReceived"),
+			FractionalizationError::TransferFailed => write!(f, "TransferFailed"),
+			FractionalizationError::ReentrantCall => write!(f, "ReentrantCall"),
+			FractionalizationError::SharesError(e) => write!(f, "{:?}", e),
+			FractionalizationError::XcRegionsPsp34Error(e) => write!(f, "{:?}", e),
+			FractionalizationError::NotAllowed => write!(f, "NotAllowed"),
+		}
+	}
+}
+
+impl From<ReentrancyGuardError> for FractionalizationError {
+	fn from(_: ReentrancyGuardError) -> Self {
+		FractionalizationError::ReentrantCall
+	}
+}
@@ -0,0 +1,332 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime gifts
+//!
+//! This contract allows gifting xcRegions to a recipient who claims the region at their own
+//! convenience.
+//!
+//! A gift is either addressed to a specific account, or to a claim code. A claim code is an ECDSA
+//! key pair whose private key is shared with the recipient off-chain, while only the hash of its
+//! public key is stored in the contract. To claim the gift, the recipient signs the claim message
+//! binding the gift to the claiming account, which prevents others from front-running the claim.
+//!
+//! Every gift has a deadline from which the sender can take back the region in case it wasn't
+//! claimed. Until then, the gift can only be claimed by its recipient.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+mod types;
+
+#[openbrush::contract(env = environment::ExtendedEnvironment)]
+pub mod coretime_gifts {
+	use crate::types::{Config, Gift, GiftError, GiftRecipient};
+	use block_number_extension::BlockNumberProviderExtension;
+	use ink::{
+		codegen::{EmitEvent, Env},
+		env::hash::{Blake2x256, CryptoHash, HashOutput},
+		prelude::vec::Vec,
+	};
+	use openbrush::{
+		contracts::traits::psp34::{Id, PSP34Ref},
+		storage::Mapping,
+		traits::Storage,
+	};
+	use primitives::{
+		coretime::{RawRegionId, Timeslice},
+		ensure,
+	};
+	use sp_arithmetic::traits::SaturatedConversion;
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimeGifts {
+		/// The configuration of the gifts contract.
+		pub config: Config,
+		/// A mapping that holds the gift of each gifted region.
+		pub gifts: Mapping<RawRegionId, Gift>,
+	}
+
+	#[ink(event)]
+	pub struct GiftSent {
+		/// The identifier of the gifted region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that gifted the region.
+		#[ink(topic)]
+		pub(crate) sender: AccountId,
+		/// The recipient the gift is addressed to.
+		pub(crate) recipient: GiftRecipient,
+		/// The timeslice from which the sender can take back the gift.
+		pub(crate) deadline: Timeslice,
+	}
+
+	#[ink(event)]
+	pub struct GiftClaimed {
+		/// The identifier of the gifted region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that claimed the region.
+		#[ink(topic)]
+		pub(crate) recipient: AccountId,
+	}
+
+	#[ink(event)]
+	pub struct GiftRefunded {
+		/// The identifier of the gifted region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that gifted the region.
+		#[ink(topic)]
+		pub(crate) sender: AccountId,
+	}
+
+	impl CoretimeGifts {
+		/// ## Arguments:
+		/// - `xc_regions_contract` - The `AccountId` of the xcRegions contract.
+		/// - `timeslice_period` - The number of relay chain blocks in a timeslice.
+		#[ink(constructor)]
+		pub fn new(xc_regions_contract: AccountId, timeslice_period: BlockNumber) -> Self {
+			assert!(timeslice_period > 0, "Timeslice period must be greater than zero");
+
+			Self {
+				config: Config { xc_regions_contract, timeslice_period },
+				gifts: Default::default(),
+			}
+		}
+
+		#[ink(message)]
+		pub fn config(&self) -> Config {
+			self.config.clone()
+		}
+
+		#[ink(message)]
+		pub fn gift(&self, id: Id) -> Option<Gift> {
+			let Id::U128(region_id) = id else { return None };
+			self.gifts.get(region_id)
+		}
+
+		/// Returns the message that has to be signed with the claim code for claiming a gift.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the gifted region.
+		/// - `claimer`: The account claiming the gift.
+		#[ink(message)]
+		pub fn claim_message(&self, id: Id, claimer: AccountId) -> Result<[u8; 32], GiftError> {
+			let Id::U128(region_id) = id else { return Err(GiftError::InvalidRegionId) };
+
+			let mut message = <Blake2x256 as HashOutput>::Type::default();
+			ink::env::hash_encoded::<Blake2x256, _>(
+				&(self.env().account_id(), region_id, claimer),
+				&mut message,
+			);
+			Ok(message)
+		}
+
+		/// A function for gifting an xcRegion.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region. The caller must own the
+		///   region and approve it to the contract beforehand.
+		/// - `recipient`: The recipient the gift is addressed to.
+		/// - `deadline`: The timeslice from which the caller can take back the gift if it wasn't
+		///   claimed.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `GiftSent` event.
+		#[ink(message)]
+		pub fn send(
+			&mut self,
+			id: Id,
+			recipient: GiftRecipient,
+			deadline: Timeslice,
+		) -> Result<(), GiftError> {
+			let Id::U128(region_id) = id else { return Err(GiftError::InvalidRegionId) };
+			let caller = self.env().caller();
+			let contract = self.env().account_id();
+
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id.clone()) == Some(caller),
+				GiftError::NotOwner
+			);
+
+			self.record_gift(caller, region_id, recipient.clone(), deadline)?;
+
+			PSP34Ref::transfer(&self.config.xc_regions_contract, contract, id.clone(), Vec::new())
+				.map_err(GiftError::XcRegionsPsp34Error)?;
+			// Don't rely on the transfer succeeding, but ensure that the contract actually received
+			// the region.
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id) == Some(contract),
+				GiftError::RegionNotReceived
+			);
+
+			self.env()
+				.emit_event(GiftSent { region_id, sender: caller, recipient, deadline });
+			Ok(())
+		}
+
+		/// A function for claiming a gift addressed to the caller.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the gifted region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `GiftClaimed` event.
+		#[ink(message)]
+		pub fn claim(&mut self, id: Id) -> Result<(), GiftError> {
+			let Id::U128(region_id) = id else { return Err(GiftError::InvalidRegionId) };
+			let caller = self.env().caller();
+
+			self.take_gift(region_id, caller, None)?;
+			self.transfer_region(region_id, caller)?;
+
+			self.env().emit_event(GiftClaimed { region_id, recipient: caller });
+			Ok(())
+		}
+
+		/// A function for claiming a gift addressed to a claim code.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the gifted region.
+		/// - `signature`: The signature of the message returned by `claim_message` for the caller,
+		///   created with the claim code.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `GiftClaimed` event.
+		#[ink(message)]
+		pub fn claim_with_code(&mut self, id: Id, signature: [u8; 65]) -> Result<(), GiftError> {
+			let caller = self.env().caller();
+			let message = self.claim_message(id.clone(), caller)?;
+			let Id::U128(region_id) = id else { return Err(GiftError::InvalidRegionId) };
+
+			let claim_key = self
+				.env()
+				.ecdsa_recover(&signature, &message)
+				.map_err(|_| GiftError::InvalidSignature)?;
+
+			self.take_gift(region_id, caller, Some(claim_key))?;
+			self.transfer_region(region_id, caller)?;
+
+			self.env().emit_event(GiftClaimed { region_id, recipient: caller });
+			Ok(())
+		}
+
+		/// A function for taking back a gift that wasn't claimed until its deadline.
+		///
+		/// Only callable by the sender of the gift.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the gifted region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `GiftRefunded` event.
+		#[ink(message)]
+		pub fn refund(&mut self, id: Id) -> Result<(), GiftError> {
+			let Id::U128(region_id) = id else { return Err(GiftError::InvalidRegionId) };
+			let caller = self.env().caller();
+
+			self.take_expired_gift(region_id, caller)?;
+			self.transfer_region(region_id, caller)?;
+
+			self.env().emit_event(GiftRefunded { region_id, sender: caller });
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimeGifts {
+		pub(crate) fn record_gift(
+			&mut self,
+			sender: AccountId,
+			region_id: RawRegionId,
+			recipient: GiftRecipient,
+			deadline: Timeslice,
+		) -> Result<(), GiftError> {
+			ensure!(!self.gifts.contains(region_id), GiftError::AlreadyGifted);
+			ensure!(deadline > self.current_timeslice(), GiftError::InvalidDeadline);
+
+			self.gifts.insert(region_id, &Gift { sender, recipient, deadline });
+			Ok(())
+		}
+
+		// Removes the gift in case it is addressed to the claimer, or to the claim code whose
+		// public key is provided.
+		pub(crate) fn take_gift(
+			&mut self,
+			region_id: RawRegionId,
+			claimer: AccountId,
+			claim_key: Option<[u8; 33]>,
+		) -> Result<Gift, GiftError> {
+			let gift = self.gifts.get(region_id).ok_or(GiftError::GiftNotFound)?;
+
+			match (&gift.recipient, claim_key) {
+				(GiftRecipient::Account(recipient), None) =>
+					ensure!(*recipient == claimer, GiftError::NotRecipient),
+				(GiftRecipient::ClaimCode(code_hash), Some(claim_key)) => {
+					let mut key_hash = <Blake2x256 as HashOutput>::Type::default();
+					Blake2x256::hash(&claim_key, &mut key_hash);
+					ensure!(key_hash == *code_hash, GiftError::InvalidSignature);
+				},
+				_ => return Err(GiftError::NotRecipient),
+			}
+
+			self.gifts.remove(region_id);
+			Ok(gift)
+		}
+
+		// Removes the gift in case the sender is taking it back after its deadline.
+		pub(crate) fn take_expired_gift(
+			&mut self,
+			region_id: RawRegionId,
+			sender: AccountId,
+		) -> Result<Gift, GiftError> {
+			let gift = self.gifts.get(region_id).ok_or(GiftError::GiftNotFound)?;
+			ensure!(gift.sender == sender, GiftError::NotSender);
+			ensure!(self.current_timeslice() >= gift.deadline, GiftError::DeadlineNotReached);
+
+			self.gifts.remove(region_id);
+			Ok(gift)
+		}
+
+		fn transfer_region(&self, region_id: RawRegionId, to: AccountId) -> Result<(), GiftError> {
+			PSP34Ref::transfer(
+				&self.config.xc_regions_contract,
+				to,
+				Id::U128(region_id),
+				Vec::new(),
+			)
+			.map_err(GiftError::XcRegionsPsp34Error)
+		}
+
+		pub(crate) fn current_timeslice(&self) -> Timeslice {
+			(self.relay_block_number() / self.config.timeslice_period).saturated_into()
+		}
+
+		#[cfg(not(test))]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().extension().relay_chain_block_number().unwrap_or_default()
+		}
+
+		#[cfg(test)]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().block_number()
+		}
+	}
+}
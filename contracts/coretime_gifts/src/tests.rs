@@ -0,0 +1,125 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_gifts::CoretimeGifts,
+	types::{Gift, GiftError, GiftRecipient},
+};
+use ink::env::{
+	hash::{Blake2x256, CryptoHash, HashOutput},
+	test::{advance_block, default_accounts, DefaultAccounts},
+	DefaultEnvironment,
+};
+use openbrush::contracts::traits::psp34::Id;
+use primitives::assert_ok;
+
+const TIMESLICE_PERIOD: u32 = 80;
+const CLAIM_KEY: [u8; 33] = [1; 33];
+
+#[ink::test]
+fn record_gift_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut gifts = new_gifts();
+
+	// The deadline must be in the future:
+	assert_eq!(
+		gifts.record_gift(bob, 0, GiftRecipient::Account(charlie), 0),
+		Err(GiftError::InvalidDeadline)
+	);
+
+	assert_ok!(gifts.record_gift(bob, 0, GiftRecipient::Account(charlie), 1));
+	assert_eq!(
+		gifts.gift(Id::U128(0)),
+		Some(Gift { sender: bob, recipient: GiftRecipient::Account(charlie), deadline: 1 })
+	);
+
+	assert_eq!(
+		gifts.record_gift(bob, 0, GiftRecipient::Account(charlie), 1),
+		Err(GiftError::AlreadyGifted)
+	);
+}
+
+#[ink::test]
+fn take_gift_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, django, .. } = get_default_accounts();
+	let mut gifts = new_gifts();
+
+	assert_eq!(gifts.take_gift(0, charlie, None), Err(GiftError::GiftNotFound));
+	assert_ok!(gifts.record_gift(bob, 0, GiftRecipient::Account(charlie), 1));
+
+	assert_eq!(gifts.take_gift(0, django, None), Err(GiftError::NotRecipient));
+	// Gifts addressed to an account can't be claimed with a claim code:
+	assert_eq!(gifts.take_gift(0, charlie, Some(CLAIM_KEY)), Err(GiftError::NotRecipient));
+
+	assert_eq!(
+		gifts.take_gift(0, charlie, None),
+		Ok(Gift { sender: bob, recipient: GiftRecipient::Account(charlie), deadline: 1 })
+	);
+	assert_eq!(gifts.gift(Id::U128(0)), None);
+}
+
+#[ink::test]
+fn take_gift_with_claim_code_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, django, .. } = get_default_accounts();
+	let mut gifts = new_gifts();
+
+	assert_ok!(gifts.record_gift(bob, 0, GiftRecipient::ClaimCode(key_hash(CLAIM_KEY)), 1));
+
+	assert_eq!(gifts.take_gift(0, django, None), Err(GiftError::NotRecipient));
+	assert_eq!(gifts.take_gift(0, django, Some([2; 33])), Err(GiftError::InvalidSignature));
+
+	// Any account holding the claim code can claim the gift:
+	assert_ok!(gifts.take_gift(0, django, Some(CLAIM_KEY)));
+	assert_eq!(gifts.gift(Id::U128(0)), None);
+}
+
+#[ink::test]
+fn take_expired_gift_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut gifts = new_gifts();
+
+	assert_eq!(gifts.take_expired_gift(0, bob), Err(GiftError::GiftNotFound));
+	assert_ok!(gifts.record_gift(bob, 0, GiftRecipient::Account(charlie), 2));
+
+	assert_eq!(gifts.take_expired_gift(0, charlie), Err(GiftError::NotSender));
+	assert_eq!(gifts.take_expired_gift(0, bob), Err(GiftError::DeadlineNotReached));
+
+	advance_n_blocks(TIMESLICE_PERIOD * 2);
+	// Once the deadline is reached the sender can take back the gift:
+	assert_ok!(gifts.take_expired_gift(0, bob));
+	assert_eq!(gifts.gift(Id::U128(0)), None);
+	assert_eq!(gifts.take_gift(0, charlie, None), Err(GiftError::GiftNotFound));
+}
+
+fn new_gifts() -> CoretimeGifts {
+	let DefaultAccounts::<DefaultEnvironment> { alice, .. } = get_default_accounts();
+	CoretimeGifts::new(alice, TIMESLICE_PERIOD)
+}
+
+fn key_hash(key: [u8; 33]) -> [u8; 32] {
+	let mut hash = <Blake2x256 as HashOutput>::Type::default();
+	Blake2x256::hash(&key, &mut hash);
+	hash
+}
+
+fn advance_n_blocks(n: u32) {
+	for _ in 0..n {
+		advance_block::<DefaultEnvironment>();
+	}
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
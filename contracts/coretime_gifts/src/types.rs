@@ -0,0 +1,97 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use openbrush::{
+	contracts::traits::psp34::PSP34Error,
+	traits::{AccountId, BlockNumber},
+};
+use primitives::coretime::Timeslice;
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Config {
+	/// The `AccountId` of the xcRegions contract.
+	pub xc_regions_contract: AccountId,
+	/// The number of relay chain blocks in a timeslice.
+	pub timeslice_period: BlockNumber,
+}
+
+/// The recipient a gift is addressed to.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum GiftRecipient {
+	/// The gift can only be claimed by the specified account.
+	Account(AccountId),
+	/// The gift can be claimed by any account holding the claim code, i.e. the private key of the
+	/// ECDSA public key whose Blake2x256 hash is specified.
+	ClaimCode([u8; 32]),
+}
+
+/// A region gifted by a sender.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Gift {
+	/// The account that gifted the region.
+	pub sender: AccountId,
+	/// The recipient the gift is addressed to.
+	pub recipient: GiftRecipient,
+	/// The timeslice from which the sender can take back the gift if it wasn't claimed.
+	pub deadline: Timeslice,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum GiftError {
+	/// The provided identifier is not a valid region id.
+	InvalidRegionId,
+	/// The region was already gifted.
+	AlreadyGifted,
+	/// The region isn't gifted.
+	GiftNotFound,
+	/// The deadline must be in the future.
+	InvalidDeadline,
+	/// The caller is not the recipient of the gift.
+	NotRecipient,
+	/// The signature doesn't match the claim code of the gift.
+	InvalidSignature,
+	/// The caller is not the sender of the gift.
+	NotSender,
+	/// The deadline of the gift wasn't reached yet.
+	DeadlineNotReached,
+	/// The contract didn't receive the region.
+	RegionNotReceived,
+	/// An error occured when transferring the xcRegion.
+	XcRegionsPsp34Error(PSP34Error),
+	/// The caller is not the owner of the region.
+	NotOwner,
+}
+
+impl core::fmt::Display for GiftError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			GiftError::InvalidRegionId => write!(f, "InvalidRegionId"),
+			GiftError::AlreadyGifted => write!(f, "AlreadyGifted"),
+			GiftError::GiftNotFound => write!(f, "GiftNotFound"),
+			GiftError::InvalidDeadline => write!(f, "InvalidDeadline"),
+			GiftError::NotRecipient => write!(f, "NotRecipient"),
+			GiftError::InvalidSignature => write!(f, "InvalidSignature"),
+			GiftError::NotSender => write!(f, "NotSender"),
+			GiftError::DeadlineNotReached => write!(f, "DeadlineNotReached"),
+			GiftError::RegionNotReceived => write!(f, "RegionNotReceived"),
+			GiftError::XcRegionsPsp34Error(e) => write!(f, "{:?}", e),
+			GiftError::NotOwner => write!(f, "NotOwner"),
+		}
+	}
+}
@@ -0,0 +1,371 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime governance
+//!
+//! This contract holds the admin role of the Coretime market, so that the parameters of the
+//! market are governed by the holders of a PSP22 governance token rather than a single key.
+//!
+//! Anyone can propose an admin call to the market, such as updating the fees, the listing deposit
+//! or the pause status. During the voting period, token holders vote on the proposal by locking
+//! their tokens in the contract, with each token counting as a single vote. Once the voting period
+//! ends, a proposal with more votes in favor than against, and whose total votes reach the
+//! quorum, can be executed by anyone within the execution period. Voters can unlock their tokens
+//! once voting has ended.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+mod types;
+
+#[openbrush::contract(env = environment::ExtendedEnvironment)]
+pub mod coretime_governance {
+	use crate::types::{Config, GovernanceError, MarketCall, Proposal, ProposalId, Vote};
+	use coretime_market::coretime_market::CoretimeMarketRef;
+	use ink::{
+		codegen::{EmitEvent, Env},
+		env::call::FromAccountId,
+		prelude::vec::Vec,
+	};
+	use openbrush::{
+		contracts::{reentrancy_guard::*, traits::psp22::PSP22Ref},
+		modifiers,
+		storage::Mapping,
+		traits::Storage,
+	};
+	use primitives::ensure;
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimeGovernance {
+		#[storage_field]
+		guard: reentrancy_guard::Data,
+		/// The configuration of the governance contract.
+		pub config: Config,
+		/// A mapping that holds all the submitted proposals.
+		pub proposals: Mapping<ProposalId, Proposal>,
+		/// A mapping that holds the votes of each account on each proposal.
+		pub votes: Mapping<(ProposalId, AccountId), Vote>,
+		/// The identifier assigned to the next proposal.
+		pub next_proposal_id: ProposalId,
+	}
+
+	#[ink(event)]
+	pub struct ProposalSubmitted {
+		/// The identifier of the proposal.
+		#[ink(topic)]
+		pub(crate) proposal_id: ProposalId,
+		/// The account that submitted the proposal.
+		#[ink(topic)]
+		pub(crate) proposer: AccountId,
+		/// The proposed call to the market.
+		pub(crate) call: MarketCall,
+		/// The block number at which voting ends.
+		pub(crate) end: BlockNumber,
+	}
+
+	#[ink(event)]
+	pub struct VoteCast {
+		/// The identifier of the proposal.
+		#[ink(topic)]
+		pub(crate) proposal_id: ProposalId,
+		/// The account that voted.
+		#[ink(topic)]
+		pub(crate) voter: AccountId,
+		/// Whether the vote is in favor of the proposal.
+		pub(crate) approve: bool,
+		/// The amount of tokens locked for the vote.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct ProposalExecuted {
+		/// The identifier of the executed proposal.
+		#[ink(topic)]
+		pub(crate) proposal_id: ProposalId,
+	}
+
+	#[ink(event)]
+	pub struct TokensUnlocked {
+		/// The identifier of the proposal.
+		#[ink(topic)]
+		pub(crate) proposal_id: ProposalId,
+		/// The account that voted.
+		#[ink(topic)]
+		pub(crate) voter: AccountId,
+		/// The amount of unlocked tokens.
+		pub(crate) amount: Balance,
+	}
+
+	impl CoretimeGovernance {
+		/// ## Arguments:
+		/// - `market_contract` - The `AccountId` of the Coretime market. The admin role of the
+		///   market must be transferred to this contract afterwards.
+		/// - `governance_token` - The `AccountId` of the PSP22 token used for voting.
+		/// - `voting_period` - The number of blocks a proposal can be voted on.
+		/// - `execution_period` - The number of blocks after voting ends during which a passed
+		///   proposal can be executed.
+		/// - `quorum` - The minimum amount of tokens that have to vote on a proposal for it to
+		///   pass.
+		#[ink(constructor)]
+		pub fn new(
+			market_contract: AccountId,
+			governance_token: AccountId,
+			voting_period: BlockNumber,
+			execution_period: BlockNumber,
+			quorum: Balance,
+		) -> Self {
+			assert!(voting_period > 0, "Voting period must be greater than zero");
+			assert!(execution_period > 0, "Execution period must be greater than zero");
+
+			Self {
+				guard: Default::default(),
+				config: Config {
+					market_contract,
+					governance_token,
+					voting_period,
+					execution_period,
+					quorum,
+				},
+				proposals: Default::default(),
+				votes: Default::default(),
+				next_proposal_id: 0,
+			}
+		}
+
+		#[ink(message)]
+		pub fn config(&self) -> Config {
+			self.config.clone()
+		}
+
+		#[ink(message)]
+		pub fn proposal(&self, proposal_id: ProposalId) -> Option<Proposal> {
+			self.proposals.get(proposal_id)
+		}
+
+		#[ink(message)]
+		pub fn vote_of(&self, proposal_id: ProposalId, voter: AccountId) -> Option<Vote> {
+			self.votes.get((proposal_id, voter))
+		}
+
+		/// A function for proposing an admin call to the market.
+		///
+		/// ## Arguments:
+		/// - `call`: The call dispatched to the market if the proposal passes.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `ProposalSubmitted` event.
+		#[ink(message)]
+		pub fn propose(&mut self, call: MarketCall) -> Result<ProposalId, GovernanceError> {
+			let caller = self.env().caller();
+
+			let (proposal_id, end) = self.submit_proposal(caller, call.clone());
+
+			self.env()
+				.emit_event(ProposalSubmitted { proposal_id, proposer: caller, call, end });
+			Ok(proposal_id)
+		}
+
+		/// A function for voting on a proposal.
+		///
+		/// The tokens are locked in the contract until voting ends. Each account can only vote
+		/// once on every proposal.
+		///
+		/// ## Arguments:
+		/// - `proposal_id`: The identifier of the proposal.
+		/// - `approve`: Whether the vote is in favor of the proposal.
+		/// - `amount`: The amount of tokens to vote with. The caller must approve the tokens to the
+		///   contract beforehand.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `VoteCast` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn vote(
+			&mut self,
+			proposal_id: ProposalId,
+			approve: bool,
+			amount: Balance,
+		) -> Result<(), GovernanceError> {
+			let caller = self.env().caller();
+
+			self.record_vote(caller, proposal_id, approve, amount)?;
+
+			PSP22Ref::transfer_from(
+				&self.config.governance_token,
+				caller,
+				self.env().account_id(),
+				amount,
+				Vec::new(),
+			)
+			.map_err(GovernanceError::GovernanceTokenError)?;
+
+			self.env().emit_event(VoteCast { proposal_id, voter: caller, approve, amount });
+			Ok(())
+		}
+
+		/// A function for executing a proposal that passed.
+		///
+		/// Callable by anyone once voting has ended, until the execution period of the proposal
+		/// ends.
+		///
+		/// ## Arguments:
+		/// - `proposal_id`: The identifier of the proposal.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `ProposalExecuted` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn execute(&mut self, proposal_id: ProposalId) -> Result<(), GovernanceError> {
+			let call = self.approve_execution(proposal_id)?;
+
+			let mut market: CoretimeMarketRef =
+				FromAccountId::from_account_id(self.config.market_contract);
+			match call {
+				MarketCall::SetFeeRate(new_rate) => market.set_fee_rate(new_rate),
+				MarketCall::SetReferralRate(new_rate) => market.set_referral_rate(new_rate),
+				MarketCall::SetFeeRecipient(new_recipient) =>
+					market.set_fee_recipient(new_recipient),
+				MarketCall::SetListingDeposit(new_deposit) =>
+					market.set_listing_deposit(new_deposit),
+				MarketCall::SetListingLimits(new_limits) => market.set_listing_limits(new_limits),
+				MarketCall::SetPaused(paused) => market.set_paused(paused),
+				MarketCall::SetAdmin(new_admin) => market.set_admin(new_admin),
+				MarketCall::SetAuctionExtension(extension) =>
+					market.set_auction_extension(extension),
+				MarketCall::SetOracleConfig(new_config) => market.set_oracle_config(new_config),
+				MarketCall::SetSaleOracle(new_oracle) => market.set_sale_oracle(new_oracle),
+				MarketCall::SetReputationRegistry(new_registry) =>
+					market.set_reputation_registry(new_registry),
+				MarketCall::Upgrade(code_hash) => market.upgrade(code_hash),
+				MarketCall::Migrate => market.migrate(),
+			}
+			.map_err(GovernanceError::MarketError)?;
+
+			self.env().emit_event(ProposalExecuted { proposal_id });
+			Ok(())
+		}
+
+		/// A function for unlocking the tokens voted with once voting has ended.
+		///
+		/// ## Arguments:
+		/// - `proposal_id`: The identifier of the proposal.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `TokensUnlocked` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn unlock(&mut self, proposal_id: ProposalId) -> Result<(), GovernanceError> {
+			let caller = self.env().caller();
+
+			let amount = self.release_vote(caller, proposal_id)?;
+
+			PSP22Ref::transfer(&self.config.governance_token, caller, amount, Vec::new())
+				.map_err(GovernanceError::GovernanceTokenError)?;
+
+			self.env().emit_event(TokensUnlocked { proposal_id, voter: caller, amount });
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimeGovernance {
+		// Records a new proposal and returns its identifier together with the block number at
+		// which voting ends.
+		pub(crate) fn submit_proposal(
+			&mut self,
+			proposer: AccountId,
+			call: MarketCall,
+		) -> (ProposalId, BlockNumber) {
+			let proposal_id = self.next_proposal_id;
+			let end = self.env().block_number().saturating_add(self.config.voting_period);
+
+			self.proposals.insert(
+				proposal_id,
+				&Proposal { proposer, call, end, ayes: 0, nays: 0, executed: false },
+			);
+			self.next_proposal_id = self.next_proposal_id.saturating_add(1);
+
+			(proposal_id, end)
+		}
+
+		pub(crate) fn record_vote(
+			&mut self,
+			voter: AccountId,
+			proposal_id: ProposalId,
+			approve: bool,
+			amount: Balance,
+		) -> Result<(), GovernanceError> {
+			ensure!(amount > 0, GovernanceError::InvalidAmount);
+			let mut proposal =
+				self.proposals.get(proposal_id).ok_or(GovernanceError::ProposalNotFound)?;
+			ensure!(self.env().block_number() < proposal.end, GovernanceError::VotingEnded);
+			ensure!(!self.votes.contains((proposal_id, voter)), GovernanceError::AlreadyVoted);
+
+			if approve {
+				proposal.ayes = proposal.ayes.saturating_add(amount);
+			} else {
+				proposal.nays = proposal.nays.saturating_add(amount);
+			}
+
+			self.proposals.insert(proposal_id, &proposal);
+			self.votes.insert((proposal_id, voter), &Vote { approve, amount });
+
+			Ok(())
+		}
+
+		// Marks the proposal as executed, in case it passed and its execution period didn't end,
+		// and returns the call to dispatch.
+		pub(crate) fn approve_execution(
+			&mut self,
+			proposal_id: ProposalId,
+		) -> Result<MarketCall, GovernanceError> {
+			let mut proposal =
+				self.proposals.get(proposal_id).ok_or(GovernanceError::ProposalNotFound)?;
+			let now = self.env().block_number();
+			ensure!(now >= proposal.end, GovernanceError::VotingOngoing);
+			ensure!(
+				now < proposal.end.saturating_add(self.config.execution_period),
+				GovernanceError::ExecutionExpired
+			);
+			ensure!(!proposal.executed, GovernanceError::AlreadyExecuted);
+			ensure!(proposal.passed(self.config.quorum), GovernanceError::ProposalRejected);
+
+			proposal.executed = true;
+			self.proposals.insert(proposal_id, &proposal);
+
+			Ok(proposal.call)
+		}
+
+		// Removes the vote of the voter once voting has ended and returns the locked amount.
+		pub(crate) fn release_vote(
+			&mut self,
+			voter: AccountId,
+			proposal_id: ProposalId,
+		) -> Result<Balance, GovernanceError> {
+			let proposal =
+				self.proposals.get(proposal_id).ok_or(GovernanceError::ProposalNotFound)?;
+			ensure!(self.env().block_number() >= proposal.end, GovernanceError::VotingOngoing);
+
+			let vote = self.votes.get((proposal_id, voter)).ok_or(GovernanceError::NotVoted)?;
+			self.votes.remove((proposal_id, voter));
+
+			Ok(vote.amount)
+		}
+	}
+}
@@ -0,0 +1,150 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_governance::CoretimeGovernance,
+	types::{GovernanceError, MarketCall, Proposal, Vote},
+};
+use ink::env::{
+	test::{advance_block, default_accounts, DefaultAccounts},
+	DefaultEnvironment,
+};
+use primitives::assert_ok;
+
+const VOTING_PERIOD: u32 = 10;
+const EXECUTION_PERIOD: u32 = 5;
+const QUORUM: u128 = 1_000;
+
+#[ink::test]
+fn submit_proposal_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, .. } = get_default_accounts();
+	let mut governance = new_governance();
+
+	assert_eq!(governance.submit_proposal(bob, MarketCall::SetPaused(true)), (0, VOTING_PERIOD));
+	assert_eq!(
+		governance.proposal(0),
+		Some(Proposal {
+			proposer: bob,
+			call: MarketCall::SetPaused(true),
+			end: VOTING_PERIOD,
+			ayes: 0,
+			nays: 0,
+			executed: false,
+		})
+	);
+
+	advance_n_blocks(1);
+	assert_eq!(
+		governance.submit_proposal(bob, MarketCall::SetFeeRate(100)),
+		(1, VOTING_PERIOD + 1)
+	);
+}
+
+#[ink::test]
+fn record_vote_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, dave, .. } = get_default_accounts();
+	let mut governance = new_governance();
+
+	assert_eq!(governance.record_vote(bob, 0, true, 100), Err(GovernanceError::ProposalNotFound));
+	governance.submit_proposal(bob, MarketCall::SetPaused(true));
+
+	assert_eq!(governance.record_vote(bob, 0, true, 0), Err(GovernanceError::InvalidAmount));
+	assert_ok!(governance.record_vote(bob, 0, true, 700));
+	assert_eq!(governance.vote_of(0, bob), Some(Vote { approve: true, amount: 700 }));
+
+	// Each account can only vote once:
+	assert_eq!(governance.record_vote(bob, 0, false, 100), Err(GovernanceError::AlreadyVoted));
+
+	assert_ok!(governance.record_vote(charlie, 0, false, 300));
+	assert_eq!(
+		governance.proposal(0).map(|proposal| (proposal.ayes, proposal.nays)),
+		Some((700, 300))
+	);
+
+	// Votes are only accepted during the voting period:
+	advance_n_blocks(VOTING_PERIOD);
+	assert_eq!(governance.record_vote(dave, 0, true, 100), Err(GovernanceError::VotingEnded));
+}
+
+#[ink::test]
+fn approve_execution_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut governance = new_governance();
+
+	assert_eq!(governance.approve_execution(0), Err(GovernanceError::ProposalNotFound));
+
+	// Passing proposal:
+	governance.submit_proposal(bob, MarketCall::SetListingDeposit(50));
+	assert_ok!(governance.record_vote(bob, 0, true, 600));
+	assert_ok!(governance.record_vote(charlie, 0, false, 400));
+	// Proposal without a majority:
+	governance.submit_proposal(bob, MarketCall::SetPaused(true));
+	assert_ok!(governance.record_vote(bob, 1, true, 500));
+	assert_ok!(governance.record_vote(charlie, 1, false, 500));
+	// Proposal without a quorum:
+	governance.submit_proposal(bob, MarketCall::SetFeeRate(100));
+	assert_ok!(governance.record_vote(bob, 2, true, 999));
+
+	assert_eq!(governance.approve_execution(0), Err(GovernanceError::VotingOngoing));
+	advance_n_blocks(VOTING_PERIOD);
+
+	assert_eq!(governance.approve_execution(0), Ok(MarketCall::SetListingDeposit(50)));
+	assert_eq!(governance.approve_execution(0), Err(GovernanceError::AlreadyExecuted));
+	assert_eq!(governance.approve_execution(1), Err(GovernanceError::ProposalRejected));
+	assert_eq!(governance.approve_execution(2), Err(GovernanceError::ProposalRejected));
+
+	// Passed proposals can only be executed within the execution period:
+	governance.submit_proposal(bob, MarketCall::Migrate);
+	assert_ok!(governance.record_vote(bob, 3, true, 1_000));
+	advance_n_blocks(VOTING_PERIOD + EXECUTION_PERIOD);
+	assert_eq!(governance.approve_execution(3), Err(GovernanceError::ExecutionExpired));
+	assert_eq!(governance.proposal(3).map(|proposal| proposal.executed), Some(false));
+}
+
+#[ink::test]
+fn release_vote_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut governance = new_governance();
+
+	assert_eq!(governance.release_vote(bob, 0), Err(GovernanceError::ProposalNotFound));
+	governance.submit_proposal(bob, MarketCall::SetPaused(true));
+	assert_ok!(governance.record_vote(bob, 0, true, 700));
+
+	// The tokens are locked until voting ends:
+	assert_eq!(governance.release_vote(bob, 0), Err(GovernanceError::VotingOngoing));
+	advance_n_blocks(VOTING_PERIOD);
+
+	assert_eq!(governance.release_vote(charlie, 0), Err(GovernanceError::NotVoted));
+	assert_eq!(governance.release_vote(bob, 0), Ok(700));
+	assert_eq!(governance.release_vote(bob, 0), Err(GovernanceError::NotVoted));
+
+	// The vote still counts towards the proposal:
+	assert_eq!(governance.proposal(0).map(|proposal| proposal.ayes), Some(700));
+}
+
+fn new_governance() -> CoretimeGovernance {
+	let DefaultAccounts::<DefaultEnvironment> { alice, django, .. } = get_default_accounts();
+	CoretimeGovernance::new(alice, django, VOTING_PERIOD, EXECUTION_PERIOD, QUORUM)
+}
+
+fn advance_n_blocks(n: u32) {
+	for _ in 0..n {
+		advance_block::<DefaultEnvironment>();
+	}
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
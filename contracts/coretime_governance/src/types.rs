@@ -0,0 +1,160 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use coretime_market::types::{BasisPoints, ListingLimits, MarketError, OracleConfig};
+use openbrush::{
+	contracts::traits::{errors::ReentrancyGuardError, psp22::PSP22Error},
+	traits::{AccountId, BlockNumber, Hash},
+};
+use primitives::Balance;
+
+/// The type used for identifying proposals.
+pub type ProposalId = u32;
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Config {
+	/// The `AccountId` of the Coretime market governed by the contract.
+	pub market_contract: AccountId,
+	/// The `AccountId` of the PSP22 token used for voting.
+	pub governance_token: AccountId,
+	/// The number of blocks a proposal can be voted on.
+	pub voting_period: BlockNumber,
+	/// The number of blocks after voting ends during which a passed proposal can be executed.
+	pub execution_period: BlockNumber,
+	/// The minimum amount of tokens that have to vote on a proposal for it to pass.
+	pub quorum: Balance,
+}
+
+/// An admin call to the Coretime market that can be proposed.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum MarketCall {
+	/// Updates the fee rate of the market.
+	SetFeeRate(BasisPoints),
+	/// Updates the share of the market fee paid to referrers.
+	SetReferralRate(BasisPoints),
+	/// Updates the recipient of the market fees.
+	SetFeeRecipient(AccountId),
+	/// Updates the deposit required for listing a region on sale.
+	SetListingDeposit(Balance),
+	/// Updates the constraints imposed on new listings.
+	SetListingLimits(ListingLimits),
+	/// Pauses or unpauses the market.
+	SetPaused(bool),
+	/// Transfers the admin role of the market to a different account.
+	SetAdmin(AccountId),
+	/// Updates the number of blocks auctions are extended by when bid on close to their end.
+	SetAuctionExtension(BlockNumber),
+	/// Updates the configuration of the price oracle used for listings priced in a reference
+	/// currency.
+	SetOracleConfig(Option<OracleConfig>),
+	/// Updates the price oracle to which the completed sales are reported.
+	SetSaleOracle(Option<AccountId>),
+	/// Updates the registry to which the events concerning the sellers are reported.
+	SetReputationRegistry(Option<AccountId>),
+	/// Upgrades the code of the market to the code with the specified hash.
+	Upgrade(Hash),
+	/// Migrates the storage of the market to the layout expected by its current code.
+	Migrate,
+}
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Proposal {
+	/// The account that submitted the proposal.
+	pub proposer: AccountId,
+	/// The call dispatched to the market if the proposal passes.
+	pub call: MarketCall,
+	/// The block number at which voting ends.
+	pub end: BlockNumber,
+	/// The amount of tokens voting in favor of the proposal.
+	pub ayes: Balance,
+	/// The amount of tokens voting against the proposal.
+	pub nays: Balance,
+	/// Whether the proposal was executed.
+	pub executed: bool,
+}
+
+impl Proposal {
+	/// Returns whether the proposal has a majority of votes in favor and reached the quorum.
+	pub fn passed(&self, quorum: Balance) -> bool {
+		self.ayes > self.nays && self.ayes.saturating_add(self.nays) >= quorum
+	}
+}
+
+/// The vote of a single account on a proposal.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Vote {
+	/// Whether the vote is in favor of the proposal.
+	pub approve: bool,
+	/// The amount of tokens locked for the vote.
+	pub amount: Balance,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum GovernanceError {
+	/// The amount must be greater than zero.
+	InvalidAmount,
+	/// There is no proposal with the provided identifier.
+	ProposalNotFound,
+	/// The voting period of the proposal has ended.
+	VotingEnded,
+	/// The voting period of the proposal hasn't ended yet.
+	VotingOngoing,
+	/// The caller already voted on the proposal.
+	AlreadyVoted,
+	/// The caller didn't vote on the proposal.
+	NotVoted,
+	/// The proposal didn't pass.
+	ProposalRejected,
+	/// The proposal was already executed.
+	AlreadyExecuted,
+	/// The call would result in reentering the contract.
+	ReentrantCall,
+	/// An error occured when transferring the governance token.
+	GovernanceTokenError(PSP22Error),
+	/// An error occured when dispatching the call to the market.
+	MarketError(MarketError),
+	/// The execution period of the proposal has ended.
+	ExecutionExpired,
+}
+
+impl core::fmt::Display for GovernanceError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			GovernanceError::InvalidAmount => write!(f, "InvalidAmount"),
+			GovernanceError::ProposalNotFound => write!(f, "ProposalNotFound"),
+			GovernanceError::VotingEnded => write!(f, "VotingEnded"),
+			GovernanceError::VotingOngoing => write!(f, "VotingOngoing"),
+			GovernanceError::AlreadyVoted => write!(f, "AlreadyVoted"),
+			GovernanceError::NotVoted => write!(f, "NotVoted"),
+			GovernanceError::ProposalRejected => write!(f, "ProposalRejected"),
+			GovernanceError::AlreadyExecuted => write!(f, "AlreadyExecuted"),
+			GovernanceError::ReentrantCall => write!(f, "ReentrantCall"),
+			GovernanceError::GovernanceTokenError(e) => write!(f, "{:?}", e),
+			GovernanceError::MarketError(e) => write!(f, "{}", e),
+			GovernanceError::ExecutionExpired => write!(f, "ExecutionExpired"),
+		}
+	}
+}
+
+impl From<ReentrancyGuardError> for GovernanceError {
+	fn from(_: ReentrancyGuardError) -> Self {
+		GovernanceError::ReentrantCall
+	}
+}
@@ -0,0 +1,569 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime lending
+//!
+//! This contract allows region owners to borrow PSP22 tokens by locking their xcRegion as
+//! collateral.
+//!
+//! The collateral is valued at the `core_bit_price` set by the admin for a single core-bit over a
+//! single timeslice, multiplied by the active core-bits of the region and the timeslices remaining
+//! until it ends. This follows the same timeslice-based pricing model as the Coretime market, but
+//! unlike the prices on the market, it can't be influenced by the sellers. Since regions lose
+//! value with every timeslice that passes, borrowers can only take a debt of up to
+//! `max_loan_to_value` of the valuation of their region.
+//!
+//! Interest accrues on the debt every timeslice at the configured `interest_rate`. Once the debt
+//! exceeds the `liquidation_threshold` share of the valuation of the collateral, anyone can
+//! liquidate the loan by repaying the whole debt in exchange for the region. Regions that already
+//! ended are valued at zero.
+//!
+//! The tokens that are lent are provided by the admin of the contract, who can also withdraw the
+//! liquidity together with the repaid interest.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+mod types;
+
+#[openbrush::contract(env = environment::ExtendedEnvironment)]
+pub mod coretime_lending {
+	use crate::types::{BasisPoints, Config, LendingError, Loan, MAX_BASIS_POINTS};
+	use block_number_extension::BlockNumberProviderExtension;
+	use ink::{
+		codegen::{EmitEvent, Env},
+		prelude::vec::Vec,
+	};
+	use openbrush::{
+		contracts::{
+			reentrancy_guard::*,
+			traits::{
+				psp22::PSP22Ref,
+				psp34::{Id, PSP34Ref},
+			},
+		},
+		modifiers,
+		storage::Mapping,
+		traits::Storage,
+	};
+	use primitives::{
+		coretime::{RawRegionId, Region, Timeslice},
+		ensure,
+	};
+	use sp_arithmetic::{traits::SaturatedConversion, FixedPointNumber, FixedU128};
+	use xc_regions_traits::RegionMetadataRef;
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimeLending {
+		#[storage_field]
+		guard: reentrancy_guard::Data,
+		/// The configuration of the lending contract.
+		pub config: Config,
+		/// The account providing the liquidity that is lent.
+		pub admin: AccountId,
+		/// A mapping that holds the loan taken against each region used as collateral.
+		pub loans: Mapping<RawRegionId, Loan>,
+	}
+
+	#[ink(event)]
+	pub struct LiquidityProvided {
+		/// The amount of loan tokens provided.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct LiquidityWithdrawn {
+		/// The amount of loan tokens withdrawn.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct CoreBitPriceUpdated {
+		/// The new valuation of a single core-bit for a single timeslice.
+		pub(crate) new_price: Balance,
+	}
+
+	#[ink(event)]
+	pub struct LoanOpened {
+		/// The identifier of the region used as collateral.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that took the loan.
+		#[ink(topic)]
+		pub(crate) borrower: AccountId,
+		/// The borrowed amount.
+		pub(crate) amount: Balance,
+		/// The valuation of the region at the time the loan was taken.
+		pub(crate) valuation: Balance,
+	}
+
+	#[ink(event)]
+	pub struct LoanRepaid {
+		/// The identifier of the region used as collateral.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The repaid amount.
+		pub(crate) amount: Balance,
+		/// The debt remaining after the repayment.
+		pub(crate) remaining_debt: Balance,
+	}
+
+	#[ink(event)]
+	pub struct LoanClosed {
+		/// The identifier of the region that got returned to the borrower.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that took the loan.
+		#[ink(topic)]
+		pub(crate) borrower: AccountId,
+	}
+
+	#[ink(event)]
+	pub struct LoanLiquidated {
+		/// The identifier of the region used as collateral.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that repaid the debt and received the region.
+		#[ink(topic)]
+		pub(crate) liquidator: AccountId,
+		/// The debt repaid by the liquidator.
+		pub(crate) debt: Balance,
+		/// The valuation of the region at the time of the liquidation.
+		pub(crate) valuation: Balance,
+	}
+
+	impl CoretimeLending {
+		/// ## Arguments:
+		/// - `xc_regions_contract` - The `AccountId` of the xcRegions contract.
+		/// - `core_bit_price` - The valuation of a single core-bit for a single timeslice.
+		/// - `loan_token` - The `AccountId` of the PSP22 token that is lent.
+		/// - `interest_rate` - The interest accrued on the debt every timeslice, expressed in basis
+		///   points.
+		/// - `max_loan_to_value` - The maximum debt that can be taken against a region, relative to
+		///   its valuation.
+		/// - `liquidation_threshold` - The debt relative to the valuation of the collateral above
+		///   which the loan can be liquidated.
+		/// - `timeslice_period` - The number of relay chain blocks in a timeslice.
+		#[ink(constructor)]
+		pub fn new(
+			xc_regions_contract: AccountId,
+			core_bit_price: Balance,
+			loan_token: AccountId,
+			interest_rate: BasisPoints,
+			max_loan_to_value: BasisPoints,
+			liquidation_threshold: BasisPoints,
+			timeslice_period: BlockNumber,
+		) -> Self {
+			assert!(timeslice_period > 0, "Timeslice period must be greater than zero");
+			assert!(
+				max_loan_to_value <= liquidation_threshold,
+				"The maximum loan to value can't exceed the liquidation threshold"
+			);
+			assert!(
+				liquidation_threshold <= MAX_BASIS_POINTS,
+				"Liquidation threshold can't exceed 100%"
+			);
+
+			Self {
+				guard: Default::default(),
+				config: Config {
+					xc_regions_contract,
+					core_bit_price,
+					loan_token,
+					interest_rate,
+					max_loan_to_value,
+					liquidation_threshold,
+					timeslice_period,
+				},
+				admin: Self::env().caller(),
+				loans: Default::default(),
+			}
+		}
+
+		#[ink(message)]
+		pub fn config(&self) -> Config {
+			self.config.clone()
+		}
+
+		#[ink(message)]
+		pub fn admin(&self) -> AccountId {
+			self.admin
+		}
+
+		#[ink(message)]
+		pub fn loan(&self, id: Id) -> Option<Loan> {
+			let Id::U128(region_id) = id else { return None };
+			self.loans.get(region_id)
+		}
+
+		/// Returns the current debt of the loan taken against the region, including the interest
+		/// accrued until the current timeslice.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region used as collateral.
+		#[ink(message)]
+		pub fn debt(&self, id: Id) -> Result<Balance, LendingError> {
+			let Id::U128(region_id) = id else { return Err(LendingError::InvalidRegionId) };
+			let loan = self.loans.get(region_id).ok_or(LendingError::LoanNotFound)?;
+
+			Ok(self.accrue_interest(loan)?.debt)
+		}
+
+		/// Returns whether the loan taken against the region can be liquidated.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region used as collateral.
+		#[ink(message)]
+		pub fn is_liquidatable(&self, id: Id) -> Result<bool, LendingError> {
+			let Id::U128(region_id) = id else { return Err(LendingError::InvalidRegionId) };
+			let loan = self.loans.get(region_id).ok_or(LendingError::LoanNotFound)?;
+
+			let loan = self.accrue_interest(loan)?;
+			let valuation = self.valuation(region_id)?;
+			Ok(loan.debt > self.share(valuation, self.config.liquidation_threshold)?)
+		}
+
+		/// A function for providing the loan tokens that are lent.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `amount`: The amount of loan tokens to provide. The admin must approve the tokens to
+		///   the contract beforehand.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn provide_liquidity(&mut self, amount: Balance) -> Result<(), LendingError> {
+			ensure!(self.env().caller() == self.admin, LendingError::NotAllowed);
+			ensure!(amount > 0, LendingError::InvalidAmount);
+
+			PSP22Ref::transfer_from(
+				&self.config.loan_token,
+				self.admin,
+				self.env().account_id(),
+				amount,
+				Vec::new(),
+			)
+			.map_err(LendingError::LoanTokenError)?;
+
+			self.env().emit_event(LiquidityProvided { amount });
+			Ok(())
+		}
+
+		/// A function for withdrawing the loan tokens held by the contract, including the repaid
+		/// interest.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `amount`: The amount of loan tokens to withdraw.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn withdraw_liquidity(&mut self, amount: Balance) -> Result<(), LendingError> {
+			ensure!(self.env().caller() == self.admin, LendingError::NotAllowed);
+			ensure!(amount > 0, LendingError::InvalidAmount);
+
+			PSP22Ref::transfer(&self.config.loan_token, self.admin, amount, Vec::new())
+				.map_err(LendingError::LoanTokenError)?;
+
+			self.env().emit_event(LiquidityWithdrawn { amount });
+			Ok(())
+		}
+
+		/// A function for updating the valuation of a single core-bit for a single timeslice.
+		///
+		/// Only callable by the admin. The updated price applies to the valuation of existing
+		/// loans as well.
+		///
+		/// ## Arguments:
+		/// - `new_price`: The new valuation of a single core-bit for a single timeslice.
+		#[ink(message)]
+		pub fn set_core_bit_price(&mut self, new_price: Balance) -> Result<(), LendingError> {
+			ensure!(self.env().caller() == self.admin, LendingError::NotAllowed);
+
+			self.config.core_bit_price = new_price;
+
+			self.env().emit_event(CoreBitPriceUpdated { new_price });
+			Ok(())
+		}
+
+		/// A function for borrowing loan tokens against an xcRegion.
+		///
+		/// The xcRegion is held by the contract until the loan is repaid.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region used as collateral. The
+		///   caller must own the region and approve it to the contract beforehand.
+		/// - `amount`: The amount of loan tokens to borrow. Can't exceed `max_loan_to_value` of the
+		///   valuation of the region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `LoanOpened` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn borrow(&mut self, id: Id, amount: Balance) -> Result<(), LendingError> {
+			let Id::U128(region_id) = id else { return Err(LendingError::InvalidRegionId) };
+			let caller = self.env().caller();
+			let contract = self.env().account_id();
+
+			// The contract may be approved for regions of other accounts as well.
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id.clone()) == Some(caller),
+				LendingError::NotAllowed
+			);
+
+			let valuation = self.valuation(region_id)?;
+			self.open_loan(caller, region_id, amount, valuation)?;
+
+			PSP34Ref::transfer(&self.config.xc_regions_contract, contract, id.clone(), Vec::new())
+				.map_err(LendingError::XcRegionsPsp34Error)?;
+			// Don't rely on the transfer succeeding, but ensure that the contract actually received
+			// the region.
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id) == Some(contract),
+				LendingError::RegionNotReceived
+			);
+
+			PSP22Ref::transfer(&self.config.loan_token, caller, amount, Vec::new())
+				.map_err(LendingError::LoanTokenError)?;
+
+			self.env()
+				.emit_event(LoanOpened { region_id, borrower: caller, amount, valuation });
+			Ok(())
+		}
+
+		/// A function for repaying the debt of a loan.
+		///
+		/// Once the whole debt is repaid, the region is returned to the borrower. Anyone can repay
+		/// the debt on behalf of the borrower.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region used as collateral.
+		/// - `amount`: The amount of loan tokens to repay. Amounts exceeding the debt are capped to
+		///   the debt. The caller must approve the tokens to the contract beforehand.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `LoanRepaid` event, followed by the `LoanClosed`
+		/// event if the whole debt got repaid.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn repay(&mut self, id: Id, amount: Balance) -> Result<(), LendingError> {
+			let Id::U128(region_id) = id else { return Err(LendingError::InvalidRegionId) };
+			let caller = self.env().caller();
+
+			let (loan, repaid) = self.apply_repayment(region_id, amount)?;
+
+			PSP22Ref::transfer_from(
+				&self.config.loan_token,
+				caller,
+				self.env().account_id(),
+				repaid,
+				Vec::new(),
+			)
+			.map_err(LendingError::LoanTokenError)?;
+
+			self.env().emit_event(LoanRepaid {
+				region_id,
+				amount: repaid,
+				remaining_debt: loan.debt,
+			});
+
+			if loan.debt == 0 {
+				PSP34Ref::transfer(&self.config.xc_regions_contract, loan.borrower, id, Vec::new())
+					.map_err(LendingError::XcRegionsPsp34Error)?;
+
+				self.env().emit_event(LoanClosed { region_id, borrower: loan.borrower });
+			}
+
+			Ok(())
+		}
+
+		/// A function for liquidating a loan whose debt exceeds the liquidation threshold.
+		///
+		/// The caller repays the whole debt and receives the region in exchange.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region used as collateral.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `LoanLiquidated` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn liquidate(&mut self, id: Id) -> Result<(), LendingError> {
+			let Id::U128(region_id) = id else { return Err(LendingError::InvalidRegionId) };
+			let caller = self.env().caller();
+
+			let valuation = self.valuation(region_id)?;
+			let loan = self.take_liquidatable_loan(region_id, valuation)?;
+
+			PSP22Ref::transfer_from(
+				&self.config.loan_token,
+				caller,
+				self.env().account_id(),
+				loan.debt,
+				Vec::new(),
+			)
+			.map_err(LendingError::LoanTokenError)?;
+			PSP34Ref::transfer(&self.config.xc_regions_contract, caller, id, Vec::new())
+				.map_err(LendingError::XcRegionsPsp34Error)?;
+
+			self.env().emit_event(LoanLiquidated {
+				region_id,
+				liquidator: caller,
+				debt: loan.debt,
+				valuation,
+			});
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimeLending {
+		// Records a new loan taken against the region, ensuring it doesn't exceed the maximum
+		// loan to value ratio.
+		pub(crate) fn open_loan(
+			&mut self,
+			borrower: AccountId,
+			region_id: RawRegionId,
+			amount: Balance,
+			valuation: Balance,
+		) -> Result<(), LendingError> {
+			ensure!(amount > 0, LendingError::InvalidAmount);
+			ensure!(!self.loans.contains(region_id), LendingError::RegionAlreadyCollateralized);
+			ensure!(
+				amount <= self.share(valuation, self.config.max_loan_to_value)?,
+				LendingError::LoanTooLarge
+			);
+
+			let loan = Loan { borrower, debt: amount, accrued_at: self.current_timeslice() };
+			self.loans.insert(region_id, &loan);
+
+			Ok(())
+		}
+
+		// Reduces the debt of the loan by up to `amount`. The loan is removed once the whole
+		// debt is repaid.
+		//
+		// Returns the updated loan together with the repaid amount.
+		pub(crate) fn apply_repayment(
+			&mut self,
+			region_id: RawRegionId,
+			amount: Balance,
+		) -> Result<(Loan, Balance), LendingError> {
+			ensure!(amount > 0, LendingError::InvalidAmount);
+			let loan = self.loans.get(region_id).ok_or(LendingError::LoanNotFound)?;
+			let mut loan = self.accrue_interest(loan)?;
+
+			let repaid = amount.min(loan.debt);
+			loan.debt = loan.debt.saturating_sub(repaid);
+
+			if loan.debt == 0 {
+				self.loans.remove(region_id);
+			} else {
+				self.loans.insert(region_id, &loan);
+			}
+
+			Ok((loan, repaid))
+		}
+
+		// Removes the loan in case its debt exceeds the liquidation threshold and returns it with
+		// the interest accrued until now.
+		pub(crate) fn take_liquidatable_loan(
+			&mut self,
+			region_id: RawRegionId,
+			valuation: Balance,
+		) -> Result<Loan, LendingError> {
+			let loan = self.loans.get(region_id).ok_or(LendingError::LoanNotFound)?;
+			let loan = self.accrue_interest(loan)?;
+			ensure!(
+				loan.debt > self.share(valuation, self.config.liquidation_threshold)?,
+				LendingError::NotLiquidatable
+			);
+
+			self.loans.remove(region_id);
+			Ok(loan)
+		}
+
+		// Compounds the interest accrued on the debt since it was last accounted for.
+		pub(crate) fn accrue_interest(&self, mut loan: Loan) -> Result<Loan, LendingError> {
+			let current_timeslice = self.current_timeslice();
+			let elapsed_timeslices = current_timeslice.saturating_sub(loan.accrued_at);
+
+			let growth = FixedU128::checked_from_rational(
+				u32::from(MAX_BASIS_POINTS).saturating_add(self.config.interest_rate.into()),
+				MAX_BASIS_POINTS,
+			)
+			.ok_or(LendingError::ArithmeticError)?;
+
+			loan.debt =
+				growth.saturating_pow(elapsed_timeslices as usize).saturating_mul_int(loan.debt);
+			loan.accrued_at = loan.accrued_at.max(current_timeslice);
+
+			Ok(loan)
+		}
+
+		// Returns the valuation of the region based on the admin-set core-bit price.
+		fn valuation(&self, region_id: RawRegionId) -> Result<Balance, LendingError> {
+			let metadata = RegionMetadataRef::get_metadata(
+				&self.config.xc_regions_contract,
+				Id::U128(region_id),
+			)
+			.map_err(|_| LendingError::ValuationUnavailable)?;
+
+			Ok(self.calculate_valuation(metadata.region()))
+		}
+
+		// Values the remaining duration of the region at `core_bit_price` per active core-bit
+		// and timeslice. Regions that already ended have no value.
+		pub(crate) fn calculate_valuation(&self, region: &Region) -> Balance {
+			let remaining_timeslices =
+				region.end.saturating_sub(region.begin.max(self.current_timeslice()));
+
+			self.config
+				.core_bit_price
+				.saturating_mul(region.mask.count_ones().into())
+				.saturating_mul(remaining_timeslices.into())
+		}
+
+		// Returns the `ratio` share of `value`.
+		pub(crate) fn share(
+			&self,
+			value: Balance,
+			ratio: BasisPoints,
+		) -> Result<Balance, LendingError> {
+			let ratio = FixedU128::checked_from_rational(ratio, MAX_BASIS_POINTS)
+				.ok_or(LendingError::ArithmeticError)?;
+
+			Ok(ratio.saturating_mul_int(value))
+		}
+
+		pub(crate) fn current_timeslice(&self) -> Timeslice {
+			(self.relay_block_number() / self.config.timeslice_period).saturated_into()
+		}
+
+		#[cfg(not(test))]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().extension().relay_chain_block_number().unwrap_or_default()
+		}
+
+		#[cfg(test)]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().block_number()
+		}
+	}
+}
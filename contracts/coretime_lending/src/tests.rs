@@ -0,0 +1,191 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_lending::CoretimeLending,
+	types::{LendingError, Loan},
+};
+use ink::env::{
+	test::{advance_block, default_accounts, set_caller, DefaultAccounts},
+	DefaultEnvironment,
+};
+use openbrush::contracts::psp34::Id;
+use primitives::{
+	assert_ok,
+	coretime::{CoreMask, Region},
+};
+
+const TIMESLICE_PERIOD: u32 = 80;
+// 1% per timeslice.
+const INTEREST_RATE: u16 = 100;
+// 50% of the valuation.
+const MAX_LOAN_TO_VALUE: u16 = 5_000;
+// 80% of the valuation.
+const LIQUIDATION_THRESHOLD: u16 = 8_000;
+const CORE_BIT_PRICE: u128 = 10;
+
+#[ink::test]
+fn open_loan_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut lending = new_lending();
+
+	assert_eq!(lending.open_loan(charlie, 0, 0, 1_000), Err(LendingError::InvalidAmount));
+	// Cannot borrow more than half of the valuation:
+	assert_eq!(lending.open_loan(charlie, 0, 501, 1_000), Err(LendingError::LoanTooLarge));
+
+	assert_ok!(lending.open_loan(charlie, 0, 500, 1_000));
+	assert_eq!(lending.loans.get(0), Some(Loan { borrower: charlie, debt: 500, accrued_at: 0 }));
+
+	// A region can only be used as collateral for a single loan:
+	assert_eq!(
+		lending.open_loan(charlie, 0, 100, 1_000),
+		Err(LendingError::RegionAlreadyCollateralized)
+	);
+}
+
+#[ink::test]
+fn interest_accrual_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut lending = new_lending();
+
+	assert_ok!(lending.open_loan(charlie, 0, 10_000, 20_000));
+	assert_eq!(lending.debt(Id::U128(0)), Ok(10_000));
+
+	// No interest accrues within the same timeslice:
+	advance_n_blocks(TIMESLICE_PERIOD - 1);
+	assert_eq!(lending.debt(Id::U128(0)), Ok(10_000));
+
+	// The interest compounds every timeslice:
+	advance_n_blocks(1);
+	assert_eq!(lending.debt(Id::U128(0)), Ok(10_100));
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(lending.debt(Id::U128(0)), Ok(10_201));
+
+	assert_eq!(lending.debt(Id::U128(1)), Err(LendingError::LoanNotFound));
+}
+
+#[ink::test]
+fn repayment_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut lending = new_lending();
+
+	assert_eq!(lending.apply_repayment(0, 100), Err(LendingError::LoanNotFound));
+
+	assert_ok!(lending.open_loan(charlie, 0, 10_000, 20_000));
+	advance_n_blocks(TIMESLICE_PERIOD);
+
+	assert_eq!(lending.apply_repayment(0, 0), Err(LendingError::InvalidAmount));
+
+	// Partial repayment of the debt including the accrued interest:
+	assert_eq!(
+		lending.apply_repayment(0, 5_000),
+		Ok((Loan { borrower: charlie, debt: 5_100, accrued_at: 1 }, 5_000))
+	);
+	assert_eq!(lending.loans.get(0), Some(Loan { borrower: charlie, debt: 5_100, accrued_at: 1 }));
+
+	// The repayment is capped to the debt and the loan is removed once repaid:
+	assert_eq!(
+		lending.apply_repayment(0, 10_000),
+		Ok((Loan { borrower: charlie, debt: 0, accrued_at: 1 }, 5_100))
+	);
+	assert_eq!(lending.loans.get(0), None);
+}
+
+#[ink::test]
+fn liquidation_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut lending = new_lending();
+
+	assert_eq!(lending.take_liquidatable_loan(0, 20_000), Err(LendingError::LoanNotFound));
+
+	assert_ok!(lending.open_loan(charlie, 0, 10_000, 20_000));
+
+	// The debt doesn't exceed 80% of the valuation:
+	assert_eq!(lending.take_liquidatable_loan(0, 20_000), Err(LendingError::NotLiquidatable));
+	assert_eq!(lending.take_liquidatable_loan(0, 12_500), Err(LendingError::NotLiquidatable));
+
+	// The valuation of the region decays over time:
+	assert_eq!(
+		lending.take_liquidatable_loan(0, 12_499),
+		Ok(Loan { borrower: charlie, debt: 10_000, accrued_at: 0 })
+	);
+	assert_eq!(lending.loans.get(0), None);
+
+	// The loan can also become liquidatable due to the accrued interest:
+	assert_ok!(lending.open_loan(charlie, 1, 10_000, 20_000));
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(
+		lending.take_liquidatable_loan(1, 12_600),
+		Ok(Loan { borrower: charlie, debt: 10_100, accrued_at: 1 })
+	);
+}
+
+#[ink::test]
+fn valuation_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, .. } = get_default_accounts();
+	let mut lending = new_lending();
+
+	// 80 active core-bits for 10 timeslices:
+	let region = Region { begin: 0, end: 10, core: 0, mask: CoreMask::complete() };
+	assert_eq!(lending.calculate_valuation(&region), 8_000);
+	// Only the active core-bits are valued:
+	let partial_region = Region { mask: CoreMask::from_chunk(0, 40), ..region.clone() };
+	assert_eq!(lending.calculate_valuation(&partial_region), 4_000);
+
+	// The valuation decays with every timeslice that passes:
+	advance_n_blocks(TIMESLICE_PERIOD * 4);
+	assert_eq!(lending.calculate_valuation(&region), 4_800);
+	// Regions that haven't started yet are valued for their whole duration:
+	let future_region = Region { begin: 6, end: 10, ..region.clone() };
+	assert_eq!(lending.calculate_valuation(&future_region), 3_200);
+
+	// Regions that already ended have no value:
+	advance_n_blocks(TIMESLICE_PERIOD * 6);
+	assert_eq!(lending.calculate_valuation(&region), 0);
+
+	// Only the admin can update the price. Listings on the market, whose price is set by the
+	// sellers, have no effect on the valuation.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(lending.set_core_bit_price(1_000), Err(LendingError::NotAllowed));
+	assert_eq!(lending.config().core_bit_price, CORE_BIT_PRICE);
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(lending.set_core_bit_price(20));
+	assert_eq!(lending.calculate_valuation(&Region { end: 20, ..region }), 16_000);
+}
+
+fn new_lending() -> CoretimeLending {
+	let DefaultAccounts::<DefaultEnvironment> { alice, charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+	CoretimeLending::new(
+		alice,
+		CORE_BIT_PRICE,
+		charlie,
+		INTEREST_RATE,
+		MAX_LOAN_TO_VALUE,
+		LIQUIDATION_THRESHOLD,
+		TIMESLICE_PERIOD,
+	)
+}
+
+fn advance_n_blocks(n: u32) {
+	for _ in 0..n {
+		advance_block::<DefaultEnvironment>();
+	}
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
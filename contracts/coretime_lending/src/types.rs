@@ -0,0 +1,117 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use openbrush::{
+	contracts::traits::{errors::ReentrancyGuardError, psp22::PSP22Error, psp34::PSP34Error},
+	traits::{AccountId, BlockNumber},
+};
+use primitives::{coretime::Timeslice, Balance};
+
+/// The type used to represent rates and ratios.
+pub type BasisPoints = u16;
+
+/// The basis points representing 100%.
+pub const MAX_BASIS_POINTS: BasisPoints = 10_000;
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Config {
+	/// The `AccountId` of the xcRegions contract.
+	pub xc_regions_contract: AccountId,
+	/// The valuation of a single core-bit for a single timeslice, used for valuing the
+	/// collateral.
+	pub core_bit_price: Balance,
+	/// The `AccountId` of the PSP22 token that is lent.
+	pub loan_token: AccountId,
+	/// The interest accrued on the debt every timeslice, expressed in basis points.
+	pub interest_rate: BasisPoints,
+	/// The maximum debt that can be taken against a region, relative to the valuation of the
+	/// region.
+	pub max_loan_to_value: BasisPoints,
+	/// The debt relative to the valuation of the collateral above which the loan can be
+	/// liquidated.
+	pub liquidation_threshold: BasisPoints,
+	/// The number of relay chain blocks in a timeslice.
+	pub timeslice_period: BlockNumber,
+}
+
+/// A loan taken against an xcRegion.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Loan {
+	/// The account that took the loan and receives the collateral back once it is repaid.
+	pub borrower: AccountId,
+	/// The debt of the borrower, including the interest accrued until `accrued_at`.
+	pub debt: Balance,
+	/// The timeslice until which the interest was accrued.
+	pub accrued_at: Timeslice,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum LendingError {
+	/// An arithmetic error occured.
+	ArithmeticError,
+	/// The provided identifier is not a valid region id.
+	InvalidRegionId,
+	/// The amount must be greater than zero.
+	InvalidAmount,
+	/// The caller is not allowed to perform the operation.
+	NotAllowed,
+	/// The region is already used as collateral for a loan.
+	RegionAlreadyCollateralized,
+	/// There is no loan taken against the region.
+	LoanNotFound,
+	/// The loan exceeds the maximum loan to value ratio.
+	LoanTooLarge,
+	/// The debt doesn't exceed the liquidation threshold.
+	NotLiquidatable,
+	/// The metadata of the region couldn't be retrieved for valuing it.
+	ValuationUnavailable,
+	/// The contract didn't receive the region.
+	RegionNotReceived,
+	/// The call would result in reentering the contract.
+	ReentrantCall,
+	/// An error occured when transferring the xcRegion.
+	XcRegionsPsp34Error(PSP34Error),
+	/// An error occured when transferring the loan token.
+	LoanTokenError(PSP22Error),
+}
+
+impl core::fmt::Display for LendingError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			LendingError::ArithmeticError => write!(f, "ArithmeticError"),
+			LendingError::InvalidRegionId => write!(f, "InvalidRegionId"),
+			LendingError::InvalidAmount => write!(f, "InvalidAmount"),
+			LendingError::NotAllowed => write!(f, "NotAllowed"),
+			LendingError::RegionAlreadyCollateralized => write!(f, "RegionAlreadyCollateralized"),
+			LendingError::LoanNotFound => write!(f, "LoanNotFound"),
+			LendingError::LoanTooLarge => write!(f, "LoanTooLarge"),
+			LendingError::NotLiquidatable => write!(f, "NotLiquidatable"),
+			LendingError::ValuationUnavailable => write!(f, "ValuationUnavailable"),
+			LendingError::RegionNotReceived => write!(f, "RegionNotReceived"),
+			LendingError::ReentrantCall => write!(f, "ReentrantCall"),
+			LendingError::XcRegionsPsp34Error(e) => write!(f, "{:?}", e),
+			LendingError::LoanTokenError(e) => write!(f, "{:?}", e),
+		}
+	}
+}
+
+impl From<ReentrancyGuardError> for LendingError {
+	fn from(_: ReentrancyGuardError) -> Self {
+		LendingError::ReentrantCall
+	}
+}
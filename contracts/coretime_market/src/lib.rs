@@ -22,6 +22,40 @@
 //! sale, based on the value of a single timeslice. This approach is useful as it allows us to
 //! emulate the expiring nature of Coretime.
 //!
+//! Alternatively, sellers can choose a different pricing strategy for their listing. For example,
+//! a Dutch auction listing starts at a high price which decreases linearly every timeslice until it
+//! reaches a floor set by the seller.
+//!
+//! For regions in high demand, sellers can opt for a candle auction, where the auction closes at a
+//! randomly chosen block within its ending period. This reduces last-second sniping.
+//!
+//! Sellers of large regions may prefer a sealed-bid auction instead, where bidders first commit to
+//! a hashed bid and only reveal it once bidding is closed, so that open bidding doesn't leak their
+//! strategy.
+//!
+//! For high-value trades, sellers can list regions in escrow mode. The payment and the region are
+//! then held by the market after the purchase, giving the buyer a window during which they can
+//! dispute the sale to an arbiter before it is finalized.
+//!
+//! Besides purchasing regions at their asking price, buyers can place escrowed offers on listed
+//! regions, which the seller can then choose to accept.
+//!
+//! Regions can be paid for either in the native token or in a PSP22 token chosen by the seller.
+//! Sellers can also denominate their price in a reference currency, e.g. USD, in which case the
+//! price is converted to the native token at the time of purchase using a price oracle.
+//!
+//! The sales paid for in the native token can be reported to a sale oracle, which computes
+//! time-weighted average prices that other contracts can use for valuing regions. Similarly,
+//! completed sales, resolved disputes and cancelled listings can be reported to a reputation
+//! registry keeping statistics of every seller.
+//!
+//! A protocol fee, expressed in basis points, is taken from every sale. The fees accrue in the
+//! contract and can be withdrawn to the configured fee recipient.
+//!
+//! Listing a region requires a deposit, which is returned once the region is removed from sale.
+//! The deposit is taken in the native token, unless the market was instantiated with a PSP22
+//! deposit asset.
+//!
 //! ## Terminology:
 //!
 //! - Expired region: A region that can no longer be assigned to any particular task.
@@ -34,23 +68,45 @@
 #[cfg(test)]
 mod tests;
 
-mod types;
+mod traits;
+pub mod types;
 
 #[openbrush::contract(env = environment::ExtendedEnvironment)]
 pub mod coretime_market {
-	use crate::types::{Config, Listing, MarketError};
+	use crate::{
+		traits::PriceOracleRef,
+		types::{
+			duration_bucket, BasisPoints, BuyOrder, CandleAuction, CandleBid, Config, CurrencyId,
+			Escrow, EscrowTerms, ExponentialDecay, FeeConfig, LinearDecay, Listing, ListingLimits,
+			MarketError, MarketStats, OracleConfig, OraclePrice, OraclePricing, PriceQuote,
+			PricingStrategy, Sale, SealedBid, SealedBidAuction, AVERAGE_SALE_PRICE_WINDOW,
+			MAX_BASIS_POINTS, MAX_EXPIRY_LOOKAHEAD, MAX_OPEN_BUY_ORDERS, MAX_PROCEEDS_RECIPIENTS,
+			MAX_SALE_HISTORY, PURGE_REWARD_RATE, STORAGE_VERSION,
+		},
+	};
 	use block_number_extension::BlockNumberProviderExtension;
+	use coretime_price_oracle::traits::SaleRecorderRef;
+	use coretime_reputation::{traits::ReputationRecorderRef, types::SellerEvent};
 	use environment::ExtendedEnvironment;
 	use ink::{
 		codegen::{EmitEvent, Env},
+		env::hash::Blake2x256,
 		prelude::vec::Vec,
 		reflect::ContractEventBase,
 		storage::Lazy,
 		EnvAccess,
 	};
-	use openbrush::{contracts::traits::psp34::Id, storage::Mapping, traits::Storage};
+	use openbrush::{
+		contracts::{
+			reentrancy_guard::*,
+			traits::{psp22::PSP22Ref, psp34::Id},
+		},
+		modifiers,
+		storage::Mapping,
+		traits::{Storage, String},
+	};
 	use primitives::{
-		coretime::{RawRegionId, Region, Timeslice, CORE_MASK_BIT_LEN},
+		coretime::{CoreIndex, RawRegionId, Region, RegionId, Timeslice, CORE_MASK_BIT_LEN},
 		ensure, Version,
 	};
 	use sp_arithmetic::{traits::SaturatedConversion, FixedPointNumber, FixedU128};
@@ -59,13 +115,104 @@ pub mod coretime_market {
 	#[ink(storage)]
 	#[derive(Storage)]
 	pub struct CoretimeMarket {
+		#[storage_field]
+		guard: reentrancy_guard::Data,
 		/// A mapping that holds information about each region listed on sale.
 		pub listings: Mapping<RawRegionId, Listing>,
 		/// A vector containing all the regions listed on sale.
 		pub listed_regions: Lazy<Vec<RawRegionId>>,
-		/// The configuration of the market. Set on contract initialization. Can't be changed
-		/// afterwards.
+		/// A mapping that holds the index of each listed region within `listed_regions`.
+		///
+		/// Used for removing regions from sale in constant time.
+		pub listed_region_indices: Mapping<RawRegionId, u32>,
+		/// A mapping that holds all the regions listed on sale by each seller.
+		pub seller_listings: Mapping<AccountId, Vec<RawRegionId>>,
+		/// A mapping that groups the listed regions by the timeslice at which they end.
+		///
+		/// Used for efficiently querying the listed regions that are about to expire.
+		pub listings_by_end: Mapping<Timeslice, Vec<RawRegionId>>,
+		/// A mapping that holds the auction parameters of regions listed in a candle auction.
+		pub candle_auctions: Mapping<RawRegionId, CandleAuction>,
+		/// A mapping that holds all the bids placed in a candle auction, ordered by the time they
+		/// were placed.
+		pub candle_bids: Mapping<RawRegionId, Vec<CandleBid>>,
+		/// A mapping that holds the tokens escrowed by each bidder in a candle auction, keyed by
+		/// the identifier of the auction.
+		pub candle_escrow: Mapping<(u32, AccountId), Balance>,
+		/// A mapping that holds the state of regions listed in a sealed-bid auction.
+		pub sealed_auctions: Mapping<RawRegionId, SealedBidAuction>,
+		/// A mapping that holds the bids committed to a sealed-bid auction by each bidder, keyed
+		/// by the identifier of the auction.
+		pub sealed_bids: Mapping<(u32, AccountId), SealedBid>,
+		/// A mapping that holds the escrow terms of regions listed in escrow mode.
+		pub escrow_terms: Mapping<RawRegionId, EscrowTerms>,
+		/// A mapping that holds the purchases locked in escrow which weren't settled yet.
+		pub escrows: Mapping<RawRegionId, Escrow>,
+		/// A mapping that holds the escrowed offers placed on listed regions by each bidder.
+		pub offers: Mapping<(RawRegionId, AccountId), Balance>,
+		/// A mapping that holds all the accounts that placed an offer on a specific region.
+		pub region_bidders: Mapping<RawRegionId, Vec<AccountId>>,
+		/// The configuration of the market. Set on contract initialization. Only the listing
+		/// deposit can be changed afterwards.
 		pub config: Config,
+		/// The account that is allowed to adjust the fee configuration of the market.
+		pub admin: AccountId,
+		/// The fee configuration of the market.
+		pub fee_config: FeeConfig,
+		/// The total amount of fees collected by the market which weren't withdrawn yet.
+		pub accrued_fees: Balance,
+		/// The configuration of the price oracle used for listings priced in a reference currency.
+		pub oracle_config: Option<OracleConfig>,
+		/// Whether the market is paused. While paused, regions can't be listed or purchased, but
+		/// sellers can still unlist their regions.
+		pub paused: bool,
+		/// The version of the storage layout. Used for determining which migrations need to be
+		/// run after upgrading the contract code.
+		pub storage_version: u16,
+		/// All the listing deposits required by the market so far, together with the block number
+		/// from which each of them applied.
+		pub listing_deposit_history: Lazy<Vec<(BlockNumber, Balance)>>,
+		/// A mapping that holds the most recent sales completed on the market.
+		///
+		/// Used as a ring buffer of `MAX_SALE_HISTORY` entries.
+		pub sales: Mapping<u32, Sale>,
+		/// The total number of sales completed on the market.
+		pub sale_count: u64,
+		/// A mapping that groups the listed regions by the core they are assigned to.
+		///
+		/// The regions of each core are sorted by their per timeslice price, cheapest first.
+		pub core_listings: Mapping<CoreIndex, Vec<RawRegionId>>,
+		/// Aggregate statistics of the sales completed on the market.
+		pub stats: MarketStats,
+		/// A mapping that holds all the open buy orders.
+		pub buy_orders: Mapping<u32, BuyOrder>,
+		/// The identifiers of all the open buy orders, in the order they were placed.
+		pub open_buy_orders: Lazy<Vec<u32>>,
+		/// The identifier assigned to the next buy order.
+		pub next_buy_order_id: u32,
+		/// The constraints imposed on new listings to prevent spamming the market.
+		pub listing_limits: ListingLimits,
+		/// The share of the market fee paid to the referrer of a sale, expressed in basis points.
+		pub referral_rate: BasisPoints,
+		/// The PSP22 token in which the listing deposit is taken. If not set, the deposit is taken
+		/// in the native token.
+		pub deposit_asset: Option<AccountId>,
+		/// A mapping that holds the average per timeslice price paid for regions in each
+		/// duration bucket.
+		///
+		/// Only sales paid for in the native token are accounted for.
+		pub comparable_sales: Mapping<u8, Balance>,
+		/// The minimum number of relay chain blocks left for bidding in an auction after a bid is
+		/// placed. Bids landing closer to the end extend the auction accordingly.
+		pub auction_extension: BlockNumber,
+		/// The price oracle to which the completed sales are reported. If not set, the sales are
+		/// not reported.
+		pub sale_oracle: Option<AccountId>,
+		/// The registry to which the events concerning the sellers are reported. If not set, the
+		/// events are not reported.
+		pub reputation_registry: Option<AccountId>,
+		/// The identifier assigned to the next candle or sealed-bid auction.
+		pub next_auction_id: u32,
 	}
 
 	#[ink(event)]
@@ -76,11 +223,144 @@ pub mod coretime_market {
 		/// The per timeslice price of the listed region.
 		pub(crate) timeslice_price: Balance,
 		/// The seller of the region
+		#[ink(topic)]
 		pub(crate) seller: AccountId,
 		/// The sale revenue recipient.
 		pub(crate) sale_recepient: AccountId,
 		/// The metadata version of the region.
 		pub(crate) metadata_version: Version,
+		/// The note attached to the region by its owner.
+		pub(crate) note: Option<String>,
+	}
+
+	#[ink(event)]
+	pub struct DutchAuctionStarted {
+		/// The identifier of the region that got listed in a Dutch auction.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The parameters of the auction.
+		pub(crate) auction: LinearDecay,
+	}
+
+	#[ink(event)]
+	pub struct CandleAuctionStarted {
+		/// The identifier of the region that got listed in a candle auction.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The parameters of the auction.
+		pub(crate) auction: CandleAuction,
+	}
+
+	#[ink(event)]
+	pub struct AuctionExtended {
+		/// The identifier of the region being auctioned.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The relay chain block number at which bidding now ends.
+		pub(crate) new_end: BlockNumber,
+	}
+
+	#[ink(event)]
+	pub struct AuctionExtensionUpdated {
+		/// The number of relay chain blocks auctions are extended by when bid on late.
+		pub(crate) extension: BlockNumber,
+	}
+
+	#[ink(event)]
+	pub struct CandleBidPlaced {
+		/// The identifier of the region being auctioned.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that placed the bid.
+		pub(crate) bidder: AccountId,
+		/// The total amount bid by the bidder.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct CandleAuctionSettled {
+		/// The identifier of the region that was auctioned.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The relay chain block number at which the auction was retroactively closed.
+		pub(crate) close_block: BlockNumber,
+		/// The winner of the auction together with the winning bid. `None` if there were no bids
+		/// placed before the auction closed.
+		pub(crate) winner: Option<(AccountId, Balance)>,
+	}
+
+	#[ink(event)]
+	pub struct SealedBidAuctionStarted {
+		/// The identifier of the region that got listed in a sealed-bid auction.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The identifier of the auction.
+		pub(crate) auction_id: u32,
+		/// The minimum amount of a valid bid.
+		pub(crate) min_bid: Balance,
+		/// The relay chain block number at which the commit phase ends.
+		pub(crate) commit_end: BlockNumber,
+		/// The relay chain block number at which the reveal phase ends.
+		pub(crate) reveal_end: BlockNumber,
+	}
+
+	#[ink(event)]
+	pub struct SealedBidCommitted {
+		/// The identifier of the region being auctioned.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that committed the bid.
+		pub(crate) bidder: AccountId,
+	}
+
+	#[ink(event)]
+	pub struct SealedBidRevealed {
+		/// The identifier of the region being auctioned.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that revealed the bid.
+		pub(crate) bidder: AccountId,
+		/// The revealed amount.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct SealedBidAuctionSettled {
+		/// The identifier of the region that was auctioned.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The winner of the auction together with the winning bid. `None` if no valid bids were
+		/// revealed.
+		pub(crate) winner: Option<(AccountId, Balance)>,
+	}
+
+	#[ink(event)]
+	pub struct EscrowOpened {
+		/// The identifier of the region that got purchased in escrow.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The buyer of the region.
+		pub(crate) buyer: AccountId,
+		/// The price locked in escrow.
+		pub(crate) price: Balance,
+		/// The relay chain block number at which the dispute window ends.
+		pub(crate) dispute_end: BlockNumber,
+	}
+
+	#[ink(event)]
+	pub struct EscrowDisputed {
+		/// The identifier of the region whose sale got disputed.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+	}
+
+	#[ink(event)]
+	pub struct EscrowSettled {
+		/// The identifier of the region whose sale got settled.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// Whether the buyer got refunded and the region returned to the seller.
+		pub(crate) refunded: bool,
 	}
 
 	#[ink(event)]
@@ -88,19 +368,75 @@ pub mod coretime_market {
 		/// The identifier of the region that got unlisted from sale.
 		#[ink(topic)]
 		pub(crate) region_id: RawRegionId,
+		/// The seller of the region.
+		#[ink(topic)]
+		pub(crate) seller: AccountId,
 		/// The account that removed the region from sale.
 		pub(crate) caller: AccountId,
 	}
 
+	#[ink(event)]
+	pub struct RegionPurged {
+		/// The identifier of the expired region that got removed from sale.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The seller of the region.
+		#[ink(topic)]
+		pub(crate) seller: AccountId,
+		/// The account that purged the listing.
+		pub(crate) caller: AccountId,
+		/// The portion of the listing deposit paid out to the caller.
+		pub(crate) reward: Balance,
+	}
+
 	#[ink(event)]
 	pub struct RegionPurchased {
 		/// The identifier of the region that got purchased.
 		#[ink(topic)]
 		pub(crate) region_id: RawRegionId,
 		/// The buyer of the region
+		#[ink(topic)]
 		pub(crate) buyer: AccountId,
+		/// The seller of the region.
+		#[ink(topic)]
+		pub(crate) seller: AccountId,
+		/// The account that received the region.
+		pub(crate) recipient: AccountId,
 		/// The total price paid for the listed region.
 		pub(crate) total_price: Balance,
+		/// The metadata version of the purchased region.
+		pub(crate) metadata_version: Version,
+		/// The timeslice at which the sale was settled.
+		pub(crate) timeslice: Timeslice,
+	}
+
+	#[ink(event)]
+	pub struct RegionRelisted {
+		/// The identifier of the relisted region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The new per timeslice price of the region.
+		pub(crate) timeslice_price: Balance,
+		/// The new sale revenue recipient.
+		pub(crate) sale_recepient: AccountId,
+	}
+
+	#[ink(event)]
+	pub struct ProceedsSplitUpdated {
+		/// The identifier of the listed region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The recipients of the sale proceeds together with their share.
+		pub(crate) split: Option<Vec<(AccountId, BasisPoints)>>,
+	}
+
+	#[ink(event)]
+	pub struct ListingExpiryUpdated {
+		/// The identifier of the listed region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The relay chain block number at which the listing lapses.
+		pub(crate) expiry: Option<BlockNumber>,
 	}
 
 	#[ink(event)]
@@ -108,22 +444,242 @@ pub mod coretime_market {
 		/// The identifier of the region that got its price updated.
 		#[ink(topic)]
 		pub(crate) region_id: RawRegionId,
+		/// The seller of the region.
+		#[ink(topic)]
+		pub(crate) seller: AccountId,
 		/// The new per timeslice price.
 		pub(crate) new_timeslice_price: Balance,
 	}
 
+	#[ink(event)]
+	pub struct BuyOrderPlaced {
+		/// The identifier of the buy order.
+		#[ink(topic)]
+		pub(crate) order_id: u32,
+		/// The placed buy order.
+		pub(crate) order: BuyOrder,
+	}
+
+	#[ink(event)]
+	pub struct BuyOrderCancelled {
+		/// The identifier of the cancelled buy order.
+		#[ink(topic)]
+		pub(crate) order_id: u32,
+	}
+
+	#[ink(event)]
+	pub struct BuyOrderFilled {
+		/// The identifier of the buy order.
+		#[ink(topic)]
+		pub(crate) order_id: u32,
+		/// The identifier of the region purchased through the buy order.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The price paid for the region.
+		pub(crate) price: Balance,
+	}
+
+	#[ink(event)]
+	pub struct OfferPlaced {
+		/// The identifier of the region on which the offer was placed.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that placed the offer.
+		pub(crate) bidder: AccountId,
+		/// The amount offered for the region.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct OfferCancelled {
+		/// The identifier of the region on which the offer was placed.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that cancelled the offer.
+		pub(crate) bidder: AccountId,
+	}
+
+	#[ink(event)]
+	pub struct OfferAccepted {
+		/// The identifier of the region that got sold.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account whose offer got accepted.
+		pub(crate) bidder: AccountId,
+		/// The amount paid for the region.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct FeeRateUpdated {
+		/// The new fee rate expressed in basis points.
+		pub(crate) new_rate: BasisPoints,
+	}
+
+	#[ink(event)]
+	pub struct ReferralRateUpdated {
+		/// The new share of the market fee paid to referrers, expressed in basis points.
+		pub(crate) new_rate: BasisPoints,
+	}
+
+	#[ink(event)]
+	pub struct ReferralRewardPaid {
+		/// The account that referred the sale.
+		#[ink(topic)]
+		pub(crate) referrer: AccountId,
+		/// The amount paid to the referrer.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct FeeRecipientUpdated {
+		/// The new recipient of the market fees.
+		pub(crate) new_recipient: AccountId,
+	}
+
+	#[ink(event)]
+	pub struct PauseStatusUpdated {
+		/// Whether the market is paused.
+		pub(crate) paused: bool,
+	}
+
+	#[ink(event)]
+	pub struct OracleConfigUpdated {
+		/// The new configuration of the price oracle.
+		pub(crate) new_config: Option<OracleConfig>,
+	}
+
+	#[ink(event)]
+	pub struct SaleOracleUpdated {
+		/// The new price oracle to which the sales are reported.
+		pub(crate) new_oracle: Option<AccountId>,
+	}
+
+	#[ink(event)]
+	pub struct ReputationRegistryUpdated {
+		/// The new registry to which the events concerning the sellers are reported.
+		pub(crate) new_registry: Option<AccountId>,
+	}
+
+	#[ink(event)]
+	pub struct ListingDepositUpdated {
+		/// The deposit required for new listings.
+		pub(crate) new_deposit: Balance,
+	}
+
+	#[ink(event)]
+	pub struct ListingLimitsUpdated {
+		/// The constraints imposed on new listings.
+		pub(crate) new_limits: ListingLimits,
+	}
+
+	#[ink(event)]
+	pub struct FeesWithdrawn {
+		/// The account that received the fees.
+		pub(crate) recipient: AccountId,
+		/// The amount of fees withdrawn.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct AdminChanged {
+		/// The new admin of the market.
+		pub(crate) new_admin: AccountId,
+	}
+
+	#[ink(event)]
+	pub struct CodeUpgraded {
+		/// The hash of the new contract code.
+		pub(crate) code_hash: Hash,
+	}
+
+	#[ink(event)]
+	pub struct StorageMigrated {
+		/// The new version of the storage layout.
+		pub(crate) storage_version: u16,
+	}
+
 	impl CoretimeMarket {
 		#[ink(constructor)]
 		pub fn new(
 			xc_regions_contract: AccountId,
 			listing_deposit: Balance,
 			timeslice_period: BlockNumber,
+			fee_rate: BasisPoints,
+			fee_recipient: AccountId,
+		) -> Self {
+			Self::new_with_deposit_asset(
+				xc_regions_contract,
+				listing_deposit,
+				timeslice_period,
+				fee_rate,
+				fee_recipient,
+				None,
+			)
+		}
+
+		/// Instantiates the market with the listing deposit taken in the specified PSP22 token
+		/// instead of the native token.
+		///
+		/// This is useful on chains where the native token is impractical for deposits. The
+		/// deposit asset can't be changed once the market is instantiated.
+		#[ink(constructor)]
+		pub fn new_with_deposit_asset(
+			xc_regions_contract: AccountId,
+			listing_deposit: Balance,
+			timeslice_period: BlockNumber,
+			fee_rate: BasisPoints,
+			fee_recipient: AccountId,
+			deposit_asset: Option<AccountId>,
 		) -> Self {
-			Self {
+			assert!(fee_rate <= MAX_BASIS_POINTS, "Fee rate can't exceed 100%");
+
+			let mut market = Self {
+				guard: Default::default(),
 				listings: Default::default(),
 				listed_regions: Default::default(),
+				listed_region_indices: Default::default(),
+				seller_listings: Default::default(),
+				listings_by_end: Default::default(),
+				candle_auctions: Default::default(),
+				candle_bids: Default::default(),
+				candle_escrow: Default::default(),
+				sealed_auctions: Default::default(),
+				sealed_bids: Default::default(),
+				escrow_terms: Default::default(),
+				escrows: Default::default(),
+				offers: Default::default(),
+				region_bidders: Default::default(),
 				config: Config { xc_regions_contract, listing_deposit, timeslice_period },
-			}
+				admin: Self::env().caller(),
+				fee_config: FeeConfig { rate: fee_rate, recipient: fee_recipient },
+				accrued_fees: Default::default(),
+				oracle_config: None,
+				paused: false,
+				storage_version: STORAGE_VERSION,
+				listing_deposit_history: Default::default(),
+				sales: Default::default(),
+				sale_count: Default::default(),
+				core_listings: Default::default(),
+				stats: Default::default(),
+				buy_orders: Default::default(),
+				open_buy_orders: Default::default(),
+				next_buy_order_id: Default::default(),
+				listing_limits: Default::default(),
+				referral_rate: Default::default(),
+				deposit_asset,
+				comparable_sales: Default::default(),
+				auction_extension: Default::default(),
+				sale_oracle: None,
+				reputation_registry: None,
+				next_auction_id: Default::default(),
+			};
+
+			market
+				.listing_deposit_history
+				.set(&ink::prelude::vec![(Self::env().block_number(), listing_deposit)]);
+
+			market
 		}
 
 		#[ink(message)]
@@ -131,42 +687,262 @@ pub mod coretime_market {
 			self.config.xc_regions_contract
 		}
 
+		#[ink(message)]
+		pub fn listing_deposit_history(&self) -> Vec<(BlockNumber, Balance)> {
+			self.listing_deposit_history.get_or_default()
+		}
+
+		#[ink(message)]
+		pub fn admin(&self) -> AccountId {
+			self.admin
+		}
+
+		#[ink(message)]
+		pub fn fee_config(&self) -> FeeConfig {
+			self.fee_config.clone()
+		}
+
+		#[ink(message)]
+		pub fn referral_rate(&self) -> BasisPoints {
+			self.referral_rate
+		}
+
+		#[ink(message)]
+		pub fn accrued_fees(&self) -> Balance {
+			self.accrued_fees
+		}
+
+		#[ink(message)]
+		pub fn oracle_config(&self) -> Option<OracleConfig> {
+			self.oracle_config.clone()
+		}
+
+		#[ink(message)]
+		pub fn sale_oracle(&self) -> Option<AccountId> {
+			self.sale_oracle
+		}
+
+		#[ink(message)]
+		pub fn reputation_registry(&self) -> Option<AccountId> {
+			self.reputation_registry
+		}
+
+		#[ink(message)]
+		pub fn auction_extension(&self) -> BlockNumber {
+			self.auction_extension
+		}
+
+		#[ink(message)]
+		pub fn deposit_asset(&self) -> Option<AccountId> {
+			self.deposit_asset
+		}
+
+		#[ink(message)]
+		pub fn listing_limits(&self) -> ListingLimits {
+			self.listing_limits.clone()
+		}
+
+		#[ink(message)]
+		pub fn paused(&self) -> bool {
+			self.paused
+		}
+
+		#[ink(message)]
+		pub fn storage_version(&self) -> u16 {
+			self.storage_version
+		}
+
 		#[ink(message)]
 		pub fn listed_regions(&self, maybe_who: Option<AccountId>) -> Vec<RawRegionId> {
 			if let Some(who) = maybe_who {
-				self.listed_regions
-					.get_or_default()
-					.into_iter()
-					.filter(|region_id| {
-						let Some(listing) = self.listings.get(region_id) else { return false };
-						listing.seller == who
-					})
-					.collect()
+				self.listings_of(who)
 			} else {
 				self.listed_regions.get_or_default()
 			}
 		}
 
+		/// Returns all the regions listed on sale by the specified seller.
 		#[ink(message)]
-		pub fn listed_region(&self, id: Id) -> Result<Option<Listing>, MarketError> {
-			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
-			Ok(self.listings.get(&region_id))
+		pub fn listings_of(&self, seller: AccountId) -> Vec<RawRegionId> {
+			self.seller_listings.get(seller).unwrap_or_default()
 		}
 
+		/// Returns all the listed regions which will expire within the specified number of
+		/// timeslices.
+		///
+		/// Given that the price of a region decreases as it approaches its end, this is useful
+		/// for finding discounted regions.
+		///
+		/// ## Arguments:
+		/// - `within`: The number of timeslices, counting from the current one, within which the
+		///   returned regions expire. Capped at `MAX_EXPIRY_LOOKAHEAD`.
 		#[ink(message)]
-		pub fn region_price(&self, id: Id) -> Result<Balance, MarketError> {
-			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+		pub fn expiring_listings(&self, within: Timeslice) -> Vec<RawRegionId> {
+			let current_timeslice = self.current_timeslice();
+			let within = within.min(MAX_EXPIRY_LOOKAHEAD);
 
-			let metadata = RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id)
-				.map_err(MarketError::XcRegionsMetadataError)?;
-			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+			(current_timeslice..=current_timeslice.saturating_add(within))
+				.flat_map(|end| self.listings_by_end.get(end).unwrap_or_default())
+				.collect()
+		}
+
+		/// Returns all the offers placed on the specified region.
+		#[ink(message)]
+		pub fn offers(&self, id: Id) -> Result<Vec<(AccountId, Balance)>, MarketError> {
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
 
-			self.calculate_region_price(metadata.region, listing)
+			Ok(self
+				.region_bidders
+				.get(region_id)
+				.unwrap_or_default()
+				.into_iter()
+				.filter_map(|bidder| self.offers.get((region_id, bidder)).map(|a| (bidder, a)))
+				.collect())
 		}
 
-		/// A function for listing a region on sale.
-		///
-		/// ## Arguments:
+		#[ink(message)]
+		pub fn listed_region(&self, id: Id) -> Result<Option<Listing>, MarketError> {
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			Ok(self.listings.get(&region_id))
+		}
+
+		#[ink(message)]
+		pub fn stats(&self) -> MarketStats {
+			self.stats.clone()
+		}
+
+		/// Returns a suggested price for the region.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region to appraise.
+		///
+		/// The suggestion blends the per timeslice price of the listing, or the floor price of
+		/// the core if the region isn't listed, with the average per timeslice price recently
+		/// paid for regions of a similar duration. The resulting per timeslice price is then
+		/// applied to the region in the same way as for listings.
+		#[ink(message)]
+		pub fn appraise(&self, id: Id) -> Result<Balance, MarketError> {
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+
+			let metadata = RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id)
+				.map_err(MarketError::XcRegionsMetadataError)?;
+
+			self.calculate_appraisal(region_id, metadata.into_region())
+		}
+
+		/// Returns the average per timeslice price paid for regions in the specified duration
+		/// bucket.
+		#[ink(message)]
+		pub fn comparable_sale_price(&self, bucket: u8) -> Option<Balance> {
+			self.comparable_sales.get(bucket)
+		}
+
+		/// Returns the lowest per timeslice price among the regions listed on sale on the
+		/// specified core.
+		///
+		/// Only regular listings paid for in the native token are taken into account, since the
+		/// price of the other listings is not determined by their `timeslice_price`.
+		#[ink(message)]
+		pub fn floor_price(&self, core: CoreIndex) -> Option<Balance> {
+			self.core_listings
+				.get(core)
+				.unwrap_or_default()
+				.into_iter()
+				.filter(|region_id| self.ensure_not_auctioned(*region_id).is_ok())
+				.filter_map(|region_id| self.listings.get(region_id))
+				.find(|listing| {
+					listing.pricing == PricingStrategy::MaskDecay && listing.payment_asset.is_none()
+				})
+				.map(|listing| listing.timeslice_price)
+		}
+
+		/// Returns the regions listed on the specified core, sorted by their per timeslice price
+		/// from the cheapest to the most expensive one.
+		///
+		/// Regions listed in a candle or sealed-bid auction are left out, since their price is
+		/// determined by the bids.
+		///
+		/// ## Arguments:
+		/// - `core`: The core to which the listed regions are assigned.
+		/// - `offset`: The number of cheapest listings to skip.
+		/// - `limit`: The maximum number of listings to return.
+		#[ink(message)]
+		pub fn core_order_book(
+			&self,
+			core: CoreIndex,
+			offset: u32,
+			limit: u32,
+		) -> Vec<(RawRegionId, Listing)> {
+			self.core_listings
+				.get(core)
+				.unwrap_or_default()
+				.into_iter()
+				.filter(|region_id| self.ensure_not_auctioned(*region_id).is_ok())
+				.skip(offset as usize)
+				.take(limit as usize)
+				.filter_map(|region_id| self.listings.get(region_id).map(|l| (region_id, l)))
+				.collect()
+		}
+
+		#[ink(message)]
+		pub fn sale_count(&self) -> u64 {
+			self.sale_count
+		}
+
+		/// Returns the most recent sales completed on the market, starting from the newest one.
+		///
+		/// ## Arguments:
+		/// - `offset`: The number of most recent sales to skip.
+		/// - `limit`: The maximum number of sales to return.
+		///
+		/// Only the last `MAX_SALE_HISTORY` sales are kept.
+		#[ink(message)]
+		pub fn sale_history(&self, offset: u32, limit: u32) -> Vec<Sale> {
+			let stored = self.sale_count.min(MAX_SALE_HISTORY as u64);
+
+			(offset as u64..stored)
+				.take(limit as usize)
+				.filter_map(|i| {
+					let index = (self.sale_count - 1 - i) % MAX_SALE_HISTORY as u64;
+					self.sales.get(index as u32)
+				})
+				.collect()
+		}
+
+		#[ink(message)]
+		pub fn region_price(&self, id: Id) -> Result<Balance, MarketError> {
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+
+			let metadata = RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id)
+				.map_err(MarketError::XcRegionsMetadataError)?;
+			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+
+			self.calculate_listing_price(metadata.into_region(), listing)
+		}
+
+		/// Returns a detailed breakdown of the price of a listed region.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the listed region.
+		///
+		/// Not supported for regions listed in an auction, since their price is determined by
+		/// bids.
+		#[ink(message)]
+		pub fn quote(&self, id: Id) -> Result<PriceQuote, MarketError> {
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+
+			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+			self.ensure_not_auctioned(region_id)?;
+
+			let metadata = RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id)
+				.map_err(MarketError::XcRegionsMetadataError)?;
+
+			self.calculate_quote(metadata.into_region(), listing)
+		}
+
+		/// A function for listing a region on sale.
+		///
+		/// ## Arguments:
 		/// - `region_id`: The `u128` encoded identifier of the region that the caller intends to
 		///   list for sale.
 		/// - `timeslice_price`: The price per a single timeslice.
@@ -180,31 +956,1923 @@ pub mod coretime_market {
 		/// deposit will be returned upon unlisting the region from sale. The rationale behind this
 		/// requirement is to prevent the contract state from becoming bloated with regions that
 		/// have expired.
+		///
+		/// If the region matches an open buy order, it is sold to the buyer right away.
 		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
 		pub fn list_region(
 			&mut self,
 			id: Id,
 			timeslice_price: Balance,
 			sale_recepient: Option<AccountId>,
 		) -> Result<(), MarketError> {
+			self.collect_listing_deposit(1)?;
+			self.ensure_min_timeslice_price(timeslice_price)?;
+
+			self._list_region(
+				id.clone(),
+				timeslice_price,
+				PricingStrategy::MaskDecay,
+				sale_recepient,
+				None,
+				None,
+			)?;
+
+			self.fill_buy_order(id)
+		}
+
+		/// A function for listing a region on sale that only specific accounts can purchase.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region that the caller intends to
+		///   list for sale.
+		/// - `timeslice_price`: The price per a single timeslice.
+		/// - `sale_recepient`: The `AccountId` receiving the payment from the sale. If not
+		///   specified this will be the caller.
+		/// - `allowed_buyers`: The accounts that are allowed to purchase the region.
+		///
+		/// This is useful for deals negotiated off-chain that still want on-chain settlement.
+		/// Apart from the purchase restriction, this behaves the same as `list_region`.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn list_region_private(
+			&mut self,
+			id: Id,
+			timeslice_price: Balance,
+			sale_recepient: Option<AccountId>,
+			allowed_buyers: Vec<AccountId>,
+		) -> Result<(), MarketError> {
+			self.collect_listing_deposit(1)?;
+			self.ensure_min_timeslice_price(timeslice_price)?;
+
+			self._list_region(
+				id,
+				timeslice_price,
+				PricingStrategy::MaskDecay,
+				sale_recepient,
+				Some(allowed_buyers),
+				None,
+			)
+		}
+
+		/// A function for listing a region on sale which is paid for in a PSP22 token.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region that the caller intends to
+		///   list for sale.
+		/// - `timeslice_price`: The price per a single timeslice, denominated in the payment asset.
+		/// - `payment_asset`: The `AccountId` of the PSP22 token contract used for payment.
+		/// - `sale_recepient`: The `AccountId` receiving the payment from the sale. If not
+		///   specified this will be the caller.
+		///
+		/// The listing deposit is paid in the deposit asset of the market, regardless of the
+		/// payment asset. Buyers of the region must approve the market contract to spend the
+		/// payment asset on their behalf.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn list_region_for_asset(
+			&mut self,
+			id: Id,
+			timeslice_price: Balance,
+			payment_asset: AccountId,
+			sale_recepient: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			self.collect_listing_deposit(1)?;
+
+			self._list_region(
+				id,
+				timeslice_price,
+				PricingStrategy::MaskDecay,
+				sale_recepient,
+				None,
+				Some(payment_asset),
+			)
+		}
+
+		/// A function for listing multiple regions on sale at once.
+		///
+		/// ## Arguments:
+		/// - `regions`: A list of regions to list on sale. Each entry contains the identifier of
+		///   the region, its per timeslice price and the optional sale recipient. Refer to
+		///   `list_region` for more details.
+		///
+		/// The caller must send a deposit equal to the listing deposit multiplied by the number of
+		/// listed regions.
+		///
+		/// The regions are listed atomically. If listing any of the regions fails, none of them
+		/// will be listed.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn list_regions(
+			&mut self,
+			regions: Vec<(Id, Balance, Option<AccountId>)>,
+		) -> Result<(), MarketError> {
+			self.collect_listing_deposit(regions.len() as u32)?;
+
+			for (id, timeslice_price, sale_recepient) in regions {
+				self.ensure_min_timeslice_price(timeslice_price)?;
+				self._list_region(
+					id.clone(),
+					timeslice_price,
+					PricingStrategy::MaskDecay,
+					sale_recepient,
+					None,
+					None,
+				)?;
+				self.fill_buy_order(id)?;
+			}
+
+			Ok(())
+		}
+
+		/// A function for listing a region on sale with a specific pricing strategy.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region that the caller intends to
+		///   list for sale.
+		/// - `timeslice_price`: The price per a single timeslice. Only used by the `MaskDecay`
+		///   pricing strategy.
+		/// - `pricing`: The strategy used for determining the price of the region. For the decay
+		///   based strategies the start of the decay is set to the current timeslice.
+		/// - `sale_recepient`: The `AccountId` receiving the payment from the sale. If not
+		///   specified this will be the caller.
+		///
+		/// Same as with `list_region` the caller must approve their region to the market contract
+		/// and send the listing deposit.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn list_region_with_pricing(
+			&mut self,
+			id: Id,
+			timeslice_price: Balance,
+			pricing: PricingStrategy,
+			sale_recepient: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			self.collect_listing_deposit(1)?;
+
+			let current_timeslice = self.current_timeslice();
+			let pricing = match pricing {
+				PricingStrategy::Linear(decay) =>
+					PricingStrategy::Linear(LinearDecay { start: current_timeslice, ..decay }),
+				PricingStrategy::Exponential(decay) => {
+					ensure!(
+						decay.decay_rate <= MAX_BASIS_POINTS,
+						MarketError::InvalidAuctionParameters
+					);
+					PricingStrategy::Exponential(ExponentialDecay {
+						start: current_timeslice,
+						..decay
+					})
+				},
+				PricingStrategy::MaskDecay => {
+					self.ensure_min_timeslice_price(timeslice_price)?;
+					PricingStrategy::MaskDecay
+				},
+				pricing => pricing,
+			};
+
+			self._list_region(id, timeslice_price, pricing, sale_recepient, None, None)
+		}
+
+		/// A function for listing a region on sale in a Dutch auction.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region that the caller intends to
+		///   list for sale.
+		/// - `start_price`: The price of the region at the start of the auction.
+		/// - `floor_price`: The lowest price the region can reach.
+		/// - `decay_per_timeslice`: The amount by which the price decreases with each timeslice.
+		/// - `sale_recepient`: The `AccountId` receiving the payment from the sale. If not
+		///   specified this will be the caller.
+		///
+		/// Unlike regular listings, the price of the region doesn't depend on its remaining
+		/// duration or its core occupancy.
+		///
+		/// Same as with `list_region` the caller must approve their region to the market contract
+		/// and send the listing deposit.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn list_region_dutch(
+			&mut self,
+			id: Id,
+			start_price: Balance,
+			floor_price: Balance,
+			decay_per_timeslice: Balance,
+			sale_recepient: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			self.collect_listing_deposit(1)?;
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let auction = LinearDecay {
+				start_price,
+				floor_price,
+				decay_per_timeslice,
+				start: self.current_timeslice(),
+			};
+
+			// The per timeslice price is not used for Dutch auction listings.
+			self._list_region(
+				id,
+				Default::default(),
+				PricingStrategy::Linear(auction.clone()),
+				sale_recepient,
+				None,
+				None,
+			)?;
+
+			self.emit_event(DutchAuctionStarted { region_id, auction });
+
+			Ok(())
+		}
+
+		/// A function for unlisting a region on sale.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region that the caller intends to
+		///   unlist from sale.
+		///
+		/// Only callable by the seller, who receives the listing deposit back. Expired regions can
+		/// also be removed by anyone through `purge_expired`.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn unlist_region(&mut self, id: Id) -> Result<(), MarketError> {
 			let caller = self.env().caller();
-			let market = self.env().account_id();
 
 			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
 
-			// Ensure that the region exists and its metadata is set.
+			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+			ensure!(caller == listing.seller, MarketError::NotAllowed);
+
 			let metadata =
 				RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id.clone())
 					.map_err(MarketError::XcRegionsMetadataError)?;
 
 			let current_timeslice = self.current_timeslice();
 
-			// It doesn't make sense to list a region that expired.
-			ensure!(metadata.region.end > current_timeslice, MarketError::RegionExpired);
+			// The seller can't withdraw a region from an auction once bids were placed.
+			if current_timeslice <= metadata.region().end {
+				ensure!(
+					self.candle_bids.get(region_id).unwrap_or_default().is_empty(),
+					MarketError::CandleAuctionListing
+				);
+				ensure!(
+					self.sealed_auctions.get(region_id).map_or(0, |a| a.commitments) == 0,
+					MarketError::SealedBidAuctionListing
+				);
+			}
+
+			// Remove the region from sale:
+			self.remove_from_sale(region_id, metadata.region().end)?;
+
+			// Transfer the region to the seller.
+			PSP34Ref::transfer(
+				&self.config.xc_regions_contract,
+				listing.seller,
+				id.clone(),
+				Default::default(),
+			)
+			.map_err(MarketError::XcRegionsPsp34Error)?;
+
+			// Return the listing deposit to the seller.
+			self.return_deposit(listing.seller, listing.deposit)?;
+
+			if current_timeslice <= metadata.region().end {
+				self.report_seller_event(listing.seller, SellerEvent::Cancellation);
+			}
+
+			self.emit_event(RegionUnlisted { region_id, seller: listing.seller, caller });
+
+			Ok(())
+		}
+
+		/// A function for removing the listing of an expired region from the market.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the expired region.
+		///
+		/// This is callable by anyone once either the region or the listing itself expired.
+		/// Expired regions no longer have any value and lapsed listings are no longer purchasable,
+		/// so removing them keeps the market state lean. As an incentive, the caller receives a
+		/// portion of the listing deposit defined by `PURGE_REWARD_RATE`. The rest of the
+		/// deposit, together with the region, is returned to the seller.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn purge_expired(&mut self, id: Id) -> Result<(), MarketError> {
+			let caller = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+
+			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+			let metadata =
+				RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id.clone())
+					.map_err(MarketError::XcRegionsMetadataError)?;
 
 			ensure!(
-				self.env().transferred_value() == self.config.listing_deposit,
-				MarketError::MissingDeposit
+				metadata.region().end <= self.current_timeslice() || self.listing_lapsed(&listing),
+				MarketError::RegionNotExpired
+			);
+
+			self.remove_from_sale(region_id, metadata.region().end)?;
+
+			// Transfer the region back to the seller.
+			PSP34Ref::transfer(
+				&self.config.xc_regions_contract,
+				listing.seller,
+				id.clone(),
+				Default::default(),
+			)
+			.map_err(MarketError::XcRegionsPsp34Error)?;
+
+			let reward = self.calculate_purge_reward(listing.deposit)?;
+			self.return_deposit(caller, reward)?;
+			self.return_deposit(listing.seller, listing.deposit.saturating_sub(reward))?;
+
+			self.emit_event(RegionPurged { region_id, seller: listing.seller, caller, reward });
+
+			Ok(())
+		}
+
+		/// A function for setting the expiry of a listing.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the listed region.
+		/// - `expiry`: The relay chain block number at which the listing lapses. If not specified,
+		///   the listing is valid until the region expires.
+		///
+		/// Once the listing lapses, the region can no longer be purchased and anyone can purge
+		/// the listing in exchange for a portion of the listing deposit.
+		#[ink(message)]
+		pub fn set_listing_expiry(
+			&mut self,
+			id: Id,
+			expiry: Option<BlockNumber>,
+		) -> Result<(), MarketError> {
+			let caller = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let mut listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+
+			ensure!(caller == listing.seller, MarketError::NotAllowed);
+			// Auctions end on their own schedule.
+			self.ensure_not_auctioned(region_id)?;
+			if let Some(expiry) = expiry {
+				ensure!(expiry > self.relay_block_number(), MarketError::InvalidExpiry);
+			}
+
+			listing.expiry = expiry;
+			self.listings.insert(&region_id, &listing);
+
+			self.emit_event(ListingExpiryUpdated { region_id, expiry });
+			Ok(())
+		}
+
+		/// A function for splitting the sale proceeds of a listing among multiple recipients.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the listed region.
+		/// - `split`: The recipients of the proceeds together with their share, expressed in basis
+		///   points. The shares must add up to 100%. If not specified, all proceeds go to the sale
+		///   recipient.
+		///
+		/// Only callable by the seller.
+		#[ink(message)]
+		pub fn set_proceeds_split(
+			&mut self,
+			id: Id,
+			split: Option<Vec<(AccountId, BasisPoints)>>,
+		) -> Result<(), MarketError> {
+			let caller = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let mut listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+
+			ensure!(caller == listing.seller, MarketError::NotAllowed);
+			if let Some(split) = &split {
+				let total = split.iter().fold(0u32, |total, (_, share)| total + *share as u32);
+				ensure!(
+					!split.is_empty() &&
+						split.len() <= MAX_PROCEEDS_RECIPIENTS &&
+						total == MAX_BASIS_POINTS as u32,
+					MarketError::InvalidProceedsSplit
+				);
+			}
+
+			listing.proceeds_split = split.clone();
+			self.listings.insert(&region_id, &listing);
+
+			self.emit_event(ProceedsSplitUpdated { region_id, split });
+			Ok(())
+		}
+
+		/// A function for updating a listed region's price.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region being listed for sale.
+		/// - `timeslice_price`: The new per timeslice price of the region.
+		#[ink(message)]
+		pub fn update_region_price(
+			&mut self,
+			id: Id,
+			new_timeslice_price: Balance,
+		) -> Result<(), MarketError> {
+			let caller = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+
+			let mut listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+
+			ensure!(caller == listing.seller, MarketError::NotAllowed);
+			ensure!(
+				matches!(listing.pricing, PricingStrategy::MaskDecay | PricingStrategy::Oracle(_)),
+				MarketError::UnsupportedPricingStrategy
+			);
+			self.ensure_not_auctioned(region_id)?;
+			if listing.pricing == PricingStrategy::MaskDecay && listing.payment_asset.is_none() {
+				self.ensure_min_timeslice_price(new_timeslice_price)?;
+			}
+
+			listing.timeslice_price = new_timeslice_price;
+			self.listings.insert(&region_id, &listing);
+			self.insert_core_listing(region_id, new_timeslice_price);
+
+			self.emit_event(RegionPriceUpdated {
+				region_id,
+				seller: listing.seller,
+				new_timeslice_price,
+			});
+			Ok(())
+		}
+
+		/// A function for updating both the price and the sale recipient of a listed region.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the listed region.
+		/// - `new_timeslice_price`: The new per timeslice price of the region.
+		/// - `new_sale_recepient`: The new `AccountId` receiving the payment from the sale. If not
+		///   specified this will be the caller.
+		///
+		/// The region stays in the market and the listing deposit is kept, so the seller doesn't
+		/// have to unlist and list the region again. Any expiry set on the listing is cleared.
+		#[ink(message)]
+		pub fn relist_region(
+			&mut self,
+			id: Id,
+			new_timeslice_price: Balance,
+			new_sale_recepient: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			let caller = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+
+			let mut listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+
+			ensure!(caller == listing.seller, MarketError::NotAllowed);
+			ensure!(
+				matches!(listing.pricing, PricingStrategy::MaskDecay | PricingStrategy::Oracle(_)),
+				MarketError::UnsupportedPricingStrategy
+			);
+			self.ensure_not_auctioned(region_id)?;
+			if listing.pricing == PricingStrategy::MaskDecay && listing.payment_asset.is_none() {
+				self.ensure_min_timeslice_price(new_timeslice_price)?;
+			}
+
+			let sale_recepient = new_sale_recepient.unwrap_or(caller);
+
+			listing.timeslice_price = new_timeslice_price;
+			listing.sale_recepient = sale_recepient;
+			listing.expiry = None;
+			self.listings.insert(&region_id, &listing);
+			self.insert_core_listing(region_id, new_timeslice_price);
+
+			self.emit_event(RegionRelisted {
+				region_id,
+				timeslice_price: new_timeslice_price,
+				sale_recepient,
+			});
+			Ok(())
+		}
+
+		/// A function for purchasing a region listed on sale.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region being listed for sale.
+		/// - `metadata_version`: The required metadata version for the region. If the
+		///   `metadata_version` does not match the current version stored in the xc-regions
+		///   contract the purchase will fail.
+		/// - `max_price`: The maximum amount the caller is willing to pay for the region. Given
+		///   that the price of a region decreases over time, the exact price at the time of
+		///   execution is not known in advance. If the computed price exceeds this bound, the
+		///   purchase will fail.
+		/// - `recipient`: The `AccountId` receiving the purchased region. If not specified this
+		///   will be the caller.
+		/// - `referrer`: The `AccountId` that referred the purchase, e.g. the frontend through
+		///   which it was made. The referrer receives a share of the market fee taken from the
+		///   sale.
+		///
+		/// In case the caller sends more tokens than the price of the region, the surplus is
+		/// returned back to the caller.
+		///
+		/// The purchase fails if the metadata stored in the xc-regions contract doesn't match the
+		/// begin, core and mask encoded in the region id. The client is still responsible for
+		/// ensuring that the end of the region is correct.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn purchase_region(
+			&mut self,
+			id: Id,
+			metadata_version: Version,
+			max_price: Option<Balance>,
+			recipient: Option<AccountId>,
+			referrer: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			let caller = self.env().caller();
+			let transferred_value = self.env().transferred_value();
+			let recipient = recipient.unwrap_or(caller);
+
+			let price = self._purchase_region(
+				id,
+				metadata_version,
+				max_price,
+				recipient,
+				referrer,
+				transferred_value,
+			)?;
+
+			// Refund the surplus to the buyer in case they overpaid.
+			let surplus = transferred_value.saturating_sub(price);
+			if surplus > 0 {
+				self.env().transfer(caller, surplus).map_err(|_| MarketError::TransferFailed)?;
+			}
+
+			Ok(())
+		}
+
+		/// A function for purchasing multiple regions listed on sale at once.
+		///
+		/// ## Arguments:
+		/// - `regions`: A list of regions to purchase. Each entry contains the identifier of the
+		///   region, its required metadata version and the optional maximum price the caller is
+		///   willing to pay for it. Refer to `purchase_region` for more details.
+		/// - `recipient`: The `AccountId` receiving all the purchased regions. If not specified
+		///   this will be the caller.
+		/// - `referrer`: The `AccountId` that referred the purchases. Refer to `purchase_region`
+		///   for more details.
+		///
+		/// The caller must send enough tokens to cover the price of all the regions. Any surplus
+		/// is returned back to the caller.
+		///
+		/// The purchase is atomic. If purchasing any of the regions fails, none of them will be
+		/// purchased.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn purchase_regions(
+			&mut self,
+			regions: Vec<(Id, Version, Option<Balance>)>,
+			recipient: Option<AccountId>,
+			referrer: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			let caller = self.env().caller();
+			let mut remaining_value = self.env().transferred_value();
+			let recipient = recipient.unwrap_or(caller);
+
+			for (id, metadata_version, max_price) in regions {
+				let price = self._purchase_region(
+					id,
+					metadata_version,
+					max_price,
+					recipient,
+					referrer,
+					remaining_value,
+				)?;
+				remaining_value = remaining_value.saturating_sub(price);
+			}
+
+			// Refund the surplus to the buyer in case they overpaid.
+			if remaining_value > 0 {
+				self.env()
+					.transfer(caller, remaining_value)
+					.map_err(|_| MarketError::TransferFailed)?;
+			}
+
+			Ok(())
+		}
+	}
+
+	// Candle auction functions:
+	impl CoretimeMarket {
+		/// A function for listing a region on sale in a candle auction.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region that the caller intends to
+		///   auction.
+		/// - `min_bid`: The minimum amount of the first bid.
+		/// - `opening_period`: The duration of the opening period in relay chain blocks.
+		/// - `ending_period`: The duration of the ending period in relay chain blocks. The auction
+		///   will be retroactively closed at a random block within this period.
+		/// - `sale_recepient`: The `AccountId` receiving the payment from the sale. If not
+		///   specified this will be the caller.
+		///
+		/// Same as with `list_region` the caller must approve their region to the market contract
+		/// and send the listing deposit.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn list_region_candle(
+			&mut self,
+			id: Id,
+			min_bid: Balance,
+			opening_period: BlockNumber,
+			ending_period: BlockNumber,
+			sale_recepient: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			self.collect_listing_deposit(1)?;
+			ensure!(ending_period > 0, MarketError::InvalidAuctionParameters);
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			// The per timeslice price is not used for candle auction listings.
+			self._list_region(
+				id,
+				Default::default(),
+				PricingStrategy::MaskDecay,
+				sale_recepient,
+				None,
+				None,
+			)?;
+
+			let auction_id = self.next_auction_id;
+			self.next_auction_id = self.next_auction_id.saturating_add(1);
+
+			let ending_period_start = self.relay_block_number().saturating_add(opening_period);
+			let auction = CandleAuction {
+				id: auction_id,
+				min_bid,
+				ending_period_start,
+				end: ending_period_start.saturating_add(ending_period),
+			};
+			self.candle_auctions.insert(region_id, &auction);
+
+			self.emit_event(CandleAuctionStarted { region_id, auction });
+
+			Ok(())
+		}
+
+		/// A function for placing a bid in a candle auction.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the auctioned region.
+		///
+		/// The tokens transferred with this call are escrowed and added to any tokens the caller
+		/// has previously escrowed in this auction. The total escrowed amount constitutes the bid
+		/// of the caller, which must be higher than the current highest bid.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn place_candle_bid(&mut self, id: Id) -> Result<(), MarketError> {
+			self.ensure_not_paused()?;
+
+			let bidder = self.env().caller();
+			let relay_block_number = self.relay_block_number();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let mut auction =
+				self.candle_auctions.get(region_id).ok_or(MarketError::AuctionNotFound)?;
+			ensure!(relay_block_number < auction.end, MarketError::AuctionEnded);
+
+			let amount = self
+				.candle_escrow
+				.get((auction.id, bidder))
+				.unwrap_or_default()
+				.saturating_add(self.env().transferred_value());
+
+			let mut bids = self.candle_bids.get(region_id).unwrap_or_default();
+			if let Some(highest_bid) = bids.last() {
+				ensure!(amount > highest_bid.amount, MarketError::BidTooLow);
+			} else {
+				ensure!(amount >= auction.min_bid, MarketError::BidTooLow);
+			}
+
+			bids.push(CandleBid { bidder, amount, block_number: relay_block_number });
+			self.candle_bids.insert(region_id, &bids);
+			self.candle_escrow.insert((auction.id, bidder), &amount);
+
+			self.emit_event(CandleBidPlaced { region_id, bidder, amount });
+
+			let new_end = self.extended_auction_end(auction.end, relay_block_number);
+			if new_end > auction.end {
+				auction.end = new_end;
+				self.candle_auctions.insert(region_id, &auction);
+				self.emit_event(AuctionExtended { region_id, new_end });
+			}
+
+			Ok(())
+		}
+
+		/// A function for settling a candle auction that has ended.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the auctioned region.
+		///
+		/// This is callable by anyone. The close block of the auction is randomly chosen within
+		/// the ending period based on the relay chain randomness, and the highest bid placed
+		/// before it wins. The region is transferred
+		/// to the winner and the winning bid is paid out to the sale recipient. If there is no
+		/// winner, the region and the listing deposit are returned to the seller.
+		///
+		/// All the bidders, including the winner for any amount exceeding their winning bid, can
+		/// reclaim their escrowed tokens through `withdraw_candle_escrow` once the auction is
+		/// settled.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn settle_candle_auction(&mut self, id: Id) -> Result<(), MarketError> {
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let auction =
+				self.candle_auctions.get(region_id).ok_or(MarketError::AuctionNotFound)?;
+			ensure!(self.relay_block_number() >= auction.end, MarketError::AuctionNotEnded);
+
+			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+			let metadata =
+				RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id.clone())
+					.map_err(MarketError::XcRegionsMetadataError)?;
+
+			let bids = self.candle_bids.get(region_id).unwrap_or_default();
+			let close_block = self.candle_close_block(&auction, self.relay_randomness()?);
+			let winning_bid =
+				bids.into_iter().take_while(|bid| bid.block_number <= close_block).last();
+
+			self.remove_from_sale(region_id, metadata.region().end)?;
+
+			if let Some(CandleBid { bidder, amount, .. }) = winning_bid {
+				let escrow = self.candle_escrow.get((auction.id, bidder)).unwrap_or_default();
+				self.candle_escrow.insert((auction.id, bidder), &escrow.saturating_sub(amount));
+
+				PSP34Ref::transfer(
+					&self.config.xc_regions_contract,
+					bidder,
+					id.clone(),
+					Default::default(),
+				)
+				.map_err(MarketError::XcRegionsPsp34Error)?;
+
+				self.record_sale(region_id, metadata.region(), &listing, bidder, amount);
+				self.pay_sale_recepient(&listing, self.env().account_id(), amount, None)?;
+
+				self.emit_event(CandleAuctionSettled {
+					region_id,
+					close_block,
+					winner: Some((bidder, amount)),
+				});
+			} else {
+				PSP34Ref::transfer(
+					&self.config.xc_regions_contract,
+					listing.seller,
+					id.clone(),
+					Default::default(),
+				)
+				.map_err(MarketError::XcRegionsPsp34Error)?;
+
+				self.return_deposit(listing.seller, listing.deposit)?;
+
+				self.emit_event(CandleAuctionSettled { region_id, close_block, winner: None });
+			}
+
+			Ok(())
+		}
+
+		/// A function for reclaiming the tokens escrowed in a candle auction.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the auctioned region.
+		/// - `auction_id`: The identifier of the auction in which the tokens were escrowed.
+		///
+		/// Only callable once the auction is no longer ongoing.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn withdraw_candle_escrow(
+			&mut self,
+			id: Id,
+			auction_id: u32,
+		) -> Result<(), MarketError> {
+			let bidder = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			// The region may have been listed in a new auction since.
+			ensure!(
+				self.candle_auctions.get(region_id).map_or(true, |a| a.id != auction_id),
+				MarketError::AuctionNotEnded
+			);
+
+			let amount = self.candle_escrow.get((auction_id, bidder)).unwrap_or_default();
+			self.candle_escrow.remove((auction_id, bidder));
+
+			self.env().transfer(bidder, amount).map_err(|_| MarketError::TransferFailed)?;
+
+			Ok(())
+		}
+
+		#[ink(message)]
+		pub fn candle_auction(&self, id: Id) -> Result<Option<CandleAuction>, MarketError> {
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			Ok(self.candle_auctions.get(region_id))
+		}
+
+		#[ink(message)]
+		pub fn candle_bids(&self, id: Id) -> Result<Vec<CandleBid>, MarketError> {
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			Ok(self.candle_bids.get(region_id).unwrap_or_default())
+		}
+	}
+
+	// Sealed-bid auction functions:
+	impl CoretimeMarket {
+		/// A function for listing a region on sale in a sealed-bid auction.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region that the caller intends to
+		///   auction.
+		/// - `min_bid`: The minimum amount of a valid bid.
+		/// - `commit_period`: The duration of the commit phase in relay chain blocks.
+		/// - `reveal_period`: The duration of the reveal phase in relay chain blocks.
+		/// - `sale_recepient`: The `AccountId` receiving the payment from the sale. If not
+		///   specified this will be the caller.
+		///
+		/// Same as with `list_region` the caller must approve their region to the market contract
+		/// and send the listing deposit.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn list_region_sealed(
+			&mut self,
+			id: Id,
+			min_bid: Balance,
+			commit_period: BlockNumber,
+			reveal_period: BlockNumber,
+			sale_recepient: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			self.collect_listing_deposit(1)?;
+			ensure!(commit_period > 0 && reveal_period > 0, MarketError::InvalidAuctionParameters);
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			// The per timeslice price is not used for sealed-bid auction listings.
+			self._list_region(
+				id,
+				Default::default(),
+				PricingStrategy::MaskDecay,
+				sale_recepient,
+				None,
+				None,
+			)?;
+
+			let auction_id = self.next_auction_id;
+			self.next_auction_id = self.next_auction_id.saturating_add(1);
+
+			let commit_end = self.relay_block_number().saturating_add(commit_period);
+			let reveal_end = commit_end.saturating_add(reveal_period);
+			self.sealed_auctions.insert(
+				region_id,
+				&SealedBidAuction {
+					id: auction_id,
+					min_bid,
+					commit_end,
+					reveal_end,
+					commitments: 0,
+					highest_bid: None,
+				},
+			);
+
+			self.emit_event(SealedBidAuctionStarted {
+				region_id,
+				auction_id,
+				min_bid,
+				commit_end,
+				reveal_end,
+			});
+
+			Ok(())
+		}
+
+		/// A function for committing a sealed bid.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the auctioned region.
+		/// - `commitment`: The Blake2x256 hash of the SCALE encoded `(bidder, amount, salt)` tuple,
+		///   where `salt` is a secret 32 byte array chosen by the bidder.
+		///
+		/// The tokens transferred with this call are escrowed as a deposit, which must be at least
+		/// the amount of the bid. To avoid leaking the bid, the deposit can exceed it. Each bidder
+		/// can only commit a single bid.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn commit_sealed_bid(&mut self, id: Id, commitment: Hash) -> Result<(), MarketError> {
+			self.ensure_not_paused()?;
+
+			let bidder = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let mut auction =
+				self.sealed_auctions.get(region_id).ok_or(MarketError::AuctionNotFound)?;
+			ensure!(
+				self.relay_block_number() < auction.commit_end,
+				MarketError::InvalidAuctionPhase
+			);
+			ensure!(!self.sealed_bids.contains((auction.id, bidder)), MarketError::NotAllowed);
+
+			self.sealed_bids.insert(
+				(auction.id, bidder),
+				&SealedBid { commitment, deposit: self.env().transferred_value(), revealed: false },
+			);
+
+			auction.commitments = auction.commitments.saturating_add(1);
+
+			// The reveal phase is shifted along with the commit phase to keep its duration.
+			let new_end = self.extended_auction_end(auction.commit_end, self.relay_block_number());
+			let extension = new_end.saturating_sub(auction.commit_end);
+			auction.commit_end = new_end;
+			auction.reveal_end = auction.reveal_end.saturating_add(extension);
+			self.sealed_auctions.insert(region_id, &auction);
+
+			self.emit_event(SealedBidCommitted { region_id, bidder });
+			if extension > 0 {
+				self.emit_event(AuctionExtended { region_id, new_end });
+			}
+
+			Ok(())
+		}
+
+		/// A function for revealing a previously committed bid.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the auctioned region.
+		/// - `amount`: The amount of the committed bid.
+		/// - `salt`: The secret used when creating the commitment.
+		///
+		/// Only callable during the reveal phase. Bids which are lower than the minimum bid or
+		/// aren't covered by the deposit are rejected.
+		#[ink(message)]
+		pub fn reveal_sealed_bid(
+			&mut self,
+			id: Id,
+			amount: Balance,
+			salt: [u8; 32],
+		) -> Result<(), MarketError> {
+			let bidder = self.env().caller();
+			let relay_block_number = self.relay_block_number();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let mut auction =
+				self.sealed_auctions.get(region_id).ok_or(MarketError::AuctionNotFound)?;
+			ensure!(
+				relay_block_number >= auction.commit_end && relay_block_number < auction.reveal_end,
+				MarketError::InvalidAuctionPhase
+			);
+
+			let mut bid =
+				self.sealed_bids.get((auction.id, bidder)).ok_or(MarketError::InvalidReveal)?;
+			ensure!(!bid.revealed, MarketError::InvalidReveal);
+			ensure!(
+				bid.commitment == Self::sealed_bid_commitment(bidder, amount, salt),
+				MarketError::InvalidReveal
+			);
+			ensure!(amount <= bid.deposit, MarketError::InsufficientFunds);
+			ensure!(amount >= auction.min_bid, MarketError::BidTooLow);
+
+			bid.revealed = true;
+			self.sealed_bids.insert((auction.id, bidder), &bid);
+
+			if auction.highest_bid.map_or(true, |(_, highest)| amount > highest) {
+				auction.highest_bid = Some((bidder, amount));
+				self.sealed_auctions.insert(region_id, &auction);
+			}
+
+			self.emit_event(SealedBidRevealed { region_id, bidder, amount });
+
+			Ok(())
+		}
+
+		/// A function for settling a sealed-bid auction once the reveal phase is over.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the auctioned region.
+		///
+		/// This is callable by anyone. The region is transferred to the highest revealed bidder
+		/// and the winning bid is paid out to the sale recipient. If no valid bids were revealed,
+		/// the region and the listing deposit are returned to the seller.
+		///
+		/// All the bidders can reclaim their remaining deposits through
+		/// `withdraw_sealed_deposit` once the auction is settled.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn settle_sealed_auction(&mut self, id: Id) -> Result<(), MarketError> {
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let auction =
+				self.sealed_auctions.get(region_id).ok_or(MarketError::AuctionNotFound)?;
+			ensure!(self.relay_block_number() >= auction.reveal_end, MarketError::AuctionNotEnded);
+
+			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+			let metadata =
+				RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id.clone())
+					.map_err(MarketError::XcRegionsMetadataError)?;
+
+			self.remove_from_sale(region_id, metadata.region().end)?;
+
+			if let Some((bidder, amount)) = auction.highest_bid {
+				if let Some(mut bid) = self.sealed_bids.get((auction.id, bidder)) {
+					bid.deposit = bid.deposit.saturating_sub(amount);
+					self.sealed_bids.insert((auction.id, bidder), &bid);
+				}
+
+				PSP34Ref::transfer(
+					&self.config.xc_regions_contract,
+					bidder,
+					id.clone(),
+					Default::default(),
+				)
+				.map_err(MarketError::XcRegionsPsp34Error)?;
+
+				self.record_sale(region_id, metadata.region(), &listing, bidder, amount);
+				self.pay_sale_recepient(&listing, self.env().account_id(), amount, None)?;
+			} else {
+				PSP34Ref::transfer(
+					&self.config.xc_regions_contract,
+					listing.seller,
+					id.clone(),
+					Default::default(),
+				)
+				.map_err(MarketError::XcRegionsPsp34Error)?;
+
+				self.return_deposit(listing.seller, listing.deposit)?;
+			}
+
+			self.emit_event(SealedBidAuctionSettled { region_id, winner: auction.highest_bid });
+
+			Ok(())
+		}
+
+		/// A function for reclaiming the deposit of a sealed bid.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the auctioned region.
+		/// - `auction_id`: The identifier of the auction to which the bid was committed.
+		///
+		/// Only callable once the auction is no longer ongoing.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn withdraw_sealed_deposit(
+			&mut self,
+			id: Id,
+			auction_id: u32,
+		) -> Result<(), MarketError> {
+			let bidder = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			// The region may have been listed in a new auction since.
+			ensure!(
+				self.sealed_auctions.get(region_id).map_or(true, |a| a.id != auction_id),
+				MarketError::AuctionNotEnded
+			);
+
+			let bid =
+				self.sealed_bids.get((auction_id, bidder)).ok_or(MarketError::AuctionNotFound)?;
+			self.sealed_bids.remove((auction_id, bidder));
+
+			self.env()
+				.transfer(bidder, bid.deposit)
+				.map_err(|_| MarketError::TransferFailed)?;
+
+			Ok(())
+		}
+
+		#[ink(message)]
+		pub fn sealed_auction(&self, id: Id) -> Result<Option<SealedBidAuction>, MarketError> {
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			Ok(self.sealed_auctions.get(region_id))
+		}
+
+		/// Computes the commitment of a sealed bid.
+		pub fn sealed_bid_commitment(bidder: AccountId, amount: Balance, salt: [u8; 32]) -> Hash {
+			let mut commitment = [0u8; 32];
+			ink::env::hash_encoded::<Blake2x256, _>(&(bidder, amount, salt), &mut commitment);
+			commitment.into()
+		}
+	}
+
+	// Escrow functions:
+	impl CoretimeMarket {
+		/// A function for listing a region on sale in escrow mode.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region that the caller intends to
+		///   list for sale.
+		/// - `timeslice_price`: The price per a single timeslice.
+		/// - `dispute_window`: The number of relay chain blocks after the purchase during which the
+		///   buyer can dispute the sale.
+		/// - `arbiter`: The account resolving disputes. If not specified, the sale can't be
+		///   disputed.
+		/// - `sale_recepient`: The `AccountId` receiving the payment from the sale. If not
+		///   specified this will be the caller.
+		///
+		/// Upon purchase, the payment and the region are held by the market until the sale is
+		/// finalized.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn list_region_escrowed(
+			&mut self,
+			id: Id,
+			timeslice_price: Balance,
+			dispute_window: BlockNumber,
+			arbiter: Option<AccountId>,
+			sale_recepient: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			self.collect_listing_deposit(1)?;
+			self.ensure_min_timeslice_price(timeslice_price)?;
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			self._list_region(
+				id,
+				timeslice_price,
+				PricingStrategy::MaskDecay,
+				sale_recepient,
+				None,
+				None,
+			)?;
+
+			self.escrow_terms.insert(region_id, &EscrowTerms { dispute_window, arbiter });
+
+			Ok(())
+		}
+
+		/// A function for finalizing a sale locked in escrow.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the purchased region.
+		///
+		/// The buyer can finalize the sale at any time, unless they disputed it. The seller can
+		/// only finalize the sale once the dispute window has passed without a dispute.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn finalize_escrow(&mut self, id: Id) -> Result<(), MarketError> {
+			let caller = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let escrow = self.escrows.get(region_id).ok_or(MarketError::EscrowNotFound)?;
+
+			ensure!(
+				caller == escrow.buyer || caller == escrow.listing.seller,
+				MarketError::NotAllowed
+			);
+			ensure!(!escrow.disputed, MarketError::InvalidEscrowState);
+			if caller != escrow.buyer {
+				ensure!(
+					self.relay_block_number() >= escrow.dispute_end,
+					MarketError::InvalidEscrowState
+				);
+			}
+
+			self.settle_escrow(region_id, escrow, false)
+		}
+
+		/// A function for disputing a sale locked in escrow.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the purchased region.
+		///
+		/// Only callable by the buyer during the dispute window. Once disputed, the sale can only
+		/// be settled by the arbiter.
+		#[ink(message)]
+		pub fn dispute_escrow(&mut self, id: Id) -> Result<(), MarketError> {
+			let caller = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let mut escrow = self.escrows.get(region_id).ok_or(MarketError::EscrowNotFound)?;
+
+			ensure!(caller == escrow.buyer, MarketError::NotAllowed);
+			ensure!(escrow.terms.arbiter.is_some(), MarketError::NotAllowed);
+			ensure!(
+				!escrow.disputed && self.relay_block_number() < escrow.dispute_end,
+				MarketError::InvalidEscrowState
+			);
+
+			escrow.disputed = true;
+			self.escrows.insert(region_id, &escrow);
+
+			self.emit_event(EscrowDisputed { region_id });
+			Ok(())
+		}
+
+		/// A function for resolving a disputed sale.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the purchased region.
+		/// - `refund`: If true, the buyer gets refunded and the region is returned to the seller.
+		///   Otherwise the sale is finalized.
+		///
+		/// Only callable by the arbiter of the sale.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn resolve_escrow(&mut self, id: Id, refund: bool) -> Result<(), MarketError> {
+			let caller = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let escrow = self.escrows.get(region_id).ok_or(MarketError::EscrowNotFound)?;
+
+			ensure!(Some(caller) == escrow.terms.arbiter, MarketError::NotAllowed);
+			ensure!(escrow.disputed, MarketError::InvalidEscrowState);
+
+			self.settle_escrow(region_id, escrow, refund)
+		}
+
+		#[ink(message)]
+		pub fn escrow(&self, id: Id) -> Result<Option<Escrow>, MarketError> {
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			Ok(self.escrows.get(region_id))
+		}
+	}
+
+	// Buy order functions:
+	impl CoretimeMarket {
+		/// A function for placing an escrowed buy order.
+		///
+		/// ## Arguments:
+		/// - `core`: The core the regions must be assigned to. If not specified, regions on any
+		///   core are accepted.
+		/// - `begin`: The earliest timeslice at which the regions can start.
+		/// - `end`: The latest timeslice at which the regions can end.
+		/// - `max_timeslice_price`: The maximum per timeslice price the buyer is willing to pay.
+		/// - `count`: The number of regions the buyer intends to purchase.
+		///
+		/// The tokens sent along with the call are escrowed and used for paying for the regions.
+		/// Whenever a matching region is listed on sale, it is purchased automatically.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn place_buy_order(
+			&mut self,
+			core: Option<CoreIndex>,
+			begin: Timeslice,
+			end: Timeslice,
+			max_timeslice_price: Balance,
+			count: u32,
+		) -> Result<(), MarketError> {
+			self.ensure_not_paused()?;
+
+			let buyer = self.env().caller();
+			let escrow = self.env().transferred_value();
+
+			ensure!(begin < end && count > 0 && escrow > 0, MarketError::InvalidBuyOrder);
+
+			let mut open_orders = self.open_buy_orders.get_or_default();
+			ensure!(open_orders.len() < MAX_OPEN_BUY_ORDERS, MarketError::TooManyBuyOrders);
+
+			let order_id = self.next_buy_order_id;
+			self.next_buy_order_id = self.next_buy_order_id.saturating_add(1);
+
+			let order = BuyOrder { buyer, core, begin, end, max_timeslice_price, count, escrow };
+			self.buy_orders.insert(order_id, &order);
+			open_orders.push(order_id);
+			self.open_buy_orders.set(&open_orders);
+
+			self.emit_event(BuyOrderPlaced { order_id, order });
+			Ok(())
+		}
+
+		/// A function for cancelling a buy order and reclaiming the remaining escrow.
+		///
+		/// ## Arguments:
+		/// - `order_id`: The identifier of the buy order.
+		///
+		/// Only callable by the buyer.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn cancel_buy_order(&mut self, order_id: u32) -> Result<(), MarketError> {
+			let caller = self.env().caller();
+
+			let order = self.buy_orders.get(order_id).ok_or(MarketError::BuyOrderNotFound)?;
+			ensure!(caller == order.buyer, MarketError::NotAllowed);
+
+			self.remove_buy_order(order_id);
+
+			self.env()
+				.transfer(order.buyer, order.escrow)
+				.map_err(|_| MarketError::TransferFailed)?;
+
+			self.emit_event(BuyOrderCancelled { order_id });
+			Ok(())
+		}
+
+		#[ink(message)]
+		pub fn buy_order(&self, order_id: u32) -> Option<BuyOrder> {
+			self.buy_orders.get(order_id)
+		}
+
+		#[ink(message)]
+		pub fn open_buy_orders(&self) -> Vec<u32> {
+			self.open_buy_orders.get_or_default()
+		}
+	}
+
+	// Offer functions:
+	impl CoretimeMarket {
+		/// A function for placing an offer on a listed region.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the listed region.
+		///
+		/// The offered amount is the value transferred with this call, and it is held in escrow
+		/// until the offer is either accepted by the seller or cancelled by the bidder.
+		///
+		/// A bidder can only have a single offer placed on a region at a time.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn make_offer(&mut self, id: Id) -> Result<(), MarketError> {
+			self.ensure_not_paused()?;
+
+			let bidder = self.env().caller();
+			let amount = self.env().transferred_value();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+			self.ensure_not_auctioned(region_id)?;
+			ensure!(listing.can_purchase(&bidder), MarketError::NotAllowed);
+			// Offers are escrowed in the native token.
+			ensure!(listing.payment_asset.is_none(), MarketError::UnsupportedPaymentAsset);
+			ensure!(amount > 0, MarketError::InvalidOfferAmount);
+			ensure!(!self.offers.contains((region_id, bidder)), MarketError::OfferAlreadyExists);
+
+			self.offers.insert((region_id, bidder), &amount);
+
+			let mut bidders = self.region_bidders.get(region_id).unwrap_or_default();
+			bidders.push(bidder);
+			self.region_bidders.insert(region_id, &bidders);
+
+			self.emit_event(OfferPlaced { region_id, bidder, amount });
+
+			Ok(())
+		}
+
+		/// A function for cancelling an offer and reclaiming the escrowed tokens.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region the offer was placed on.
+		///
+		/// This can be called at any time, even if the region is no longer listed on sale.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn cancel_offer(&mut self, id: Id) -> Result<(), MarketError> {
+			let bidder = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let amount = self.take_offer(region_id, bidder)?;
+
+			self.env().transfer(bidder, amount).map_err(|_| MarketError::TransferFailed)?;
+
+			self.emit_event(OfferCancelled { region_id, bidder });
+
+			Ok(())
+		}
+
+		/// A function for accepting an offer placed on a listed region.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the listed region.
+		/// - `bidder`: The account whose offer is being accepted.
+		///
+		/// Only the seller of the region can accept offers. The region is transferred to the
+		/// bidder and the escrowed tokens are paid out to the sale recipient. Other offers placed
+		/// on the region remain escrowed until their bidders cancel them.
+		///
+		/// For escrowed listings the sale is locked in escrow instead, the same way as when the
+		/// region is purchased, so the bidder can dispute it within the dispute window.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn accept_offer(&mut self, id: Id, bidder: AccountId) -> Result<(), MarketError> {
+			self.ensure_not_paused()?;
+
+			let caller = self.env().caller();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+			ensure!(caller == listing.seller, MarketError::NotAllowed);
+			ensure!(!self.listing_lapsed(&listing), MarketError::ListingExpired);
+
+			let metadata =
+				RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id.clone())
+					.map_err(MarketError::XcRegionsMetadataError)?;
+
+			let escrow_terms = self.escrow_terms.get(region_id);
+
+			let amount = self.take_offer(region_id, bidder)?;
+			self.remove_from_sale(region_id, metadata.region().end)?;
+
+			if let Some(terms) = escrow_terms {
+				self.open_escrow(region_id, listing, terms, bidder, bidder, None, amount);
+				self.emit_event(OfferAccepted { region_id, bidder, amount });
+				return Ok(())
+			}
+
+			// Transfer the region to the bidder.
+			PSP34Ref::transfer(
+				&self.config.xc_regions_contract,
+				bidder,
+				id.clone(),
+				Default::default(),
+			)
+			.map_err(MarketError::XcRegionsPsp34Error)?;
+
+			self.record_sale(region_id, metadata.region(), &listing, bidder, amount);
+			self.pay_sale_recepient(&listing, self.env().account_id(), amount, None)?;
+
+			self.emit_event(OfferAccepted { region_id, bidder, amount });
+
+			Ok(())
+		}
+	}
+
+	// Admin functions:
+	impl CoretimeMarket {
+		/// A function for updating the fee rate of the market.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_rate`: The new fee rate expressed in basis points.
+		#[ink(message)]
+		pub fn set_fee_rate(&mut self, new_rate: BasisPoints) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+			ensure!(new_rate <= MAX_BASIS_POINTS, MarketError::InvalidFeeRate);
+
+			self.fee_config.rate = new_rate;
+
+			self.emit_event(FeeRateUpdated { new_rate });
+			Ok(())
+		}
+
+		/// A function for updating the share of the market fee paid to referrers.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_rate`: The share of the market fee paid to the referrer of a sale, expressed in
+		///   basis points.
+		#[ink(message)]
+		pub fn set_referral_rate(&mut self, new_rate: BasisPoints) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+			ensure!(new_rate <= MAX_BASIS_POINTS, MarketError::InvalidFeeRate);
+
+			self.referral_rate = new_rate;
+
+			self.emit_event(ReferralRateUpdated { new_rate });
+			Ok(())
+		}
+
+		/// A function for updating the recipient of the market fees.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_recipient`: The account that will receive the fees upon withdrawal.
+		#[ink(message)]
+		pub fn set_fee_recipient(&mut self, new_recipient: AccountId) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+
+			self.fee_config.recipient = new_recipient;
+
+			self.emit_event(FeeRecipientUpdated { new_recipient });
+			Ok(())
+		}
+
+		/// A function for updating the deposit required for listing a region on sale.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_deposit`: The deposit required for new listings. Existing listings keep the
+		///   deposit they paid.
+		#[ink(message)]
+		pub fn set_listing_deposit(&mut self, new_deposit: Balance) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+
+			self.config.listing_deposit = new_deposit;
+
+			let mut history = self.listing_deposit_history.get_or_default();
+			history.push((self.env().block_number(), new_deposit));
+			self.listing_deposit_history.set(&history);
+
+			self.emit_event(ListingDepositUpdated { new_deposit });
+			Ok(())
+		}
+
+		/// A function for updating the constraints imposed on new listings.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_limits`: The new listing constraints. Existing listings are not affected.
+		#[ink(message)]
+		pub fn set_listing_limits(&mut self, new_limits: ListingLimits) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+
+			self.listing_limits = new_limits.clone();
+
+			self.emit_event(ListingLimitsUpdated { new_limits });
+			Ok(())
+		}
+
+		/// A function for updating the anti-sniping extension of auctions.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `extension`: The number of relay chain blocks. Whenever a bid lands within this many
+		///   blocks of the end of bidding in a candle auction, or of the commit phase in a
+		///   sealed-bid auction, bidding is extended so that it ends `extension` blocks after the
+		///   bid. Setting this to zero disables the extension.
+		#[ink(message)]
+		pub fn set_auction_extension(&mut self, extension: BlockNumber) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+
+			self.auction_extension = extension;
+
+			self.emit_event(AuctionExtensionUpdated { extension });
+			Ok(())
+		}
+
+		/// A function for pausing or unpausing the market.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `paused`: Whether the market should be paused. While paused, regions can't be listed
+		///   or purchased and no new offers or bids can be placed. Withdrawing regions and funds
+		///   from the market is still possible.
+		#[ink(message)]
+		pub fn set_paused(&mut self, paused: bool) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+
+			self.paused = paused;
+
+			self.emit_event(PauseStatusUpdated { paused });
+			Ok(())
+		}
+
+		/// A function for updating the price oracle used for listings priced in a reference
+		/// currency.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_config`: The new configuration of the price oracle. If not specified, listings
+		///   priced in a reference currency will only be purchasable at their fallback price.
+		#[ink(message)]
+		pub fn set_oracle_config(
+			&mut self,
+			new_config: Option<OracleConfig>,
+		) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+
+			self.oracle_config = new_config.clone();
+
+			self.emit_event(OracleConfigUpdated { new_config });
+			Ok(())
+		}
+
+		/// A function for updating the price oracle to which the completed sales are reported.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_oracle`: The new price oracle. If not specified, the sales are no longer
+		///   reported.
+		#[ink(message)]
+		pub fn set_sale_oracle(
+			&mut self,
+			new_oracle: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+
+			self.sale_oracle = new_oracle;
+
+			self.emit_event(SaleOracleUpdated { new_oracle });
+			Ok(())
+		}
+
+		/// A function for updating the registry to which the events concerning the sellers are
+		/// reported.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_registry`: The new reputation registry. If not specified, the events are no
+		///   longer reported.
+		#[ink(message)]
+		pub fn set_reputation_registry(
+			&mut self,
+			new_registry: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+
+			self.reputation_registry = new_registry;
+
+			self.emit_event(ReputationRegistryUpdated { new_registry });
+			Ok(())
+		}
+
+		/// A function for transferring the admin role to a different account.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_admin`: The account that will become the new admin.
+		#[ink(message)]
+		pub fn set_admin(&mut self, new_admin: AccountId) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+
+			self.admin = new_admin;
+
+			self.emit_event(AdminChanged { new_admin });
+			Ok(())
+		}
+
+		/// A function for withdrawing all the accrued fees to the fee recipient.
+		///
+		/// This is callable by anyone since the fees can only be sent to the configured fee
+		/// recipient.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn withdraw_fees(&mut self) -> Result<(), MarketError> {
+			let amount = self.accrued_fees;
+			let recipient = self.fee_config.recipient;
+
+			self.accrued_fees = Default::default();
+			self.env()
+				.transfer(recipient, amount)
+				.map_err(|_| MarketError::TransferFailed)?;
+
+			self.emit_event(FeesWithdrawn { recipient, amount });
+			Ok(())
+		}
+
+		/// A function for upgrading the code of the market contract.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `code_hash`: The hash of the new contract code. The code must already be uploaded on
+		///   chain.
+		///
+		/// The storage of the contract is kept. If the new code changes the storage layout, it must
+		/// bump `STORAGE_VERSION` and implement the migration in `migrate`, which must be called
+		/// right after the upgrade.
+		#[ink(message)]
+		pub fn upgrade(&mut self, code_hash: Hash) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+
+			self.env().set_code_hash(&code_hash).map_err(|_| MarketError::UpgradeFailed)?;
+
+			self.emit_event(CodeUpgraded { code_hash });
+			Ok(())
+		}
+
+		/// A function for migrating the storage of the contract to the layout expected by the
+		/// current code.
+		///
+		/// Only callable by the admin.
+		#[ink(message)]
+		pub fn migrate(&mut self) -> Result<(), MarketError> {
+			ensure!(self.env().caller() == self.admin, MarketError::NotAllowed);
+			ensure!(self.storage_version < STORAGE_VERSION, MarketError::AlreadyMigrated);
+
+			// NOTE: Migrations between storage versions should be added here.
+
+			self.storage_version = STORAGE_VERSION;
+
+			self.emit_event(StorageMigrated { storage_version: STORAGE_VERSION });
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimeMarket {
+		// Returns the current price of a listed region based on its pricing strategy.
+		pub(crate) fn calculate_listing_price(
+			&self,
+			region: Region,
+			listing: Listing,
+		) -> Result<Balance, MarketError> {
+			match listing.pricing {
+				PricingStrategy::MaskDecay => self.calculate_region_price(region, listing),
+				PricingStrategy::Linear(decay) => Ok(self.calculate_linear_decay_price(decay)),
+				PricingStrategy::Exponential(decay) =>
+					self.calculate_exponential_decay_price(decay),
+				PricingStrategy::Flat(price) => Ok(price),
+				PricingStrategy::Oracle(pricing) =>
+					self.calculate_oracle_price(region, listing, pricing),
+			}
+		}
+
+		pub(crate) fn calculate_oracle_price(
+			&self,
+			region: Region,
+			listing: Listing,
+			pricing: OraclePricing,
+		) -> Result<Balance, MarketError> {
+			if let Some(rate) = self.oracle_rate(pricing.currency) {
+				let reference_price = self.calculate_region_price(region, listing)?;
+				return Ok(FixedU128::from_inner(rate).saturating_mul_int(reference_price))
+			}
+
+			let fallback_timeslice_price =
+				pricing.fallback_timeslice_price.ok_or(MarketError::OraclePriceUnavailable)?;
+
+			self.calculate_region_price(
+				region,
+				Listing { timeslice_price: fallback_timeslice_price, ..listing },
+			)
+		}
+
+		// Returns the price of the reference `currency` in the native token. Returns `None` if the
+		// oracle isn't configured, the call to the oracle fails or the reported price is stale.
+		fn oracle_rate(&self, currency: CurrencyId) -> Option<u128> {
+			let oracle_config = self.oracle_config.clone()?;
+
+			let OraclePrice { rate, updated_at } =
+				PriceOracleRef::price_builder(&oracle_config.oracle, currency)
+					.try_invoke()
+					.ok()?
+					.ok()??;
+
+			let age = self.env().block_number().saturating_sub(updated_at);
+			(age <= oracle_config.max_staleness && rate > 0).then_some(rate)
+		}
+
+		pub(crate) fn calculate_quote(
+			&self,
+			region: Region,
+			listing: Listing,
+		) -> Result<PriceQuote, MarketError> {
+			let current_timeslice = self.current_timeslice();
+			ensure!(region.end > current_timeslice, MarketError::RegionExpired);
+
+			let duration = region.end.saturating_sub(region.begin);
+			let remaining_timeslices =
+				region.end.saturating_sub(current_timeslice.max(region.begin));
+
+			let price = self.calculate_listing_price(region.clone(), listing.clone())?;
+			let undecayed_price = match listing.pricing {
+				PricingStrategy::Linear(LinearDecay { start_price, .. }) |
+				PricingStrategy::Exponential(ExponentialDecay { start_price, .. }) => start_price,
+				PricingStrategy::Flat(price) => price,
+				// The price of timeslice-based listings decreases as the region gets consumed.
+				PricingStrategy::MaskDecay | PricingStrategy::Oracle(_) =>
+					FixedU128::checked_from_rational(duration, remaining_timeslices)
+						.ok_or(MarketError::ArithmeticError)?
+						.saturating_mul_int(price),
+			};
+			let fee = self.calculate_fee(price)?;
+
+			Ok(PriceQuote {
+				active_bits: region.mask.count_ones(),
+				remaining_timeslices,
+				timeslice_price: listing.timeslice_price,
+				undecayed_price,
+				decay: undecayed_price.saturating_sub(price),
+				fee,
+				seller_proceeds: price.saturating_sub(fee),
+				price,
+				payment_asset: listing.payment_asset,
+			})
+		}
+
+		pub(crate) fn calculate_linear_decay_price(&self, decay: LinearDecay) -> Balance {
+			let elapsed_timeslices = self.current_timeslice().saturating_sub(decay.start);
+			let decay_amount = decay.decay_per_timeslice.saturating_mul(elapsed_timeslices.into());
+
+			decay.start_price.saturating_sub(decay_amount).max(decay.floor_price)
+		}
+
+		pub(crate) fn calculate_exponential_decay_price(
+			&self,
+			decay: ExponentialDecay,
+		) -> Result<Balance, MarketError> {
+			let elapsed_timeslices = self.current_timeslice().saturating_sub(decay.start);
+
+			let retained = FixedU128::checked_from_rational(
+				MAX_BASIS_POINTS.saturating_sub(decay.decay_rate),
+				MAX_BASIS_POINTS,
+			)
+			.ok_or(MarketError::ArithmeticError)?;
+
+			let price = retained
+				.saturating_pow(elapsed_timeslices as usize)
+				.saturating_mul_int(decay.start_price);
+
+			Ok(price.max(decay.floor_price))
+		}
+
+		pub(crate) fn calculate_region_price(
+			&self,
+			region: Region,
+			listing: Listing,
+		) -> Result<Balance, MarketError> {
+			self.calculate_mask_decay_price(region, listing.timeslice_price)
+		}
+
+		// Returns the price of the region when valued at `timeslice_price` for every timeslice
+		// of a whole core.
+		fn calculate_mask_decay_price(
+			&self,
+			region: Region,
+			timeslice_price: Balance,
+		) -> Result<Balance, MarketError> {
+			let current_timeslice = self.current_timeslice();
+
+			let duration = region.end.saturating_sub(region.begin);
+
+			let core_occupancy =
+				FixedU128::checked_from_rational(region.mask.count_ones(), CORE_MASK_BIT_LEN)
+					.ok_or(MarketError::ArithmeticError)?;
+
+			let per_timeslice_price = (core_occupancy * timeslice_price.into())
+				.into_inner()
+				.saturating_div(FixedU128::accuracy());
+
+			if current_timeslice < region.begin {
+				// The region didn't start yet, so there is no value lost.
+				let price = per_timeslice_price.saturating_mul(duration.into());
+
+				return Ok(price);
+			}
+
+			let remaining_timeslices = region.end.saturating_sub(current_timeslice);
+			let price = per_timeslice_price.saturating_mul(remaining_timeslices.into());
+
+			Ok(price)
+		}
+
+		pub(crate) fn calculate_fee(&self, price: Balance) -> Result<Balance, MarketError> {
+			let fee_rate = FixedU128::checked_from_rational(self.fee_config.rate, MAX_BASIS_POINTS)
+				.ok_or(MarketError::ArithmeticError)?;
+
+			Ok(fee_rate.saturating_mul_int(price))
+		}
+
+		// List a region on sale. The caller of this function is responsible for ensuring that the
+		// listing deposit was paid.
+		fn _list_region(
+			&mut self,
+			id: Id,
+			timeslice_price: Balance,
+			pricing: PricingStrategy,
+			sale_recepient: Option<AccountId>,
+			allowed_buyers: Option<Vec<AccountId>>,
+			payment_asset: Option<AccountId>,
+		) -> Result<(), MarketError> {
+			self.ensure_not_paused()?;
+
+			match &pricing {
+				PricingStrategy::Linear(LinearDecay { start_price, floor_price, .. }) |
+				PricingStrategy::Exponential(ExponentialDecay {
+					start_price, floor_price, ..
+				}) => ensure!(floor_price <= start_price, MarketError::InvalidAuctionParameters),
+				_ => {},
+			}
+
+			let caller = self.env().caller();
+			let market = self.env().account_id();
+
+			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			ensure!(!self.listings.contains(&region_id), MarketError::RegionAlreadyListed);
+
+			if let Some(max_listings) = self.listing_limits.max_listings_per_account {
+				let listed = self.seller_listings.get(caller).unwrap_or_default().len();
+				ensure!(listed < max_listings as usize, MarketError::TooManyListings);
+			}
+
+			// Ensure that the region exists and its metadata is set.
+			let metadata =
+				RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id.clone())
+					.map_err(MarketError::XcRegionsMetadataError)?;
+			let metadata_version =
+				RegionMetadataRef::metadata_version(&self.config.xc_regions_contract, id.clone())
+					.map_err(MarketError::XcRegionsMetadataError)?;
+			let note = RegionMetadataRef::note(&self.config.xc_regions_contract, id.clone());
+
+			let current_timeslice = self.current_timeslice();
+
+			// It doesn't make sense to list a region that expired.
+			ensure!(metadata.region().end > current_timeslice, MarketError::RegionExpired);
+
+			let sale_recepient = sale_recepient.unwrap_or(caller);
+
+			self.add_to_sale(
+				region_id,
+				metadata.region().end,
+				Listing {
+					seller: caller,
+					timeslice_price,
+					sale_recepient,
+					metadata_version,
+					deposit: self.config.listing_deposit,
+					expiry: None,
+					proceeds_split: None,
+					pricing,
+					payment_asset,
+					allowed_buyers,
+				},
 			);
 
 			// Transfer the region to the market.
@@ -216,222 +2884,784 @@ pub mod coretime_market {
 			)
 			.map_err(MarketError::XcRegionsPsp34Error)?;
 
-			let sale_recepient = sale_recepient.unwrap_or(caller);
-
-			self.listings.insert(
-				&region_id,
-				&Listing {
-					seller: caller,
-					timeslice_price,
-					sale_recepient,
-					metadata_version: metadata.version,
-				},
+			// Don't rely on the transfer succeeding, but ensure that the market actually received
+			// the region. If not, the listing recorded above gets reverted.
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id.clone()) == Some(market),
+				MarketError::RegionNotReceived
 			);
 
-			let mut listed_regions = self.listed_regions.get_or_default();
-			listed_regions.push(region_id);
-			self.listed_regions.set(&listed_regions);
-
 			self.emit_event(RegionListed {
 				region_id,
 				timeslice_price,
 				seller: caller,
 				sale_recepient,
-				metadata_version: metadata.version,
+				metadata_version,
+				note,
 			});
 
 			Ok(())
 		}
 
-		/// A function for unlisting a region on sale.
-		///
-		/// ## Arguments:
-		/// - `region_id`: The `u128` encoded identifier of the region that the caller intends to
-		///   unlist from sale.
-		///
-		/// In case the region is expired, this is callable by anyone and the caller will receive
-		/// the listing deposit as a reward.
-		#[ink(message)]
-		pub fn unlist_region(&mut self, id: Id) -> Result<(), MarketError> {
+		// Purchase a region listed on sale. Returns the amount of native tokens spent on the
+		// purchase, which can't exceed the `available_value`.
+		#[allow(clippy::too_many_arguments)]
+		fn _purchase_region(
+			&mut self,
+			id: Id,
+			metadata_version: Version,
+			max_price: Option<Balance>,
+			recipient: AccountId,
+			referrer: Option<AccountId>,
+			available_value: Balance,
+		) -> Result<Balance, MarketError> {
+			self.ensure_not_paused()?;
+
 			let caller = self.env().caller();
 
 			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
-
 			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+			self.ensure_not_auctioned(region_id)?;
+			// For private listings it is the recipient of the region that must be allowed.
+			ensure!(listing.can_purchase(&recipient), MarketError::NotAllowed);
+			ensure!(!self.listing_lapsed(&listing), MarketError::ListingExpired);
+
 			let metadata =
 				RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id.clone())
 					.map_err(MarketError::XcRegionsMetadataError)?;
 
-			let current_timeslice = self.current_timeslice();
+			// It doesn't make sense to purchase a region that expired.
+			ensure!(metadata.region().end > self.current_timeslice(), MarketError::RegionExpired);
 
-			// If the region is expired this is callable by anyone, otherwise only the seller can
-			// unlist the region from the market.
-			ensure!(
-				caller == listing.seller || current_timeslice > metadata.region.end,
-				MarketError::NotAllowed
-			);
+			let price = self.calculate_listing_price(metadata.region().clone(), listing.clone())?;
+			// Regions paid for in a PSP22 token don't require any native tokens.
+			let native_price = if listing.payment_asset.is_some() { 0 } else { price };
+			ensure!(available_value >= native_price, MarketError::InsufficientFunds);
+			if let Some(max_price) = max_price {
+				ensure!(price <= max_price, MarketError::PriceExceedsMaximum);
+			}
 
-			// Transfer the region to the seller.
+			ensure!(listing.metadata_version == metadata_version, MarketError::MetadataNotMatching);
+			Self::ensure_metadata_consistent(region_id, metadata.region())?;
+
+			let escrow_terms = self.escrow_terms.get(region_id);
+
+			// Remove the region from sale:
+			self.remove_from_sale(region_id, metadata.region().end)?;
+
+			if let Some(terms) = escrow_terms {
+				self.open_escrow(region_id, listing, terms, caller, recipient, referrer, price);
+				return Ok(native_price)
+			}
+
+			// Transfer the region to the recipient.
 			PSP34Ref::transfer(
 				&self.config.xc_regions_contract,
-				listing.seller,
+				recipient,
 				id.clone(),
 				Default::default(),
 			)
 			.map_err(MarketError::XcRegionsPsp34Error)?;
 
-			// Remove the region from sale:
-			self.remove_from_sale(region_id)?;
+			self.record_sale(region_id, metadata.region(), &listing, caller, price);
+			self.pay_sale_recepient(&listing, caller, price, referrer)?;
 
-			// Reward the caller with listing deposit.
-			self.env()
-				.transfer(caller, self.config.listing_deposit)
-				.map_err(|_| MarketError::TransferFailed)?;
+			self.emit_event(RegionPurchased {
+				region_id,
+				buyer: caller,
+				seller: listing.seller,
+				recipient,
+				total_price: price,
+				metadata_version: listing.metadata_version,
+				timeslice: self.current_timeslice(),
+			});
 
-			self.emit_event(RegionUnlisted { region_id, caller });
+			Ok(native_price)
+		}
 
-			Ok(())
+		// Lock a sale in escrow. The payment and the region are held by the market until the sale
+		// is settled.
+		#[allow(clippy::too_many_arguments)]
+		fn open_escrow(
+			&mut self,
+			region_id: RawRegionId,
+			listing: Listing,
+			terms: EscrowTerms,
+			buyer: AccountId,
+			recipient: AccountId,
+			referrer: Option<AccountId>,
+			price: Balance,
+		) {
+			let dispute_end = self.relay_block_number().saturating_add(terms.dispute_window);
+			self.escrows.insert(
+				region_id,
+				&Escrow {
+					listing,
+					terms,
+					buyer,
+					recipient,
+					referrer,
+					price,
+					dispute_end,
+					disputed: false,
+				},
+			);
+
+			self.emit_event(EscrowOpened { region_id, buyer, price, dispute_end });
 		}
 
-		/// A function for updating a listed region's price.
-		///
-		/// ## Arguments:
-		/// - `region_id`: The `u128` encoded identifier of the region being listed for sale.
-		/// - `timeslice_price`: The new per timeslice price of the region.
-		#[ink(message)]
-		pub fn update_region_price(
+		// Settle a sale locked in escrow. Either the region is delivered to the recipient and the
+		// seller gets paid, or the buyer gets refunded and the region is returned to the seller.
+		fn settle_escrow(
 			&mut self,
-			id: Id,
-			new_timeslice_price: Balance,
+			region_id: RawRegionId,
+			escrow: Escrow,
+			refund: bool,
 		) -> Result<(), MarketError> {
-			let caller = self.env().caller();
+			self.escrows.remove(region_id);
 
-			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
+			let Escrow { listing, buyer, recipient, referrer, price, disputed, .. } = escrow;
+			if disputed {
+				self.report_seller_event(listing.seller, SellerEvent::Dispute { refunded: refund });
+			}
 
-			let mut listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+			let receiver = if refund { listing.seller } else { recipient };
 
-			ensure!(caller == listing.seller, MarketError::NotAllowed);
+			PSP34Ref::transfer(
+				&self.config.xc_regions_contract,
+				receiver,
+				Id::U128(region_id),
+				Default::default(),
+			)
+			.map_err(MarketError::XcRegionsPsp34Error)?;
 
-			listing.timeslice_price = new_timeslice_price;
-			self.listings.insert(&region_id, &listing);
+			if refund {
+				self.env().transfer(buyer, price).map_err(|_| MarketError::TransferFailed)?;
+				self.return_deposit(listing.seller, listing.deposit)?;
+			} else {
+				let metadata = RegionMetadataRef::get_metadata(
+					&self.config.xc_regions_contract,
+					Id::U128(region_id),
+				)
+				.map_err(MarketError::XcRegionsMetadataError)?;
+
+				self.record_sale(region_id, metadata.region(), &listing, buyer, price);
+				self.pay_sale_recepient(&listing, self.env().account_id(), price, referrer)?;
+				self.emit_event(RegionPurchased {
+					region_id,
+					buyer,
+					seller: listing.seller,
+					recipient,
+					total_price: price,
+					metadata_version: listing.metadata_version,
+					timeslice: self.current_timeslice(),
+				});
+			}
 
-			self.emit_event(RegionPriceUpdated { region_id, new_timeslice_price });
+			self.emit_event(EscrowSettled { region_id, refunded: refund });
 			Ok(())
 		}
 
-		/// A function for purchasing a region listed on sale.
-		///
-		/// ## Arguments:
-		/// - `region_id`: The `u128` encoded identifier of the region being listed for sale.
-		/// - `metadata_version`: The required metadata version for the region. If the
-		///   `metadata_version` does not match the current version stored in the xc-regions
-		///   contract the purchase will fail.
-		///
-		/// IMPORTANT NOTE: The client is responsible for ensuring that the metadata of the listed
-		/// region is correct.
-		#[ink(message, payable)]
-		pub fn purchase_region(
+		// Ensure that the metadata describes the region identified by `region_id`.
+		//
+		// The begin, core and mask of a region are encoded in its id, so metadata which doesn't
+		// match them is stale or was tampered with.
+		pub(crate) fn ensure_metadata_consistent(
+			region_id: RawRegionId,
+			region: &Region,
+		) -> Result<(), MarketError> {
+			let RegionId { begin, core, mask } = RegionId::from(region_id);
+			ensure!(
+				region.begin == begin && region.core == core && region.mask.is_subset_of(&mask),
+				MarketError::RegionIdMismatch
+			);
+			Ok(())
+		}
+
+		// Returns the block at which an auction bid on at `now` should end, so that there are
+		// always at least `auction_extension` blocks left after the last bid.
+		pub(crate) fn extended_auction_end(
+			&self,
+			end: BlockNumber,
+			now: BlockNumber,
+		) -> BlockNumber {
+			end.max(now.saturating_add(self.auction_extension))
+		}
+
+		// Returns whether the listing passed its expiry.
+		pub(crate) fn listing_lapsed(&self, listing: &Listing) -> bool {
+			listing.expiry.map_or(false, |expiry| expiry <= self.relay_block_number())
+		}
+
+		// Collect the deposit required for listing `count` regions from the caller.
+		//
+		// If a deposit asset is configured, the deposit is transferred from the caller, who must
+		// approve the tokens to the market beforehand. Otherwise, it must be sent along with the
+		// call.
+		fn collect_listing_deposit(&self, count: u32) -> Result<(), MarketError> {
+			let required_deposit = self.config.listing_deposit.saturating_mul(count as Balance);
+
+			let Some(deposit_asset) = self.deposit_asset else {
+				ensure!(
+					self.env().transferred_value() == required_deposit,
+					MarketError::MissingDeposit
+				);
+				return Ok(())
+			};
+
+			// Any native tokens sent along would get stuck in the market.
+			ensure!(self.env().transferred_value() == 0, MarketError::MissingDeposit);
+			if required_deposit > 0 {
+				PSP22Ref::transfer_from(
+					&deposit_asset,
+					self.env().caller(),
+					self.env().account_id(),
+					required_deposit,
+					Vec::new(),
+				)
+				.map_err(MarketError::DepositAssetError)?;
+			}
+
+			Ok(())
+		}
+
+		// Return a listing deposit, or a portion of it, in the asset it was taken in.
+		fn return_deposit(&self, to: AccountId, amount: Balance) -> Result<(), MarketError> {
+			match self.deposit_asset {
+				Some(deposit_asset) if amount > 0 =>
+					PSP22Ref::transfer(&deposit_asset, to, amount, Vec::new())
+						.map_err(MarketError::DepositAssetError),
+				Some(_) => Ok(()),
+				None => self.env().transfer(to, amount).map_err(|_| MarketError::TransferFailed),
+			}
+		}
+
+		fn ensure_min_timeslice_price(&self, timeslice_price: Balance) -> Result<(), MarketError> {
+			ensure!(
+				timeslice_price >= self.listing_limits.min_timeslice_price,
+				MarketError::PriceTooLow
+			);
+			Ok(())
+		}
+
+		fn ensure_not_paused(&self) -> Result<(), MarketError> {
+			ensure!(!self.paused, MarketError::MarketPaused);
+			Ok(())
+		}
+
+		// Ensure that the region is not listed in an auction in which the price is determined
+		// by bids.
+		fn ensure_not_auctioned(&self, region_id: RawRegionId) -> Result<(), MarketError> {
+			ensure!(!self.candle_auctions.contains(region_id), MarketError::CandleAuctionListing);
+			ensure!(
+				!self.sealed_auctions.contains(region_id),
+				MarketError::SealedBidAuctionListing
+			);
+			Ok(())
+		}
+
+		// Pay out the sale proceeds to the sale recipient of the listing, or split them among the
+		// recipients of the proceeds split if one is set.
+		//
+		// For listings paid for in the native token, the `price` must already be held by the
+		// contract and the market fee is retained in the contract until withdrawn. For listings
+		// paid for in a PSP22 token, the tokens are transferred directly from the `payer` and the
+		// market fee is sent to the fee recipient.
+		//
+		// If the sale was referred, the referrer's share is deducted from the market fee.
+		//
+		// Given that the region got sold, the listing deposit is released back to the seller.
+		pub(crate) fn pay_sale_recepient(
 			&mut self,
-			id: Id,
-			metadata_version: Version,
+			listing: &Listing,
+			payer: AccountId,
+			price: Balance,
+			referrer: Option<AccountId>,
 		) -> Result<(), MarketError> {
-			let caller = self.env().caller();
-			let transferred_value = self.env().transferred_value();
+			self.return_deposit(listing.seller, listing.deposit)?;
+
+			let mut fee = self.calculate_fee(price)?;
+			let payouts = self.calculate_payouts(listing, price.saturating_sub(fee))?;
+
+			let referral_reward = match referrer {
+				Some(_) => self.calculate_referral_reward(fee)?,
+				None => 0,
+			};
+			fee = fee.saturating_sub(referral_reward);
+
+			if let Some(payment_asset) = listing.payment_asset {
+				for (recipient, amount) in payouts {
+					PSP22Ref::transfer_from(&payment_asset, payer, recipient, amount, Vec::new())
+						.map_err(MarketError::PaymentAssetError)?;
+				}
+
+				if let (Some(referrer), true) = (referrer, referral_reward > 0) {
+					PSP22Ref::transfer_from(
+						&payment_asset,
+						payer,
+						referrer,
+						referral_reward,
+						Vec::new(),
+					)
+					.map_err(MarketError::PaymentAssetError)?;
+					self.emit_event(ReferralRewardPaid { referrer, amount: referral_reward });
+				}
+
+				if fee > 0 {
+					PSP22Ref::transfer_from(
+						&payment_asset,
+						payer,
+						self.fee_config.recipient,
+						fee,
+						Vec::new(),
+					)
+					.map_err(MarketError::PaymentAssetError)?;
+				}
+
+				return Ok(())
+			}
+
+			self.accrued_fees = self.accrued_fees.saturating_add(fee);
 
+			for (recipient, amount) in payouts {
+				self.env()
+					.transfer(recipient, amount)
+					.map_err(|_| MarketError::TransferFailed)?;
+			}
+
+			if let (Some(referrer), true) = (referrer, referral_reward > 0) {
+				self.env()
+					.transfer(referrer, referral_reward)
+					.map_err(|_| MarketError::TransferFailed)?;
+				self.emit_event(ReferralRewardPaid { referrer, amount: referral_reward });
+			}
+
+			Ok(())
+		}
+
+		// Returns the share of the market fee paid to the referrer of a sale.
+		pub(crate) fn calculate_referral_reward(
+			&self,
+			fee: Balance,
+		) -> Result<Balance, MarketError> {
+			let referral_rate =
+				FixedU128::checked_from_rational(self.referral_rate, MAX_BASIS_POINTS)
+					.ok_or(MarketError::ArithmeticError)?;
+
+			Ok(referral_rate.saturating_mul_int(fee))
+		}
+
+		// Split the sale proceeds of a listing among its recipients. Any remainder left due to
+		// rounding is paid to the sale recipient.
+		pub(crate) fn calculate_payouts(
+			&self,
+			listing: &Listing,
+			proceeds: Balance,
+		) -> Result<Vec<(AccountId, Balance)>, MarketError> {
+			let Some(split) = &listing.proceeds_split else {
+				return Ok(ink::prelude::vec![(listing.sale_recepient, proceeds)])
+			};
+
+			let mut payouts = Vec::new();
+			let mut remainder = proceeds;
+			for (recipient, share) in split {
+				let amount = FixedU128::checked_from_rational(*share, MAX_BASIS_POINTS)
+					.ok_or(MarketError::ArithmeticError)?
+					.saturating_mul_int(proceeds);
+
+				remainder = remainder.saturating_sub(amount);
+				payouts.push((*recipient, amount));
+			}
+
+			if remainder > 0 {
+				payouts.push((listing.sale_recepient, remainder));
+			}
+
+			Ok(payouts)
+		}
+
+		// Record a completed sale in the sale history and update the market statistics. Once the
+		// history is full, the oldest sale gets overwritten.
+		pub(crate) fn record_sale(
+			&mut self,
+			region_id: RawRegionId,
+			region: &Region,
+			listing: &Listing,
+			buyer: AccountId,
+			price: Balance,
+		) {
+			let sale = Sale {
+				region_id,
+				price,
+				buyer,
+				seller: listing.seller,
+				timeslice: self.current_timeslice(),
+			};
+
+			// Sales paid for in a PSP22 token are not comparable with the rest, so they are not
+			// accounted for in the statistics.
+			if listing.payment_asset.is_none() {
+				let average = if self.stats.total_volume == 0 {
+					price
+				} else {
+					self.stats
+						.average_sale_price
+						.saturating_mul(AVERAGE_SALE_PRICE_WINDOW - 1)
+						.saturating_add(price) /
+						AVERAGE_SALE_PRICE_WINDOW
+				};
+
+				self.stats.total_volume = self.stats.total_volume.saturating_add(price);
+				self.stats.average_sale_price = average;
+
+				self.record_comparable_sale(region, price);
+			}
+
+			let index = self.sale_count % MAX_SALE_HISTORY as u64;
+			self.sales.insert(index as u32, &sale);
+			self.sale_count = self.sale_count.saturating_add(1);
+
+			self.report_seller_event(listing.seller, SellerEvent::Sale);
+		}
+
+		// Update the average timeslice price paid for regions in the duration bucket of the sold
+		// region.
+		//
+		// The price paid is normalized to the price of a single timeslice of a whole core, so
+		// that it is comparable with the `timeslice_price` of listings.
+		fn record_comparable_sale(&mut self, region: &Region, price: Balance) {
+			let remaining_timeslices =
+				region.end.saturating_sub(self.current_timeslice().max(region.begin));
+			let active_bits = region.mask.count_ones();
+			if remaining_timeslices == 0 || active_bits == 0 {
+				return
+			}
+
+			let timeslice_price = price.saturating_mul(CORE_MASK_BIT_LEN as Balance) /
+				(Balance::from(active_bits) * Balance::from(remaining_timeslices));
+
+			let bucket = duration_bucket(region.end.saturating_sub(region.begin));
+			let average = match self.comparable_sales.get(bucket) {
+				Some(average) =>
+					average
+						.saturating_mul(AVERAGE_SALE_PRICE_WINDOW - 1)
+						.saturating_add(timeslice_price) /
+						AVERAGE_SALE_PRICE_WINDOW,
+				None => timeslice_price,
+			};
+			self.comparable_sales.insert(bucket, &average);
+
+			self.report_sale(bucket, timeslice_price);
+		}
+
+		// Report the sale to the sale oracle, if configured.
+		//
+		// A failing oracle must not prevent regions from being sold, so the outcome of the call is
+		// ignored.
+		fn report_sale(&self, bucket: u8, timeslice_price: Balance) {
+			if let Some(oracle) = self.sale_oracle {
+				let _ = SaleRecorderRef::record_sale_builder(&oracle, bucket, timeslice_price)
+					.try_invoke();
+			}
+		}
+
+		// Report an event concerning a seller to the reputation registry, if configured.
+		//
+		// Same as with the sale oracle, the outcome of the call is ignored.
+		fn report_seller_event(&self, seller: AccountId, event: SellerEvent) {
+			if let Some(registry) = self.reputation_registry {
+				let _ =
+					ReputationRecorderRef::record_builder(&registry, seller, event).try_invoke();
+			}
+		}
+
+		// Suggest a price for the region by blending the per timeslice price the region would be
+		// valued at by the market with the average paid for comparable regions.
+		//
+		// The region is valued at its own `timeslice_price` if listed at a fixed price, otherwise
+		// at the floor price of its core.
+		pub(crate) fn calculate_appraisal(
+			&self,
+			region_id: RawRegionId,
+			region: Region,
+		) -> Result<Balance, MarketError> {
+			ensure!(region.end > self.current_timeslice(), MarketError::RegionExpired);
+
+			let model_price = match self.listings.get(region_id) {
+				Some(listing)
+					if listing.pricing == PricingStrategy::MaskDecay &&
+						listing.payment_asset.is_none() &&
+						self.ensure_not_auctioned(region_id).is_ok() =>
+					Some(listing.timeslice_price),
+				_ => self.floor_price(region.core),
+			};
+			let comparable_price = self
+				.comparable_sales
+				.get(duration_bucket(region.end.saturating_sub(region.begin)));
+
+			let timeslice_price = match (model_price, comparable_price) {
+				(Some(model), Some(comparable)) => model.saturating_add(comparable) / 2,
+				(Some(price), None) | (None, Some(price)) => price,
+				(None, None) => return Err(MarketError::AppraisalUnavailable),
+			};
+
+			self.calculate_mask_decay_price(region, timeslice_price)
+		}
+
+		// Purchase the listed region on behalf of the first open buy order it matches, if any.
+		fn fill_buy_order(&mut self, id: Id) -> Result<(), MarketError> {
 			let Id::U128(region_id) = id else { return Err(MarketError::InvalidRegionId) };
 			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
 
 			let metadata =
 				RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id.clone())
 					.map_err(MarketError::XcRegionsMetadataError)?;
+			let price = self.calculate_listing_price(metadata.region().clone(), listing.clone())?;
+
+			let Some(order_id) = self.find_matching_buy_order(metadata.region(), &listing, price)
+			else {
+				return Ok(())
+			};
+			let mut order = self.buy_orders.get(order_id).ok_or(MarketError::BuyOrderNotFound)?;
+
+			order.escrow = order.escrow.saturating_sub(price);
+			order.count = order.count.saturating_sub(1);
+			let leftover = if order.count == 0 {
+				self.remove_buy_order(order_id);
+				order.escrow
+			} else {
+				self.buy_orders.insert(order_id, &order);
+				0
+			};
 
-			let price = self.calculate_region_price(metadata.region, listing.clone())?;
-			ensure!(transferred_value >= price, MarketError::InsufficientFunds);
-
-			ensure!(listing.metadata_version == metadata_version, MarketError::MetadataNotMatching);
+			self.remove_from_sale(region_id, metadata.region().end)?;
+			self.record_sale(region_id, metadata.region(), &listing, order.buyer, price);
 
-			// Transfer the region to the buyer.
 			PSP34Ref::transfer(
 				&self.config.xc_regions_contract,
-				caller,
+				order.buyer,
 				id.clone(),
 				Default::default(),
 			)
 			.map_err(MarketError::XcRegionsPsp34Error)?;
 
-			// Remove the region from sale:
-			self.remove_from_sale(region_id)?;
-
-			// Transfer the tokens to the sale recipient.
-			self.env()
-				.transfer(listing.sale_recepient, price)
-				.map_err(|_| MarketError::TransferFailed)?;
+			self.pay_sale_recepient(&listing, self.env().account_id(), price, None)?;
 
-			self.emit_event(RegionPurchased { region_id, buyer: caller, total_price: price });
+			// Return the remaining escrow once the order is completely filled.
+			if leftover > 0 {
+				self.env()
+					.transfer(order.buyer, leftover)
+					.map_err(|_| MarketError::TransferFailed)?;
+			}
 
+			self.emit_event(BuyOrderFilled { order_id, region_id, price });
 			Ok(())
 		}
-	}
 
-	// Internal functions:
-	impl CoretimeMarket {
-		pub(crate) fn calculate_region_price(
+		// Returns the first open buy order, in the order of placement, that the listed region
+		// matches.
+		pub(crate) fn find_matching_buy_order(
 			&self,
-			region: Region,
-			listing: Listing,
-		) -> Result<Balance, MarketError> {
-			let current_timeslice = self.current_timeslice();
+			region: &Region,
+			listing: &Listing,
+			price: Balance,
+		) -> Option<u32> {
+			// Only regular listings paid for in the native token can fill buy orders.
+			if listing.pricing != PricingStrategy::MaskDecay || listing.payment_asset.is_some() {
+				return None
+			}
 
-			let duration = region.end.saturating_sub(region.begin);
+			self.open_buy_orders.get_or_default().into_iter().find(|order_id| {
+				self.buy_orders.get(order_id).map_or(false, |order| {
+					order.matches(region, listing.timeslice_price, price) &&
+						listing.can_purchase(&order.buyer)
+				})
+			})
+		}
 
-			let core_occupancy =
-				FixedU128::checked_from_rational(region.mask.count_ones(), CORE_MASK_BIT_LEN)
-					.ok_or(MarketError::ArithmeticError)?;
+		fn remove_buy_order(&mut self, order_id: u32) {
+			self.buy_orders.remove(order_id);
 
-			let per_timeslice_price = (core_occupancy * listing.timeslice_price.into())
-				.into_inner()
-				.saturating_div(FixedU128::accuracy());
+			let mut open_orders = self.open_buy_orders.get_or_default();
+			open_orders.retain(|id| *id != order_id);
+			self.open_buy_orders.set(&open_orders);
+		}
 
-			if current_timeslice < region.begin {
-				// The region didn't start yet, so there is no value lost.
-				let price = per_timeslice_price.saturating_mul(duration.into());
+		// Remove an offer from storage and return the offered amount.
+		fn take_offer(
+			&mut self,
+			region_id: RawRegionId,
+			bidder: AccountId,
+		) -> Result<Balance, MarketError> {
+			let amount = self.offers.get((region_id, bidder)).ok_or(MarketError::OfferNotFound)?;
+			self.offers.remove((region_id, bidder));
 
-				return Ok(price);
+			let mut bidders = self.region_bidders.get(region_id).unwrap_or_default();
+			bidders.retain(|b| *b != bidder);
+			if bidders.is_empty() {
+				self.region_bidders.remove(region_id);
+			} else {
+				self.region_bidders.insert(region_id, &bidders);
 			}
 
-			let remaining_timeslices = region.end.saturating_sub(current_timeslice);
-			let price = per_timeslice_price.saturating_mul(remaining_timeslices.into());
+			Ok(amount)
+		}
 
-			Ok(price)
+		pub(crate) fn calculate_purge_reward(
+			&self,
+			deposit: Balance,
+		) -> Result<Balance, MarketError> {
+			let reward_rate = FixedU128::checked_from_rational(PURGE_REWARD_RATE, MAX_BASIS_POINTS)
+				.ok_or(MarketError::ArithmeticError)?;
+
+			Ok(reward_rate.saturating_mul_int(deposit))
+		}
+
+		// Add a region to the list of regions on sale
+		pub(crate) fn add_to_sale(
+			&mut self,
+			region_id: RawRegionId,
+			region_end: Timeslice,
+			listing: Listing,
+		) {
+			let mut listed_regions = self.listed_regions.get_or_default();
+			self.listed_region_indices.insert(&region_id, &(listed_regions.len() as u32));
+			listed_regions.push(region_id);
+			self.listed_regions.set(&listed_regions);
+
+			let mut seller_listings = self.seller_listings.get(listing.seller).unwrap_or_default();
+			seller_listings.push(region_id);
+			self.seller_listings.insert(listing.seller, &seller_listings);
+
+			let mut ending_regions = self.listings_by_end.get(region_end).unwrap_or_default();
+			ending_regions.push(region_id);
+			self.listings_by_end.insert(region_end, &ending_regions);
+
+			self.insert_core_listing(region_id, listing.timeslice_price);
+
+			self.listings.insert(&region_id, &listing);
+		}
+
+		// Place the region among the listings of its core so that they remain sorted by price.
+		//
+		// If the region is already present, it is moved to the position matching its new price.
+		fn insert_core_listing(&mut self, region_id: RawRegionId, timeslice_price: Balance) {
+			let core = RegionId::from(region_id).core;
+			let mut core_listings = self.core_listings.get(core).unwrap_or_default();
+			core_listings.retain(|r| *r != region_id);
+
+			// Listings with the same price are kept in the order they were listed.
+			let position = core_listings.partition_point(|r| {
+				self.listings.get(r).map_or(0, |listing| listing.timeslice_price) <= timeslice_price
+			});
+			core_listings.insert(position, region_id);
+			self.core_listings.insert(core, &core_listings);
 		}
 
 		// Remove a region from sale
-		fn remove_from_sale(&mut self, region_id: RawRegionId) -> Result<(), MarketError> {
-			let region_index = self
-				.listed_regions
-				.get_or_default()
-				.iter()
-				.position(|r| *r == region_id)
-				.ok_or(MarketError::RegionNotListed)?;
+		pub(crate) fn remove_from_sale(
+			&mut self,
+			region_id: RawRegionId,
+			region_end: Timeslice,
+		) -> Result<(), MarketError> {
+			let listing = self.listings.get(&region_id).ok_or(MarketError::RegionNotListed)?;
+			let region_index =
+				self.listed_region_indices.get(&region_id).ok_or(MarketError::RegionNotListed)?;
 
 			let mut listed_regions = self.listed_regions.get_or_default();
-			listed_regions.remove(region_index);
+			listed_regions.swap_remove(region_index as usize);
+
+			// The last region was moved to the position of the removed one, so its index needs
+			// to be updated.
+			if let Some(moved_region) = listed_regions.get(region_index as usize) {
+				self.listed_region_indices.insert(moved_region, &region_index);
+			}
+
 			self.listed_regions.set(&listed_regions);
+			self.listed_region_indices.remove(&region_id);
+			self.listings.remove(&region_id);
+			// The escrow of the auction bidders is kept under the identifier of the auction, so
+			// that it can be withdrawn without being carried over to a later auction of the region.
+			self.candle_auctions.remove(region_id);
+			self.candle_bids.remove(region_id);
+			self.sealed_auctions.remove(region_id);
+			self.escrow_terms.remove(region_id);
+
+			let mut seller_listings = self.seller_listings.get(listing.seller).unwrap_or_default();
+			seller_listings.retain(|r| *r != region_id);
+			if seller_listings.is_empty() {
+				self.seller_listings.remove(listing.seller);
+			} else {
+				self.seller_listings.insert(listing.seller, &seller_listings);
+			}
+
+			let mut ending_regions = self.listings_by_end.get(region_end).unwrap_or_default();
+			ending_regions.retain(|r| *r != region_id);
+			if ending_regions.is_empty() {
+				self.listings_by_end.remove(region_end);
+			} else {
+				self.listings_by_end.insert(region_end, &ending_regions);
+			}
+
+			let core = RegionId::from(region_id).core;
+			let mut core_listings = self.core_listings.get(core).unwrap_or_default();
+			core_listings.retain(|r| *r != region_id);
+			if core_listings.is_empty() {
+				self.core_listings.remove(core);
+			} else {
+				self.core_listings.insert(core, &core_listings);
+			}
 
 			Ok(())
 		}
 
-		#[cfg(not(test))]
+		// Randomly choose the block at which a candle auction is retroactively closed.
+		//
+		// The seed is derived from the relay chain randomness, which comes from the VRF outputs of
+		// the relay chain block authors. Unlike the bids, it can't be influenced by the bidders.
+		//
+		// NOTE: The relay chain randomness only changes once per epoch, so the outcome doesn't
+		// depend on the block in which the auction is settled within the same epoch.
+		pub(crate) fn candle_close_block(
+			&self,
+			auction: &CandleAuction,
+			randomness: [u8; 32],
+		) -> BlockNumber {
+			let mut seed = [0u8; 32];
+			ink::env::hash_encoded::<Blake2x256, _>(&(auction.id, randomness), &mut seed);
+
+			let ending_period = auction.end.saturating_sub(auction.ending_period_start);
+			let offset = u32::from_le_bytes([seed[0], seed[1], seed[2], seed[3]]) %
+				ending_period.saturating_add(1);
+
+			auction.ending_period_start.saturating_add(offset)
+		}
+
 		pub(crate) fn current_timeslice(&self) -> Timeslice {
-			let latest_rc_block =
-				self.env().extension().relay_chain_block_number().unwrap_or_default();
-			(latest_rc_block / self.config.timeslice_period).saturated_into()
+			(self.relay_block_number() / self.config.timeslice_period).saturated_into()
+		}
+
+		#[cfg(not(test))]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().extension().relay_chain_block_number().unwrap_or_default()
 		}
 
 		#[cfg(test)]
-		pub(crate) fn current_timeslice(&self) -> Timeslice {
-			let latest_block = self.env().block_number();
-			(latest_block / self.config.timeslice_period).saturated_into()
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().block_number()
+		}
+
+		#[cfg(not(test))]
+		pub(crate) fn relay_randomness(&self) -> Result<[u8; 32], MarketError> {
+			self.env()
+				.extension()
+				.relay_chain_randomness()
+				.map_err(|_| MarketError::RandomnessUnavailable)
+		}
+
+		#[cfg(test)]
+		pub(crate) fn relay_randomness(&self) -> Result<[u8; 32], MarketError> {
+			Ok(Default::default())
 		}
 
 		fn emit_event<Event: Into<<CoretimeMarket as ContractEventBase>::Type>>(&self, e: Event) {
@@ -447,7 +3677,7 @@ pub mod coretime_market {
 		use super::*;
 		use environment::ExtendedEnvironment;
 		use ink_e2e::MessageBuilder;
-		use primitives::coretime::TIMESLICE_PERIOD;
+		use primitives::{address_of, coretime::TIMESLICE_PERIOD};
 		use xc_regions::xc_regions::XcRegionsRef;
 
 		type E2EResult<T> = Result<T, Box<dyn std::error::Error>>;
@@ -456,15 +3686,20 @@ pub mod coretime_market {
 
 		#[ink_e2e::test(environment = ExtendedEnvironment)]
 		async fn constructor_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-			let constructor = XcRegionsRef::new();
+			let constructor = XcRegionsRef::new(42);
 			let xc_regions_acc_id = client
 				.instantiate("xc-regions", &ink_e2e::alice(), constructor, 0, None)
 				.await
 				.expect("instantiate failed")
 				.account_id;
 
-			let constructor =
-				CoretimeMarketRef::new(xc_regions_acc_id, REQUIRED_DEPOSIT, TIMESLICE_PERIOD);
+			let constructor = CoretimeMarketRef::new(
+				xc_regions_acc_id,
+				REQUIRED_DEPOSIT,
+				TIMESLICE_PERIOD,
+				0,
+				address_of!(Alice),
+			);
 			let market_acc_id = client
 				.instantiate("coretime-market", &ink_e2e::alice(), constructor, 0, None)
 				.await
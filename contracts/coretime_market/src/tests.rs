@@ -1,16 +1,33 @@
-use crate::{coretime_market::CoretimeMarket, types::Listing};
+use crate::{
+	coretime_market::CoretimeMarket,
+	types::{
+		duration_bucket, BuyOrder, CandleAuction, CandleBid, Escrow, EscrowTerms, ExponentialDecay,
+		FeeConfig, LinearDecay, Listing, ListingLimits, MarketError, MarketStats, OracleConfig,
+		OraclePricing, PriceQuote, PricingStrategy, Sale, SealedBidAuction, MAX_EXPIRY_LOOKAHEAD,
+		MAX_SALE_HISTORY, STORAGE_VERSION,
+	},
+};
 use ink::env::{
-	test::{default_accounts, DefaultAccounts},
+	test::{
+		default_accounts, get_account_balance, set_account_balance, set_caller,
+		set_value_transferred, DefaultAccounts,
+	},
 	DefaultEnvironment,
 };
-use openbrush::traits::BlockNumber;
-use primitives::coretime::{CoreMask, Region, Timeslice, TIMESLICE_PERIOD};
+use openbrush::{
+	contracts::traits::psp34::Id,
+	traits::{AccountId, BlockNumber},
+};
+use primitives::{
+	assert_ok,
+	coretime::{CoreMask, RawRegionId, Region, RegionId, Timeslice, TIMESLICE_PERIOD},
+};
 
 #[ink::test]
 fn calculate_region_price_works() {
 	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
 
-	let market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD);
+	let market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
 	// Works for regions which haven't yet started.
 
 	// complete coremask, so 80 active bits.
@@ -22,6 +39,12 @@ fn calculate_region_price_works() {
 				timeslice_price: 10,
 				sale_recepient: charlie,
 				metadata_version: 0,
+				deposit: 0,
+				expiry: None,
+				proceeds_split: None,
+				pricing: PricingStrategy::MaskDecay,
+				payment_asset: None,
+				allowed_buyers: None,
 			}
 		),
 		Ok(80) // 8 * 10
@@ -36,6 +59,12 @@ fn calculate_region_price_works() {
 				timeslice_price: 10,
 				sale_recepient: charlie,
 				metadata_version: 0,
+				deposit: 0,
+				expiry: None,
+				proceeds_split: None,
+				pricing: PricingStrategy::MaskDecay,
+				payment_asset: None,
+				allowed_buyers: None,
 			}
 		),
 		Ok(40) // (10 / 2) * 8
@@ -51,6 +80,12 @@ fn calculate_region_price_works() {
 				timeslice_price: 10,
 				sale_recepient: charlie,
 				metadata_version: 0,
+				deposit: 0,
+				expiry: None,
+				proceeds_split: None,
+				pricing: PricingStrategy::MaskDecay,
+				payment_asset: None,
+				allowed_buyers: None,
 			}
 		),
 		// 1/4th of the region is wasted, so the price is decreased proportionally.
@@ -67,12 +102,1312 @@ fn calculate_region_price_works() {
 				timeslice_price: 10,
 				sale_recepient: charlie,
 				metadata_version: 0,
+				deposit: 0,
+				expiry: None,
+				proceeds_split: None,
+				pricing: PricingStrategy::MaskDecay,
+				payment_asset: None,
+				allowed_buyers: None,
 			}
 		),
 		Ok(0)
 	);
 }
 
+#[ink::test]
+fn calculate_linear_decay_price_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+
+	let market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	let decay =
+		LinearDecay { start_price: 100, floor_price: 40, decay_per_timeslice: 10, start: 2 };
+
+	// The price doesn't decay before the start.
+	assert_eq!(market.calculate_linear_decay_price(decay.clone()), 100);
+
+	advance_n_blocks(timeslice_to_block_number(2)); // the current timeslice will be 2.
+	assert_eq!(market.calculate_linear_decay_price(decay.clone()), 100);
+
+	advance_n_blocks(timeslice_to_block_number(3)); // the current timeslice will be 5.
+	assert_eq!(market.calculate_linear_decay_price(decay.clone()), 70);
+
+	// The price can't go below the floor price.
+	advance_n_blocks(timeslice_to_block_number(10)); // the current timeslice will be 15.
+	assert_eq!(market.calculate_linear_decay_price(decay), 40);
+}
+
+#[ink::test]
+fn calculate_exponential_decay_price_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+
+	let market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	// 10% of the price is lost every timeslice.
+	let decay =
+		ExponentialDecay { start_price: 1_000, floor_price: 500, decay_rate: 1_000, start: 0 };
+
+	assert_eq!(market.calculate_exponential_decay_price(decay.clone()), Ok(1_000));
+
+	advance_n_blocks(timeslice_to_block_number(1)); // the current timeslice will be 1.
+	assert_eq!(market.calculate_exponential_decay_price(decay.clone()), Ok(900));
+
+	advance_n_blocks(timeslice_to_block_number(1)); // the current timeslice will be 2.
+	assert_eq!(market.calculate_exponential_decay_price(decay.clone()), Ok(810));
+
+	// The price can't go below the floor price.
+	advance_n_blocks(timeslice_to_block_number(8)); // the current timeslice will be 10.
+	assert_eq!(market.calculate_exponential_decay_price(decay), Ok(500));
+}
+
+#[ink::test]
+fn calculate_listing_price_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+
+	let market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	let region = Region { begin: 2, end: 10, core: 0, mask: CoreMask::complete() };
+
+	// Mask based decay:
+	assert_eq!(market.calculate_listing_price(region.clone(), listing(charlie)), Ok(80));
+
+	// Linear decay:
+	let pricing = PricingStrategy::Linear(LinearDecay {
+		start_price: 100,
+		floor_price: 0,
+		decay_per_timeslice: 10,
+		start: 0,
+	});
+	assert_eq!(
+		market.calculate_listing_price(region.clone(), Listing { pricing, ..listing(charlie) }),
+		Ok(100)
+	);
+
+	// Exponential decay:
+	let pricing = PricingStrategy::Exponential(ExponentialDecay {
+		start_price: 200,
+		floor_price: 0,
+		decay_rate: 1_000,
+		start: 0,
+	});
+	assert_eq!(
+		market.calculate_listing_price(region.clone(), Listing { pricing, ..listing(charlie) }),
+		Ok(200)
+	);
+
+	// Flat price:
+	advance_n_blocks(timeslice_to_block_number(5)); // the current timeslice will be 5.
+	let pricing = PricingStrategy::Flat(300);
+	assert_eq!(
+		market.calculate_listing_price(region, Listing { pricing, ..listing(charlie) }),
+		Ok(300)
+	);
+}
+
+#[ink::test]
+fn appraisal_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	let region_id =
+		|begin, core| RawRegionId::from(RegionId { begin, core, mask: CoreMask::complete() });
+	let region = Region { begin: 0, end: 10, core: 0, mask: CoreMask::complete() };
+
+	assert_eq!(
+		market.calculate_appraisal(region_id(0, 0), region.clone()),
+		Err(MarketError::AppraisalUnavailable)
+	);
+
+	// A whole core sold for 1000 with 10 timeslices remaining.
+	market.record_sale(region_id(1, 1), &region, &listing(charlie), bob, 1_000);
+	assert_eq!(market.comparable_sale_price(duration_bucket(10)), Some(100));
+	assert_eq!(market.comparable_sale_price(duration_bucket(20)), None);
+	assert_eq!(market.calculate_appraisal(region_id(0, 0), region.clone()), Ok(1_000));
+
+	// The floor price of the core is blended with the comparable sales.
+	market.add_to_sale(region_id(2, 0), 10, Listing { timeslice_price: 50, ..listing(charlie) });
+	assert_eq!(market.calculate_appraisal(region_id(0, 0), region.clone()), Ok(750));
+	assert_eq!(
+		market.calculate_appraisal(
+			region_id(0, 0),
+			Region { mask: CoreMask::from_chunk(0, 40), ..region.clone() }
+		),
+		Ok(375)
+	);
+
+	// Auctioned regions are valued at the floor price rather than their unused listing price.
+	market.add_to_sale(region_id(5, 0), 10, Listing { timeslice_price: 0, ..listing(charlie) });
+	market.candle_auctions.insert(
+		region_id(5, 0),
+		&CandleAuction { id: 0, min_bid: 0, ending_period_start: 5, end: 10 },
+	);
+	assert_eq!(market.calculate_appraisal(region_id(5, 0), region.clone()), Ok(750));
+
+	// The average is updated with every sale.
+	market.record_sale(region_id(3, 1), &region, &listing(charlie), bob, 3_000);
+	assert_eq!(market.comparable_sale_price(duration_bucket(10)), Some(110));
+
+	// Sales paid for in a PSP22 token are not taken into account.
+	market.record_sale(
+		region_id(4, 1),
+		&region,
+		&Listing { payment_asset: Some(bob), ..listing(charlie) },
+		bob,
+		3_000,
+	);
+	assert_eq!(market.comparable_sale_price(duration_bucket(10)), Some(110));
+}
+
+#[ink::test]
+fn duration_bucket_works() {
+	assert_eq!(duration_bucket(0), 0);
+	assert_eq!(duration_bucket(1), 1);
+	assert_eq!(duration_bucket(2), 2);
+	assert_eq!(duration_bucket(3), 2);
+	assert_eq!(duration_bucket(4), 3);
+	assert_eq!(duration_bucket(10), 4);
+}
+
+#[ink::test]
+fn calculate_quote_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	// 10% fee:
+	let market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 1_000, charlie);
+
+	let region = Region { begin: 2, end: 10, core: 0, mask: CoreMask::complete() };
+
+	advance_n_blocks(timeslice_to_block_number(6));
+	assert_eq!(
+		market.calculate_quote(region.clone(), listing(charlie)),
+		Ok(PriceQuote {
+			active_bits: 80,
+			remaining_timeslices: 4,
+			timeslice_price: 10,
+			undecayed_price: 80,
+			decay: 40,
+			fee: 4,
+			seller_proceeds: 36,
+			price: 40,
+			payment_asset: None,
+		})
+	);
+
+	// Can't quote expired regions.
+	advance_n_blocks(timeslice_to_block_number(4));
+	assert_eq!(market.calculate_quote(region, listing(charlie)), Err(MarketError::RegionExpired));
+}
+
+#[ink::test]
+fn private_listings_work() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+
+	// Anyone can purchase a public listing.
+	assert!(listing(charlie).can_purchase(&bob));
+	assert!(listing(charlie).can_purchase(&eve));
+
+	let private_listing = Listing { allowed_buyers: Some(vec![bob]), ..listing(charlie) };
+	assert!(private_listing.can_purchase(&bob));
+	assert!(!private_listing.can_purchase(&eve));
+
+	// Offers can only be placed by the allowed buyers.
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	market.add_to_sale(0, 10, private_listing);
+
+	set_value_transferred::<DefaultEnvironment>(100);
+	set_caller::<DefaultEnvironment>(eve);
+	assert_eq!(market.make_offer(Id::U128(0)), Err(MarketError::NotAllowed));
+	set_caller::<DefaultEnvironment>(bob);
+	assert_ok!(market.make_offer(Id::U128(0)));
+}
+
+#[ink::test]
+fn ensure_metadata_consistent_works() {
+	let region_id = RawRegionId::from(RegionId { begin: 2, core: 1, mask: CoreMask::complete() });
+	let region = Region { begin: 2, end: 10, core: 1, mask: CoreMask::complete() };
+
+	assert_ok!(CoretimeMarket::ensure_metadata_consistent(region_id, &region));
+
+	// The mask of an interlaced region is a subset of the mask encoded in the region id.
+	assert_ok!(CoretimeMarket::ensure_metadata_consistent(
+		region_id,
+		&Region { mask: CoreMask::from_chunk(0, 40), ..region.clone() }
+	));
+
+	// Every other component encoded in the region id must match.
+	assert_eq!(
+		CoretimeMarket::ensure_metadata_consistent(
+			region_id,
+			&Region { begin: 3, ..region.clone() }
+		),
+		Err(MarketError::RegionIdMismatch)
+	);
+	assert_eq!(
+		CoretimeMarket::ensure_metadata_consistent(
+			region_id,
+			&Region { core: 0, ..region.clone() }
+		),
+		Err(MarketError::RegionIdMismatch)
+	);
+	let interlaced_id =
+		RawRegionId::from(RegionId { begin: 2, core: 1, mask: CoreMask::from_chunk(0, 40) });
+	assert_eq!(
+		CoretimeMarket::ensure_metadata_consistent(
+			interlaced_id,
+			&Region { mask: CoreMask::from_chunk(40, 80), ..region }
+		),
+		Err(MarketError::RegionIdMismatch)
+	);
+}
+
+#[ink::test]
+fn sale_releases_listing_deposit() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+	// 10% fee:
+	let mut market = CoretimeMarket::new(charlie, 50, TIMESLICE_PERIOD, 1_000, eve);
+	let contract = ink::env::account_id::<DefaultEnvironment>();
+
+	// The market holds the listing deposit and the price paid by the buyer.
+	set_account_balance::<DefaultEnvironment>(contract, 150);
+	let bob_balance = get_account_balance::<DefaultEnvironment>(bob).unwrap();
+	let charlie_balance = get_account_balance::<DefaultEnvironment>(charlie).unwrap();
+
+	let listing = Listing { deposit: 50, sale_recepient: charlie, ..listing(bob) };
+	assert_ok!(market.pay_sale_recepient(&listing, contract, 100, None));
+
+	// The seller gets the deposit back and the sale recipient receives the proceeds.
+	assert_eq!(get_account_balance::<DefaultEnvironment>(bob), Ok(bob_balance + 50));
+	assert_eq!(get_account_balance::<DefaultEnvironment>(charlie), Ok(charlie_balance + 90));
+	// Only the fee remains in the market.
+	assert_eq!(get_account_balance::<DefaultEnvironment>(contract), Ok(10));
+	assert_eq!(market.accrued_fees(), 10);
+}
+
+#[ink::test]
+fn calculate_fee_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+
+	// No fee is taken when the rate is zero.
+	let market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	assert_eq!(market.calculate_fee(1_000), Ok(0));
+
+	// 2.5% fee:
+	let market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 250, charlie);
+	assert_eq!(market.calculate_fee(1_000), Ok(25));
+	assert_eq!(market.calculate_fee(0), Ok(0));
+
+	// 100% fee:
+	let market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 10_000, charlie);
+	assert_eq!(market.calculate_fee(1_000), Ok(1_000));
+}
+
+#[ink::test]
+fn referral_rewards_work() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 250, charlie);
+	assert_eq!(market.referral_rate(), 0);
+	assert_eq!(market.calculate_referral_reward(25), Ok(0));
+
+	// Only the admin can update the referral rate.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.set_referral_rate(2_000), Err(MarketError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(alice);
+	// The referrer can't receive more than the whole fee.
+	assert_eq!(market.set_referral_rate(10_001), Err(MarketError::InvalidFeeRate));
+
+	// 20% of the fee is paid to the referrer:
+	assert_ok!(market.set_referral_rate(2_000));
+	assert_eq!(market.referral_rate(), 2_000);
+	let fee = market.calculate_fee(1_000).unwrap();
+	assert_eq!(market.calculate_referral_reward(fee), Ok(5));
+}
+
+#[ink::test]
+fn set_fee_rate_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	assert_eq!(market.admin(), alice);
+
+	// Only the admin can update the fee rate.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.set_fee_rate(100), Err(MarketError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(alice);
+	// The fee rate can't exceed 100%.
+	assert_eq!(market.set_fee_rate(10_001), Err(MarketError::InvalidFeeRate));
+
+	assert_ok!(market.set_fee_rate(100));
+	assert_eq!(market.fee_config(), FeeConfig { rate: 100, recipient: charlie });
+
+	assert_ok!(market.set_fee_recipient(bob));
+	assert_eq!(market.fee_config(), FeeConfig { rate: 100, recipient: bob });
+}
+
+#[ink::test]
+fn calculate_oracle_price_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	let region = Region { begin: 2, end: 10, core: 0, mask: CoreMask::complete() };
+
+	// Without an oracle, the fallback price is used.
+	let pricing = OraclePricing { currency: 0, fallback_timeslice_price: Some(5) };
+	assert_eq!(market.calculate_oracle_price(region.clone(), listing(charlie), pricing), Ok(40));
+
+	// Without a fallback price, the region can't be priced.
+	let pricing = OraclePricing { currency: 0, fallback_timeslice_price: None };
+	assert_eq!(
+		market.calculate_oracle_price(region, listing(charlie), pricing),
+		Err(MarketError::OraclePriceUnavailable)
+	);
+
+	// Only the admin can configure the oracle.
+	let config = OracleConfig { oracle: bob, max_staleness: 10 };
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.set_oracle_config(Some(config.clone())), Err(MarketError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(market.set_oracle_config(Some(config.clone())));
+	assert_eq!(market.oracle_config(), Some(config));
+}
+
+#[ink::test]
+fn set_sale_oracle_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	assert_eq!(market.sale_oracle(), None);
+
+	// Only the admin can set the sale oracle.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.set_sale_oracle(Some(charlie)), Err(MarketError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(market.set_sale_oracle(Some(charlie)));
+	assert_eq!(market.sale_oracle(), Some(charlie));
+	assert_ok!(market.set_sale_oracle(None));
+	assert_eq!(market.sale_oracle(), None);
+}
+
+#[ink::test]
+fn set_reputation_registry_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	assert_eq!(market.reputation_registry(), None);
+
+	// Only the admin can set the reputation registry.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.set_reputation_registry(Some(charlie)), Err(MarketError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(market.set_reputation_registry(Some(charlie)));
+	assert_eq!(market.reputation_registry(), Some(charlie));
+}
+
+#[ink::test]
+fn listing_limits_work() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	assert_eq!(market.listing_limits(), ListingLimits::default());
+
+	let limits = ListingLimits { min_timeslice_price: 5, max_listings_per_account: Some(1) };
+
+	// Only the admin can update the listing limits.
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_eq!(market.set_listing_limits(limits.clone()), Err(MarketError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(market.set_listing_limits(limits.clone()));
+	assert_eq!(market.listing_limits(), limits);
+
+	// Regions can't be listed below the minimum price.
+	assert_eq!(market.list_region(Id::U128(0), 4, None), Err(MarketError::PriceTooLow));
+	assert_eq!(market.list_regions(vec![(Id::U128(0), 4, None)]), Err(MarketError::PriceTooLow));
+	assert_eq!(
+		market.list_region_escrowed(Id::U128(0), 4, 10, None, None),
+		Err(MarketError::PriceTooLow)
+	);
+
+	// The price of existing listings can't be lowered below the minimum either.
+	let region_id = RawRegionId::from(RegionId { begin: 0, core: 0, mask: CoreMask::complete() });
+	market.add_to_sale(region_id, 10, listing(charlie));
+
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_eq!(market.update_region_price(Id::U128(region_id), 4), Err(MarketError::PriceTooLow));
+	assert_eq!(market.relist_region(Id::U128(region_id), 4, None), Err(MarketError::PriceTooLow));
+	assert_ok!(market.update_region_price(Id::U128(region_id), 5));
+}
+
+#[ink::test]
+fn pausing_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	market.add_to_sale(0, 10, listing(charlie));
+
+	// Only the admin can pause the market.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.set_paused(true), Err(MarketError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(market.set_paused(true));
+	assert!(market.paused());
+
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.list_region(Id::U128(1), 10, None), Err(MarketError::MarketPaused));
+	set_value_transferred::<DefaultEnvironment>(100);
+	assert_eq!(market.make_offer(Id::U128(0)), Err(MarketError::MarketPaused));
+	assert_eq!(
+		market.purchase_region(Id::U128(0), 0, None, None, None),
+		Err(MarketError::MarketPaused)
+	);
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(market.set_paused(false));
+
+	set_caller::<DefaultEnvironment>(bob);
+	assert_ok!(market.make_offer(Id::U128(0)));
+}
+
+#[ink::test]
+fn migrate_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	assert_eq!(market.storage_version(), STORAGE_VERSION);
+	// A freshly deployed market doesn't require any migrations.
+	assert_eq!(market.migrate(), Err(MarketError::AlreadyMigrated));
+
+	market.storage_version = 0;
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.migrate(), Err(MarketError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(market.migrate());
+	assert_eq!(market.storage_version(), STORAGE_VERSION);
+}
+
+#[ink::test]
+fn deposit_asset_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+
+	let market = CoretimeMarket::new(charlie, 100, TIMESLICE_PERIOD, 0, charlie);
+	assert_eq!(market.deposit_asset(), None);
+
+	let mut market = CoretimeMarket::new_with_deposit_asset(
+		charlie,
+		100,
+		TIMESLICE_PERIOD,
+		0,
+		charlie,
+		Some(bob),
+	);
+	assert_eq!(market.deposit_asset(), Some(bob));
+
+	// The deposit can't be paid in the native token.
+	set_value_transferred::<DefaultEnvironment>(100);
+	assert_eq!(market.list_region(Id::U128(0), 10, None), Err(MarketError::MissingDeposit));
+}
+
+#[ink::test]
+fn set_listing_deposit_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut market = CoretimeMarket::new(charlie, 1_000, TIMESLICE_PERIOD, 0, charlie);
+	assert_eq!(market.listing_deposit_history(), vec![(0, 1_000)]);
+
+	// Only the admin can update the listing deposit.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.set_listing_deposit(2_000), Err(MarketError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(alice);
+	advance_n_blocks(5);
+	assert_ok!(market.set_listing_deposit(2_000));
+	assert_eq!(market.config.listing_deposit, 2_000);
+	assert_eq!(market.listing_deposit_history(), vec![(0, 1_000), (5, 2_000)]);
+}
+
+#[ink::test]
+fn listing_expiry_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	market.add_to_sale(0, 10, listing(charlie));
+
+	// Only the seller can set the expiry of a listing.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.set_listing_expiry(Id::U128(0), Some(5)), Err(MarketError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(charlie);
+	advance_n_blocks(2);
+	// The expiry must be in the future.
+	assert_eq!(market.set_listing_expiry(Id::U128(0), Some(2)), Err(MarketError::InvalidExpiry));
+	assert_ok!(market.set_listing_expiry(Id::U128(0), Some(5)));
+
+	let listing = market.listings.get(0).unwrap();
+	assert!(!market.listing_lapsed(&listing));
+	advance_n_blocks(3);
+	assert!(market.listing_lapsed(&listing));
+
+	// Removing the expiry makes the listing valid again.
+	assert_ok!(market.set_listing_expiry(Id::U128(0), None));
+	assert!(!market.listing_lapsed(&market.listings.get(0).unwrap()));
+}
+
+#[ink::test]
+fn relist_region_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	market.add_to_sale(0, 10, Listing { expiry: Some(5), ..listing(charlie) });
+	market.add_to_sale(1, 10, Listing { pricing: PricingStrategy::Flat(10), ..listing(charlie) });
+
+	// Only the seller can relist the region.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.relist_region(Id::U128(0), 20, None), Err(MarketError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_eq!(
+		market.relist_region(Id::U128(1), 20, None),
+		Err(MarketError::UnsupportedPricingStrategy)
+	);
+
+	assert_ok!(market.relist_region(Id::U128(0), 20, Some(eve)));
+	assert_eq!(
+		market.listings.get(0),
+		Some(Listing { timeslice_price: 20, sale_recepient: eve, ..listing(charlie) })
+	);
+}
+
+#[ink::test]
+fn proceeds_split_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	market.add_to_sale(0, 10, listing(charlie));
+	assert_eq!(market.calculate_payouts(&listing(charlie), 1_000), Ok(vec![(charlie, 1_000)]));
+
+	// Only the seller can split the proceeds.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(
+		market.set_proceeds_split(Id::U128(0), Some(vec![(bob, 10_000)])),
+		Err(MarketError::NotAllowed)
+	);
+
+	set_caller::<DefaultEnvironment>(charlie);
+	// The shares must add up to 100%.
+	assert_eq!(
+		market.set_proceeds_split(Id::U128(0), Some(vec![(bob, 5_000), (eve, 4_000)])),
+		Err(MarketError::InvalidProceedsSplit)
+	);
+	assert_eq!(
+		market.set_proceeds_split(Id::U128(0), Some(vec![])),
+		Err(MarketError::InvalidProceedsSplit)
+	);
+
+	assert_ok!(market.set_proceeds_split(Id::U128(0), Some(vec![(bob, 3_333), (eve, 6_667)])));
+	let listing = market.listings.get(0).unwrap();
+	// The remainder left due to rounding goes to the sale recipient.
+	assert_eq!(
+		market.calculate_payouts(&listing, 1_000),
+		Ok(vec![(bob, 333), (eve, 666), (charlie, 1)])
+	);
+	assert_eq!(market.calculate_payouts(&listing, 10_000), Ok(vec![(bob, 3_333), (eve, 6_667)]));
+}
+
+#[ink::test]
+fn calculate_purge_reward_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+
+	let market = CoretimeMarket::new(charlie, 1_000, TIMESLICE_PERIOD, 0, charlie);
+	assert_eq!(market.calculate_purge_reward(1_000), Ok(500));
+	assert_eq!(market.calculate_purge_reward(0), Ok(0));
+}
+
+#[ink::test]
+fn remove_from_sale_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	// Cannot remove a region that is not listed.
+	assert_eq!(market.remove_from_sale(0, 10), Err(MarketError::RegionNotListed));
+
+	market.add_to_sale(0, 10, listing(charlie));
+	market.add_to_sale(1, 10, listing(charlie));
+	market.add_to_sale(2, 10, listing(charlie));
+	assert_eq!(market.listed_regions(None), vec![0, 1, 2]);
+
+	// Removing a region moves the last region in its place.
+	assert_ok!(market.remove_from_sale(0, 10));
+	assert_eq!(market.listed_regions(None), vec![2, 1]);
+	assert_eq!(market.listed_region_indices.get(2), Some(0));
+	assert_eq!(market.listed_region_indices.get(1), Some(1));
+	assert_eq!(market.listed_region_indices.get(0), None);
+
+	// Removing the last region works.
+	assert_ok!(market.remove_from_sale(1, 10));
+	assert_eq!(market.listed_regions(None), vec![2]);
+	assert_eq!(market.listed_region_indices.get(2), Some(0));
+
+	assert_ok!(market.remove_from_sale(2, 10));
+	assert_eq!(market.listed_regions(None), vec![]);
+	assert_eq!(market.remove_from_sale(2, 10), Err(MarketError::RegionNotListed));
+}
+
+#[ink::test]
+fn seller_listings_work() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	assert_eq!(market.listings_of(charlie), vec![]);
+
+	market.add_to_sale(0, 10, listing(charlie));
+	market.add_to_sale(1, 10, listing(bob));
+	market.add_to_sale(2, 10, listing(charlie));
+
+	assert_eq!(market.listings_of(charlie), vec![0, 2]);
+	assert_eq!(market.listings_of(bob), vec![1]);
+	assert_eq!(market.listed_regions(Some(charlie)), vec![0, 2]);
+
+	assert_ok!(market.remove_from_sale(0, 10));
+	assert_eq!(market.listings_of(charlie), vec![2]);
+
+	assert_ok!(market.remove_from_sale(1, 10));
+	assert_eq!(market.listings_of(bob), vec![]);
+	assert!(market.seller_listings.get(bob).is_none());
+}
+
+#[ink::test]
+fn expiring_listings_work() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	market.add_to_sale(0, 5, listing(charlie));
+	market.add_to_sale(1, 10, listing(charlie));
+	market.add_to_sale(2, 5, listing(charlie));
+	market.add_to_sale(3, 20, listing(charlie));
+
+	assert_eq!(market.expiring_listings(4), vec![]);
+	assert_eq!(market.expiring_listings(5), vec![0, 2]);
+	assert_eq!(market.expiring_listings(10), vec![0, 2, 1]);
+
+	advance_n_blocks(timeslice_to_block_number(6)); // the current timeslice will be 6.
+													// Expired regions are not returned.
+	assert_eq!(market.expiring_listings(4), vec![1]);
+	assert_eq!(market.expiring_listings(14), vec![1, 3]);
+
+	assert_ok!(market.remove_from_sale(1, 10));
+	assert_eq!(market.expiring_listings(14), vec![3]);
+	assert!(market.listings_by_end.get(10).is_none());
+
+	// The lookahead is capped to bound the number of storage reads.
+	market.add_to_sale(4, 6 + MAX_EXPIRY_LOOKAHEAD + 1, listing(charlie));
+	assert_eq!(market.expiring_listings(Timeslice::MAX), vec![3]);
+}
+
+#[ink::test]
+fn offers_work() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	let contract = ink::env::account_id::<DefaultEnvironment>();
+
+	// Cannot place an offer on a region which is not listed.
+	set_caller::<DefaultEnvironment>(bob);
+	set_value_transferred::<DefaultEnvironment>(100);
+	assert_eq!(market.make_offer(Id::U128(0)), Err(MarketError::RegionNotListed));
+
+	market.add_to_sale(0, 10, listing(charlie));
+
+	// Cannot place an offer without sending any tokens.
+	set_value_transferred::<DefaultEnvironment>(0);
+	assert_eq!(market.make_offer(Id::U128(0)), Err(MarketError::InvalidOfferAmount));
+
+	set_value_transferred::<DefaultEnvironment>(100);
+	assert_ok!(market.make_offer(Id::U128(0)));
+	// A bidder can only have one offer placed on a region.
+	assert_eq!(market.make_offer(Id::U128(0)), Err(MarketError::OfferAlreadyExists));
+
+	set_caller::<DefaultEnvironment>(eve);
+	set_value_transferred::<DefaultEnvironment>(200);
+	assert_ok!(market.make_offer(Id::U128(0)));
+
+	assert_eq!(market.offers(Id::U128(0)), Ok(vec![(bob, 100), (eve, 200)]));
+
+	// Cancelling an offer returns the escrowed tokens.
+	set_account_balance::<DefaultEnvironment>(contract, 300);
+	set_caller::<DefaultEnvironment>(bob);
+	let bob_balance = get_account_balance::<DefaultEnvironment>(bob).unwrap();
+	assert_ok!(market.cancel_offer(Id::U128(0)));
+	assert_eq!(get_account_balance::<DefaultEnvironment>(bob), Ok(bob_balance + 100));
+	assert_eq!(market.offers(Id::U128(0)), Ok(vec![(eve, 200)]));
+
+	assert_eq!(market.cancel_offer(Id::U128(0)), Err(MarketError::OfferNotFound));
+
+	// Offers can't be accepted on lapsed listings.
+	market.add_to_sale(1, 10, Listing { expiry: Some(1), ..listing(charlie) });
+	advance_block();
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_eq!(market.accept_offer(Id::U128(1), eve), Err(MarketError::ListingExpired));
+}
+
+#[ink::test]
+fn buy_orders_work() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	let contract = ink::env::account_id::<DefaultEnvironment>();
+
+	// Cannot place a buy order without escrowing any tokens.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.place_buy_order(None, 0, 10, 10, 1), Err(MarketError::InvalidBuyOrder));
+
+	set_value_transferred::<DefaultEnvironment>(100);
+	assert_eq!(market.place_buy_order(None, 10, 10, 10, 1), Err(MarketError::InvalidBuyOrder));
+	assert_eq!(market.place_buy_order(None, 0, 10, 10, 0), Err(MarketError::InvalidBuyOrder));
+
+	assert_ok!(market.place_buy_order(Some(1), 0, 10, 10, 1));
+	assert_ok!(market.place_buy_order(None, 0, 10, 10, 2));
+	assert_eq!(market.open_buy_orders(), vec![0, 1]);
+	assert_eq!(
+		market.buy_order(1),
+		Some(BuyOrder {
+			buyer: bob,
+			core: None,
+			begin: 0,
+			end: 10,
+			max_timeslice_price: 10,
+			count: 2,
+			escrow: 100
+		})
+	);
+
+	let region = Region { begin: 2, end: 10, core: 0, mask: CoreMask::complete() };
+	// The first order only accepts regions on core 1.
+	assert_eq!(market.find_matching_buy_order(&region, &listing(charlie), 80), Some(1));
+	assert_eq!(
+		market.find_matching_buy_order(
+			&Region { core: 1, ..region.clone() },
+			&listing(charlie),
+			80
+		),
+		Some(0)
+	);
+	// The price can't exceed the escrow or the maximum price.
+	assert_eq!(market.find_matching_buy_order(&region, &listing(charlie), 101), None);
+	assert_eq!(
+		market.find_matching_buy_order(
+			&region,
+			&Listing { timeslice_price: 11, ..listing(charlie) },
+			80
+		),
+		None
+	);
+	// The region must be within the timeslice range of the order.
+	assert_eq!(
+		market.find_matching_buy_order(
+			&Region { end: 11, ..region.clone() },
+			&listing(charlie),
+			80
+		),
+		None
+	);
+
+	// Only the buyer can cancel the order.
+	set_caller::<DefaultEnvironment>(eve);
+	assert_eq!(market.cancel_buy_order(0), Err(MarketError::NotAllowed));
+
+	set_account_balance::<DefaultEnvironment>(contract, 200);
+	set_caller::<DefaultEnvironment>(bob);
+	let bob_balance = get_account_balance::<DefaultEnvironment>(bob).unwrap();
+	assert_ok!(market.cancel_buy_order(0));
+	assert_eq!(get_account_balance::<DefaultEnvironment>(bob), Ok(bob_balance + 100));
+	assert_eq!(market.open_buy_orders(), vec![1]);
+	assert_eq!(market.cancel_buy_order(0), Err(MarketError::BuyOrderNotFound));
+}
+
+#[ink::test]
+fn auction_extension_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	// Only the admin can set the extension.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(market.set_auction_extension(3), Err(MarketError::NotAllowed));
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(market.set_auction_extension(3));
+	assert_eq!(market.auction_extension(), 3);
+
+	assert_eq!(market.extended_auction_end(10, 5), 10);
+	assert_eq!(market.extended_auction_end(10, 7), 10);
+	assert_eq!(market.extended_auction_end(10, 8), 11);
+
+	market
+		.candle_auctions
+		.insert(0, &CandleAuction { id: 0, min_bid: 50, ending_period_start: 5, end: 10 });
+	market.sealed_auctions.insert(
+		1,
+		&SealedBidAuction {
+			id: 1,
+			min_bid: 50,
+			commit_end: 10,
+			reveal_end: 15,
+			commitments: 0,
+			highest_bid: None,
+		},
+	);
+
+	// Bids placed before the final window don't extend the auctions.
+	set_caller::<DefaultEnvironment>(bob);
+	set_value_transferred::<DefaultEnvironment>(50);
+	assert_ok!(market.place_candle_bid(Id::U128(0)));
+	assert_eq!(market.candle_auctions.get(0).map(|a| a.end), Some(10));
+	assert_ok!(market.commit_sealed_bid(Id::U128(1), Default::default()));
+	assert_eq!(market.sealed_auctions.get(1).map(|a| (a.commit_end, a.reveal_end)), Some((10, 15)));
+
+	// Late bids extend the auctions.
+	advance_n_blocks(9);
+	set_caller::<DefaultEnvironment>(charlie);
+	set_value_transferred::<DefaultEnvironment>(60);
+	assert_ok!(market.place_candle_bid(Id::U128(0)));
+	assert_eq!(market.candle_auctions.get(0).map(|a| a.end), Some(12));
+	assert_ok!(market.commit_sealed_bid(Id::U128(1), Default::default()));
+	assert_eq!(market.sealed_auctions.get(1).map(|a| (a.commit_end, a.reveal_end)), Some((12, 17)));
+}
+
+#[ink::test]
+fn candle_bids_work() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	market.add_to_sale(0, 10, listing(charlie));
+	market
+		.candle_auctions
+		.insert(0, &CandleAuction { id: 0, min_bid: 50, ending_period_start: 5, end: 10 });
+
+	// The first bid must be at least the minimum bid.
+	set_caller::<DefaultEnvironment>(bob);
+	set_value_transferred::<DefaultEnvironment>(40);
+	assert_eq!(market.place_candle_bid(Id::U128(0)), Err(MarketError::BidTooLow));
+
+	set_value_transferred::<DefaultEnvironment>(50);
+	assert_ok!(market.place_candle_bid(Id::U128(0)));
+
+	// Subsequent bids must be higher than the highest bid.
+	set_caller::<DefaultEnvironment>(eve);
+	assert_eq!(market.place_candle_bid(Id::U128(0)), Err(MarketError::BidTooLow));
+	set_value_transferred::<DefaultEnvironment>(60);
+	assert_ok!(market.place_candle_bid(Id::U128(0)));
+
+	// Tokens sent by the same bidder accumulate.
+	advance_block();
+	set_caller::<DefaultEnvironment>(bob);
+	set_value_transferred::<DefaultEnvironment>(20);
+	assert_ok!(market.place_candle_bid(Id::U128(0)));
+
+	assert_eq!(
+		market.candle_bids(Id::U128(0)),
+		Ok(vec![
+			CandleBid { bidder: bob, amount: 50, block_number: 0 },
+			CandleBid { bidder: eve, amount: 60, block_number: 0 },
+			CandleBid { bidder: bob, amount: 70, block_number: 1 },
+		])
+	);
+	assert_eq!(market.candle_escrow.get((0, bob)), Some(70));
+
+	// Escrow can't be withdrawn while the auction is ongoing.
+	assert_eq!(market.withdraw_candle_escrow(Id::U128(0), 0), Err(MarketError::AuctionNotEnded));
+
+	// Bids can't be placed once the auction ends.
+	advance_n_blocks(9);
+	assert_eq!(market.place_candle_bid(Id::U128(0)), Err(MarketError::AuctionEnded));
+}
+
+#[ink::test]
+fn candle_escrow_is_kept_per_auction() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	let contract = ink::env::account_id::<DefaultEnvironment>();
+
+	market.add_to_sale(0, 10, listing(charlie));
+	market
+		.candle_auctions
+		.insert(0, &CandleAuction { id: 0, min_bid: 50, ending_period_start: 5, end: 10 });
+
+	set_caller::<DefaultEnvironment>(bob);
+	set_value_transferred::<DefaultEnvironment>(70);
+	assert_ok!(market.place_candle_bid(Id::U128(0)));
+
+	// The auction ends without bob withdrawing the escrow and the region gets relisted.
+	assert_ok!(market.remove_from_sale(0, 10));
+	market.add_to_sale(0, 10, listing(charlie));
+	market
+		.candle_auctions
+		.insert(0, &CandleAuction { id: 1, min_bid: 50, ending_period_start: 5, end: 10 });
+
+	// The escrow of the previous auction doesn't count towards the bids in the new one.
+	set_value_transferred::<DefaultEnvironment>(10);
+	assert_eq!(market.place_candle_bid(Id::U128(0)), Err(MarketError::BidTooLow));
+	set_value_transferred::<DefaultEnvironment>(50);
+	assert_ok!(market.place_candle_bid(Id::U128(0)));
+	assert_eq!(
+		market.candle_bids(Id::U128(0)),
+		Ok(vec![CandleBid { bidder: bob, amount: 50, block_number: 0 }])
+	);
+
+	// The escrow of the new auction is locked, while the previous one can be withdrawn.
+	assert_eq!(market.withdraw_candle_escrow(Id::U128(0), 1), Err(MarketError::AuctionNotEnded));
+	set_account_balance::<DefaultEnvironment>(contract, 120);
+	let bob_balance = get_account_balance::<DefaultEnvironment>(bob).unwrap();
+	assert_ok!(market.withdraw_candle_escrow(Id::U128(0), 0));
+	assert_eq!(get_account_balance::<DefaultEnvironment>(bob), Ok(bob_balance + 70));
+	assert_eq!(market.candle_escrow.get((0, bob)), None);
+	assert_eq!(market.candle_escrow.get((1, bob)), Some(50));
+}
+
+#[ink::test]
+fn candle_close_block_is_within_ending_period() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	for id in 0..50 {
+		let auction = CandleAuction { id, min_bid: 0, ending_period_start: 100, end: 110 };
+		let close_block = market.candle_close_block(&auction, [id as u8; 32]);
+		assert!((100..=110).contains(&close_block));
+	}
+}
+
+#[ink::test]
+fn candle_close_block_is_independent_of_bids() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	let auction = CandleAuction { id: 0, min_bid: 0, ending_period_start: 5, end: 10 };
+	market.add_to_sale(0, 10, listing(charlie));
+	market.candle_auctions.insert(0, &auction);
+	let randomness = market.relay_randomness().unwrap();
+	let close_block = market.candle_close_block(&auction, randomness);
+
+	// Placing bids doesn't change the block at which the auction closes.
+	advance_n_blocks(6);
+	set_caller::<DefaultEnvironment>(bob);
+	set_value_transferred::<DefaultEnvironment>(10);
+	assert_ok!(market.place_candle_bid(Id::U128(0)));
+
+	let auction = market.candle_auctions.get(0).unwrap();
+	assert_eq!(market.candle_close_block(&auction, randomness), close_block);
+
+	// Settling the auction in a later block doesn't change the outcome either.
+	advance_n_blocks(5);
+	assert_eq!(
+		market.candle_close_block(&auction, market.relay_randomness().unwrap()),
+		close_block
+	);
+}
+
+#[ink::test]
+fn sealed_bids_work() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	market.add_to_sale(0, 10, listing(charlie));
+	market.sealed_auctions.insert(
+		0,
+		&SealedBidAuction {
+			id: 0,
+			min_bid: 50,
+			commit_end: 5,
+			reveal_end: 10,
+			commitments: 0,
+			highest_bid: None,
+		},
+	);
+
+	let bob_salt = [1u8; 32];
+	let eve_salt = [2u8; 32];
+
+	set_caller::<DefaultEnvironment>(bob);
+	set_value_transferred::<DefaultEnvironment>(100);
+	assert_ok!(market
+		.commit_sealed_bid(Id::U128(0), CoretimeMarket::sealed_bid_commitment(bob, 80, bob_salt)));
+	// Each bidder can commit only once.
+	assert_eq!(
+		market.commit_sealed_bid(
+			Id::U128(0),
+			CoretimeMarket::sealed_bid_commitment(bob, 1, bob_salt)
+		),
+		Err(MarketError::NotAllowed)
+	);
+
+	set_caller::<DefaultEnvironment>(eve);
+	assert_ok!(market
+		.commit_sealed_bid(Id::U128(0), CoretimeMarket::sealed_bid_commitment(eve, 90, eve_salt)));
+
+	// Can't reveal during the commit phase.
+	assert_eq!(
+		market.reveal_sealed_bid(Id::U128(0), 90, eve_salt),
+		Err(MarketError::InvalidAuctionPhase)
+	);
+
+	advance_n_blocks(5);
+	// Can't commit during the reveal phase.
+	assert_eq!(
+		market.commit_sealed_bid(Id::U128(0), Default::default()),
+		Err(MarketError::InvalidAuctionPhase)
+	);
+
+	// The revealed bid must match the commitment.
+	assert_eq!(
+		market.reveal_sealed_bid(Id::U128(0), 95, eve_salt),
+		Err(MarketError::InvalidReveal)
+	);
+	assert_ok!(market.reveal_sealed_bid(Id::U128(0), 90, eve_salt));
+
+	set_caller::<DefaultEnvironment>(bob);
+	assert_ok!(market.reveal_sealed_bid(Id::U128(0), 80, bob_salt));
+
+	let auction = market.sealed_auction(Id::U128(0)).unwrap().unwrap();
+	assert_eq!(auction.commitments, 2);
+	assert_eq!(auction.highest_bid, Some((eve, 90)));
+}
+
+#[ink::test]
+fn sealed_bids_are_kept_per_auction() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+	let contract = ink::env::account_id::<DefaultEnvironment>();
+
+	let auction = |id| SealedBidAuction {
+		id,
+		min_bid: 50,
+		commit_end: 5,
+		reveal_end: 10,
+		commitments: 0,
+		highest_bid: None,
+	};
+	market.add_to_sale(0, 10, listing(charlie));
+	market.sealed_auctions.insert(0, &auction(0));
+
+	set_caller::<DefaultEnvironment>(bob);
+	set_value_transferred::<DefaultEnvironment>(100);
+	assert_ok!(market.commit_sealed_bid(Id::U128(0), Default::default()));
+
+	// The auction ends without bob withdrawing the deposit and the region gets relisted.
+	assert_ok!(market.remove_from_sale(0, 10));
+	market.add_to_sale(0, 10, listing(charlie));
+	market.sealed_auctions.insert(0, &auction(1));
+
+	// The bid committed to the previous auction doesn't carry over to the new one.
+	set_value_transferred::<DefaultEnvironment>(60);
+	assert_ok!(market.commit_sealed_bid(Id::U128(0), Default::default()));
+	assert_eq!(market.sealed_bids.get((1, bob)).map(|bid| bid.deposit), Some(60));
+
+	// The deposit of the new auction is locked, while the previous one can be withdrawn.
+	assert_eq!(market.withdraw_sealed_deposit(Id::U128(0), 1), Err(MarketError::AuctionNotEnded));
+	set_account_balance::<DefaultEnvironment>(contract, 160);
+	let bob_balance = get_account_balance::<DefaultEnvironment>(bob).unwrap();
+	assert_ok!(market.withdraw_sealed_deposit(Id::U128(0), 0));
+	assert_eq!(get_account_balance::<DefaultEnvironment>(bob), Ok(bob_balance + 100));
+	assert_eq!(market.sealed_bids.get((0, bob)), None);
+}
+
+#[ink::test]
+fn escrow_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, django, eve, .. } =
+		get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	assert_eq!(market.dispute_escrow(Id::U128(0)), Err(MarketError::EscrowNotFound));
+
+	market.escrows.insert(
+		0,
+		&Escrow {
+			listing: listing(charlie),
+			terms: EscrowTerms { dispute_window: 5, arbiter: Some(django) },
+			buyer: bob,
+			recipient: bob,
+			referrer: None,
+			price: 100,
+			dispute_end: 5,
+			disputed: false,
+		},
+	);
+
+	// Only the parties of the sale can finalize it.
+	set_caller::<DefaultEnvironment>(eve);
+	assert_eq!(market.finalize_escrow(Id::U128(0)), Err(MarketError::NotAllowed));
+
+	// The seller can't finalize the sale during the dispute window.
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_eq!(market.finalize_escrow(Id::U128(0)), Err(MarketError::InvalidEscrowState));
+	// Only the buyer can dispute the sale.
+	assert_eq!(market.dispute_escrow(Id::U128(0)), Err(MarketError::NotAllowed));
+
+	// The arbiter can't resolve a sale that isn't disputed.
+	set_caller::<DefaultEnvironment>(django);
+	assert_eq!(market.resolve_escrow(Id::U128(0), true), Err(MarketError::InvalidEscrowState));
+
+	set_caller::<DefaultEnvironment>(bob);
+	assert_ok!(market.dispute_escrow(Id::U128(0)));
+	assert!(market.escrow(Id::U128(0)).unwrap().unwrap().disputed);
+	assert_eq!(market.dispute_escrow(Id::U128(0)), Err(MarketError::InvalidEscrowState));
+
+	// Once disputed, only the arbiter can settle the sale.
+	assert_eq!(market.finalize_escrow(Id::U128(0)), Err(MarketError::InvalidEscrowState));
+	set_caller::<DefaultEnvironment>(eve);
+	assert_eq!(market.resolve_escrow(Id::U128(0), true), Err(MarketError::NotAllowed));
+}
+
+#[ink::test]
+fn sale_history_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	assert!(market.sale_history(0, 10).is_empty());
+
+	market.record_sale(0, &Region::default(), &listing(charlie), bob, 100);
+	advance_n_blocks(timeslice_to_block_number(1));
+	market.record_sale(1, &Region::default(), &listing(charlie), bob, 200);
+	market.record_sale(2, &Region::default(), &listing(bob), charlie, 300);
+
+	let sale = |region_id, seller, buyer, price, timeslice| Sale {
+		region_id,
+		price,
+		buyer,
+		seller,
+		timeslice,
+	};
+
+	assert_eq!(market.sale_count(), 3);
+	// The newest sales are returned first.
+	assert_eq!(
+		market.sale_history(0, 2),
+		vec![sale(2, bob, charlie, 300, 1), sale(1, charlie, bob, 200, 1)]
+	);
+	assert_eq!(market.sale_history(2, 10), vec![sale(0, charlie, bob, 100, 0)]);
+	assert!(market.sale_history(3, 10).is_empty());
+
+	// Once the history is full, the oldest sales get overwritten.
+	for region_id in 3..=MAX_SALE_HISTORY as u128 {
+		market.record_sale(region_id, &Region::default(), &listing(charlie), bob, 100);
+	}
+	assert_eq!(market.sale_count(), MAX_SALE_HISTORY as u64 + 1);
+	assert_eq!(market.sale_history(0, u32::MAX).len(), MAX_SALE_HISTORY as usize);
+	assert_eq!(market.sale_history(MAX_SALE_HISTORY - 1, 1), vec![sale(1, charlie, bob, 200, 1)]);
+}
+
+#[ink::test]
+fn market_stats_work() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	assert_eq!(market.stats(), MarketStats { total_volume: 0, average_sale_price: 0 });
+
+	market.record_sale(0, &Region::default(), &listing(charlie), bob, 1_000);
+	assert_eq!(market.stats(), MarketStats { total_volume: 1_000, average_sale_price: 1_000 });
+
+	market.record_sale(1, &Region::default(), &listing(charlie), bob, 3_000);
+	assert_eq!(market.stats(), MarketStats { total_volume: 4_000, average_sale_price: 1_100 });
+
+	// Sales paid for in a PSP22 token are not accounted for.
+	market.record_sale(
+		2,
+		&Region::default(),
+		&Listing { payment_asset: Some(bob), ..listing(charlie) },
+		bob,
+		5_000,
+	);
+	assert_eq!(market.stats(), MarketStats { total_volume: 4_000, average_sale_price: 1_100 });
+	assert_eq!(market.sale_count(), 3);
+}
+
+#[ink::test]
+fn floor_price_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	let region_id =
+		|core| RawRegionId::from(RegionId { begin: 0, core, mask: CoreMask::complete() });
+
+	assert_eq!(market.floor_price(0), None);
+
+	market.add_to_sale(region_id(0), 10, Listing { timeslice_price: 20, ..listing(charlie) });
+	market.add_to_sale(region_id(1), 10, Listing { timeslice_price: 5, ..listing(charlie) });
+	assert_eq!(market.floor_price(0), Some(20));
+	assert_eq!(market.floor_price(1), Some(5));
+
+	// Listings with a different pricing strategy are not taken into account.
+	let region = RegionId { begin: 1, core: 0, mask: CoreMask::complete() }.into();
+	market.add_to_sale(
+		region,
+		10,
+		Listing { pricing: PricingStrategy::Flat(1), ..listing(charlie) },
+	);
+	assert_eq!(market.floor_price(0), Some(20));
+
+	assert_ok!(market.remove_from_sale(region_id(0), 10));
+	assert_eq!(market.floor_price(0), None);
+}
+
+#[ink::test]
+fn core_order_book_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut market = CoretimeMarket::new(charlie, 0, TIMESLICE_PERIOD, 0, charlie);
+
+	let region_id =
+		|begin, core| RawRegionId::from(RegionId { begin, core, mask: CoreMask::complete() });
+	let listing_for = |timeslice_price| Listing { timeslice_price, ..listing(charlie) };
+
+	assert!(market.core_order_book(0, 0, 10).is_empty());
+
+	market.add_to_sale(region_id(0, 0), 10, listing_for(20));
+	market.add_to_sale(region_id(1, 0), 10, listing_for(5));
+	market.add_to_sale(region_id(2, 0), 10, listing_for(10));
+	market.add_to_sale(region_id(3, 0), 10, listing_for(10));
+	market.add_to_sale(region_id(0, 1), 10, listing_for(1));
+
+	// Listings are sorted by price, with equally priced ones kept in listing order.
+	assert_eq!(
+		market.core_order_book(0, 0, 10),
+		vec![
+			(region_id(1, 0), listing_for(5)),
+			(region_id(2, 0), listing_for(10)),
+			(region_id(3, 0), listing_for(10)),
+			(region_id(0, 0), listing_for(20)),
+		]
+	);
+	assert_eq!(
+		market.core_order_book(0, 1, 2),
+		vec![(region_id(2, 0), listing_for(10)), (region_id(3, 0), listing_for(10))]
+	);
+	assert_eq!(market.core_order_book(1, 0, 10), vec![(region_id(0, 1), listing_for(1))]);
+
+	// Auctioned regions are left out.
+	market.add_to_sale(region_id(4, 0), 10, listing_for(0));
+	market.candle_auctions.insert(
+		region_id(4, 0),
+		&CandleAuction { id: 0, min_bid: 0, ending_period_start: 5, end: 10 },
+	);
+	assert_eq!(market.core_order_book(0, 0, 1), vec![(region_id(1, 0), listing_for(5))]);
+	assert_eq!(market.core_order_book(0, 0, 10).len(), 4);
+
+	// Updating the price moves the listing accordingly.
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(market.update_region_price(Id::U128(region_id(0, 0)), 1));
+	assert_eq!(market.core_order_book(0, 0, 1), vec![(region_id(0, 0), listing_for(1))]);
+	assert_eq!(market.floor_price(0), Some(1));
+
+	assert_ok!(market.remove_from_sale(region_id(0, 0), 10));
+	assert_eq!(market.core_order_book(0, 0, 1), vec![(region_id(1, 0), listing_for(5))]);
+}
+
 fn advance_n_blocks(n: u32) {
 	for _ in 0..n {
 		advance_block();
@@ -82,6 +1417,21 @@ fn advance_block() {
 	ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
 }
 
+fn listing(seller: AccountId) -> Listing {
+	Listing {
+		seller,
+		timeslice_price: 10,
+		sale_recepient: seller,
+		metadata_version: 0,
+		deposit: 0,
+		expiry: None,
+		proceeds_split: None,
+		pricing: PricingStrategy::MaskDecay,
+		payment_asset: None,
+		allowed_buyers: None,
+	}
+}
+
 fn timeslice_to_block_number(timeslice: Timeslice) -> BlockNumber {
 	timeslice * TIMESLICE_PERIOD
 }
@@ -0,0 +1,28 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::types::{CurrencyId, OraclePrice};
+
+#[openbrush::wrapper]
+pub type PriceOracleRef = dyn PriceOracle;
+
+/// The interface of a price oracle used by the market for converting prices denominated in a
+/// reference currency into the native token.
+#[openbrush::trait_definition]
+pub trait PriceOracle {
+	/// Returns the latest price of the reference `currency` in the native token, if known.
+	#[ink(message)]
+	fn price(&self, currency: CurrencyId) -> Option<OraclePrice>;
+}
@@ -13,13 +13,64 @@
 // You should have received a copy of the GNU General Public License
 // along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
 
+use ink::prelude::vec::Vec;
 use openbrush::{
-	contracts::traits::psp34::PSP34Error,
-	traits::{AccountId, BlockNumber},
+	contracts::traits::{errors::ReentrancyGuardError, psp22::PSP22Error, psp34::PSP34Error},
+	traits::{AccountId, BlockNumber, Hash},
+};
+use primitives::{
+	coretime::{CoreIndex, RawRegionId, Region, Timeslice},
+	Balance, Version,
 };
-use primitives::{Balance, Version};
 use xc_regions::types::XcRegionsError;
 
+/// Type used for expressing fee rates in basis points. A single basis point is 0.01%.
+pub type BasisPoints = u16;
+
+/// The number of basis points that make up 100%.
+pub const MAX_BASIS_POINTS: BasisPoints = 10_000;
+
+/// The portion of the listing deposit, expressed in basis points, that is paid out to the caller
+/// who purges an expired listing. The rest of the deposit is returned to the seller.
+pub const PURGE_REWARD_RATE: BasisPoints = 5_000;
+
+/// The version of the storage layout of the market.
+///
+/// Version 1 is the layout of the first upgradeable release of the market. Markets deployed from
+/// earlier code have no `upgrade` message and a different layout, so they can't be upgraded in
+/// place and their listings have to be moved to a new deployment.
+///
+/// Must be incremented whenever a new version of the contract changes the storage layout in a way
+/// that requires a migration.
+pub const STORAGE_VERSION: u16 = 1;
+
+/// The maximum number of recipients the sale proceeds of a listing can be split among.
+pub const MAX_PROCEEDS_RECIPIENTS: usize = 10;
+
+/// The maximum number of buy orders that can be open at the same time.
+///
+/// Bounds the cost of matching a newly listed region against the open buy orders.
+pub const MAX_OPEN_BUY_ORDERS: usize = 100;
+
+/// The maximum number of timeslices ahead that `expiring_listings` looks for expiring regions.
+///
+/// Bounds the number of storage reads performed by the query.
+pub const MAX_EXPIRY_LOOKAHEAD: Timeslice = 1_000;
+
+/// Returns the bucket in which regions of the specified duration are grouped when comparing
+/// sales.
+///
+/// Bucket `n` contains the regions lasting at least `2^(n - 1)` and less than `2^n` timeslices.
+pub fn duration_bucket(duration: Timeslice) -> u8 {
+	(Timeslice::BITS - duration.leading_zeros()) as u8
+}
+
+/// The maximum number of sales kept in the sale history of the market.
+pub const MAX_SALE_HISTORY: u32 = 1_000;
+
+/// The number of sales over which the average sale price is smoothed.
+pub const AVERAGE_SALE_PRICE_WINDOW: Balance = 20;
+
 /// The configuration of the coretime market
 #[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
@@ -32,6 +83,52 @@ pub struct Config {
 	pub timeslice_period: BlockNumber,
 }
 
+/// The configuration of the market fee.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct FeeConfig {
+	/// The fee rate taken from every sale, expressed in basis points.
+	pub rate: BasisPoints,
+	/// The `AccountId` receiving the accrued fees upon withdrawal.
+	pub recipient: AccountId,
+}
+
+/// The constraints imposed on new listings.
+#[derive(scale::Decode, scale::Encode, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct ListingLimits {
+	/// The minimum per timeslice price of regions listed for the native token.
+	pub min_timeslice_price: Balance,
+	/// The maximum number of regions a single account can have listed at the same time. If not
+	/// specified, there is no limit.
+	pub max_listings_per_account: Option<u32>,
+}
+
+/// The identifier of a reference currency as known by the price oracle.
+pub type CurrencyId = u32;
+
+/// The configuration of the price oracle used for listings priced in a reference currency.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct OracleConfig {
+	/// The `AccountId` of the price oracle contract.
+	pub oracle: AccountId,
+	/// The maximum number of blocks since the last price update after which the price reported
+	/// by the oracle is considered stale.
+	pub max_staleness: BlockNumber,
+}
+
+/// A price reported by the price oracle.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct OraclePrice {
+	/// The amount of native tokens worth a single unit of the reference currency, expressed as a
+	/// fixed point number with 18 decimals.
+	pub rate: u128,
+	/// The block number at which the price was last updated.
+	pub updated_at: BlockNumber,
+}
+
 #[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum MarketError {
@@ -57,6 +154,85 @@ pub enum MarketError {
 	XcRegionsPsp34Error(PSP34Error),
 	/// An error occured when calling the xc-regions contract through the metadata interface.
 	XcRegionsMetadataError(XcRegionsError),
+	/// The specified region is not expired.
+	RegionNotExpired,
+	/// The caller already has an offer placed on the region.
+	OfferAlreadyExists,
+	/// No offer was found for the specified region and bidder.
+	OfferNotFound,
+	/// The caller tried to place an offer without sending any tokens.
+	InvalidOfferAmount,
+	/// The provided auction or pricing parameters are invalid.
+	InvalidAuctionParameters,
+	/// The operation is not supported by the pricing strategy of the listing.
+	UnsupportedPricingStrategy,
+	/// The operation is not supported for regions listed in a candle auction.
+	CandleAuctionListing,
+	/// The operation is not supported for regions listed in a sealed-bid auction.
+	SealedBidAuctionListing,
+	/// The current phase of the auction doesn't allow this operation.
+	InvalidAuctionPhase,
+	/// The revealed bid doesn't match the committed one.
+	InvalidReveal,
+	/// No auction was found for the specified region.
+	AuctionNotFound,
+	/// The auction has already ended.
+	AuctionEnded,
+	/// The auction didn't end yet.
+	AuctionNotEnded,
+	/// The bid is lower than the minimum required bid.
+	BidTooLow,
+	/// No escrow was found for the specified region.
+	EscrowNotFound,
+	/// The current state of the escrow doesn't allow this operation.
+	InvalidEscrowState,
+	/// The region is already listed on sale.
+	RegionAlreadyListed,
+	/// The price of the region is higher than the maximum price the caller is willing to pay.
+	PriceExceedsMaximum,
+	/// The metadata stored in the xc-regions contract doesn't match the region components
+	/// encoded in the region id.
+	RegionIdMismatch,
+	/// The specified fee rate exceeds 100%.
+	InvalidFeeRate,
+	/// The operation is not supported for listings paid in a PSP22 token.
+	UnsupportedPaymentAsset,
+	/// An error occured when transferring the PSP22 payment asset.
+	PaymentAssetError(PSP22Error),
+	/// An error occured when transferring the PSP22 deposit asset.
+	DepositAssetError(PSP22Error),
+	/// There are neither listings nor sales to appraise the region from.
+	AppraisalUnavailable,
+	/// The price oracle didn't report an up to date price and the listing has no fallback price.
+	OraclePriceUnavailable,
+	/// No buy order was found with the specified identifier.
+	BuyOrderNotFound,
+	/// The parameters of the buy order are invalid.
+	InvalidBuyOrder,
+	/// The maximum number of open buy orders was reached.
+	TooManyBuyOrders,
+	/// The per timeslice price is below the minimum allowed by the market.
+	PriceTooLow,
+	/// The seller reached the maximum number of simultaneous listings.
+	TooManyListings,
+	/// The shares of the proceeds split don't add up to 100% or there are too many recipients.
+	InvalidProceedsSplit,
+	/// The listing lapsed and is no longer purchasable.
+	ListingExpired,
+	/// The provided listing expiry is in the past.
+	InvalidExpiry,
+	/// The market didn't receive the region it was supposed to hold.
+	RegionNotReceived,
+	/// Replacing the code of the contract failed.
+	UpgradeFailed,
+	/// The storage of the contract is already up to date.
+	AlreadyMigrated,
+	/// The operation is not allowed while the market is paused.
+	MarketPaused,
+	/// The market was called again while still executing a previous call.
+	ReentrantCall,
+	/// The relay chain randomness required for closing the auction couldn't be obtained.
+	RandomnessUnavailable,
 }
 
 impl core::fmt::Display for MarketError {
@@ -73,16 +249,62 @@ impl core::fmt::Display for MarketError {
 			MarketError::NotAllowed => write!(f, "NotAllowed"),
 			MarketError::XcRegionsPsp34Error(e) => write!(f, "{:?}", e),
 			MarketError::XcRegionsMetadataError(e) => write!(f, "{}", e),
+			MarketError::RegionNotExpired => write!(f, "RegionNotExpired"),
+			MarketError::OfferAlreadyExists => write!(f, "OfferAlreadyExists"),
+			MarketError::OfferNotFound => write!(f, "OfferNotFound"),
+			MarketError::InvalidOfferAmount => write!(f, "InvalidOfferAmount"),
+			MarketError::InvalidAuctionParameters => write!(f, "InvalidAuctionParameters"),
+			MarketError::UnsupportedPricingStrategy => write!(f, "UnsupportedPricingStrategy"),
+			MarketError::CandleAuctionListing => write!(f, "CandleAuctionListing"),
+			MarketError::SealedBidAuctionListing => write!(f, "SealedBidAuctionListing"),
+			MarketError::InvalidAuctionPhase => write!(f, "InvalidAuctionPhase"),
+			MarketError::InvalidReveal => write!(f, "InvalidReveal"),
+			MarketError::AuctionNotFound => write!(f, "AuctionNotFound"),
+			MarketError::AuctionEnded => write!(f, "AuctionEnded"),
+			MarketError::AuctionNotEnded => write!(f, "AuctionNotEnded"),
+			MarketError::BidTooLow => write!(f, "BidTooLow"),
+			MarketError::EscrowNotFound => write!(f, "EscrowNotFound"),
+			MarketError::InvalidEscrowState => write!(f, "InvalidEscrowState"),
+			MarketError::RegionAlreadyListed => write!(f, "RegionAlreadyListed"),
+			MarketError::PriceExceedsMaximum => write!(f, "PriceExceedsMaximum"),
+			MarketError::RegionIdMismatch => write!(f, "RegionIdMismatch"),
+			MarketError::InvalidFeeRate => write!(f, "InvalidFeeRate"),
+			MarketError::UnsupportedPaymentAsset => write!(f, "UnsupportedPaymentAsset"),
+			MarketError::PaymentAssetError(e) => write!(f, "{:?}", e),
+			MarketError::DepositAssetError(e) => write!(f, "{:?}", e),
+			MarketError::AppraisalUnavailable => write!(f, "AppraisalUnavailable"),
+			MarketError::OraclePriceUnavailable => write!(f, "OraclePriceUnavailable"),
+			MarketError::BuyOrderNotFound => write!(f, "BuyOrderNotFound"),
+			MarketError::InvalidBuyOrder => write!(f, "InvalidBuyOrder"),
+			MarketError::TooManyBuyOrders => write!(f, "TooManyBuyOrders"),
+			MarketError::PriceTooLow => write!(f, "PriceTooLow"),
+			MarketError::TooManyListings => write!(f, "TooManyListings"),
+			MarketError::InvalidProceedsSplit => write!(f, "InvalidProceedsSplit"),
+			MarketError::ListingExpired => write!(f, "ListingExpired"),
+			MarketError::InvalidExpiry => write!(f, "InvalidExpiry"),
+			MarketError::RegionNotReceived => write!(f, "RegionNotReceived"),
+			MarketError::UpgradeFailed => write!(f, "UpgradeFailed"),
+			MarketError::AlreadyMigrated => write!(f, "AlreadyMigrated"),
+			MarketError::MarketPaused => write!(f, "MarketPaused"),
+			MarketError::ReentrantCall => write!(f, "ReentrantCall"),
+			MarketError::RandomnessUnavailable => write!(f, "RandomnessUnavailable"),
 		}
 	}
 }
 
+impl From<ReentrancyGuardError> for MarketError {
+	fn from(_: ReentrancyGuardError) -> Self {
+		MarketError::ReentrantCall
+	}
+}
+
 #[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
 pub struct Listing {
 	/// The `AccountId` selling the specific region.
 	pub seller: AccountId,
-	/// The price per a single timeslice.
+	/// The price per a single timeslice. Only used by the `MaskDecay` and `Oracle` pricing
+	/// strategies.
 	pub timeslice_price: Balance,
 	/// The `AccountId` receiving the payment from the sale.
 	///
@@ -90,4 +312,284 @@ pub struct Listing {
 	pub sale_recepient: AccountId,
 	/// The metadata version of the region listed on sale. Used to prevent front running attacks.
 	pub metadata_version: Version,
+	/// The listing deposit paid by the seller.
+	pub deposit: Balance,
+	/// The relay chain block number at which the listing lapses.
+	///
+	/// If not specified, the listing is valid until the region expires.
+	pub expiry: Option<BlockNumber>,
+	/// The recipients of the sale proceeds together with their share, expressed in basis points.
+	///
+	/// If not specified, all proceeds go to the `sale_recepient`.
+	pub proceeds_split: Option<Vec<(AccountId, BasisPoints)>>,
+	/// The strategy used for determining the price of the region.
+	pub pricing: PricingStrategy,
+	/// The PSP22 token in which the region is paid for.
+	///
+	/// If not specified, the region is paid for in the native token.
+	pub payment_asset: Option<AccountId>,
+	/// The accounts that are allowed to purchase the region.
+	///
+	/// If not specified, anyone can purchase the region.
+	pub allowed_buyers: Option<Vec<AccountId>>,
+}
+
+impl Listing {
+	/// Returns whether the specified account is allowed to purchase the listed region.
+	pub fn can_purchase(&self, who: &AccountId) -> bool {
+		self.allowed_buyers.as_ref().map_or(true, |allowed| allowed.contains(who))
+	}
+}
+
+/// The strategy used for determining the price of a listed region.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum PricingStrategy {
+	/// The default timeslice-based model. The price is based on the `timeslice_price` of the
+	/// listing, the remaining duration of the region and its core occupancy.
+	MaskDecay,
+	/// The price decreases linearly every timeslice until it reaches a floor. Used for Dutch
+	/// auction listings.
+	Linear(LinearDecay),
+	/// The price decreases by a fixed percentage every timeslice until it reaches a floor.
+	Exponential(ExponentialDecay),
+	/// The price of the region stays the same until it is sold.
+	Flat(Balance),
+	/// Same as `MaskDecay`, except that the `timeslice_price` is denominated in a reference
+	/// currency and converted to the native token at the time of purchase.
+	Oracle(OraclePricing),
+}
+
+/// The parameters of listings priced in a reference currency.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct OraclePricing {
+	/// The reference currency in which the `timeslice_price` of the listing is denominated.
+	pub currency: CurrencyId,
+	/// The price per a single timeslice in the native token, used when the price oracle is
+	/// unavailable or its price is stale.
+	///
+	/// If not specified, the region can't be purchased until the oracle price is up to date.
+	pub fallback_timeslice_price: Option<Balance>,
+}
+
+/// The parameters of the linear price decay model.
+///
+/// The price of the region starts at `start_price` and decreases linearly every timeslice until it
+/// reaches the `floor_price`.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct LinearDecay {
+	/// The price of the region at the time of listing.
+	pub start_price: Balance,
+	/// The lowest price the region can reach.
+	pub floor_price: Balance,
+	/// The amount by which the price decreases with each timeslice.
+	pub decay_per_timeslice: Balance,
+	/// The timeslice at which the price starts decaying. Set by the contract upon listing.
+	pub start: Timeslice,
+}
+
+/// The parameters of the exponential price decay model.
+///
+/// The price of the region starts at `start_price` and decreases by `decay_rate` every timeslice
+/// until it reaches the `floor_price`.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct ExponentialDecay {
+	/// The price of the region at the time of listing.
+	pub start_price: Balance,
+	/// The lowest price the region can reach.
+	pub floor_price: Balance,
+	/// The portion of the price lost with each timeslice, expressed in basis points.
+	pub decay_rate: BasisPoints,
+	/// The timeslice at which the price starts decaying. Set by the contract upon listing.
+	pub start: Timeslice,
+}
+
+/// The parameters of a candle auction listing.
+///
+/// Similar to Polkadot parachain auctions, the auction consists of an opening period followed by
+/// an ending period. Once the auction ends, a random block within the ending period is chosen
+/// retroactively, and the highest bid placed before that block wins the auction. Since bidders
+/// can't know when the auction actually closed, this discourages last-second bidding.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct CandleAuction {
+	/// The identifier of the auction. The tokens escrowed by the bidders are tracked under it, so
+	/// that they are kept apart from the escrow of any later auction of the same region.
+	pub id: u32,
+	/// The minimum amount of the first bid.
+	pub min_bid: Balance,
+	/// The relay chain block number at which the ending period starts.
+	pub ending_period_start: BlockNumber,
+	/// The relay chain block number at which the auction ends.
+	pub end: BlockNumber,
+}
+
+/// A bid placed in a candle auction.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct CandleBid {
+	/// The account that placed the bid.
+	pub bidder: AccountId,
+	/// The total amount bid by the bidder.
+	pub amount: Balance,
+	/// The relay chain block number at which the bid was placed.
+	pub block_number: BlockNumber,
+}
+
+/// The state of a sealed-bid auction.
+///
+/// During the commit phase bidders submit a hash of their bid together with a deposit which must
+/// cover the bid. Once the commit phase ends, the bidders reveal their bids and the highest
+/// revealed bid wins the auction.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct SealedBidAuction {
+	/// The identifier of the auction. The bids are tracked under it, so that they are kept apart
+	/// from the bids of any later auction of the same region.
+	pub id: u32,
+	/// The minimum amount of a valid bid.
+	pub min_bid: Balance,
+	/// The relay chain block number at which the commit phase ends.
+	pub commit_end: BlockNumber,
+	/// The relay chain block number at which the reveal phase ends.
+	pub reveal_end: BlockNumber,
+	/// The number of bids committed to the auction.
+	pub commitments: u32,
+	/// The highest revealed bid so far.
+	pub highest_bid: Option<(AccountId, Balance)>,
+}
+
+/// A bid committed to a sealed-bid auction.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct SealedBid {
+	/// The Blake2x256 hash of the SCALE encoded `(bidder, amount, salt)` tuple.
+	pub commitment: Hash,
+	/// The deposit escrowed by the bidder. Must be at least the amount of the bid.
+	pub deposit: Balance,
+	/// Whether the bid has been revealed or not.
+	pub revealed: bool,
+}
+
+/// The escrow terms of a listing.
+///
+/// Regions sold through an escrowed listing are not delivered immediately. Instead, the payment
+/// and the region are held by the market until the sale is finalized.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct EscrowTerms {
+	/// The number of relay chain blocks after the purchase during which the buyer can dispute
+	/// the sale.
+	pub dispute_window: BlockNumber,
+	/// The account resolving disputes. If not specified, the sale can't be disputed.
+	pub arbiter: Option<AccountId>,
+}
+
+/// A purchase locked in escrow.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Escrow {
+	/// The listing from which the region was purchased.
+	pub listing: Listing,
+	/// The escrow terms of the listing.
+	pub terms: EscrowTerms,
+	/// The account that paid for the region.
+	pub buyer: AccountId,
+	/// The account that receives the region once the sale is finalized.
+	pub recipient: AccountId,
+	/// The account that referred the purchase, if any.
+	pub referrer: Option<AccountId>,
+	/// The price paid for the region.
+	pub price: Balance,
+	/// The relay chain block number at which the dispute window ends.
+	pub dispute_end: BlockNumber,
+	/// Whether the buyer disputed the sale.
+	pub disputed: bool,
+}
+
+/// A sale completed on the market.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Sale {
+	/// The identifier of the sold region.
+	pub region_id: RawRegionId,
+	/// The price paid for the region.
+	pub price: Balance,
+	/// The buyer of the region.
+	pub buyer: AccountId,
+	/// The seller of the region.
+	pub seller: AccountId,
+	/// The timeslice at which the sale was completed.
+	pub timeslice: Timeslice,
+}
+
+/// Aggregate statistics of the sales completed on the market.
+///
+/// Only sales paid for in the native token are accounted for.
+#[derive(scale::Decode, scale::Encode, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct MarketStats {
+	/// The sum of the prices of all the sales.
+	pub total_volume: Balance,
+	/// The exponential moving average of the sale price over the last
+	/// `AVERAGE_SALE_PRICE_WINDOW` sales.
+	pub average_sale_price: Balance,
+}
+
+/// A detailed breakdown of the price of a listed region.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct PriceQuote {
+	/// The number of active bits in the core mask of the region.
+	pub active_bits: u32,
+	/// The number of timeslices the region can still be used for.
+	pub remaining_timeslices: Timeslice,
+	/// The price per a single timeslice set by the seller.
+	pub timeslice_price: Balance,
+	/// The price of the region before any decay was applied.
+	pub undecayed_price: Balance,
+	/// The amount by which the price decreased since the region was listed or started.
+	pub decay: Balance,
+	/// The market fee taken from the price.
+	pub fee: Balance,
+	/// The amount received by the sale recipient.
+	pub seller_proceeds: Balance,
+	/// The final amount payable by the buyer.
+	pub price: Balance,
+	/// The PSP22 token in which the price is paid. `None` if paid in the native token.
+	pub payment_asset: Option<AccountId>,
+}
+
+/// An escrowed order for purchasing regions matching the specified criteria.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct BuyOrder {
+	/// The account that placed the order.
+	pub buyer: AccountId,
+	/// The core the regions must be assigned to. If not specified, any core is accepted.
+	pub core: Option<CoreIndex>,
+	/// The earliest timeslice at which the regions can start.
+	pub begin: Timeslice,
+	/// The latest timeslice at which the regions can end.
+	pub end: Timeslice,
+	/// The maximum per timeslice price the buyer is willing to pay.
+	pub max_timeslice_price: Balance,
+	/// The number of regions that remain to be purchased.
+	pub count: u32,
+	/// The remaining tokens escrowed for paying for the regions.
+	pub escrow: Balance,
+}
+
+impl BuyOrder {
+	/// Returns whether a region listed for the specified price satisfies the order.
+	pub fn matches(&self, region: &Region, timeslice_price: Balance, price: Balance) -> bool {
+		self.core.map_or(true, |core| core == region.core) &&
+			region.begin >= self.begin &&
+			region.end <= self.end &&
+			timeslice_price <= self.max_timeslice_price &&
+			price <= self.escrow
+	}
 }
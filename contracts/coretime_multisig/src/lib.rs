@@ -0,0 +1,442 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime multisig
+//!
+//! This contract holds xcRegions in the custody of multiple owners, requiring the approval of a
+//! threshold of the owners for any action performed on the held regions.
+//!
+//! Any owner can propose transferring a held region to another account, listing it on the
+//! Coretime market, withdrawing it from the market back into custody or withdrawing its
+//! underlying region to an account on the Coretime chain. Owners can also propose transferring
+//! native funds out of the contract. The proposer approves the proposal right away, and once the
+//! number of approvals reaches the threshold, any owner can execute it. Owners can revoke their
+//! approval as long as the proposal wasn't executed.
+//!
+//! Regions are placed into custody by simply transferring the xcRegion to the contract. The
+//! listing deposits required by the market are paid from the balance of the contract, which can
+//! be topped up through `deposit`.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+mod types;
+
+#[openbrush::contract(env = environment::ExtendedEnvironment)]
+pub mod coretime_multisig {
+	use crate::types::{Action, MultisigError, Proposal, ProposalId, RegionAction};
+	use coretime_market::coretime_market::CoretimeMarketRef;
+	use ink::{
+		codegen::{EmitEvent, Env, TraitCallBuilder},
+		env::call::FromAccountId,
+		prelude::{vec, vec::Vec},
+	};
+	use openbrush::{
+		contracts::{
+			reentrancy_guard::*,
+			traits::psp34::{Id, PSP34Ref},
+		},
+		modifiers,
+		storage::Mapping,
+		traits::Storage,
+	};
+	use primitives::ensure;
+	use xc_regions::xc_regions::XcRegionsRef;
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimeMultisig {
+		#[storage_field]
+		guard: reentrancy_guard::Data,
+		/// The `AccountId` of the xcRegions contract.
+		pub xc_regions_contract: AccountId,
+		/// The `AccountId` of the Coretime market.
+		pub market_contract: AccountId,
+		/// The owners of the multisig.
+		pub owners: Vec<AccountId>,
+		/// The number of approvals required for executing a proposal.
+		pub threshold: u32,
+		/// A mapping that holds all the created proposals.
+		pub proposals: Mapping<ProposalId, Proposal>,
+		/// The identifier assigned to the next proposal.
+		pub next_proposal_id: ProposalId,
+	}
+
+	#[ink(event)]
+	pub struct Deposited {
+		/// The account that deposited the funds.
+		#[ink(topic)]
+		pub(crate) from: AccountId,
+		/// The deposited amount.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct ProposalCreated {
+		/// The identifier of the proposal.
+		#[ink(topic)]
+		pub(crate) proposal_id: ProposalId,
+		/// The owner that created the proposal.
+		#[ink(topic)]
+		pub(crate) proposer: AccountId,
+		/// The proposed action.
+		pub(crate) action: Action,
+	}
+
+	#[ink(event)]
+	pub struct ProposalApproved {
+		/// The identifier of the proposal.
+		#[ink(topic)]
+		pub(crate) proposal_id: ProposalId,
+		/// The owner that approved the proposal.
+		#[ink(topic)]
+		pub(crate) owner: AccountId,
+		/// The number of approvals of the proposal.
+		pub(crate) approvals: u32,
+	}
+
+	#[ink(event)]
+	pub struct ApprovalRevoked {
+		/// The identifier of the proposal.
+		#[ink(topic)]
+		pub(crate) proposal_id: ProposalId,
+		/// The owner that revoked the approval.
+		#[ink(topic)]
+		pub(crate) owner: AccountId,
+		/// The number of approvals of the proposal.
+		pub(crate) approvals: u32,
+	}
+
+	#[ink(event)]
+	pub struct ProposalExecuted {
+		/// The identifier of the executed proposal.
+		#[ink(topic)]
+		pub(crate) proposal_id: ProposalId,
+	}
+
+	impl CoretimeMultisig {
+		/// ## Arguments:
+		/// - `xc_regions_contract` - The `AccountId` of the xcRegions contract.
+		/// - `market_contract` - The `AccountId` of the Coretime market.
+		/// - `owners` - The owners of the multisig.
+		/// - `threshold` - The number of approvals required for executing a proposal.
+		#[ink(constructor)]
+		pub fn new(
+			xc_regions_contract: AccountId,
+			market_contract: AccountId,
+			owners: Vec<AccountId>,
+			threshold: u32,
+		) -> Self {
+			let mut unique_owners = owners.clone();
+			unique_owners.sort();
+			unique_owners.dedup();
+			assert!(unique_owners.len() == owners.len(), "Owners must be unique");
+			assert!(
+				threshold > 0 && threshold as usize <= owners.len(),
+				"Threshold must be between one and the number of owners"
+			);
+
+			Self {
+				guard: Default::default(),
+				xc_regions_contract,
+				market_contract,
+				owners,
+				threshold,
+				proposals: Default::default(),
+				next_proposal_id: 0,
+			}
+		}
+
+		#[ink(message)]
+		pub fn owners(&self) -> Vec<AccountId> {
+			self.owners.clone()
+		}
+
+		#[ink(message)]
+		pub fn threshold(&self) -> u32 {
+			self.threshold
+		}
+
+		#[ink(message)]
+		pub fn proposal(&self, proposal_id: ProposalId) -> Option<Proposal> {
+			self.proposals.get(proposal_id)
+		}
+
+		/// A function for depositing funds used for paying the listing deposits.
+		///
+		/// The funds can only be transferred out of the multisig through an approved proposal.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `Deposited` event.
+		#[ink(message, payable)]
+		pub fn deposit(&mut self) -> Result<(), MultisigError> {
+			let from = self.env().caller();
+			let amount = self.env().transferred_value();
+
+			self.env().emit_event(Deposited { from, amount });
+			Ok(())
+		}
+
+		/// A function for proposing an action on a region held by the multisig.
+		///
+		/// Only callable by an owner, whose approval is recorded right away.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region.
+		/// - `action`: The proposed action.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `ProposalCreated` event.
+		#[ink(message)]
+		pub fn propose(
+			&mut self,
+			id: Id,
+			action: RegionAction,
+		) -> Result<ProposalId, MultisigError> {
+			let Id::U128(region_id) = id else { return Err(MultisigError::InvalidRegionId) };
+			let caller = self.env().caller();
+			let action = Action::Region { region_id, action };
+
+			let proposal_id = self.create_proposal(caller, action.clone())?;
+
+			self.env().emit_event(ProposalCreated { proposal_id, proposer: caller, action });
+			Ok(proposal_id)
+		}
+
+		/// A function for proposing a transfer of native funds held by the multisig.
+		///
+		/// Only callable by an owner, whose approval is recorded right away.
+		///
+		/// ## Arguments:
+		/// - `to`: The account receiving the funds.
+		/// - `amount`: The amount of funds transferred.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `ProposalCreated` event.
+		#[ink(message)]
+		pub fn propose_funds_transfer(
+			&mut self,
+			to: AccountId,
+			amount: Balance,
+		) -> Result<ProposalId, MultisigError> {
+			let caller = self.env().caller();
+			let action = Action::TransferFunds { to, amount };
+
+			let proposal_id = self.create_proposal(caller, action.clone())?;
+
+			self.env().emit_event(ProposalCreated { proposal_id, proposer: caller, action });
+			Ok(proposal_id)
+		}
+
+		/// A function for approving a proposal.
+		///
+		/// Only callable by an owner.
+		///
+		/// ## Arguments:
+		/// - `proposal_id`: The identifier of the proposal.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `ProposalApproved` event.
+		#[ink(message)]
+		pub fn approve(&mut self, proposal_id: ProposalId) -> Result<(), MultisigError> {
+			let caller = self.env().caller();
+
+			let approvals = self.add_approval(caller, proposal_id)?;
+
+			self.env()
+				.emit_event(ProposalApproved { proposal_id, owner: caller, approvals });
+			Ok(())
+		}
+
+		/// A function for revoking the approval of a proposal.
+		///
+		/// Only callable by an owner that approved the proposal.
+		///
+		/// ## Arguments:
+		/// - `proposal_id`: The identifier of the proposal.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `ApprovalRevoked` event.
+		#[ink(message)]
+		pub fn revoke(&mut self, proposal_id: ProposalId) -> Result<(), MultisigError> {
+			let caller = self.env().caller();
+
+			let approvals = self.remove_approval(caller, proposal_id)?;
+
+			self.env().emit_event(ApprovalRevoked { proposal_id, owner: caller, approvals });
+			Ok(())
+		}
+
+		/// A function for executing a proposal that reached the approval threshold.
+		///
+		/// Only callable by an owner.
+		///
+		/// ## Arguments:
+		/// - `proposal_id`: The identifier of the proposal.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `ProposalExecuted` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn execute(&mut self, proposal_id: ProposalId) -> Result<(), MultisigError> {
+			let caller = self.env().caller();
+
+			let proposal = self.approve_execution(caller, proposal_id)?;
+
+			match proposal.action {
+				Action::Region { region_id, action } =>
+					self.execute_region_action(Id::U128(region_id), action)?,
+				Action::TransferFunds { to, amount } =>
+					self.env().transfer(to, amount).map_err(|_| MultisigError::TransferFailed)?,
+			}
+
+			self.env().emit_event(ProposalExecuted { proposal_id });
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimeMultisig {
+		fn execute_region_action(&self, id: Id, action: RegionAction) -> Result<(), MultisigError> {
+			match action {
+				RegionAction::Transfer { to } =>
+					PSP34Ref::transfer(&self.xc_regions_contract, to, id, Vec::new())
+						.map_err(MultisigError::XcRegionsPsp34Error)?,
+				RegionAction::List { timeslice_price, sale_recipient, deposit } => {
+					PSP34Ref::approve(
+						&self.xc_regions_contract,
+						self.market_contract,
+						Some(id.clone()),
+						true,
+					)
+					.map_err(MultisigError::XcRegionsPsp34Error)?;
+
+					let mut market: CoretimeMarketRef =
+						FromAccountId::from_account_id(self.market_contract);
+					market
+						.call_mut()
+						.list_region(id, timeslice_price, sale_recipient)
+						.transferred_value(deposit)
+						.try_invoke()
+						.map_err(|_| MultisigError::MarketCallFailed)?
+						.map_err(|_| MultisigError::MarketCallFailed)?
+						.map_err(MultisigError::MarketError)?;
+				},
+				RegionAction::Unlist => {
+					let mut market: CoretimeMarketRef =
+						FromAccountId::from_account_id(self.market_contract);
+					market.unlist_region(id).map_err(MultisigError::MarketError)?;
+				},
+				RegionAction::Withdraw { beneficiary, fee } => {
+					let mut xc_regions: XcRegionsRef =
+						FromAccountId::from_account_id(self.xc_regions_contract);
+					xc_regions
+						.return_to_coretime_chain(id, beneficiary, fee)
+						.map_err(MultisigError::XcRegionsError)?;
+				},
+			}
+
+			Ok(())
+		}
+
+		pub(crate) fn create_proposal(
+			&mut self,
+			proposer: AccountId,
+			action: Action,
+		) -> Result<ProposalId, MultisigError> {
+			ensure!(self.owners.contains(&proposer), MultisigError::NotOwner);
+
+			let proposal_id = self.next_proposal_id;
+			self.proposals.insert(
+				proposal_id,
+				&Proposal { proposer, action, approvals: vec![proposer], executed: false },
+			);
+			self.next_proposal_id = self.next_proposal_id.saturating_add(1);
+
+			Ok(proposal_id)
+		}
+
+		// Records the approval of the owner and returns the number of approvals.
+		pub(crate) fn add_approval(
+			&mut self,
+			owner: AccountId,
+			proposal_id: ProposalId,
+		) -> Result<u32, MultisigError> {
+			let mut proposal = self.pending_proposal(owner, proposal_id)?;
+			ensure!(!proposal.approvals.contains(&owner), MultisigError::AlreadyApproved);
+
+			proposal.approvals.push(owner);
+			self.proposals.insert(proposal_id, &proposal);
+
+			Ok(proposal.approvals.len() as u32)
+		}
+
+		// Removes the approval of the owner and returns the number of approvals.
+		pub(crate) fn remove_approval(
+			&mut self,
+			owner: AccountId,
+			proposal_id: ProposalId,
+		) -> Result<u32, MultisigError> {
+			let mut proposal = self.pending_proposal(owner, proposal_id)?;
+			let index = proposal
+				.approvals
+				.iter()
+				.position(|approver| *approver == owner)
+				.ok_or(MultisigError::NotApproved)?;
+
+			proposal.approvals.remove(index);
+			self.proposals.insert(proposal_id, &proposal);
+
+			Ok(proposal.approvals.len() as u32)
+		}
+
+		// Marks the proposal as executed, in case it reached the approval threshold, and returns
+		// it.
+		pub(crate) fn approve_execution(
+			&mut self,
+			owner: AccountId,
+			proposal_id: ProposalId,
+		) -> Result<Proposal, MultisigError> {
+			let mut proposal = self.pending_proposal(owner, proposal_id)?;
+			ensure!(
+				proposal.approvals.len() >= self.threshold as usize,
+				MultisigError::ThresholdNotReached
+			);
+
+			proposal.executed = true;
+			self.proposals.insert(proposal_id, &proposal);
+
+			Ok(proposal)
+		}
+
+		// Returns the proposal in case the caller is an owner and the proposal wasn't executed
+		// yet.
+		fn pending_proposal(
+			&self,
+			owner: AccountId,
+			proposal_id: ProposalId,
+		) -> Result<Proposal, MultisigError> {
+			ensure!(self.owners.contains(&owner), MultisigError::NotOwner);
+			let proposal =
+				self.proposals.get(proposal_id).ok_or(MultisigError::ProposalNotFound)?;
+			ensure!(!proposal.executed, MultisigError::AlreadyExecuted);
+
+			Ok(proposal)
+		}
+	}
+}
@@ -0,0 +1,159 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_multisig::CoretimeMultisig,
+	types::{Action, MultisigError, Proposal, RegionAction},
+};
+use ink::env::{
+	test::{
+		default_accounts, get_account_balance, set_account_balance, set_caller, DefaultAccounts,
+	},
+	DefaultEnvironment,
+};
+use primitives::assert_ok;
+
+#[ink::test]
+fn create_proposal_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+	let mut multisig = new_multisig();
+
+	// Only owners can create proposals:
+	assert_eq!(
+		multisig.create_proposal(eve, region_action(0, RegionAction::Unlist)),
+		Err(MultisigError::NotOwner)
+	);
+
+	assert_eq!(
+		multisig.create_proposal(bob, region_action(0, RegionAction::Transfer { to: eve })),
+		Ok(0)
+	);
+	assert_eq!(
+		multisig.proposal(0),
+		Some(Proposal {
+			proposer: bob,
+			action: region_action(0, RegionAction::Transfer { to: eve }),
+			approvals: vec![bob],
+			executed: false,
+		})
+	);
+	assert_eq!(multisig.create_proposal(bob, region_action(1, RegionAction::Unlist)), Ok(1));
+	assert_eq!(
+		multisig.create_proposal(charlie, Action::TransferFunds { to: eve, amount: 100 }),
+		Ok(2)
+	);
+}
+
+#[ink::test]
+fn approvals_work() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, dave, eve, .. } =
+		get_default_accounts();
+	let mut multisig = new_multisig();
+
+	assert_eq!(multisig.add_approval(bob, 0), Err(MultisigError::ProposalNotFound));
+	assert_ok!(multisig.create_proposal(bob, region_action(0, RegionAction::Transfer { to: eve })));
+
+	assert_eq!(multisig.add_approval(eve, 0), Err(MultisigError::NotOwner));
+	// The proposer approves the proposal upon creating it:
+	assert_eq!(multisig.add_approval(bob, 0), Err(MultisigError::AlreadyApproved));
+	assert_eq!(multisig.add_approval(charlie, 0), Ok(2));
+
+	assert_eq!(multisig.remove_approval(dave, 0), Err(MultisigError::NotApproved));
+	assert_eq!(multisig.remove_approval(bob, 0), Ok(1));
+	assert_eq!(multisig.add_approval(dave, 0), Ok(2));
+	assert_eq!(multisig.proposal(0).map(|proposal| proposal.approvals), Some(vec![charlie, dave]));
+}
+
+#[ink::test]
+fn approve_execution_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+	let mut multisig = new_multisig();
+
+	assert_eq!(multisig.approve_execution(bob, 0), Err(MultisigError::ProposalNotFound));
+	assert_ok!(multisig.create_proposal(bob, region_action(0, RegionAction::Transfer { to: eve })));
+
+	assert_eq!(multisig.approve_execution(eve, 0), Err(MultisigError::NotOwner));
+	assert_eq!(multisig.approve_execution(bob, 0), Err(MultisigError::ThresholdNotReached));
+
+	assert_ok!(multisig.add_approval(charlie, 0));
+	assert_eq!(
+		multisig.approve_execution(bob, 0).map(|proposal| proposal.action),
+		Ok(region_action(0, RegionAction::Transfer { to: eve }))
+	);
+
+	// Executed proposals can no longer be executed nor approved:
+	assert_eq!(multisig.approve_execution(bob, 0), Err(MultisigError::AlreadyExecuted));
+	assert_eq!(multisig.remove_approval(charlie, 0), Err(MultisigError::AlreadyExecuted));
+}
+
+#[ink::test]
+fn withdraw_proposal_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+	let mut multisig = new_multisig();
+
+	// The underlying region is sent to the beneficiary rather than left with the multisig:
+	let withdraw = region_action(0, RegionAction::Withdraw { beneficiary: eve, fee: 10 });
+	assert_eq!(multisig.create_proposal(bob, withdraw.clone()), Ok(0));
+	assert_eq!(multisig.approve_execution(bob, 0), Err(MultisigError::ThresholdNotReached));
+
+	assert_ok!(multisig.add_approval(charlie, 0));
+	assert_eq!(
+		multisig.approve_execution(charlie, 0).map(|proposal| proposal.action),
+		Ok(withdraw)
+	);
+	assert_eq!(multisig.proposal(0).map(|proposal| proposal.executed), Some(true));
+}
+
+#[ink::test]
+fn transfer_funds_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, eve, .. } = get_default_accounts();
+	let mut multisig = new_multisig();
+	let contract = ink::env::account_id::<DefaultEnvironment>();
+
+	set_account_balance::<DefaultEnvironment>(contract, 1_000);
+	let eve_balance = get_account_balance::<DefaultEnvironment>(eve).unwrap();
+
+	set_caller::<DefaultEnvironment>(eve);
+	assert_eq!(multisig.propose_funds_transfer(eve, 400), Err(MultisigError::NotOwner));
+
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(multisig.propose_funds_transfer(eve, 400), Ok(0));
+
+	// The funds can only be transferred once the proposal reaches the approval threshold:
+	assert_eq!(multisig.execute(0), Err(MultisigError::ThresholdNotReached));
+	assert_eq!(get_account_balance::<DefaultEnvironment>(eve), Ok(eve_balance));
+
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(multisig.approve(0));
+	assert_ok!(multisig.execute(0));
+
+	assert_eq!(get_account_balance::<DefaultEnvironment>(eve), Ok(eve_balance + 400));
+	assert_eq!(get_account_balance::<DefaultEnvironment>(contract), Ok(600));
+	assert_eq!(multisig.execute(0), Err(MultisigError::AlreadyExecuted));
+}
+
+fn new_multisig() -> CoretimeMultisig {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, dave, django, .. } =
+		get_default_accounts();
+	CoretimeMultisig::new(alice, django, vec![bob, charlie, dave], 2)
+}
+
+fn region_action(region_id: u128, action: RegionAction) -> Action {
+	Action::Region { region_id, action }
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
@@ -0,0 +1,123 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use coretime_market::types::MarketError;
+use ink::prelude::vec::Vec;
+use openbrush::{
+	contracts::traits::{errors::ReentrancyGuardError, psp34::PSP34Error},
+	traits::AccountId,
+};
+use primitives::{coretime::RawRegionId, Balance};
+use xc_regions::types::XcRegionsError;
+
+/// The type used for identifying proposals.
+pub type ProposalId = u32;
+
+/// An action on a region held by the multisig that can be proposed.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum RegionAction {
+	/// Transfers the xcRegion to the specified account.
+	Transfer { to: AccountId },
+	/// Lists the region on the Coretime market. The listing deposit is paid from the balance of
+	/// the multisig.
+	List { timeslice_price: Balance, sale_recipient: Option<AccountId>, deposit: Balance },
+	/// Withdraws the region listed on the Coretime market back into the custody of the multisig.
+	Unlist,
+	/// Withdraws the underlying region of the xcRegion and sends it to the beneficiary on the
+	/// Coretime chain. The XCM fee is paid by the xcRegions contract and can't exceed its
+	/// `max_return_fee`.
+	Withdraw { beneficiary: AccountId, fee: Balance },
+}
+
+/// An action that can be proposed.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum Action {
+	/// An action on a region held by the multisig.
+	Region { region_id: RawRegionId, action: RegionAction },
+	/// Transfers native funds held by the multisig to the specified account.
+	TransferFunds { to: AccountId, amount: Balance },
+}
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Proposal {
+	/// The owner that created the proposal.
+	pub proposer: AccountId,
+	/// The proposed action.
+	pub action: Action,
+	/// The owners that approved the proposal.
+	pub approvals: Vec<AccountId>,
+	/// Whether the proposal was executed.
+	pub executed: bool,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum MultisigError {
+	/// The provided identifier is not a valid region id.
+	InvalidRegionId,
+	/// The caller is not an owner of the multisig.
+	NotOwner,
+	/// There is no proposal with the provided identifier.
+	ProposalNotFound,
+	/// The caller already approved the proposal.
+	AlreadyApproved,
+	/// The caller didn't approve the proposal.
+	NotApproved,
+	/// The proposal doesn't have enough approvals to be executed.
+	ThresholdNotReached,
+	/// The proposal was already executed.
+	AlreadyExecuted,
+	/// Failed to dispatch the call to the market.
+	MarketCallFailed,
+	/// Failed to transfer the native funds.
+	TransferFailed,
+	/// The call would result in reentering the contract.
+	ReentrantCall,
+	/// An error occured when transferring or approving the xcRegion.
+	XcRegionsPsp34Error(PSP34Error),
+	/// An error occured when withdrawing the xcRegion.
+	XcRegionsError(XcRegionsError),
+	/// An error occured when listing or unlisting the region on the market.
+	MarketError(MarketError),
+}
+
+impl core::fmt::Display for MultisigError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			MultisigError::InvalidRegionId => write!(f, "InvalidRegionId"),
+			MultisigError::NotOwner => write!(f, "NotOwner"),
+			MultisigError::ProposalNotFound => write!(f, "ProposalNotFound"),
+			MultisigError::AlreadyApproved => write!(f, "AlreadyApproved"),
+			MultisigError::NotApproved => write!(f, "NotApproved"),
+			MultisigError::ThresholdNotReached => write!(f, "ThresholdNotReached"),
+			MultisigError::AlreadyExecuted => write!(f, "AlreadyExecuted"),
+			MultisigError::MarketCallFailed => write!(f, "MarketCallFailed"),
+			MultisigError::TransferFailed => write!(f, "TransferFailed"),
+			MultisigError::ReentrantCall => write!(f, "ReentrantCall"),
+			MultisigError::XcRegionsPsp34Error(e) => write!(f, "{:?}", e),
+			MultisigError::XcRegionsError(e) => write!(f, "{}", e),
+			MultisigError::MarketError(e) => write!(f, "{}", e),
+		}
+	}
+}
+
+impl From<ReentrancyGuardError> for MultisigError {
+	fn from(_: ReentrancyGuardError) -> Self {
+		MultisigError::ReentrantCall
+	}
+}
@@ -0,0 +1,692 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime pool
+//!
+//! This contract pools the regions contributed by multiple owners and allows consumers to draw
+//! blockspace from the pool. The revenue is distributed among the contributors pro-rata to the
+//! core-bits and duration they contributed, mirroring the instantaneous coretime pool on the
+//! Coretime chain.
+//!
+//! Upon contributing a region, the contributor is assigned a weight equal to the number of
+//! core-bits of the region. The weight becomes active from the timeslice following the
+//! contribution, or from the beginning of the region if it starts later, and is removed from the
+//! pool once the region ends or the contributor withdraws it. This way the revenue of every draw is
+//! only distributed among the regions that were part of the pool before the draw, for as long as
+//! they provide blockspace.
+//!
+//! Consumers draw the blockspace of an active pooled region until a timeslice of their choice by
+//! paying the configured price for every core-bit and timeslice. For the duration of the draw, the
+//! consumer is set as the user of the xcRegion. The payment isn't distributed at once, but
+//! streamed to the pool one timeslice at a time until the draw ends, so that each timeslice of
+//! revenue only goes to the regions providing blockspace at that timeslice. Contributors
+//! withdrawing their region during a draw therefore forfeit the rest of its revenue.
+//!
+//! The revenue is tracked through a cumulative revenue index, which represents the revenue a
+//! single unit of weight accrued since the deployment of the contract. The index is recorded at
+//! every timeslice at which the weight of the pool or the streamed revenue changes, so that the
+//! revenue of each contributor can be accounted for lazily, whenever the contributor interacts
+//! with the contract.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+mod types;
+
+#[openbrush::contract(env = environment::ExtendedEnvironment)]
+pub mod coretime_pool {
+	use crate::types::{
+		Config, Contribution, ContributorInfo, PoolError, PoolProgress, WeightChange,
+	};
+	use block_number_extension::BlockNumberProviderExtension;
+	use ink::{
+		codegen::{EmitEvent, Env},
+		env::call::FromAccountId,
+		prelude::vec::Vec,
+	};
+	use openbrush::{
+		contracts::{
+			reentrancy_guard::*,
+			traits::psp34::{Id, PSP34Ref},
+		},
+		modifiers,
+		storage::Mapping,
+		traits::Storage,
+	};
+	use primitives::{
+		coretime::{RawRegionId, Region, Timeslice},
+		ensure,
+	};
+	use sp_arithmetic::{traits::SaturatedConversion, FixedPointNumber, FixedU128};
+	use xc_regions::{traits::RegionMetadataRef, xc_regions::XcRegionsRef};
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimePool {
+		#[storage_field]
+		guard: reentrancy_guard::Data,
+		/// The configuration of the pool.
+		pub config: Config,
+		/// A mapping that holds the contribution of every region in the pool.
+		pub contributions: Mapping<RawRegionId, Contribution>,
+		/// A mapping that holds the contributions and revenue accounting of every contributor.
+		pub contributors: Mapping<AccountId, ContributorInfo>,
+		/// The total weight of the active regions in the pool.
+		pub total_weight: u128,
+		/// The inner value of the cumulative revenue per unit of weight.
+		pub revenue_index: u128,
+		/// The upcoming changes of the total weight, stored by the timeslice at which they take
+		/// effect.
+		pub weight_changes: Mapping<Timeslice, WeightChange>,
+		/// The timeslices with an upcoming weight or revenue rate change, in ascending order.
+		pub scheduled_timeslices: Vec<Timeslice>,
+		/// The inner value of the revenue index at each timeslice at which the weight or the
+		/// revenue rate changed.
+		pub revenue_indices: Mapping<Timeslice, u128>,
+		/// The revenue streamed to the pool every timeslice by the ongoing draws.
+		pub revenue_rate: Balance,
+		/// The revenue rate of the draws ending at each timeslice.
+		pub rate_changes: Mapping<Timeslice, Balance>,
+		/// The timeslice until which the streamed revenue is accounted for in the revenue index.
+		pub accrued_until: Timeslice,
+	}
+
+	#[ink(event)]
+	pub struct RegionContributed {
+		/// The identifier of the contributed region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that contributed the region.
+		#[ink(topic)]
+		pub(crate) contributor: AccountId,
+		/// The weight of the contribution.
+		pub(crate) weight: u128,
+	}
+
+	#[ink(event)]
+	pub struct RegionWithdrawn {
+		/// The identifier of the withdrawn region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that contributed the region.
+		#[ink(topic)]
+		pub(crate) contributor: AccountId,
+	}
+
+	#[ink(event)]
+	pub struct BlockspaceDrawn {
+		/// The identifier of the region whose blockspace was drawn.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account drawing the blockspace.
+		#[ink(topic)]
+		pub(crate) consumer: AccountId,
+		/// The timeslice until which the blockspace is drawn.
+		pub(crate) until: Timeslice,
+		/// The amount paid for the blockspace.
+		pub(crate) cost: Balance,
+	}
+
+	#[ink(event)]
+	pub struct RevenueClaimed {
+		/// The account that claimed its revenue.
+		#[ink(topic)]
+		pub(crate) contributor: AccountId,
+		/// The claimed revenue.
+		pub(crate) amount: Balance,
+	}
+
+	impl CoretimePool {
+		/// ## Arguments:
+		/// - `xc_regions_contract` - The `AccountId` of the xcRegions contract.
+		/// - `timeslice_period` - The number of relay chain blocks in a timeslice.
+		/// - `core_bit_price` - The price a consumer pays for a single core-bit for the duration of
+		///   a timeslice.
+		#[ink(constructor)]
+		pub fn new(
+			xc_regions_contract: AccountId,
+			timeslice_period: BlockNumber,
+			core_bit_price: Balance,
+		) -> Self {
+			assert!(timeslice_period > 0, "Timeslice period must be greater than zero");
+
+			Self {
+				guard: Default::default(),
+				config: Config { xc_regions_contract, timeslice_period, core_bit_price },
+				contributions: Default::default(),
+				contributors: Default::default(),
+				total_weight: 0,
+				revenue_index: 0,
+				weight_changes: Default::default(),
+				scheduled_timeslices: Default::default(),
+				revenue_indices: Default::default(),
+				revenue_rate: 0,
+				rate_changes: Default::default(),
+				accrued_until: 0,
+			}
+		}
+
+		#[ink(message)]
+		pub fn config(&self) -> Config {
+			self.config.clone()
+		}
+
+		#[ink(message)]
+		pub fn contribution(&self, id: Id) -> Option<Contribution> {
+			let Id::U128(region_id) = id else { return None };
+			self.contributions.get(region_id)
+		}
+
+		#[ink(message)]
+		pub fn contributor(&self, who: AccountId) -> Option<ContributorInfo> {
+			self.contributors.get(who)
+		}
+
+		/// Returns the total weight of the regions active at the current timeslice.
+		#[ink(message)]
+		pub fn total_weight(&self) -> u128 {
+			let current_timeslice = self.current_timeslice();
+			self.scheduled_timeslices
+				.iter()
+				.take_while(|t| **t <= current_timeslice)
+				.map(|t| self.weight_changes.get(t).unwrap_or_default())
+				.fold(self.total_weight, |weight, change| change.apply(weight))
+		}
+
+		/// Returns the revenue the contributor can currently claim.
+		///
+		/// ## Arguments:
+		/// - `who`: The account of the contributor.
+		#[ink(message)]
+		pub fn claimable_revenue(&self, who: AccountId) -> Result<Balance, PoolError> {
+			let info = self.accrue_revenue(self.contributors.get(who).unwrap_or_default())?;
+			Ok(info.unclaimed)
+		}
+
+		/// Returns the cost of drawing the blockspace of a pooled region.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the pooled region.
+		/// - `until`: The timeslice until which the blockspace is drawn.
+		#[ink(message)]
+		pub fn draw_cost(&self, id: Id, until: Timeslice) -> Result<Balance, PoolError> {
+			let Id::U128(region_id) = id else { return Err(PoolError::InvalidRegionId) };
+			let contribution =
+				self.contributions.get(region_id).ok_or(PoolError::NotContributed)?;
+
+			self.blockspace_cost(&contribution.region, until)
+		}
+
+		/// A function for contributing an xcRegion to the pool.
+		///
+		/// The xcRegion is held by the contract until the contributor withdraws it.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region. The caller must own the
+		///   region and approve it to the contract beforehand.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionContributed` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn contribute(&mut self, id: Id) -> Result<(), PoolError> {
+			let Id::U128(region_id) = id else { return Err(PoolError::InvalidRegionId) };
+			let caller = self.env().caller();
+			let contract = self.env().account_id();
+
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id.clone()) == Some(caller),
+				PoolError::NotAllowed
+			);
+
+			let metadata =
+				RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id.clone())
+					.map_err(|_| PoolError::MetadataNotFound)?;
+			let weight = self.add_contribution(caller, region_id, metadata.region().clone())?;
+
+			PSP34Ref::transfer(&self.config.xc_regions_contract, contract, id.clone(), Vec::new())
+				.map_err(PoolError::XcRegionsPsp34Error)?;
+			// Don't rely on the transfer succeeding, but ensure that the contract actually received
+			// the region.
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id) == Some(contract),
+				PoolError::RegionNotReceived
+			);
+
+			self.env()
+				.emit_event(RegionContributed { region_id, contributor: caller, weight });
+			Ok(())
+		}
+
+		/// A function for withdrawing a contributed xcRegion from the pool.
+		///
+		/// Only callable by the contributor of the region while its blockspace isn't drawn.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the pooled region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionWithdrawn` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn withdraw(&mut self, id: Id) -> Result<(), PoolError> {
+			let Id::U128(region_id) = id else { return Err(PoolError::InvalidRegionId) };
+			let caller = self.env().caller();
+
+			self.remove_contribution(caller, region_id)?;
+
+			PSP34Ref::transfer(&self.config.xc_regions_contract, caller, id, Vec::new())
+				.map_err(PoolError::XcRegionsPsp34Error)?;
+
+			self.env().emit_event(RegionWithdrawn { region_id, contributor: caller });
+			Ok(())
+		}
+
+		/// A function for drawing the blockspace of a pooled region.
+		///
+		/// The blockspace can only be drawn once the contribution of the region is active. The
+		/// caller is set as the user of the xcRegion until the specified timeslice. The
+		/// payment is streamed to the contributors over the duration of the draw. Any value
+		/// transferred on top of the cost is returned to the caller.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the pooled region.
+		/// - `until`: The timeslice until which the blockspace is drawn.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `BlockspaceDrawn` event.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn draw(&mut self, id: Id, until: Timeslice) -> Result<(), PoolError> {
+			let Id::U128(region_id) = id else { return Err(PoolError::InvalidRegionId) };
+			let caller = self.env().caller();
+			let transferred_value = self.env().transferred_value();
+
+			let cost = self.draw_blockspace(caller, region_id, until, transferred_value)?;
+
+			let mut xc_regions: XcRegionsRef =
+				FromAccountId::from_account_id(self.config.xc_regions_contract);
+			xc_regions
+				.set_user(id, Some(caller), until)
+				.map_err(PoolError::XcRegionsError)?;
+
+			let surplus = transferred_value.saturating_sub(cost);
+			if surplus > 0 {
+				self.env().transfer(caller, surplus).map_err(|_| PoolError::TransferFailed)?;
+			}
+
+			self.env()
+				.emit_event(BlockspaceDrawn { region_id, consumer: caller, until, cost });
+			Ok(())
+		}
+
+		/// A function for claiming the revenue accrued by the contributions of the caller.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RevenueClaimed` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn claim_revenue(&mut self) -> Result<(), PoolError> {
+			let caller = self.env().caller();
+
+			let amount = self.take_revenue(caller)?;
+			self.env().transfer(caller, amount).map_err(|_| PoolError::TransferFailed)?;
+
+			self.env().emit_event(RevenueClaimed { contributor: caller, amount });
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimePool {
+		// Adds the region to the pool and returns the weight of the contribution.
+		//
+		// The weight becomes active from the next timeslice, or from the beginning of the region
+		// if it starts later, until the region ends.
+		pub(crate) fn add_contribution(
+			&mut self,
+			contributor: AccountId,
+			region_id: RawRegionId,
+			region: Region,
+		) -> Result<u128, PoolError> {
+			ensure!(!self.contributions.contains(region_id), PoolError::AlreadyContributed);
+			self.update_pool()?;
+
+			let active_from = region.begin.max(self.current_timeslice().saturating_add(1));
+			let weight = u128::from(region.mask.count_ones());
+			ensure!(weight > 0 && active_from < region.end, PoolError::RegionExpired);
+
+			let mut info =
+				self.accrue_revenue(self.contributors.get(contributor).unwrap_or_default())?;
+			self.schedule_weight_change(
+				&mut info,
+				active_from,
+				WeightChange { added: weight, removed: 0 },
+			);
+			self.schedule_weight_change(
+				&mut info,
+				region.end,
+				WeightChange { added: 0, removed: weight },
+			);
+
+			self.contributors.insert(contributor, &info);
+			self.contributions.insert(
+				region_id,
+				&Contribution { contributor, region, weight, active_from, draw: None },
+			);
+
+			Ok(weight)
+		}
+
+		// Removes the region from the pool, in case its blockspace isn't drawn.
+		pub(crate) fn remove_contribution(
+			&mut self,
+			contributor: AccountId,
+			region_id: RawRegionId,
+		) -> Result<(), PoolError> {
+			let contribution =
+				self.contributions.get(region_id).ok_or(PoolError::NotContributed)?;
+			ensure!(contribution.contributor == contributor, PoolError::NotAllowed);
+			ensure!(!self.is_drawn(&contribution), PoolError::RegionInUse);
+			self.update_pool()?;
+
+			let mut info =
+				self.accrue_revenue(self.contributors.get(contributor).unwrap_or_default())?;
+			let Contribution { weight, active_from, region, .. } = contribution;
+			let current_timeslice = self.current_timeslice();
+
+			if current_timeslice < active_from {
+				// The contribution isn't active yet.
+				self.cancel_weight_change(
+					&mut info,
+					active_from,
+					WeightChange { added: weight, removed: 0 },
+				);
+			} else if current_timeslice < region.end {
+				info.weight = info.weight.saturating_sub(weight);
+				self.total_weight = self.total_weight.saturating_sub(weight);
+			}
+			// Otherwise the weight of the expired region was already removed.
+			if current_timeslice < region.end {
+				self.cancel_weight_change(
+					&mut info,
+					region.end,
+					WeightChange { added: 0, removed: weight },
+				);
+			}
+
+			if info.weight == 0 && info.unclaimed == 0 && info.scheduled.is_empty() {
+				self.contributors.remove(contributor);
+			} else {
+				self.contributors.insert(contributor, &info);
+			}
+			self.contributions.remove(region_id);
+
+			Ok(())
+		}
+
+		// Records the draw of the blockspace of the region, in case the payment covers its cost,
+		// and streams the cost to the pool over the duration of the draw.
+		//
+		// Returns the cost of the drawn blockspace.
+		pub(crate) fn draw_blockspace(
+			&mut self,
+			consumer: AccountId,
+			region_id: RawRegionId,
+			until: Timeslice,
+			payment: Balance,
+		) -> Result<Balance, PoolError> {
+			let mut contribution =
+				self.contributions.get(region_id).ok_or(PoolError::NotContributed)?;
+			ensure!(
+				contribution.active_from <= self.current_timeslice(),
+				PoolError::ContributionNotActive
+			);
+			ensure!(!self.is_drawn(&contribution), PoolError::RegionInUse);
+			self.update_pool()?;
+
+			let cost = self.blockspace_cost(&contribution.region, until)?;
+			ensure!(payment >= cost, PoolError::InsufficientPayment);
+
+			contribution.draw = Some((consumer, until));
+			self.contributions.insert(region_id, &contribution);
+
+			// The contribution is active, so the cost covers every timeslice from now on.
+			let duration = until.saturating_sub(self.current_timeslice());
+			let rate = cost.checked_div(duration.into()).ok_or(PoolError::ArithmeticError)?;
+			self.revenue_rate = self.revenue_rate.saturating_add(rate);
+			self.schedule_timeslice(until);
+			let scheduled = self.rate_changes.get(until).unwrap_or_default();
+			self.rate_changes.insert(until, &scheduled.saturating_add(rate));
+
+			Ok(cost)
+		}
+
+		// Resets the revenue claimable by the contributor and returns it.
+		pub(crate) fn take_revenue(
+			&mut self,
+			contributor: AccountId,
+		) -> Result<Balance, PoolError> {
+			self.update_pool()?;
+
+			let mut info =
+				self.accrue_revenue(self.contributors.get(contributor).unwrap_or_default())?;
+			ensure!(info.unclaimed > 0, PoolError::NothingToClaim);
+
+			let amount = core::mem::take(&mut info.unclaimed);
+			if info.weight == 0 && info.scheduled.is_empty() {
+				self.contributors.remove(contributor);
+			} else {
+				self.contributors.insert(contributor, &info);
+			}
+
+			Ok(amount)
+		}
+
+		// Account for the revenue accrued by the contributions since the contributor's last
+		// interaction, applying the weight changes of the contributor that took effect since.
+		fn accrue_revenue(&self, mut info: ContributorInfo) -> Result<ContributorInfo, PoolError> {
+			let current_timeslice = self.current_timeslice();
+			let progress = self.pool_progress()?;
+			let due = info.scheduled.iter().take_while(|(t, _)| *t <= current_timeslice).count();
+			let applied: Vec<_> = info.scheduled.drain(..due).collect();
+
+			for (timeslice, change) in applied {
+				// The revenue index isn't recorded yet in case the pool wasn't updated since the
+				// weight change.
+				let index = match progress.applied.iter().find(|(t, _)| *t == timeslice) {
+					Some((_, index)) => *index,
+					None => self.revenue_indices.get(timeslice).unwrap_or(progress.revenue_index),
+				};
+				info = Self::accrue_until(info, index)?;
+				info.weight = change.apply(info.weight);
+			}
+
+			Self::accrue_until(info, progress.revenue_index)
+		}
+
+		// Account for the revenue accrued by the active weight of the contributor until the
+		// revenue index reached `index`.
+		fn accrue_until(
+			mut info: ContributorInfo,
+			index: u128,
+		) -> Result<ContributorInfo, PoolError> {
+			let delta = index.checked_sub(info.revenue_index).ok_or(PoolError::ArithmeticError)?;
+
+			info.unclaimed = info
+				.unclaimed
+				.saturating_add(FixedU128::from_inner(delta).saturating_mul_int(info.weight));
+			info.revenue_index = index;
+
+			Ok(info)
+		}
+
+		// Accounts for the revenue streamed to the pool until the current timeslice and applies
+		// the scheduled changes which took effect since, recording the revenue index at which
+		// each of them took effect.
+		pub(crate) fn update_pool(&mut self) -> Result<(), PoolError> {
+			let progress = self.pool_progress()?;
+			self.scheduled_timeslices.drain(..progress.applied.len());
+
+			for (timeslice, index) in progress.applied {
+				self.weight_changes.remove(timeslice);
+				self.rate_changes.remove(timeslice);
+				self.revenue_indices.insert(timeslice, &index);
+			}
+
+			self.total_weight = progress.total_weight;
+			self.revenue_rate = progress.revenue_rate;
+			self.revenue_index = progress.revenue_index;
+			self.accrued_until = self.current_timeslice();
+
+			Ok(())
+		}
+
+		// Computes the state of the revenue distribution at the current timeslice without
+		// updating the pool.
+		//
+		// The revenue rate is distributed among the weight active during each timeslice, so the
+		// revenue index is advanced up to every scheduled change before applying it.
+		pub(crate) fn pool_progress(&self) -> Result<PoolProgress, PoolError> {
+			let current_timeslice = self.current_timeslice();
+			let mut progress = PoolProgress {
+				applied: Vec::new(),
+				total_weight: self.total_weight,
+				revenue_rate: self.revenue_rate,
+				revenue_index: self.revenue_index,
+			};
+			let mut accrued_until = self.accrued_until;
+
+			for timeslice in
+				self.scheduled_timeslices.iter().take_while(|t| **t <= current_timeslice)
+			{
+				Self::stream_revenue(&mut progress, timeslice.saturating_sub(accrued_until))?;
+				accrued_until = *timeslice;
+				progress.applied.push((*timeslice, progress.revenue_index));
+
+				let change = self.weight_changes.get(timeslice).unwrap_or_default();
+				progress.total_weight = change.apply(progress.total_weight);
+				let ended_draws = self.rate_changes.get(timeslice).unwrap_or_default();
+				progress.revenue_rate = progress.revenue_rate.saturating_sub(ended_draws);
+			}
+
+			Self::stream_revenue(&mut progress, current_timeslice.saturating_sub(accrued_until))?;
+			Ok(progress)
+		}
+
+		// Advances the revenue index by the revenue streamed over `duration` timeslices.
+		fn stream_revenue(
+			progress: &mut PoolProgress,
+			duration: Timeslice,
+		) -> Result<(), PoolError> {
+			// The drawn regions are part of the pool until their draw ends, so the revenue always
+			// has an active weight to go to.
+			if progress.revenue_rate == 0 || duration == 0 || progress.total_weight == 0 {
+				return Ok(())
+			}
+
+			let revenue = progress
+				.revenue_rate
+				.checked_mul(duration.into())
+				.ok_or(PoolError::ArithmeticError)?;
+			let increment = FixedU128::checked_from_rational(revenue, progress.total_weight)
+				.ok_or(PoolError::ArithmeticError)?;
+			progress.revenue_index = FixedU128::from_inner(progress.revenue_index)
+				.checked_add(&increment)
+				.ok_or(PoolError::ArithmeticError)?
+				.into_inner();
+
+			Ok(())
+		}
+
+		fn schedule_timeslice(&mut self, timeslice: Timeslice) {
+			if let Err(index) = self.scheduled_timeslices.binary_search(&timeslice) {
+				self.scheduled_timeslices.insert(index, timeslice);
+			}
+		}
+
+		// Schedules a change of the weight of the pool and of the contributor at `timeslice`.
+		fn schedule_weight_change(
+			&mut self,
+			info: &mut ContributorInfo,
+			timeslice: Timeslice,
+			change: WeightChange,
+		) {
+			self.schedule_timeslice(timeslice);
+			let scheduled = self.weight_changes.get(timeslice).unwrap_or_default();
+			self.weight_changes.insert(timeslice, &scheduled.saturating_add(change));
+
+			match info.scheduled.binary_search_by_key(&timeslice, |(t, _)| *t) {
+				Ok(index) =>
+					info.scheduled[index].1 = info.scheduled[index].1.saturating_add(change),
+				Err(index) => info.scheduled.insert(index, (timeslice, change)),
+			}
+		}
+
+		// Cancels a previously scheduled change of the weight of the pool and of the contributor.
+		fn cancel_weight_change(
+			&mut self,
+			info: &mut ContributorInfo,
+			timeslice: Timeslice,
+			change: WeightChange,
+		) {
+			if let Some(scheduled) = self.weight_changes.get(timeslice) {
+				self.weight_changes.insert(timeslice, &scheduled.saturating_sub(change));
+			}
+
+			if let Ok(index) = info.scheduled.binary_search_by_key(&timeslice, |(t, _)| *t) {
+				let remaining = info.scheduled[index].1.saturating_sub(change);
+				if remaining == WeightChange::default() {
+					info.scheduled.remove(index);
+				} else {
+					info.scheduled[index].1 = remaining;
+				}
+			}
+		}
+
+		// Returns the cost of drawing the blockspace of the region from now until `until`.
+		fn blockspace_cost(&self, region: &Region, until: Timeslice) -> Result<Balance, PoolError> {
+			let start = region.begin.max(self.current_timeslice());
+			ensure!(until > start && until <= region.end, PoolError::InvalidDuration);
+
+			self.config
+				.core_bit_price
+				.checked_mul(region.mask.count_ones().into())
+				.and_then(|price| price.checked_mul(until.saturating_sub(start).into()))
+				.ok_or(PoolError::ArithmeticError)
+		}
+
+		fn is_drawn(&self, contribution: &Contribution) -> bool {
+			matches!(contribution.draw, Some((_, until)) if until > self.current_timeslice())
+		}
+
+		pub(crate) fn current_timeslice(&self) -> Timeslice {
+			(self.relay_block_number() / self.config.timeslice_period).saturated_into()
+		}
+
+		#[cfg(not(test))]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().extension().relay_chain_block_number().unwrap_or_default()
+		}
+
+		#[cfg(test)]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().block_number()
+		}
+	}
+}
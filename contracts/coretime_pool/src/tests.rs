@@ -0,0 +1,203 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_pool::CoretimePool,
+	types::{Contribution, ContributorInfo, PoolError, WeightChange},
+};
+use ink::env::{
+	test::{advance_block, default_accounts, DefaultAccounts},
+	DefaultEnvironment,
+};
+use openbrush::contracts::psp34::Id;
+use primitives::{
+	assert_ok,
+	coretime::{CoreMask, Region},
+};
+
+const TIMESLICE_PERIOD: u32 = 80;
+const CORE_BIT_PRICE: u128 = 1;
+
+#[ink::test]
+fn contribution_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut pool = new_pool();
+
+	// Regions without any remaining blockspace can't be contributed:
+	assert_eq!(
+		pool.add_contribution(bob, 0, region(0, 0, CoreMask::complete())),
+		Err(PoolError::RegionExpired)
+	);
+	assert_eq!(
+		pool.add_contribution(bob, 0, region(0, 1, CoreMask::complete())),
+		Err(PoolError::RegionExpired)
+	);
+
+	// The weight is the number of core-bits, which becomes active from the next timeslice:
+	assert_eq!(pool.add_contribution(bob, 0, region(0, 10, CoreMask::complete())), Ok(80));
+	assert_eq!(
+		pool.contribution(Id::U128(0)),
+		Some(Contribution {
+			contributor: bob,
+			region: region(0, 10, CoreMask::complete()),
+			weight: 80,
+			active_from: 1,
+			draw: None,
+		})
+	);
+	assert_eq!(
+		pool.add_contribution(bob, 0, region(0, 10, CoreMask::complete())),
+		Err(PoolError::AlreadyContributed)
+	);
+	assert_eq!(pool.total_weight(), 0);
+
+	advance_n_blocks(TIMESLICE_PERIOD * 5);
+	assert_eq!(pool.total_weight(), 80);
+	assert_eq!(
+		pool.add_contribution(charlie, 1, region(0, 10, CoreMask::from_chunk(0, 40))),
+		Ok(40)
+	);
+	assert_eq!(pool.total_weight(), 80);
+
+	// Only the contributor can withdraw the region:
+	assert_eq!(pool.remove_contribution(charlie, 0), Err(PoolError::NotAllowed));
+	assert_ok!(pool.remove_contribution(bob, 0));
+	assert_eq!(pool.remove_contribution(bob, 0), Err(PoolError::NotContributed));
+	assert_eq!(pool.contributor(bob), None);
+	assert_eq!(pool.total_weight(), 0);
+
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(pool.total_weight(), 40);
+
+	// The weight is removed from the pool once the region ends, even without a withdrawal:
+	advance_n_blocks(TIMESLICE_PERIOD * 4);
+	assert_eq!(pool.total_weight(), 0);
+	assert_ok!(pool.remove_contribution(charlie, 1));
+	assert_eq!(pool.contributor(charlie), None);
+	assert_eq!(pool.total_weight(), 0);
+}
+
+#[ink::test]
+fn draw_blockspace_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, dave, eve, .. } =
+		get_default_accounts();
+	let mut pool = new_pool();
+
+	assert_eq!(pool.draw_blockspace(dave, 0, 5, 1_000), Err(PoolError::NotContributed));
+
+	assert_ok!(pool.add_contribution(bob, 0, region(0, 10, CoreMask::complete())));
+
+	// The blockspace can only be drawn once the contribution is active:
+	assert_eq!(pool.draw_blockspace(dave, 0, 5, 1_000), Err(PoolError::ContributionNotActive));
+	advance_n_blocks(TIMESLICE_PERIOD);
+
+	// The blockspace can only be drawn until a future timeslice within the region:
+	assert_eq!(pool.draw_blockspace(dave, 0, 1, 1_000), Err(PoolError::InvalidDuration));
+	assert_eq!(pool.draw_blockspace(dave, 0, 11, 1_000), Err(PoolError::InvalidDuration));
+
+	assert_eq!(pool.draw_cost(Id::U128(0), 5), Ok(320));
+	assert_eq!(pool.draw_blockspace(dave, 0, 5, 319), Err(PoolError::InsufficientPayment));
+	assert_eq!(pool.draw_blockspace(dave, 0, 5, 1_000), Ok(320));
+	assert_eq!(pool.contribution(Id::U128(0)).and_then(|c| c.draw), Some((dave, 5)));
+
+	// The blockspace can't be drawn by anyone else, nor the region withdrawn, during the draw:
+	assert_eq!(pool.draw_blockspace(eve, 0, 10, 1_000), Err(PoolError::RegionInUse));
+	assert_eq!(pool.remove_contribution(bob, 0), Err(PoolError::RegionInUse));
+
+	advance_n_blocks(TIMESLICE_PERIOD * 4);
+	assert_eq!(pool.draw_blockspace(eve, 0, 10, 1_000), Ok(400));
+
+	assert_eq!(pool.add_contribution(charlie, 1, region(0, 10, CoreMask::complete())), Ok(80));
+}
+
+#[ink::test]
+fn revenue_distribution_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, dave, eve, .. } =
+		get_default_accounts();
+	let mut pool = new_pool();
+
+	assert_eq!(pool.take_revenue(bob), Err(PoolError::NothingToClaim));
+
+	assert_ok!(pool.add_contribution(bob, 0, region(0, 10, CoreMask::complete())));
+	assert_ok!(pool.add_contribution(charlie, 1, region(0, 10, CoreMask::from_chunk(0, 40))));
+	advance_n_blocks(TIMESLICE_PERIOD);
+
+	// The revenue is streamed over the duration of the draw:
+	assert_eq!(pool.draw_blockspace(dave, 0, 5, 320), Ok(320));
+	assert_eq!(pool.claimable_revenue(bob), Ok(0));
+
+	// and distributed pro-rata to the weight of the contributions:
+	advance_n_blocks(TIMESLICE_PERIOD * 2);
+	assert_eq!(pool.claimable_revenue(bob), Ok(106));
+	assert_eq!(pool.claimable_revenue(charlie), Ok(53));
+
+	// Withdrawing a region forfeits the revenue of the remaining draw period:
+	assert_ok!(pool.remove_contribution(charlie, 1));
+	advance_n_blocks(TIMESLICE_PERIOD * 2);
+	assert_eq!(pool.claimable_revenue(charlie), Ok(53));
+	assert_eq!(pool.claimable_revenue(bob), Ok(266));
+
+	assert_eq!(pool.take_revenue(charlie), Ok(53));
+	assert_eq!(pool.contributor(charlie), None);
+	assert_eq!(pool.take_revenue(bob), Ok(266));
+	assert_eq!(pool.take_revenue(bob), Err(PoolError::NothingToClaim));
+
+	// Contributions becoming active during a draw take part in the rest of its revenue:
+	assert_ok!(pool.add_contribution(eve, 2, region(0, 10, CoreMask::complete())));
+	assert_eq!(pool.draw_blockspace(dave, 2, 9, 320), Err(PoolError::ContributionNotActive));
+	assert_eq!(pool.draw_blockspace(dave, 0, 9, 320), Ok(320));
+	advance_n_blocks(TIMESLICE_PERIOD * 2);
+	assert_eq!(pool.claimable_revenue(bob), Ok(120));
+	assert_eq!(pool.claimable_revenue(eve), Ok(40));
+
+	// Expired contributions don't receive any revenue from later draws:
+	advance_n_blocks(TIMESLICE_PERIOD * 3);
+	assert_ok!(pool.add_contribution(eve, 3, region(10, 20, CoreMask::complete())));
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(pool.draw_blockspace(dave, 3, 15, 320), Ok(320));
+	advance_n_blocks(TIMESLICE_PERIOD * 4);
+
+	assert_eq!(pool.take_revenue(bob), Ok(200));
+	assert_eq!(pool.contributor(bob), None);
+	assert_eq!(pool.take_revenue(eve), Ok(440));
+	assert_eq!(
+		pool.contributor(eve),
+		Some(ContributorInfo {
+			weight: 80,
+			revenue_index: pool.revenue_index,
+			unclaimed: 0,
+			scheduled: vec![(20, WeightChange { added: 0, removed: 80 })],
+		})
+	);
+}
+
+fn new_pool() -> CoretimePool {
+	let DefaultAccounts::<DefaultEnvironment> { alice, .. } = get_default_accounts();
+	CoretimePool::new(alice, TIMESLICE_PERIOD, CORE_BIT_PRICE)
+}
+
+fn region(begin: u32, end: u32, mask: CoreMask) -> Region {
+	Region { begin, end, core: 0, mask }
+}
+
+fn advance_n_blocks(n: u32) {
+	for _ in 0..n {
+		advance_block::<DefaultEnvironment>();
+	}
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
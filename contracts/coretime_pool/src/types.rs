@@ -0,0 +1,186 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use ink::prelude::vec::Vec;
+use openbrush::{
+	contracts::traits::{errors::ReentrancyGuardError, psp34::PSP34Error},
+	traits::{AccountId, BlockNumber},
+};
+use primitives::{
+	coretime::{Region, Timeslice},
+	Balance,
+};
+use xc_regions::types::XcRegionsError;
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Config {
+	/// The `AccountId` of the xcRegions contract.
+	pub xc_regions_contract: AccountId,
+	/// The number of relay chain blocks in a timeslice.
+	pub timeslice_period: BlockNumber,
+	/// The price a consumer pays for a single core-bit for the duration of a timeslice.
+	pub core_bit_price: Balance,
+}
+
+/// A region contributed to the pool.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Contribution {
+	/// The account that contributed the region.
+	pub contributor: AccountId,
+	/// The metadata of the contributed region.
+	pub region: Region,
+	/// The weight of the contribution, i.e. the number of contributed core-bits.
+	pub weight: u128,
+	/// The timeslice from which the contribution takes part in the revenue distribution.
+	pub active_from: Timeslice,
+	/// The consumer drawing the blockspace of the region together with the timeslice until which
+	/// the blockspace is drawn.
+	pub draw: Option<(AccountId, Timeslice)>,
+}
+
+/// The contributions of a single account together with its revenue accounting.
+#[derive(scale::Decode, scale::Encode, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct ContributorInfo {
+	/// The total weight of the active regions contributed by the account.
+	pub weight: u128,
+	/// The inner value of the revenue index at which the revenue of the contributor was last
+	/// accounted for.
+	pub revenue_index: u128,
+	/// The revenue accounted for the contributor which wasn't claimed yet.
+	pub unclaimed: Balance,
+	/// The upcoming changes of the weight of the contributor, ordered by the timeslice at which
+	/// they take effect.
+	pub scheduled: Vec<(Timeslice, WeightChange)>,
+}
+
+/// The weight added to and removed from the pool at a specific timeslice.
+#[derive(scale::Decode, scale::Encode, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct WeightChange {
+	/// The weight of the contributions which become active.
+	pub added: u128,
+	/// The weight of the contributions which expire.
+	pub removed: u128,
+}
+
+impl WeightChange {
+	/// Adds up the two weight changes.
+	pub fn saturating_add(self, other: Self) -> Self {
+		Self {
+			added: self.added.saturating_add(other.added),
+			removed: self.removed.saturating_add(other.removed),
+		}
+	}
+
+	/// Cancels the `other` weight change out of this one.
+	pub fn saturating_sub(self, other: Self) -> Self {
+		Self {
+			added: self.added.saturating_sub(other.added),
+			removed: self.removed.saturating_sub(other.removed),
+		}
+	}
+
+	/// Applies the weight change to `weight`.
+	pub fn apply(&self, weight: u128) -> u128 {
+		weight.saturating_add(self.added).saturating_sub(self.removed)
+	}
+}
+
+/// The state of the revenue distribution of the pool at the current timeslice, including the
+/// scheduled changes which took effect since the pool was last updated.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct PoolProgress {
+	/// The timeslices at which the applied changes took effect, together with the inner value of
+	/// the revenue index at each of them.
+	pub applied: Vec<(Timeslice, u128)>,
+	/// The total weight of the active regions in the pool.
+	pub total_weight: u128,
+	/// The revenue streamed to the pool every timeslice.
+	pub revenue_rate: Balance,
+	/// The inner value of the cumulative revenue per unit of weight.
+	pub revenue_index: u128,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PoolError {
+	/// An arithmetic error occured.
+	ArithmeticError,
+	/// The provided identifier is not a valid region id.
+	InvalidRegionId,
+	/// The caller is not allowed to perform the operation.
+	NotAllowed,
+	/// The metadata of the region couldn't be retrieved from the xcRegions contract.
+	MetadataNotFound,
+	/// The region doesn't have any blockspace left to contribute or draw.
+	RegionExpired,
+	/// The contribution of the region doesn't take part in the pool yet.
+	ContributionNotActive,
+	/// The region was already contributed to the pool.
+	AlreadyContributed,
+	/// The region isn't part of the pool.
+	NotContributed,
+	/// The blockspace of the region is currently drawn by a consumer.
+	RegionInUse,
+	/// The blockspace can only be drawn until a future timeslice within the region.
+	InvalidDuration,
+	/// The transferred value doesn't cover the cost of the drawn blockspace.
+	InsufficientPayment,
+	/// The caller doesn't have any revenue to claim.
+	NothingToClaim,
+	/// The contract didn't receive the region.
+	RegionNotReceived,
+	/// Failed to transfer the native tokens.
+	TransferFailed,
+	/// The call would result in reentering the contract.
+	ReentrantCall,
+	/// An error occured when transferring the xcRegion.
+	XcRegionsPsp34Error(PSP34Error),
+	/// An error occured when updating the user of the xcRegion.
+	XcRegionsError(XcRegionsError),
+}
+
+impl core::fmt::Display for PoolError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			PoolError::ArithmeticError => write!(f, "ArithmeticError"),
+			PoolError::InvalidRegionId => write!(f, "InvalidRegionId"),
+			PoolError::NotAllowed => write!(f, "NotAllowed"),
+			PoolError::MetadataNotFound => write!(f, "MetadataNotFound"),
+			PoolError::RegionExpired => write!(f, "RegionExpired"),
+			PoolError::ContributionNotActive => write!(f, "ContributionNotActive"),
+			PoolError::AlreadyContributed => write!(f, "AlreadyContributed"),
+			PoolError::NotContributed => write!(f, "NotContributed"),
+			PoolError::RegionInUse => write!(f, "RegionInUse"),
+			PoolError::InvalidDuration => write!(f, "InvalidDuration"),
+			PoolError::InsufficientPayment => write!(f, "InsufficientPayment"),
+			PoolError::NothingToClaim => write!(f, "NothingToClaim"),
+			PoolError::RegionNotReceived => write!(f, "RegionNotReceived"),
+			PoolError::TransferFailed => write!(f, "TransferFailed"),
+			PoolError::ReentrantCall => write!(f, "ReentrantCall"),
+			PoolError::XcRegionsPsp34Error(e) => write!(f, "{:?}", e),
+			PoolError::XcRegionsError(e) => write!(f, "{}", e),
+		}
+	}
+}
+
+impl From<ReentrancyGuardError> for PoolError {
+	fn from(_: ReentrancyGuardError) -> Self {
+		PoolError::ReentrantCall
+	}
+}
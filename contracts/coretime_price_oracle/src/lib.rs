@@ -0,0 +1,182 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime price oracle
+//!
+//! This contract computes time-weighted average prices (TWAP) from the sales completed on the
+//! Coretime market, which other contracts such as lending or futures markets can consume as a
+//! price oracle.
+//!
+//! The market pushes the per timeslice price paid in every sale into the oracle, grouped by the
+//! duration bucket of the sold region. For every bucket, the oracle keeps a cumulative price,
+//! which is the sum of the latest sale price over all the blocks since the first sale. The
+//! average price over a window is derived from the difference of the cumulative price at the
+//! start and at the end of the window.
+//!
+//! Each sale creates an observation of the cumulative price. The most recent `MAX_OBSERVATIONS`
+//! observations of each bucket are kept, which bounds how far back the average price can be
+//! computed. Taking the average over a longer window makes the price harder to manipulate through
+//! individual sales.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+pub mod traits;
+pub mod types;
+
+/// The maximum number of observations kept for every duration bucket.
+pub const MAX_OBSERVATIONS: u32 = 64;
+
+#[openbrush::contract]
+pub mod coretime_price_oracle {
+	use crate::{
+		traits::{salepriceoracle_external, salerecorder_external, SalePriceOracle, SaleRecorder},
+		types::{Observation, OracleError},
+		MAX_OBSERVATIONS,
+	};
+	use ink::codegen::{EmitEvent, Env};
+	use openbrush::{storage::Mapping, traits::Storage};
+	use primitives::{coretime::Timeslice, ensure};
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimePriceOracle {
+		/// The `AccountId` of the Coretime market, which is the only account allowed to record
+		/// sales.
+		pub market_contract: AccountId,
+		/// A mapping that holds the observations of each duration bucket.
+		///
+		/// Used as a ring buffer of `MAX_OBSERVATIONS` entries per bucket.
+		pub observations: Mapping<(u8, u32), Observation>,
+		/// The total number of observations made for each duration bucket.
+		pub observation_count: Mapping<u8, u32>,
+	}
+
+	#[ink(event)]
+	pub struct SaleRecorded {
+		/// The duration bucket of the sold region.
+		#[ink(topic)]
+		pub(crate) bucket: u8,
+		/// The price paid normalized to a single timeslice of a whole core.
+		pub(crate) timeslice_price: Balance,
+	}
+
+	impl CoretimePriceOracle {
+		/// ## Arguments:
+		/// - `market_contract` - The `AccountId` of the Coretime market.
+		#[ink(constructor)]
+		pub fn new(market_contract: AccountId) -> Self {
+			Self {
+				market_contract,
+				observations: Default::default(),
+				observation_count: Default::default(),
+			}
+		}
+
+		#[ink(message)]
+		pub fn market_contract(&self) -> AccountId {
+			self.market_contract
+		}
+	}
+
+	impl SaleRecorder for CoretimePriceOracle {
+		/// Records a sale completed on the market.
+		///
+		/// Only callable by the market.
+		///
+		/// ## Arguments:
+		/// - `bucket` - The duration bucket of the sold region.
+		/// - `timeslice_price` - The price paid normalized to a single timeslice of a whole core.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `SaleRecorded` event.
+		#[ink(message)]
+		fn record_sale(&mut self, bucket: u8, timeslice_price: Balance) -> Result<(), OracleError> {
+			ensure!(self.env().caller() == self.market_contract, OracleError::NotMarket);
+
+			self.observe(bucket, timeslice_price)?;
+
+			self.env().emit_event(SaleRecorded { bucket, timeslice_price });
+			Ok(())
+		}
+	}
+
+	impl SalePriceOracle for CoretimePriceOracle {
+		#[ink(message)]
+		fn twap(&self, bucket: u8, window: BlockNumber) -> Option<Balance> {
+			let now = self.env().block_number();
+			if window == 0 || window > now {
+				return None
+			}
+			let start = now - window;
+
+			let count = self.observation_count.get(bucket).unwrap_or_default();
+			let latest = self.latest_observation(bucket)?;
+
+			// Find the most recent observation made before the window started.
+			let first = (count.saturating_sub(MAX_OBSERVATIONS)..count)
+				.rev()
+				.filter_map(|index| self.observations.get((bucket, index % MAX_OBSERVATIONS)))
+				.find(|observation| observation.block <= start)?;
+
+			let cumulative = latest.cumulative_at(now).saturating_sub(first.cumulative_at(start));
+			Some(cumulative / window as u128)
+		}
+
+		#[ink(message)]
+		fn latest_observation(&self, bucket: u8) -> Option<Observation> {
+			let count = self.observation_count.get(bucket)?;
+			self.observations.get((bucket, count.checked_sub(1)? % MAX_OBSERVATIONS))
+		}
+	}
+
+	// Internal functions:
+	impl CoretimePriceOracle {
+		pub(crate) fn observe(
+			&mut self,
+			bucket: u8,
+			timeslice_price: Balance,
+		) -> Result<(), OracleError> {
+			ensure!(u32::from(bucket) <= Timeslice::BITS, OracleError::InvalidBucket);
+
+			let now = self.env().block_number();
+			let count = self.observation_count.get(bucket).unwrap_or_default();
+
+			let observation = match self.latest_observation(bucket) {
+				// Only the last sale of a block is accounted for.
+				Some(latest) if latest.block == now => {
+					self.observations.insert(
+						(bucket, (count - 1) % MAX_OBSERVATIONS),
+						&Observation { price: timeslice_price, ..latest },
+					);
+					return Ok(())
+				},
+				Some(latest) => Observation {
+					block: now,
+					cumulative: latest.cumulative_at(now),
+					price: timeslice_price,
+				},
+				None => Observation { block: now, cumulative: 0, price: timeslice_price },
+			};
+
+			self.observations.insert((bucket, count % MAX_OBSERVATIONS), &observation);
+			self.observation_count.insert(bucket, &count.saturating_add(1));
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,122 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_price_oracle::CoretimePriceOracle,
+	traits::{SalePriceOracle, SaleRecorder},
+	types::{Observation, OracleError},
+	MAX_OBSERVATIONS,
+};
+use ink::env::{
+	test::{advance_block, default_accounts, set_caller, DefaultAccounts},
+	DefaultEnvironment,
+};
+use primitives::assert_ok;
+
+#[ink::test]
+fn record_sale_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, .. } = get_default_accounts();
+	let mut oracle = new_oracle();
+
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(oracle.record_sale(0, 100), Err(OracleError::NotMarket));
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_eq!(oracle.record_sale(33, 100), Err(OracleError::InvalidBucket));
+	assert_ok!(oracle.record_sale(1, 100));
+	assert_eq!(
+		oracle.latest_observation(1),
+		Some(Observation { block: 0, cumulative: 0, price: 100 })
+	);
+}
+
+#[ink::test]
+fn observe_works() {
+	let mut oracle = new_oracle();
+
+	assert_eq!(oracle.latest_observation(1), None);
+	assert_ok!(oracle.observe(1, 100));
+
+	// Only the last sale within a block is accounted for:
+	assert_ok!(oracle.observe(1, 200));
+	assert_eq!(
+		oracle.latest_observation(1),
+		Some(Observation { block: 0, cumulative: 0, price: 200 })
+	);
+	assert_eq!(oracle.observation_count.get(1), Some(1));
+
+	advance_n_blocks(10);
+	assert_ok!(oracle.observe(1, 300));
+	assert_eq!(
+		oracle.latest_observation(1),
+		Some(Observation { block: 10, cumulative: 2000, price: 300 })
+	);
+	assert_eq!(oracle.observation_count.get(1), Some(2));
+
+	// The buckets are observed independently:
+	assert_eq!(oracle.latest_observation(2), None);
+}
+
+#[ink::test]
+fn twap_works() {
+	let mut oracle = new_oracle();
+
+	assert_eq!(oracle.twap(1, 1), None);
+
+	assert_ok!(oracle.observe(1, 100));
+	advance_n_blocks(10);
+	assert_ok!(oracle.observe(1, 200));
+	advance_n_blocks(10);
+
+	assert_eq!(oracle.twap(1, 0), None);
+	assert_eq!(oracle.twap(1, 20), Some(150));
+	assert_eq!(oracle.twap(1, 10), Some(200));
+	assert_eq!(oracle.twap(1, 5), Some(200));
+	assert_eq!(oracle.twap(1, 15), Some(166));
+	// The window can't start before the first observation:
+	assert_eq!(oracle.twap(1, 21), None);
+	assert_eq!(oracle.twap(2, 10), None);
+}
+
+#[ink::test]
+fn twap_is_limited_by_kept_observations() {
+	let mut oracle = new_oracle();
+
+	for _ in 0..MAX_OBSERVATIONS {
+		assert_ok!(oracle.observe(1, 100));
+		advance_block::<DefaultEnvironment>();
+	}
+	assert_ok!(oracle.observe(1, 100));
+
+	// The observation made at block zero was overwritten:
+	let now = MAX_OBSERVATIONS;
+	assert_eq!(oracle.twap(1, now - 1), Some(100));
+	assert_eq!(oracle.twap(1, now), None);
+}
+
+fn new_oracle() -> CoretimePriceOracle {
+	let DefaultAccounts::<DefaultEnvironment> { alice, .. } = get_default_accounts();
+	CoretimePriceOracle::new(alice)
+}
+
+fn advance_n_blocks(n: u32) {
+	for _ in 0..n {
+		advance_block::<DefaultEnvironment>();
+	}
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
@@ -0,0 +1,51 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::types::{Observation, OracleError};
+use openbrush::traits::BlockNumber;
+use primitives::Balance;
+
+#[openbrush::wrapper]
+pub type SaleRecorderRef = dyn SaleRecorder;
+
+/// The interface through which the Coretime market reports its sales.
+#[openbrush::trait_definition]
+pub trait SaleRecorder {
+	/// Records a sale completed on the market.
+	///
+	/// ## Arguments:
+	/// - `bucket` - The duration bucket of the sold region.
+	/// - `timeslice_price` - The price paid normalized to a single timeslice of a whole core.
+	#[ink(message)]
+	fn record_sale(&mut self, bucket: u8, timeslice_price: Balance) -> Result<(), OracleError>;
+}
+
+#[openbrush::wrapper]
+pub type SalePriceOracleRef = dyn SalePriceOracle;
+
+/// The interface of the price oracle used by other contracts for valuing regions.
+#[openbrush::trait_definition]
+pub trait SalePriceOracle {
+	/// Returns the time-weighted average per timeslice price of the regions in the duration
+	/// bucket over the last `window` blocks.
+	///
+	/// Returns `None` if the recorded observations don't cover the whole window.
+	#[ink(message)]
+	fn twap(&self, bucket: u8, window: BlockNumber) -> Option<Balance>;
+
+	/// Returns the latest observation of the duration bucket, if any sale was recorded.
+	#[ink(message)]
+	fn latest_observation(&self, bucket: u8) -> Option<Observation>;
+}
@@ -0,0 +1,57 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use openbrush::traits::BlockNumber;
+use primitives::Balance;
+
+/// A price observation of a duration bucket.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Observation {
+	/// The block number at which the observation was made.
+	pub block: BlockNumber,
+	/// The sum of the per timeslice price of every block up to the observation.
+	pub cumulative: u128,
+	/// The per timeslice price paid in the latest sale at the time of the observation. The price
+	/// holds until the next observation.
+	pub price: Balance,
+}
+
+impl Observation {
+	/// Returns the cumulative price at the specified block, assuming no sale happened since the
+	/// observation.
+	pub fn cumulative_at(&self, block: BlockNumber) -> u128 {
+		let elapsed = block.saturating_sub(self.block);
+		self.cumulative.saturating_add(self.price.saturating_mul(elapsed as u128))
+	}
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum OracleError {
+	/// The caller is not the Coretime market.
+	NotMarket,
+	/// There is no duration bucket with the provided index.
+	InvalidBucket,
+}
+
+impl core::fmt::Display for OracleError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			OracleError::NotMarket => write!(f, "NotMarket"),
+			OracleError::InvalidBucket => write!(f, "InvalidBucket"),
+		}
+	}
+}
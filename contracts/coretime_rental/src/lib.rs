@@ -0,0 +1,586 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime rental
+//!
+//! This contract allows region owners to rent out their xcRegions while retaining the ownership
+//! over them.
+//!
+//! The owner escrows the region in the contract and sets the rent that has to be paid for every
+//! timeslice. The tenant deposits native tokens from which the rent of every timeslice is streamed
+//! to the owner as soon as the timeslice begins, so the timeslice in which the tenancy starts is
+//! paid for upfront. For as long as the deposit covers the rent, the tenant is set as the user of
+//! the xcRegion, which allows them to make use of the region without owning it.
+//!
+//! The tenant can top up the stream to extend the tenancy or end the tenancy at any time, getting
+//! back the part of the deposit that wasn't streamed yet. Once the deposit runs out, the user role
+//! of the tenant expires and the owner can reclaim the region.
+//!
+//! No rent is due once the region ends, so the part of a deposit or top-up that would pay for
+//! timeslices after the end of the region is returned to the tenant right away.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+mod types;
+
+#[openbrush::contract(env = environment::ExtendedEnvironment)]
+pub mod coretime_rental {
+	use crate::types::{Config, Rental, RentalError, Tenancy};
+	use block_number_extension::BlockNumberProviderExtension;
+	use ink::{
+		codegen::{EmitEvent, Env},
+		env::call::FromAccountId,
+		prelude::vec::Vec,
+	};
+	use openbrush::{
+		contracts::{
+			reentrancy_guard::*,
+			traits::psp34::{Id, PSP34Ref},
+		},
+		modifiers,
+		storage::Mapping,
+		traits::Storage,
+	};
+	use primitives::{
+		coretime::{RawRegionId, Timeslice},
+		ensure,
+	};
+	use sp_arithmetic::traits::SaturatedConversion;
+	use xc_regions::{traits::RegionMetadataRef, xc_regions::XcRegionsRef};
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimeRental {
+		#[storage_field]
+		guard: reentrancy_guard::Data,
+		/// The configuration of the rental contract.
+		pub config: Config,
+		/// A mapping that holds the rental of each region escrowed in the contract.
+		pub rentals: Mapping<RawRegionId, Rental>,
+	}
+
+	#[ink(event)]
+	pub struct RegionOffered {
+		/// The identifier of the region offered for rent.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The owner of the region.
+		#[ink(topic)]
+		pub(crate) owner: AccountId,
+		/// The rent paid for every timeslice.
+		pub(crate) timeslice_price: Balance,
+	}
+
+	#[ink(event)]
+	pub struct TenancyStarted {
+		/// The identifier of the rented region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account renting the region.
+		#[ink(topic)]
+		pub(crate) tenant: AccountId,
+		/// The deposit streamed to the owner.
+		pub(crate) deposit: Balance,
+		/// The timeslice until which the rent is paid for.
+		pub(crate) paid_until: Timeslice,
+	}
+
+	#[ink(event)]
+	pub struct StreamToppedUp {
+		/// The identifier of the rented region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The amount added to the deposit.
+		pub(crate) amount: Balance,
+		/// The timeslice until which the rent is paid for.
+		pub(crate) paid_until: Timeslice,
+	}
+
+	#[ink(event)]
+	pub struct PaymentClaimed {
+		/// The identifier of the rented region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The owner of the region.
+		#[ink(topic)]
+		pub(crate) owner: AccountId,
+		/// The claimed rent.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct TenancyEnded {
+		/// The identifier of the rented region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that rented the region.
+		#[ink(topic)]
+		pub(crate) tenant: AccountId,
+		/// The part of the deposit returned to the tenant.
+		pub(crate) refund: Balance,
+	}
+
+	#[ink(event)]
+	pub struct RegionReclaimed {
+		/// The identifier of the region returned to its owner.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The owner of the region.
+		#[ink(topic)]
+		pub(crate) owner: AccountId,
+		/// The rent paid out to the owner upon reclaiming the region.
+		pub(crate) claimed: Balance,
+	}
+
+	impl CoretimeRental {
+		/// ## Arguments:
+		/// - `xc_regions_contract` - The `AccountId` of the xcRegions contract.
+		/// - `timeslice_period` - The number of relay chain blocks in a timeslice.
+		#[ink(constructor)]
+		pub fn new(xc_regions_contract: AccountId, timeslice_period: BlockNumber) -> Self {
+			assert!(timeslice_period > 0, "Timeslice period must be greater than zero");
+
+			Self {
+				guard: Default::default(),
+				config: Config { xc_regions_contract, timeslice_period },
+				rentals: Default::default(),
+			}
+		}
+
+		#[ink(message)]
+		pub fn config(&self) -> Config {
+			self.config.clone()
+		}
+
+		/// Returns the rental of the region, with the rent streamed until the current timeslice.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the rented region.
+		#[ink(message)]
+		pub fn rental(&self, id: Id) -> Option<Rental> {
+			let Id::U128(region_id) = id else { return None };
+			self.rentals.get(region_id).map(|rental| self.settle(rental))
+		}
+
+		/// Returns the timeslice until which the rent of the current tenant is paid for.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the rented region.
+		#[ink(message)]
+		pub fn paid_until(&self, id: Id) -> Option<Timeslice> {
+			let rental = self.rental(id)?;
+			rental.tenancy.map(|tenancy| tenancy.paid_until(rental.timeslice_price))
+		}
+
+		/// A function for offering an xcRegion for rent.
+		///
+		/// The xcRegion is held by the contract until the owner reclaims it.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the region. The caller must own the
+		///   region and approve it to the contract beforehand.
+		/// - `timeslice_price`: The rent paid by the tenant for every timeslice.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionOffered` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn offer(&mut self, id: Id, timeslice_price: Balance) -> Result<(), RentalError> {
+			let Id::U128(region_id) = id else { return Err(RentalError::InvalidRegionId) };
+			let caller = self.env().caller();
+			let contract = self.env().account_id();
+
+			ensure!(timeslice_price > 0, RentalError::InvalidPrice);
+			ensure!(!self.rentals.contains(region_id), RentalError::AlreadyOffered);
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id.clone()) == Some(caller),
+				RentalError::NotAllowed
+			);
+
+			let metadata =
+				RegionMetadataRef::get_metadata(&self.config.xc_regions_contract, id.clone())
+					.map_err(RentalError::XcRegionsError)?;
+			let end = metadata.region().end;
+			ensure!(end > self.current_timeslice(), RentalError::RegionExpired);
+
+			PSP34Ref::transfer(&self.config.xc_regions_contract, contract, id.clone(), Vec::new())
+				.map_err(RentalError::XcRegionsPsp34Error)?;
+			// Don't rely on the transfer succeeding, but ensure that the contract actually received
+			// the region.
+			ensure!(
+				PSP34Ref::owner_of(&self.config.xc_regions_contract, id) == Some(contract),
+				RentalError::RegionNotReceived
+			);
+
+			self.rentals.insert(
+				region_id,
+				&Rental { owner: caller, timeslice_price, end, claimable: 0, tenancy: None },
+			);
+
+			self.env()
+				.emit_event(RegionOffered { region_id, owner: caller, timeslice_price });
+			Ok(())
+		}
+
+		/// A function for renting a region offered in the contract.
+		///
+		/// The transferred value is used as the deposit which is streamed to the owner. The
+		/// caller is set as the user of the xcRegion until the deposit runs out. The part of the
+		/// transferred value exceeding the rent due until the region ends is returned.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the rented region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `TenancyStarted` event.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn rent(&mut self, id: Id) -> Result<(), RentalError> {
+			let Id::U128(region_id) = id else { return Err(RentalError::InvalidRegionId) };
+			let caller = self.env().caller();
+			let transferred_value = self.env().transferred_value();
+
+			let (paid_until, previous, excess) =
+				self.start_tenancy(region_id, caller, transferred_value)?;
+			// The remainder of an expired tenancy which doesn't cover a whole timeslice is
+			// returned to the previous tenant.
+			if let Some(previous) = previous.filter(|previous| previous.deposit > 0) {
+				self.env()
+					.transfer(previous.tenant, previous.deposit)
+					.map_err(|_| RentalError::TransferFailed)?;
+			}
+			if excess > 0 {
+				self.env().transfer(caller, excess).map_err(|_| RentalError::TransferFailed)?;
+			}
+
+			self.update_user(id, Some(caller), paid_until)?;
+
+			self.env().emit_event(TenancyStarted {
+				region_id,
+				tenant: caller,
+				deposit: transferred_value.saturating_sub(excess),
+				paid_until,
+			});
+			Ok(())
+		}
+
+		/// A function for extending the tenancy by adding the transferred value to the deposit.
+		///
+		/// Only callable by the tenant while the rent is still paid for. The part of the
+		/// transferred value exceeding the rent due until the region ends is returned.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the rented region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `StreamToppedUp` event.
+		#[ink(message, payable)]
+		#[modifiers(non_reentrant)]
+		pub fn top_up(&mut self, id: Id) -> Result<(), RentalError> {
+			let Id::U128(region_id) = id else { return Err(RentalError::InvalidRegionId) };
+			let caller = self.env().caller();
+			let transferred_value = self.env().transferred_value();
+
+			let (paid_until, excess) = self.top_up_stream(region_id, caller, transferred_value)?;
+			if excess > 0 {
+				self.env().transfer(caller, excess).map_err(|_| RentalError::TransferFailed)?;
+			}
+			self.update_user(id, Some(caller), paid_until)?;
+
+			let amount = transferred_value.saturating_sub(excess);
+			self.env().emit_event(StreamToppedUp { region_id, amount, paid_until });
+			Ok(())
+		}
+
+		/// A function for ending the tenancy.
+		///
+		/// The part of the deposit that wasn't streamed to the owner yet is returned to the
+		/// tenant. The rent of the current timeslice is already streamed and isn't refunded.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the rented region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `TenancyEnded` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn end_tenancy(&mut self, id: Id) -> Result<(), RentalError> {
+			let Id::U128(region_id) = id else { return Err(RentalError::InvalidRegionId) };
+			let caller = self.env().caller();
+
+			let refund = self.stop_tenancy(region_id, caller)?;
+			self.update_user(id, None, 0)?;
+
+			if refund > 0 {
+				self.env().transfer(caller, refund).map_err(|_| RentalError::TransferFailed)?;
+			}
+
+			self.env().emit_event(TenancyEnded { region_id, tenant: caller, refund });
+			Ok(())
+		}
+
+		/// A function for claiming the rent streamed to the owner.
+		///
+		/// Only callable by the owner of the region.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the rented region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `PaymentClaimed` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn claim(&mut self, id: Id) -> Result<(), RentalError> {
+			let Id::U128(region_id) = id else { return Err(RentalError::InvalidRegionId) };
+			let caller = self.env().caller();
+
+			let amount = self.take_claimable(region_id, caller)?;
+			self.env().transfer(caller, amount).map_err(|_| RentalError::TransferFailed)?;
+
+			self.env().emit_event(PaymentClaimed { region_id, owner: caller, amount });
+			Ok(())
+		}
+
+		/// A function for reclaiming the region from the contract.
+		///
+		/// Only callable by the owner of the region once it is no longer rented out or the rent
+		/// of the tenant stopped being paid for. The unclaimed rent is paid out to the owner.
+		///
+		/// ## Arguments:
+		/// - `region_id`: The `u128` encoded identifier of the rented region.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionReclaimed` event.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn reclaim(&mut self, id: Id) -> Result<(), RentalError> {
+			let Id::U128(region_id) = id else { return Err(RentalError::InvalidRegionId) };
+			let caller = self.env().caller();
+
+			let rental = self.close_rental(region_id, caller)?;
+
+			if let Some(tenancy) = rental.tenancy {
+				self.update_user(id.clone(), None, 0)?;
+				if tenancy.deposit > 0 {
+					self.env()
+						.transfer(tenancy.tenant, tenancy.deposit)
+						.map_err(|_| RentalError::TransferFailed)?;
+				}
+			}
+			if rental.claimable > 0 {
+				self.env()
+					.transfer(caller, rental.claimable)
+					.map_err(|_| RentalError::TransferFailed)?;
+			}
+
+			PSP34Ref::transfer(&self.config.xc_regions_contract, caller, id, Vec::new())
+				.map_err(RentalError::XcRegionsPsp34Error)?;
+
+			self.env().emit_event(RegionReclaimed {
+				region_id,
+				owner: caller,
+				claimed: rental.claimable,
+			});
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimeRental {
+		// Starts a new tenancy in case the region isn't rented out or the rent of the previous
+		// tenant stopped being paid for.
+		//
+		// Returns the timeslice until which the rent is paid for, the previous tenancy and the
+		// part of the deposit exceeding the rent due until the region ends.
+		pub(crate) fn start_tenancy(
+			&mut self,
+			region_id: RawRegionId,
+			tenant: AccountId,
+			deposit: Balance,
+		) -> Result<(Timeslice, Option<Tenancy>, Balance), RentalError> {
+			let rental = self.rentals.get(region_id).ok_or(RentalError::NotOffered)?;
+			let mut rental = self.settle(rental);
+			let current_timeslice = self.current_timeslice();
+
+			ensure!(current_timeslice < rental.end, RentalError::RegionExpired);
+			ensure!(deposit >= rental.timeslice_price, RentalError::InsufficientDeposit);
+			if let Some(previous) = &rental.tenancy {
+				ensure!(
+					previous.paid_until(rental.timeslice_price) <= current_timeslice,
+					RentalError::RegionOccupied
+				);
+			}
+
+			let accepted = deposit.min(rental.rent_until_end(current_timeslice));
+			let tenancy = Tenancy { tenant, deposit: accepted, settled_at: current_timeslice };
+			let previous = rental.tenancy.replace(tenancy);
+			// Stream the rent of the current timeslice right away.
+			let rental = self.settle(rental);
+			let paid_until = rental
+				.tenancy
+				.as_ref()
+				.map_or(current_timeslice, |tenancy| tenancy.paid_until(rental.timeslice_price));
+			self.rentals.insert(region_id, &rental);
+
+			Ok((paid_until, previous, deposit.saturating_sub(accepted)))
+		}
+
+		// Adds `amount` to the deposit of the tenant.
+		//
+		// Returns the timeslice until which the rent is paid for together with the part of
+		// `amount` exceeding the rent due until the region ends.
+		pub(crate) fn top_up_stream(
+			&mut self,
+			region_id: RawRegionId,
+			tenant: AccountId,
+			amount: Balance,
+		) -> Result<(Timeslice, Balance), RentalError> {
+			ensure!(amount > 0, RentalError::InsufficientDeposit);
+			let rental = self.rentals.get(region_id).ok_or(RentalError::NotOffered)?;
+			let mut rental = self.settle(rental);
+			let current_timeslice = self.current_timeslice();
+
+			let tenancy = rental.tenancy.as_mut().ok_or(RentalError::NoTenancy)?;
+			ensure!(tenancy.tenant == tenant, RentalError::NotAllowed);
+			ensure!(
+				tenancy.paid_until(rental.timeslice_price) > current_timeslice,
+				RentalError::TenancyExpired
+			);
+
+			let accepted = amount
+				.min(rental.rent_until_end(tenancy.settled_at).saturating_sub(tenancy.deposit));
+			tenancy.deposit = tenancy.deposit.saturating_add(accepted);
+			let paid_until = tenancy.paid_until(rental.timeslice_price);
+			self.rentals.insert(region_id, &rental);
+
+			Ok((paid_until, amount.saturating_sub(accepted)))
+		}
+
+		// Removes the tenancy of the region.
+		//
+		// Returns the part of the deposit that wasn't streamed to the owner.
+		pub(crate) fn stop_tenancy(
+			&mut self,
+			region_id: RawRegionId,
+			tenant: AccountId,
+		) -> Result<Balance, RentalError> {
+			let rental = self.rentals.get(region_id).ok_or(RentalError::NotOffered)?;
+			let mut rental = self.settle(rental);
+
+			let tenancy = rental.tenancy.take().ok_or(RentalError::NoTenancy)?;
+			ensure!(tenancy.tenant == tenant, RentalError::NotAllowed);
+			self.rentals.insert(region_id, &rental);
+
+			Ok(tenancy.deposit)
+		}
+
+		// Resets the rent claimable by the owner and returns it.
+		pub(crate) fn take_claimable(
+			&mut self,
+			region_id: RawRegionId,
+			owner: AccountId,
+		) -> Result<Balance, RentalError> {
+			let rental = self.rentals.get(region_id).ok_or(RentalError::NotOffered)?;
+			let mut rental = self.settle(rental);
+
+			ensure!(rental.owner == owner, RentalError::NotAllowed);
+			ensure!(rental.claimable > 0, RentalError::NothingToClaim);
+
+			let claimable = core::mem::take(&mut rental.claimable);
+			self.rentals.insert(region_id, &rental);
+
+			Ok(claimable)
+		}
+
+		// Removes the rental in case the region isn't rented out or the rent of the tenant
+		// stopped being paid for.
+		pub(crate) fn close_rental(
+			&mut self,
+			region_id: RawRegionId,
+			owner: AccountId,
+		) -> Result<Rental, RentalError> {
+			let rental = self.rentals.get(region_id).ok_or(RentalError::NotOffered)?;
+			let rental = self.settle(rental);
+
+			ensure!(rental.owner == owner, RentalError::NotAllowed);
+			if let Some(tenancy) = &rental.tenancy {
+				ensure!(
+					tenancy.paid_until(rental.timeslice_price) <= self.current_timeslice(),
+					RentalError::PaymentsOngoing
+				);
+			}
+
+			self.rentals.remove(region_id);
+			Ok(rental)
+		}
+
+		// Streams the rent of every timeslice that began since the tenancy was last settled,
+		// including the current one, from the deposit of the tenant to the owner. Only the rent of
+		// whole timeslices is streamed, so a remainder that doesn't cover a timeslice stays in
+		// the deposit. No rent is streamed for the timeslices after the region ends.
+		pub(crate) fn settle(&self, mut rental: Rental) -> Rental {
+			let current_timeslice = self.current_timeslice();
+			let Some(tenancy) = rental.tenancy.as_mut() else { return rental };
+
+			let due_timeslices = current_timeslice
+				.saturating_add(1)
+				.min(rental.end)
+				.saturating_sub(tenancy.settled_at);
+			let paid_timeslices: Timeslice = tenancy
+				.deposit
+				.checked_div(rental.timeslice_price)
+				.unwrap_or_default()
+				.try_into()
+				.unwrap_or(Timeslice::MAX);
+			let streamed_timeslices = due_timeslices.min(paid_timeslices);
+			let streamed = rental.timeslice_price.saturating_mul(streamed_timeslices.into());
+
+			tenancy.deposit = tenancy.deposit.saturating_sub(streamed);
+			tenancy.settled_at = tenancy.settled_at.saturating_add(streamed_timeslices);
+			rental.claimable = rental.claimable.saturating_add(streamed);
+
+			rental
+		}
+
+		fn update_user(
+			&self,
+			id: Id,
+			user: Option<AccountId>,
+			expires: Timeslice,
+		) -> Result<(), RentalError> {
+			let mut xc_regions: XcRegionsRef =
+				FromAccountId::from_account_id(self.config.xc_regions_contract);
+			xc_regions.set_user(id, user, expires).map_err(RentalError::XcRegionsError)
+		}
+
+		pub(crate) fn current_timeslice(&self) -> Timeslice {
+			(self.relay_block_number() / self.config.timeslice_period).saturated_into()
+		}
+
+		#[cfg(not(test))]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().extension().relay_chain_block_number().unwrap_or_default()
+		}
+
+		#[cfg(test)]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().block_number()
+		}
+	}
+}
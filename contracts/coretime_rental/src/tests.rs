@@ -0,0 +1,239 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_rental::CoretimeRental,
+	types::{Rental, RentalError, Tenancy},
+};
+use ink::env::{
+	test::{advance_block, default_accounts, DefaultAccounts},
+	DefaultEnvironment,
+};
+use openbrush::contracts::psp34::Id;
+use primitives::assert_ok;
+
+const TIMESLICE_PERIOD: u32 = 80;
+const TIMESLICE_PRICE: u128 = 100;
+const REGION_END: u32 = 100;
+
+#[ink::test]
+fn start_tenancy_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, dave, .. } = get_default_accounts();
+	let mut rental = new_rental();
+
+	assert_eq!(rental.start_tenancy(0, charlie, 1_000), Err(RentalError::NotOffered));
+	offer(&mut rental, 0, bob, REGION_END);
+
+	// The deposit must cover at least a single timeslice:
+	assert_eq!(
+		rental.start_tenancy(0, charlie, TIMESLICE_PRICE - 1),
+		Err(RentalError::InsufficientDeposit)
+	);
+
+	assert_eq!(rental.start_tenancy(0, charlie, 250), Ok((2, None, 0)));
+	assert_eq!(rental.paid_until(Id::U128(0)), Some(2));
+	// The rent of the current timeslice is paid upfront:
+	assert_eq!(
+		rental.rental(Id::U128(0)).and_then(|rental| rental.tenancy),
+		Some(Tenancy { tenant: charlie, deposit: 150, settled_at: 1 })
+	);
+
+	// The region can't be rented by anyone else while the rent is paid for:
+	assert_eq!(rental.start_tenancy(0, dave, 1_000), Err(RentalError::RegionOccupied));
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(rental.start_tenancy(0, dave, 1_000), Err(RentalError::RegionOccupied));
+
+	// Once the deposit runs out the region can be rented again. The remainder is returned as
+	// part of the previous tenancy:
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(
+		rental.start_tenancy(0, dave, 1_000),
+		Ok((12, Some(Tenancy { tenant: charlie, deposit: 50, settled_at: 2 }), 0))
+	);
+	// The rent of the previous tenant together with the first timeslice of the new one:
+	assert_eq!(rental.rental(Id::U128(0)).map(|rental| rental.claimable), Some(300));
+}
+
+#[ink::test]
+fn streaming_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut rental = new_rental();
+	offer(&mut rental, 0, bob, REGION_END);
+
+	assert_eq!(rental.take_claimable(0, bob), Err(RentalError::NothingToClaim));
+	assert_ok!(rental.start_tenancy(0, charlie, 1_000));
+
+	// The rent of the current timeslice is streamed upfront, and nothing more is streamed
+	// within the same timeslice:
+	advance_n_blocks(TIMESLICE_PERIOD - 1);
+	assert_eq!(rental.rental(Id::U128(0)).map(|rental| rental.claimable), Some(100));
+
+	// The rent is streamed every timeslice:
+	advance_n_blocks(TIMESLICE_PERIOD * 2 + 1);
+	assert_eq!(
+		rental.rental(Id::U128(0)),
+		Some(Rental {
+			owner: bob,
+			timeslice_price: TIMESLICE_PRICE,
+			end: REGION_END,
+			claimable: 400,
+			tenancy: Some(Tenancy { tenant: charlie, deposit: 600, settled_at: 4 }),
+		})
+	);
+
+	// Only the owner can claim the rent:
+	assert_eq!(rental.take_claimable(0, charlie), Err(RentalError::NotAllowed));
+	assert_eq!(rental.take_claimable(0, bob), Ok(400));
+	assert_eq!(rental.take_claimable(0, bob), Err(RentalError::NothingToClaim));
+
+	// The streamed rent is capped to the deposit:
+	advance_n_blocks(TIMESLICE_PERIOD * 20);
+	assert_eq!(rental.take_claimable(0, bob), Ok(600));
+	assert_eq!(rental.paid_until(Id::U128(0)), Some(10));
+}
+
+#[ink::test]
+fn top_up_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, dave, .. } = get_default_accounts();
+	let mut rental = new_rental();
+	offer(&mut rental, 0, bob, REGION_END);
+
+	assert_eq!(rental.top_up_stream(0, charlie, 100), Err(RentalError::NoTenancy));
+	assert_ok!(rental.start_tenancy(0, charlie, 200));
+
+	assert_eq!(rental.top_up_stream(0, charlie, 0), Err(RentalError::InsufficientDeposit));
+	assert_eq!(rental.top_up_stream(0, dave, 100), Err(RentalError::NotAllowed));
+
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(rental.top_up_stream(0, charlie, 100), Ok((3, 0)));
+	assert_eq!(rental.paid_until(Id::U128(0)), Some(3));
+
+	// The stream can't be topped up once the payments stopped:
+	advance_n_blocks(TIMESLICE_PERIOD * 2);
+	assert_eq!(rental.top_up_stream(0, charlie, 100), Err(RentalError::TenancyExpired));
+}
+
+#[ink::test]
+fn stop_tenancy_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, dave, .. } = get_default_accounts();
+	let mut rental = new_rental();
+	offer(&mut rental, 0, bob, REGION_END);
+
+	assert_eq!(rental.stop_tenancy(0, charlie), Err(RentalError::NoTenancy));
+	assert_ok!(rental.start_tenancy(0, charlie, 1_000));
+	assert_eq!(rental.stop_tenancy(0, dave), Err(RentalError::NotAllowed));
+
+	// The tenant gets back the part of the deposit which wasn't streamed:
+	advance_n_blocks(TIMESLICE_PERIOD * 4);
+	assert_eq!(rental.stop_tenancy(0, charlie), Ok(500));
+	assert_eq!(
+		rental.rental(Id::U128(0)),
+		Some(Rental {
+			owner: bob,
+			timeslice_price: TIMESLICE_PRICE,
+			end: REGION_END,
+			claimable: 500,
+			tenancy: None
+		})
+	);
+
+	// Ending the tenancy in the timeslice it started doesn't refund the rent of that timeslice:
+	assert_ok!(rental.start_tenancy(0, charlie, 1_000));
+	assert_eq!(rental.stop_tenancy(0, charlie), Ok(900));
+	assert_eq!(rental.rental(Id::U128(0)).map(|rental| rental.claimable), Some(600));
+}
+
+#[ink::test]
+fn close_rental_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut rental = new_rental();
+
+	assert_eq!(rental.close_rental(0, bob), Err(RentalError::NotOffered));
+	offer(&mut rental, 0, bob, REGION_END);
+	assert_ok!(rental.start_tenancy(0, charlie, 250));
+
+	assert_eq!(rental.close_rental(0, charlie), Err(RentalError::NotAllowed));
+	// The region can't be reclaimed while the rent is paid for:
+	assert_eq!(rental.close_rental(0, bob), Err(RentalError::PaymentsOngoing));
+
+	advance_n_blocks(TIMESLICE_PERIOD * 2);
+	assert_eq!(
+		rental.close_rental(0, bob),
+		Ok(Rental {
+			owner: bob,
+			timeslice_price: TIMESLICE_PRICE,
+			end: REGION_END,
+			claimable: 200,
+			tenancy: Some(Tenancy { tenant: charlie, deposit: 50, settled_at: 2 }),
+		})
+	);
+	assert_eq!(rental.rental(Id::U128(0)), None);
+}
+
+#[ink::test]
+fn region_end_caps_rent() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, dave, .. } = get_default_accounts();
+	let mut rental = new_rental();
+	offer(&mut rental, 0, bob, 5);
+
+	// Only the rent due until the region ends is kept from the deposit:
+	assert_eq!(rental.start_tenancy(0, charlie, 1_000), Ok((5, None, 500)));
+	assert_eq!(rental.top_up_stream(0, charlie, 100), Ok((5, 100)));
+
+	// No rent is streamed for the timeslices after the region ends:
+	advance_n_blocks(TIMESLICE_PERIOD * 10);
+	assert_eq!(
+		rental.rental(Id::U128(0)),
+		Some(Rental {
+			owner: bob,
+			timeslice_price: TIMESLICE_PRICE,
+			end: 5,
+			claimable: 500,
+			tenancy: Some(Tenancy { tenant: charlie, deposit: 0, settled_at: 5 }),
+		})
+	);
+
+	// Regions that ended can't be rented anymore:
+	assert_eq!(rental.start_tenancy(0, dave, 1_000), Err(RentalError::RegionExpired));
+}
+
+fn new_rental() -> CoretimeRental {
+	let DefaultAccounts::<DefaultEnvironment> { alice, .. } = get_default_accounts();
+	CoretimeRental::new(alice, TIMESLICE_PERIOD)
+}
+
+// Offering a region involves a cross-contract transfer of the xcRegion, so the tests insert the
+// rental directly.
+fn offer(
+	rental: &mut CoretimeRental,
+	region_id: u128,
+	owner: ink::primitives::AccountId,
+	end: u32,
+) {
+	rental.rentals.insert(
+		region_id,
+		&Rental { owner, timeslice_price: TIMESLICE_PRICE, end, claimable: 0, tenancy: None },
+	);
+}
+
+fn advance_n_blocks(n: u32) {
+	for _ in 0..n {
+		advance_block::<DefaultEnvironment>();
+	}
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
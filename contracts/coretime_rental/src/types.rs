@@ -0,0 +1,143 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use openbrush::{
+	contracts::traits::{errors::ReentrancyGuardError, psp34::PSP34Error},
+	traits::{AccountId, BlockNumber},
+};
+use primitives::{coretime::Timeslice, Balance};
+use xc_regions::types::XcRegionsError;
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Config {
+	/// The `AccountId` of the xcRegions contract.
+	pub xc_regions_contract: AccountId,
+	/// The number of relay chain blocks in a timeslice.
+	pub timeslice_period: BlockNumber,
+}
+
+/// A region escrowed in the contract to be rented out.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Rental {
+	/// The owner of the region, receiving the rent.
+	pub owner: AccountId,
+	/// The rent paid by the tenant for every timeslice.
+	pub timeslice_price: Balance,
+	/// The timeslice at which the region ends. No rent is due from then on.
+	pub end: Timeslice,
+	/// The rent streamed to the owner which wasn't claimed yet.
+	pub claimable: Balance,
+	/// The current tenancy of the region, if rented out.
+	pub tenancy: Option<Tenancy>,
+}
+
+impl Rental {
+	/// Returns the rent of every timeslice from `from` until the region ends.
+	pub fn rent_until_end(&self, from: Timeslice) -> Balance {
+		self.timeslice_price.saturating_mul(self.end.saturating_sub(from).into())
+	}
+}
+
+/// The tenancy of a rented region.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Tenancy {
+	/// The account renting the region.
+	pub tenant: AccountId,
+	/// The deposit of the tenant which wasn't streamed to the owner yet.
+	pub deposit: Balance,
+	/// The timeslice until which the rent was streamed to the owner.
+	pub settled_at: Timeslice,
+}
+
+impl Tenancy {
+	/// Returns the timeslice until which the rent is paid for.
+	pub fn paid_until(&self, timeslice_price: Balance) -> Timeslice {
+		let paid_timeslices = self.deposit.checked_div(timeslice_price).unwrap_or_default();
+		self.settled_at
+			.saturating_add(paid_timeslices.try_into().unwrap_or(Timeslice::MAX))
+	}
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum RentalError {
+	/// The provided identifier is not a valid region id.
+	InvalidRegionId,
+	/// The price must be greater than zero.
+	InvalidPrice,
+	/// The caller is not allowed to perform the operation.
+	NotAllowed,
+	/// The region is already offered for rent.
+	AlreadyOffered,
+	/// The region isn't offered for rent.
+	NotOffered,
+	/// The region is currently rented out.
+	RegionOccupied,
+	/// The region isn't rented out.
+	NoTenancy,
+	/// The deposit doesn't cover the rent of a single timeslice.
+	InsufficientDeposit,
+	/// The rent of the tenant is still paid for.
+	PaymentsOngoing,
+	/// The rent of the tenant stopped being paid for.
+	TenancyExpired,
+	/// The caller doesn't have any rent to claim.
+	NothingToClaim,
+	/// The contract didn't receive the region.
+	RegionNotReceived,
+	/// Failed to transfer the native tokens.
+	TransferFailed,
+	/// The call would result in reentering the contract.
+	ReentrantCall,
+	/// An error occured when transferring the xcRegion.
+	XcRegionsPsp34Error(PSP34Error),
+	/// An error occured when updating the user of the xcRegion.
+	XcRegionsError(XcRegionsError),
+	/// The region has already ended.
+	RegionExpired,
+}
+
+impl core::fmt::Display for RentalError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			RentalError::InvalidRegionId => write!(f, "InvalidRegionId"),
+			RentalError::InvalidPrice => write!(f, "InvalidPrice"),
+			RentalError::NotAllowed => write!(f, "NotAllowed"),
+			RentalError::AlreadyOffered => write!(f, "AlreadyOffered"),
+			RentalError::NotOffered => write!(f, "NotOffered"),
+			RentalError::RegionOccupied => write!(f, "RegionOccupied"),
+			RentalError::NoTenancy => write!(f, "NoTenancy"),
+			RentalError::InsufficientDeposit => write!(f, "InsufficientDeposit"),
+			RentalError::PaymentsOngoing => write!(f, "PaymentsOngoing"),
+			RentalError::TenancyExpired => write!(f, "TenancyExpired"),
+			RentalError::NothingToClaim => write!(f, "NothingToClaim"),
+			RentalError::RegionNotReceived => write!(f, "RegionNotReceived"),
+			RentalError::TransferFailed => write!(f, "TransferFailed"),
+			RentalError::ReentrantCall => write!(f, "ReentrantCall"),
+			RentalError::XcRegionsPsp34Error(e) => write!(f, "{:?}", e),
+			RentalError::XcRegionsError(e) => write!(f, "{}", e),
+			RentalError::RegionExpired => write!(f, "RegionExpired"),
+		}
+	}
+}
+
+impl From<ReentrancyGuardError> for RentalError {
+	fn from(_: ReentrancyGuardError) -> Self {
+		RentalError::ReentrantCall
+	}
+}
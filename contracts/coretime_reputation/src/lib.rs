@@ -0,0 +1,139 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime reputation
+//!
+//! This contract tracks the reputation of the sellers on the Coretime market, allowing frontends
+//! to badge reputable sellers.
+//!
+//! The market reports the events concerning its sellers as they get settled: completed sales,
+//! disputed sales resolved by an arbiter, and regions unlisted by their seller before expiring.
+//! For every seller, the registry keeps a count of each kind of event.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+pub mod traits;
+pub mod types;
+
+#[openbrush::contract]
+pub mod coretime_reputation {
+	use crate::{
+		traits::{reputationrecorder_external, ReputationRecorder},
+		types::{ReputationError, SellerEvent, SellerStats},
+	};
+	use ink::{
+		codegen::{EmitEvent, Env},
+		prelude::vec::Vec,
+	};
+	use openbrush::{storage::Mapping, traits::Storage};
+	use primitives::ensure;
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimeReputation {
+		/// The `AccountId` of the Coretime market, which is the only account allowed to record
+		/// events.
+		pub market_contract: AccountId,
+		/// A mapping that holds the statistics of each seller.
+		pub stats: Mapping<AccountId, SellerStats>,
+	}
+
+	#[ink(event)]
+	pub struct SellerEventRecorded {
+		/// The seller the event concerns.
+		#[ink(topic)]
+		pub(crate) seller: AccountId,
+		/// The recorded event.
+		pub(crate) event: SellerEvent,
+	}
+
+	impl CoretimeReputation {
+		/// ## Arguments:
+		/// - `market_contract` - The `AccountId` of the Coretime market.
+		#[ink(constructor)]
+		pub fn new(market_contract: AccountId) -> Self {
+			Self { market_contract, stats: Default::default() }
+		}
+
+		#[ink(message)]
+		pub fn market_contract(&self) -> AccountId {
+			self.market_contract
+		}
+
+		#[ink(message)]
+		pub fn stats(&self, seller: AccountId) -> SellerStats {
+			self.stats.get(seller).unwrap_or_default()
+		}
+
+		/// A function to retrieve the statistics of multiple sellers at once.
+		///
+		/// The results are returned in the same order as the provided sellers.
+		///
+		/// ## Arguments:
+		/// - `sellers` - The sellers whose statistics to retrieve.
+		#[ink(message)]
+		pub fn stats_batch(&self, sellers: Vec<AccountId>) -> Vec<SellerStats> {
+			sellers.into_iter().map(|seller| self.stats(seller)).collect()
+		}
+	}
+
+	impl ReputationRecorder for CoretimeReputation {
+		/// Records an event concerning a seller.
+		///
+		/// Only callable by the market.
+		///
+		/// ## Arguments:
+		/// - `seller` - The seller the event concerns.
+		/// - `event` - The recorded event.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `SellerEventRecorded` event.
+		#[ink(message)]
+		fn record(&mut self, seller: AccountId, event: SellerEvent) -> Result<(), ReputationError> {
+			ensure!(self.env().caller() == self.market_contract, ReputationError::NotMarket);
+
+			self.apply(seller, &event);
+
+			self.env().emit_event(SellerEventRecorded { seller, event });
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimeReputation {
+		pub(crate) fn apply(&mut self, seller: AccountId, event: &SellerEvent) {
+			let mut stats = self.stats(seller);
+
+			match event {
+				SellerEvent::Sale =>
+					stats.completed_sales = stats.completed_sales.saturating_add(1),
+				SellerEvent::Dispute { refunded } => {
+					stats.disputes = stats.disputes.saturating_add(1);
+					if *refunded {
+						stats.disputes_lost = stats.disputes_lost.saturating_add(1);
+					}
+				},
+				SellerEvent::Cancellation =>
+					stats.cancellations = stats.cancellations.saturating_add(1),
+			}
+
+			self.stats.insert(seller, &stats);
+		}
+	}
+}
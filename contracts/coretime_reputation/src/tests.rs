@@ -0,0 +1,71 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_reputation::CoretimeReputation,
+	traits::ReputationRecorder,
+	types::{ReputationError, SellerEvent, SellerStats},
+};
+use ink::env::{
+	test::{default_accounts, set_caller, DefaultAccounts},
+	DefaultEnvironment,
+};
+use primitives::assert_ok;
+
+#[ink::test]
+fn record_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	let mut reputation = new_reputation();
+
+	// Only the market can record events.
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(reputation.record(charlie, SellerEvent::Sale), Err(ReputationError::NotMarket));
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(reputation.record(charlie, SellerEvent::Sale));
+	assert_eq!(reputation.stats(charlie), SellerStats { completed_sales: 1, ..Default::default() });
+}
+
+#[ink::test]
+fn apply_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, django, .. } = get_default_accounts();
+	let mut reputation = new_reputation();
+
+	reputation.apply(charlie, &SellerEvent::Sale);
+	reputation.apply(charlie, &SellerEvent::Sale);
+	reputation.apply(charlie, &SellerEvent::Dispute { refunded: false });
+	reputation.apply(charlie, &SellerEvent::Dispute { refunded: true });
+	reputation.apply(charlie, &SellerEvent::Cancellation);
+	reputation.apply(django, &SellerEvent::Cancellation);
+
+	let charlie_stats =
+		SellerStats { completed_sales: 2, disputes: 2, disputes_lost: 1, cancellations: 1 };
+	let django_stats = SellerStats { cancellations: 1, ..Default::default() };
+	assert_eq!(reputation.stats(charlie), charlie_stats);
+	assert_eq!(reputation.stats(django), django_stats);
+	assert_eq!(
+		reputation.stats_batch(vec![django, bob, charlie]),
+		vec![django_stats, SellerStats::default(), charlie_stats]
+	);
+}
+
+fn new_reputation() -> CoretimeReputation {
+	let DefaultAccounts::<DefaultEnvironment> { alice, .. } = get_default_accounts();
+	CoretimeReputation::new(alice)
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
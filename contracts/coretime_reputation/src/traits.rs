@@ -0,0 +1,32 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::types::{ReputationError, SellerEvent};
+use openbrush::traits::AccountId;
+
+#[openbrush::wrapper]
+pub type ReputationRecorderRef = dyn ReputationRecorder;
+
+/// The interface through which the Coretime market reports the events concerning its sellers.
+#[openbrush::trait_definition]
+pub trait ReputationRecorder {
+	/// Records an event concerning a seller.
+	///
+	/// ## Arguments:
+	/// - `seller` - The seller the event concerns.
+	/// - `event` - The recorded event.
+	#[ink(message)]
+	fn record(&mut self, seller: AccountId, event: SellerEvent) -> Result<(), ReputationError>;
+}
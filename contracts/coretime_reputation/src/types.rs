@@ -0,0 +1,58 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+/// An event concerning a seller on the Coretime market.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum SellerEvent {
+	/// A sale of the seller was completed.
+	Sale,
+	/// A disputed sale of the seller was resolved by the arbiter.
+	Dispute {
+		/// Whether the buyer got refunded.
+		refunded: bool,
+	},
+	/// The seller unlisted a region before it expired.
+	Cancellation,
+}
+
+/// The statistics of a seller on the Coretime market.
+#[derive(scale::Decode, scale::Encode, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct SellerStats {
+	/// The number of completed sales.
+	pub completed_sales: u32,
+	/// The number of disputed sales.
+	pub disputes: u32,
+	/// The number of disputed sales in which the buyer got refunded.
+	pub disputes_lost: u32,
+	/// The number of regions unlisted by the seller before they expired.
+	pub cancellations: u32,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ReputationError {
+	/// The caller is not the Coretime market.
+	NotMarket,
+}
+
+impl core::fmt::Display for ReputationError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			ReputationError::NotMarket => write!(f, "NotMarket"),
+		}
+	}
+}
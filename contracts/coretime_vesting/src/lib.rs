@@ -0,0 +1,288 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Coretime vesting
+//!
+//! This contract escrows xcRegions and releases them to a beneficiary only once a specified
+//! timeslice is reached, enabling deferred handovers of regions agreed upon off-chain.
+//!
+//! The regions are escrowed as part of a vesting schedule, in which every region has its own
+//! release timeslice. This way a handover can happen all at once, by releasing all regions at the
+//! same timeslice, or in stages. Once a stage can be released, anyone can trigger the transfer of
+//! the region to the beneficiary. Schedules are irrevocable.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+mod types;
+
+/// The maximum number of stages a single vesting schedule can have.
+pub const MAX_STAGES: usize = 16;
+
+#[openbrush::contract(env = environment::ExtendedEnvironment)]
+pub mod coretime_vesting {
+	use crate::{
+		types::{Config, Schedule, ScheduleId, Stage, VestingError},
+		MAX_STAGES,
+	};
+	use block_number_extension::BlockNumberProviderExtension;
+	use ink::{
+		codegen::{EmitEvent, Env},
+		prelude::vec::Vec,
+	};
+	use openbrush::{
+		contracts::traits::psp34::{Id, PSP34Ref},
+		storage::Mapping,
+		traits::Storage,
+	};
+	use primitives::{
+		coretime::{RawRegionId, Timeslice},
+		ensure,
+	};
+	use sp_arithmetic::traits::SaturatedConversion;
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct CoretimeVesting {
+		/// The configuration of the vesting contract.
+		pub config: Config,
+		/// A mapping that holds all the vesting schedules with unreleased stages.
+		pub schedules: Mapping<ScheduleId, Schedule>,
+		/// The identifier assigned to the next schedule.
+		pub next_schedule_id: ScheduleId,
+	}
+
+	#[ink(event)]
+	pub struct ScheduleCreated {
+		/// The identifier of the schedule.
+		#[ink(topic)]
+		pub(crate) schedule_id: ScheduleId,
+		/// The account that escrowed the regions.
+		#[ink(topic)]
+		pub(crate) depositor: AccountId,
+		/// The account receiving the regions.
+		#[ink(topic)]
+		pub(crate) beneficiary: AccountId,
+		/// The stages of the schedule.
+		pub(crate) stages: Vec<Stage>,
+	}
+
+	#[ink(event)]
+	pub struct RegionReleased {
+		/// The identifier of the schedule.
+		#[ink(topic)]
+		pub(crate) schedule_id: ScheduleId,
+		/// The identifier of the released region.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that received the region.
+		pub(crate) beneficiary: AccountId,
+	}
+
+	impl CoretimeVesting {
+		/// ## Arguments:
+		/// - `xc_regions_contract` - The `AccountId` of the xcRegions contract.
+		/// - `timeslice_period` - The number of relay chain blocks in a timeslice.
+		#[ink(constructor)]
+		pub fn new(xc_regions_contract: AccountId, timeslice_period: BlockNumber) -> Self {
+			assert!(timeslice_period > 0, "Timeslice period must be greater than zero");
+
+			Self {
+				config: Config { xc_regions_contract, timeslice_period },
+				schedules: Default::default(),
+				next_schedule_id: 0,
+			}
+		}
+
+		#[ink(message)]
+		pub fn config(&self) -> Config {
+			self.config.clone()
+		}
+
+		#[ink(message)]
+		pub fn schedule(&self, schedule_id: ScheduleId) -> Option<Schedule> {
+			self.schedules.get(schedule_id)
+		}
+
+		/// A function for escrowing xcRegions which are released to the beneficiary in stages.
+		///
+		/// ## Arguments:
+		/// - `beneficiary`: The account receiving the regions.
+		/// - `stages`: The `u128` encoded identifiers of the regions together with the timeslice
+		///   from which each of them can be released. The caller must own the regions and approve
+		///   them to the contract beforehand.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `ScheduleCreated` event.
+		#[ink(message)]
+		pub fn create_schedule(
+			&mut self,
+			beneficiary: AccountId,
+			stages: Vec<(Id, Timeslice)>,
+		) -> Result<ScheduleId, VestingError> {
+			let caller = self.env().caller();
+			let contract = self.env().account_id();
+
+			let stages = stages
+				.into_iter()
+				.map(|(id, release_at)| match id {
+					Id::U128(region_id) => Ok(Stage { region_id, release_at }),
+					_ => Err(VestingError::InvalidRegionId),
+				})
+				.collect::<Result<Vec<_>, _>>()?;
+			for stage in stages.iter() {
+				ensure!(
+					PSP34Ref::owner_of(&self.config.xc_regions_contract, Id::U128(stage.region_id)) ==
+						Some(caller),
+					VestingError::NotAllowed
+				);
+			}
+			let schedule_id = self.record_schedule(caller, beneficiary, stages.clone())?;
+
+			for stage in stages.iter() {
+				let id = Id::U128(stage.region_id);
+				PSP34Ref::transfer(
+					&self.config.xc_regions_contract,
+					contract,
+					id.clone(),
+					Vec::new(),
+				)
+				.map_err(VestingError::XcRegionsPsp34Error)?;
+				// Don't rely on the transfer succeeding, but ensure that the contract actually
+				// received the region.
+				ensure!(
+					PSP34Ref::owner_of(&self.config.xc_regions_contract, id) == Some(contract),
+					VestingError::RegionNotReceived
+				);
+			}
+
+			self.env().emit_event(ScheduleCreated {
+				schedule_id,
+				depositor: caller,
+				beneficiary,
+				stages,
+			});
+			Ok(schedule_id)
+		}
+
+		/// A function for releasing all the stages of a schedule that reached their release
+		/// timeslice.
+		///
+		/// Callable by anyone since the regions can only be released to the beneficiary.
+		///
+		/// ## Arguments:
+		/// - `schedule_id`: The identifier of the schedule.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionReleased` event for every released region.
+		#[ink(message)]
+		pub fn release(&mut self, schedule_id: ScheduleId) -> Result<(), VestingError> {
+			let (beneficiary, released) = self.take_releasable(schedule_id)?;
+
+			for region_id in released {
+				PSP34Ref::transfer(
+					&self.config.xc_regions_contract,
+					beneficiary,
+					Id::U128(region_id),
+					Vec::new(),
+				)
+				.map_err(VestingError::XcRegionsPsp34Error)?;
+
+				self.env().emit_event(RegionReleased { schedule_id, region_id, beneficiary });
+			}
+
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl CoretimeVesting {
+		// Records a new schedule and returns its identifier.
+		pub(crate) fn record_schedule(
+			&mut self,
+			depositor: AccountId,
+			beneficiary: AccountId,
+			stages: Vec<Stage>,
+		) -> Result<ScheduleId, VestingError> {
+			ensure!(
+				!stages.is_empty() && stages.len() <= MAX_STAGES,
+				VestingError::InvalidStageCount
+			);
+
+			let current_timeslice = self.current_timeslice();
+			for (index, stage) in stages.iter().enumerate() {
+				ensure!(
+					stage.release_at > current_timeslice,
+					VestingError::InvalidReleaseTimeslice
+				);
+				ensure!(
+					!stages[..index].iter().any(|other| other.region_id == stage.region_id),
+					VestingError::DuplicateRegion
+				);
+			}
+
+			let schedule_id = self.next_schedule_id;
+			self.schedules.insert(schedule_id, &Schedule { depositor, beneficiary, stages });
+			self.next_schedule_id = self.next_schedule_id.saturating_add(1);
+
+			Ok(schedule_id)
+		}
+
+		// Removes the stages of the schedule that reached their release timeslice. The schedule
+		// is removed once all of its stages are released.
+		//
+		// Returns the beneficiary together with the released regions.
+		pub(crate) fn take_releasable(
+			&mut self,
+			schedule_id: ScheduleId,
+		) -> Result<(AccountId, Vec<RawRegionId>), VestingError> {
+			let mut schedule =
+				self.schedules.get(schedule_id).ok_or(VestingError::ScheduleNotFound)?;
+			let current_timeslice = self.current_timeslice();
+
+			let (released, remaining): (Vec<Stage>, Vec<Stage>) = schedule
+				.stages
+				.into_iter()
+				.partition(|stage| stage.release_at <= current_timeslice);
+			ensure!(!released.is_empty(), VestingError::NothingToRelease);
+
+			if remaining.is_empty() {
+				self.schedules.remove(schedule_id);
+			} else {
+				schedule.stages = remaining;
+				self.schedules.insert(schedule_id, &schedule);
+			}
+
+			Ok((schedule.beneficiary, released.into_iter().map(|stage| stage.region_id).collect()))
+		}
+
+		pub(crate) fn current_timeslice(&self) -> Timeslice {
+			(self.relay_block_number() / self.config.timeslice_period).saturated_into()
+		}
+
+		#[cfg(not(test))]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().extension().relay_chain_block_number().unwrap_or_default()
+		}
+
+		#[cfg(test)]
+		pub(crate) fn relay_block_number(&self) -> BlockNumber {
+			self.env().block_number()
+		}
+	}
+}
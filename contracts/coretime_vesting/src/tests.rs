@@ -0,0 +1,104 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	coretime_vesting::CoretimeVesting,
+	types::{Schedule, Stage, VestingError},
+	MAX_STAGES,
+};
+use ink::env::{
+	test::{advance_block, default_accounts, DefaultAccounts},
+	DefaultEnvironment,
+};
+use primitives::assert_ok;
+
+const TIMESLICE_PERIOD: u32 = 80;
+
+#[ink::test]
+fn record_schedule_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut vesting = new_vesting();
+
+	assert_eq!(vesting.record_schedule(bob, charlie, vec![]), Err(VestingError::InvalidStageCount));
+	assert_eq!(
+		vesting.record_schedule(
+			bob,
+			charlie,
+			(0..=MAX_STAGES as u128).map(|i| stage(i, 1)).collect()
+		),
+		Err(VestingError::InvalidStageCount)
+	);
+	// The release timeslice must be in the future:
+	assert_eq!(
+		vesting.record_schedule(bob, charlie, vec![stage(0, 0)]),
+		Err(VestingError::InvalidReleaseTimeslice)
+	);
+	assert_eq!(
+		vesting.record_schedule(bob, charlie, vec![stage(0, 1), stage(0, 2)]),
+		Err(VestingError::DuplicateRegion)
+	);
+
+	assert_eq!(vesting.record_schedule(bob, charlie, vec![stage(0, 1), stage(1, 2)]), Ok(0));
+	assert_eq!(
+		vesting.schedule(0),
+		Some(Schedule {
+			depositor: bob,
+			beneficiary: charlie,
+			stages: vec![stage(0, 1), stage(1, 2)]
+		})
+	);
+	assert_eq!(vesting.record_schedule(bob, charlie, vec![stage(2, 1)]), Ok(1));
+}
+
+#[ink::test]
+fn take_releasable_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut vesting = new_vesting();
+
+	assert_eq!(vesting.take_releasable(0), Err(VestingError::ScheduleNotFound));
+	assert_ok!(vesting.record_schedule(bob, charlie, vec![stage(0, 1), stage(1, 3), stage(2, 1)]));
+
+	assert_eq!(vesting.take_releasable(0), Err(VestingError::NothingToRelease));
+
+	// The stages are released once they reach their release timeslice:
+	advance_n_blocks(TIMESLICE_PERIOD * 2);
+	assert_eq!(vesting.take_releasable(0), Ok((charlie, vec![0, 2])));
+	assert_eq!(vesting.schedule(0).map(|schedule| schedule.stages), Some(vec![stage(1, 3)]));
+	assert_eq!(vesting.take_releasable(0), Err(VestingError::NothingToRelease));
+
+	// The schedule is removed once all stages are released:
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(vesting.take_releasable(0), Ok((charlie, vec![1])));
+	assert_eq!(vesting.schedule(0), None);
+}
+
+fn new_vesting() -> CoretimeVesting {
+	let DefaultAccounts::<DefaultEnvironment> { alice, .. } = get_default_accounts();
+	CoretimeVesting::new(alice, TIMESLICE_PERIOD)
+}
+
+fn stage(region_id: u128, release_at: u32) -> Stage {
+	Stage { region_id, release_at }
+}
+
+fn advance_n_blocks(n: u32) {
+	for _ in 0..n {
+		advance_block::<DefaultEnvironment>();
+	}
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
@@ -0,0 +1,94 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use ink::prelude::vec::Vec;
+use openbrush::{
+	contracts::traits::psp34::PSP34Error,
+	traits::{AccountId, BlockNumber},
+};
+use primitives::coretime::{RawRegionId, Timeslice};
+
+/// The type used for identifying vesting schedules.
+pub type ScheduleId = u32;
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Config {
+	/// The `AccountId` of the xcRegions contract.
+	pub xc_regions_contract: AccountId,
+	/// The number of relay chain blocks in a timeslice.
+	pub timeslice_period: BlockNumber,
+}
+
+/// A single region of a vesting schedule.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Stage {
+	/// The identifier of the escrowed region.
+	pub region_id: RawRegionId,
+	/// The timeslice from which the region can be released to the beneficiary.
+	pub release_at: Timeslice,
+}
+
+/// The regions escrowed for a beneficiary, released in one or more stages.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Schedule {
+	/// The account that escrowed the regions.
+	pub depositor: AccountId,
+	/// The account receiving the regions.
+	pub beneficiary: AccountId,
+	/// The stages that weren't released yet.
+	pub stages: Vec<Stage>,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum VestingError {
+	/// The provided identifier is not a valid region id.
+	InvalidRegionId,
+	/// A schedule must consist of at least one and at most `MAX_STAGES` stages.
+	InvalidStageCount,
+	/// The same region can't be part of multiple stages.
+	DuplicateRegion,
+	/// The release timeslice of a stage must be in the future.
+	InvalidReleaseTimeslice,
+	/// There is no schedule with the provided identifier.
+	ScheduleNotFound,
+	/// None of the remaining stages can be released yet.
+	NothingToRelease,
+	/// The contract didn't receive the region.
+	RegionNotReceived,
+	/// An error occured when transferring the xcRegion.
+	XcRegionsPsp34Error(PSP34Error),
+	/// The caller is not allowed to perform the operation.
+	NotAllowed,
+}
+
+impl core::fmt::Display for VestingError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			VestingError::InvalidRegionId => write!(f, "InvalidRegionId"),
+			VestingError::InvalidStageCount => write!(f, "InvalidStageCount"),
+			VestingError::DuplicateRegion => write!(f, "DuplicateRegion"),
+			VestingError::InvalidReleaseTimeslice => write!(f, "InvalidReleaseTimeslice"),
+			VestingError::ScheduleNotFound => write!(f, "ScheduleNotFound"),
+			VestingError::NothingToRelease => write!(f, "NothingToRelease"),
+			VestingError::RegionNotReceived => write!(f, "RegionNotReceived"),
+			VestingError::XcRegionsPsp34Error(e) => write!(f, "{:?}", e),
+			VestingError::NotAllowed => write!(f, "NotAllowed"),
+		}
+	}
+}
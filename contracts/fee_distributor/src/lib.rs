@@ -0,0 +1,400 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Fee distributor
+//!
+//! This is a companion contract of the Coretime market which distributes the fees accrued by the
+//! market among the holders of a PSP22 token who stake it in this contract.
+//!
+//! For the contract to receive the fees, it has to be configured as the fee recipient of the
+//! market. The fees withdrawn from the market, or sent through `deposit_fees`, are distributed at
+//! the start of every epoch to the stakers, pro-rata to their active stake.
+//!
+//! Newly added stake only becomes active at the next distribution. This prevents accounts from
+//! staking right before a distribution to capture a share of the fees accrued over the whole
+//! epoch.
+//!
+//! The rewards are tracked through a cumulative reward index, which represents the rewards a
+//! single unit of active stake accrued since the deployment of the contract. This way the rewards
+//! of each staker can be accounted for lazily, whenever the staker interacts with the contract.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+mod tests;
+
+mod types;
+
+#[openbrush::contract]
+pub mod fee_distributor {
+	use crate::types::{Config, EpochIndex, FeeDistributorError, StakerInfo};
+	use ink::{
+		codegen::{EmitEvent, Env},
+		prelude::vec::Vec,
+	};
+	use openbrush::{
+		contracts::{reentrancy_guard::*, traits::psp22::PSP22Ref},
+		modifiers,
+		storage::Mapping,
+		traits::Storage,
+	};
+	use primitives::ensure;
+	use sp_arithmetic::{traits::SaturatedConversion, FixedPointNumber, FixedU128};
+
+	#[ink(storage)]
+	#[derive(Storage)]
+	pub struct FeeDistributor {
+		#[storage_field]
+		guard: reentrancy_guard::Data,
+		/// The configuration of the fee distributor.
+		pub config: Config,
+		/// A mapping that holds the stake of each account.
+		pub stakers: Mapping<AccountId, StakerInfo>,
+		/// The sum of the stake taken into account when distributing fees.
+		pub total_active: Balance,
+		/// The sum of the stake that becomes active at the next distribution.
+		pub total_pending: Balance,
+		/// The inner value of the cumulative reward index.
+		///
+		/// Represents the rewards accrued by a single unit of active stake so far.
+		pub reward_index: u128,
+		/// A mapping that holds the inner value of the reward index at which the stake added in a
+		/// specific epoch became active.
+		pub activation_index: Mapping<EpochIndex, u128>,
+		/// The epoch in which the last distribution took place.
+		pub last_epoch: EpochIndex,
+		/// The rewards that were distributed to stakers, but weren't claimed yet.
+		pub reserved: Balance,
+	}
+
+	#[ink(event)]
+	pub struct FeesDeposited {
+		/// The account that deposited the fees.
+		#[ink(topic)]
+		pub(crate) from: AccountId,
+		/// The deposited amount.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct FeesDistributed {
+		/// The epoch at the start of which the fees were distributed.
+		#[ink(topic)]
+		pub(crate) epoch: EpochIndex,
+		/// The amount distributed among the stakers.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct Staked {
+		/// The account that staked the tokens.
+		#[ink(topic)]
+		pub(crate) staker: AccountId,
+		/// The staked amount.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct Unstaked {
+		/// The account that unstaked the tokens.
+		#[ink(topic)]
+		pub(crate) staker: AccountId,
+		/// The unstaked amount.
+		pub(crate) amount: Balance,
+	}
+
+	#[ink(event)]
+	pub struct RewardsClaimed {
+		/// The account that claimed the rewards.
+		#[ink(topic)]
+		pub(crate) staker: AccountId,
+		/// The claimed amount.
+		pub(crate) amount: Balance,
+	}
+
+	impl FeeDistributor {
+		#[ink(constructor)]
+		pub fn new(staking_token: AccountId, epoch_length: BlockNumber) -> Self {
+			assert!(epoch_length > 0, "Epoch length must be greater than zero");
+
+			let mut distributor = Self {
+				guard: Default::default(),
+				config: Config { staking_token, epoch_length },
+				stakers: Default::default(),
+				total_active: Default::default(),
+				total_pending: Default::default(),
+				reward_index: Default::default(),
+				activation_index: Default::default(),
+				last_epoch: Default::default(),
+				reserved: Default::default(),
+			};
+			distributor.last_epoch = distributor.current_epoch();
+			distributor
+		}
+
+		#[ink(message)]
+		pub fn config(&self) -> Config {
+			self.config.clone()
+		}
+
+		#[ink(message)]
+		pub fn staker(&self, who: AccountId) -> Option<StakerInfo> {
+			self.stakers.get(who)
+		}
+
+		#[ink(message)]
+		pub fn total_staked(&self) -> Balance {
+			self.total_active.saturating_add(self.total_pending)
+		}
+
+		#[ink(message)]
+		pub fn current_epoch(&self) -> EpochIndex {
+			(self.env().block_number() / self.config.epoch_length).saturated_into()
+		}
+
+		/// Returns the rewards the account can claim.
+		///
+		/// The fees received since the last distribution are not included until they are
+		/// distributed at the start of the next epoch.
+		#[ink(message)]
+		pub fn claimable_rewards(&self, who: AccountId) -> Result<Balance, FeeDistributorError> {
+			let info = self.stakers.get(who).unwrap_or_default();
+			Ok(self.accrue_rewards(info)?.unclaimed)
+		}
+
+		/// A function for depositing fees to be distributed among the stakers.
+		///
+		/// The fees are distributed at the start of the next epoch. Fees can also be sent to the
+		/// contract through a plain transfer, e.g. when it is configured as the fee recipient of
+		/// the market.
+		#[ink(message, payable)]
+		pub fn deposit_fees(&mut self) -> Result<(), FeeDistributorError> {
+			let amount = self.env().transferred_value();
+			ensure!(amount > 0, FeeDistributorError::InvalidAmount);
+
+			self.env().emit_event(FeesDeposited { from: self.env().caller(), amount });
+			Ok(())
+		}
+
+		/// A function for distributing the fees received since the last distribution.
+		///
+		/// The distribution happens automatically whenever a staker interacts with the contract
+		/// in a new epoch. This is callable by anyone in case no staker does so.
+		#[ink(message)]
+		pub fn distribute(&mut self) -> Result<(), FeeDistributorError> {
+			self.update_epoch()
+		}
+
+		/// A function for staking tokens in order to receive a share of the fees.
+		///
+		/// ## Arguments:
+		/// - `amount`: The amount of staking tokens to stake. The caller must approve the tokens to
+		///   the contract beforehand.
+		///
+		/// The stake becomes active at the next distribution.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn stake(&mut self, amount: Balance) -> Result<(), FeeDistributorError> {
+			let caller = self.env().caller();
+
+			self.add_stake(caller, amount)?;
+
+			PSP22Ref::transfer_from(
+				&self.config.staking_token,
+				caller,
+				self.env().account_id(),
+				amount,
+				Vec::new(),
+			)
+			.map_err(FeeDistributorError::StakingTokenError)?;
+
+			self.env().emit_event(Staked { staker: caller, amount });
+			Ok(())
+		}
+
+		/// A function for unstaking tokens.
+		///
+		/// ## Arguments:
+		/// - `amount`: The amount of staking tokens to unstake. Pending stake is unstaked first.
+		///
+		/// The rewards accrued until now remain claimable.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn unstake(&mut self, amount: Balance) -> Result<(), FeeDistributorError> {
+			let caller = self.env().caller();
+
+			self.remove_stake(caller, amount)?;
+
+			PSP22Ref::transfer(&self.config.staking_token, caller, amount, Vec::new())
+				.map_err(FeeDistributorError::StakingTokenError)?;
+
+			self.env().emit_event(Unstaked { staker: caller, amount });
+			Ok(())
+		}
+
+		/// A function for claiming the rewards accrued by the caller.
+		#[ink(message)]
+		#[modifiers(non_reentrant)]
+		pub fn claim_rewards(&mut self) -> Result<(), FeeDistributorError> {
+			let caller = self.env().caller();
+
+			self.update_epoch()?;
+			let mut info = self.accrue_rewards(self.stakers.get(caller).unwrap_or_default())?;
+
+			let amount = info.unclaimed;
+			ensure!(amount > 0, FeeDistributorError::NothingToClaim);
+
+			info.unclaimed = 0;
+			self.stakers.insert(caller, &info);
+			self.reserved = self.reserved.saturating_sub(amount);
+
+			self.env()
+				.transfer(caller, amount)
+				.map_err(|_| FeeDistributorError::TransferFailed)?;
+
+			self.env().emit_event(RewardsClaimed { staker: caller, amount });
+			Ok(())
+		}
+	}
+
+	// Internal functions:
+	impl FeeDistributor {
+		// Distribute the fees received since the last distribution, in case a new epoch started.
+		//
+		// The stake that was pending during the previous epoch becomes active afterwards.
+		pub(crate) fn update_epoch(&mut self) -> Result<(), FeeDistributorError> {
+			let epoch = self.current_epoch();
+			if epoch <= self.last_epoch {
+				return Ok(())
+			}
+
+			let mut distributed = 0;
+			let available = self.undistributed_fees();
+			if self.total_active > 0 && available > 0 {
+				let increment = FixedU128::checked_from_rational(available, self.total_active)
+					.ok_or(FeeDistributorError::ArithmeticError)?;
+				self.reward_index = FixedU128::from_inner(self.reward_index)
+					.checked_add(&increment)
+					.ok_or(FeeDistributorError::ArithmeticError)?
+					.into_inner();
+
+				// Due to rounding, the distributed amount can be slightly lower than the available
+				// one. The remainder is distributed in the next epoch.
+				distributed = increment.saturating_mul_int(self.total_active);
+				self.reserved = self.reserved.saturating_add(distributed);
+			}
+
+			self.activation_index.insert(self.last_epoch, &self.reward_index);
+			self.total_active = self.total_active.saturating_add(self.total_pending);
+			self.total_pending = 0;
+			self.last_epoch = epoch;
+
+			self.env().emit_event(FeesDistributed { epoch, amount: distributed });
+			Ok(())
+		}
+
+		pub(crate) fn add_stake(
+			&mut self,
+			who: AccountId,
+			amount: Balance,
+		) -> Result<(), FeeDistributorError> {
+			ensure!(amount > 0, FeeDistributorError::InvalidAmount);
+
+			self.update_epoch()?;
+			let mut info = self.accrue_rewards(self.stakers.get(who).unwrap_or_default())?;
+
+			// After accruing the rewards, any pending stake was added in the current epoch.
+			info.pending = info.pending.saturating_add(amount);
+			info.pending_epoch = self.last_epoch;
+			self.total_pending = self.total_pending.saturating_add(amount);
+
+			self.stakers.insert(who, &info);
+			Ok(())
+		}
+
+		pub(crate) fn remove_stake(
+			&mut self,
+			who: AccountId,
+			amount: Balance,
+		) -> Result<(), FeeDistributorError> {
+			ensure!(amount > 0, FeeDistributorError::InvalidAmount);
+
+			self.update_epoch()?;
+			let mut info = self.accrue_rewards(self.stakers.get(who).unwrap_or_default())?;
+			ensure!(info.total() >= amount, FeeDistributorError::InsufficientStake);
+
+			let from_pending = amount.min(info.pending);
+			let from_active = amount.saturating_sub(from_pending);
+
+			info.pending = info.pending.saturating_sub(from_pending);
+			info.active = info.active.saturating_sub(from_active);
+			self.total_pending = self.total_pending.saturating_sub(from_pending);
+			self.total_active = self.total_active.saturating_sub(from_active);
+
+			if info.total() == 0 && info.unclaimed == 0 {
+				self.stakers.remove(who);
+			} else {
+				self.stakers.insert(who, &info);
+			}
+			Ok(())
+		}
+
+		// Account for the rewards accrued by the stake since the staker's last interaction.
+		fn accrue_rewards(&self, mut info: StakerInfo) -> Result<StakerInfo, FeeDistributorError> {
+			// The pending stake became active at the first distribution after it was added.
+			if info.pending > 0 && info.pending_epoch < self.last_epoch {
+				let activation_index =
+					self.activation_index.get(info.pending_epoch).unwrap_or(self.reward_index);
+				info.unclaimed = info.unclaimed.saturating_add(self.rewards(
+					info.active,
+					info.reward_index,
+					activation_index,
+				)?);
+				info.reward_index = activation_index;
+				info.active = info.active.saturating_add(info.pending);
+				info.pending = 0;
+			}
+
+			info.unclaimed = info.unclaimed.saturating_add(self.rewards(
+				info.active,
+				info.reward_index,
+				self.reward_index,
+			)?);
+			info.reward_index = self.reward_index;
+
+			Ok(info)
+		}
+
+		// Returns the rewards accrued by the stake as the reward index moved from `from` to `to`.
+		fn rewards(
+			&self,
+			stake: Balance,
+			from: u128,
+			to: u128,
+		) -> Result<Balance, FeeDistributorError> {
+			let delta = to.checked_sub(from).ok_or(FeeDistributorError::ArithmeticError)?;
+			Ok(FixedU128::from_inner(delta).saturating_mul_int(stake))
+		}
+
+		// The fees held by the contract which weren't distributed yet.
+		pub(crate) fn undistributed_fees(&self) -> Balance {
+			self.env()
+				.balance()
+				.saturating_sub(self.env().minimum_balance())
+				.saturating_sub(self.reserved)
+		}
+	}
+}
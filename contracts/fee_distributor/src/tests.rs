@@ -0,0 +1,147 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	fee_distributor::FeeDistributor,
+	types::{FeeDistributorError, StakerInfo},
+};
+use ink::env::{
+	test::{
+		advance_block, default_accounts, get_account_balance, set_account_balance, set_caller,
+		DefaultAccounts,
+	},
+	DefaultEnvironment,
+};
+use primitives::{assert_ok, Balance};
+
+const EPOCH_LENGTH: u32 = 10;
+
+// The existential deposit of the off-chain test environment.
+const MINIMUM_BALANCE: Balance = 1_000_000;
+
+#[ink::test]
+fn staking_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	let mut distributor = FeeDistributor::new(charlie, EPOCH_LENGTH);
+
+	assert_eq!(distributor.add_stake(alice, 0), Err(FeeDistributorError::InvalidAmount));
+
+	assert_ok!(distributor.add_stake(alice, 100));
+	assert_ok!(distributor.add_stake(alice, 50));
+	assert_ok!(distributor.add_stake(bob, 300));
+	assert_eq!(
+		distributor.staker(alice),
+		Some(StakerInfo {
+			active: 0,
+			pending: 150,
+			pending_epoch: 0,
+			reward_index: 0,
+			unclaimed: 0
+		})
+	);
+	assert_eq!(distributor.total_staked(), 450);
+
+	// The pending stake becomes active in the next epoch.
+	advance_n_blocks(EPOCH_LENGTH);
+	assert_ok!(distributor.distribute());
+	assert_eq!(distributor.total_active, 450);
+	assert_eq!(distributor.total_pending, 0);
+
+	assert_ok!(distributor.add_stake(alice, 10));
+	assert_eq!(
+		distributor.staker(alice),
+		Some(StakerInfo {
+			active: 150,
+			pending: 10,
+			pending_epoch: 1,
+			reward_index: 0,
+			unclaimed: 0
+		})
+	);
+
+	// Pending stake is removed first.
+	assert_eq!(distributor.remove_stake(alice, 161), Err(FeeDistributorError::InsufficientStake));
+	assert_ok!(distributor.remove_stake(alice, 20));
+	assert_eq!(distributor.staker(alice).map(|info| (info.active, info.pending)), Some((140, 0)));
+	assert_eq!(distributor.total_staked(), 440);
+
+	assert_ok!(distributor.remove_stake(alice, 140));
+	assert_eq!(distributor.staker(alice), None);
+	assert_eq!(distributor.total_staked(), 300);
+}
+
+#[ink::test]
+fn fee_distribution_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	let mut distributor = FeeDistributor::new(charlie, EPOCH_LENGTH);
+	let contract = ink::env::account_id::<DefaultEnvironment>();
+
+	set_account_balance::<DefaultEnvironment>(contract, MINIMUM_BALANCE);
+	assert_eq!(distributor.undistributed_fees(), 0);
+
+	assert_ok!(distributor.add_stake(alice, 100));
+	set_account_balance::<DefaultEnvironment>(contract, MINIMUM_BALANCE + 1_000);
+
+	// No stake is active yet, so the fees are kept for the next distribution.
+	advance_n_blocks(EPOCH_LENGTH);
+	assert_ok!(distributor.distribute());
+	assert_eq!(distributor.undistributed_fees(), 1_000);
+
+	// Bob stakes right before the distribution, but his stake isn't active yet.
+	assert_ok!(distributor.add_stake(bob, 300));
+	advance_n_blocks(EPOCH_LENGTH);
+	assert_ok!(distributor.distribute());
+	assert_eq!(distributor.undistributed_fees(), 0);
+	assert_eq!(distributor.claimable_rewards(alice), Ok(1_000));
+	assert_eq!(distributor.claimable_rewards(bob), Ok(0));
+
+	// Distributing multiple times in the same epoch has no effect.
+	set_account_balance::<DefaultEnvironment>(contract, MINIMUM_BALANCE + 1_400);
+	assert_ok!(distributor.distribute());
+	assert_eq!(distributor.claimable_rewards(alice), Ok(1_000));
+
+	// The fees are distributed pro-rata to the active stake.
+	advance_n_blocks(EPOCH_LENGTH);
+	assert_ok!(distributor.distribute());
+	assert_eq!(distributor.claimable_rewards(alice), Ok(1_100));
+	assert_eq!(distributor.claimable_rewards(bob), Ok(300));
+
+	set_caller::<DefaultEnvironment>(bob);
+	let bob_balance = get_account_balance::<DefaultEnvironment>(bob).unwrap();
+	assert_ok!(distributor.claim_rewards());
+	assert_eq!(get_account_balance::<DefaultEnvironment>(bob), Ok(bob_balance + 300));
+	assert_eq!(distributor.claimable_rewards(bob), Ok(0));
+	assert_eq!(distributor.claim_rewards(), Err(FeeDistributorError::NothingToClaim));
+
+	// Unstaking keeps the accrued rewards claimable.
+	assert_ok!(distributor.remove_stake(alice, 100));
+	assert_eq!(distributor.claimable_rewards(alice), Ok(1_100));
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(distributor.claim_rewards());
+	assert_eq!(distributor.staker(alice).map(|info| info.unclaimed), Some(0));
+	assert_eq!(distributor.reserved, 0);
+	assert_eq!(distributor.undistributed_fees(), 0);
+}
+
+fn advance_n_blocks(n: u32) {
+	for _ in 0..n {
+		advance_block::<DefaultEnvironment>();
+	}
+}
+
+fn get_default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+	default_accounts::<DefaultEnvironment>()
+}
@@ -0,0 +1,95 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use openbrush::{
+	contracts::traits::{errors::ReentrancyGuardError, psp22::PSP22Error},
+	traits::{AccountId, BlockNumber},
+};
+use primitives::Balance;
+
+/// The type used for numbering the distribution epochs.
+pub type EpochIndex = u32;
+
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Config {
+	/// The `AccountId` of the PSP22 token that is staked for receiving a share of the fees.
+	pub staking_token: AccountId,
+	/// The duration of a single distribution epoch in block numbers.
+	pub epoch_length: BlockNumber,
+}
+
+/// The stake of a single account together with its reward accounting.
+#[derive(scale::Decode, scale::Encode, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct StakerInfo {
+	/// The stake that is taken into account when distributing fees.
+	pub active: Balance,
+	/// The stake that becomes active at the next distribution.
+	pub pending: Balance,
+	/// The epoch in which the pending stake was added.
+	pub pending_epoch: EpochIndex,
+	/// The inner value of the reward index at which the rewards of the staker were last
+	/// accounted for.
+	pub reward_index: u128,
+	/// The rewards accounted for the staker which weren't claimed yet.
+	pub unclaimed: Balance,
+}
+
+impl StakerInfo {
+	/// Returns the total stake of the account, active and pending.
+	pub fn total(&self) -> Balance {
+		self.active.saturating_add(self.pending)
+	}
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum FeeDistributorError {
+	/// An arithmetic error occured.
+	ArithmeticError,
+	/// The amount must be greater than zero.
+	InvalidAmount,
+	/// The caller doesn't have enough tokens staked.
+	InsufficientStake,
+	/// The caller doesn't have any rewards to claim.
+	NothingToClaim,
+	/// Failed to transfer the native tokens.
+	TransferFailed,
+	/// The call would result in reentering the contract.
+	ReentrantCall,
+	/// An error occured when transferring the staking token.
+	StakingTokenError(PSP22Error),
+}
+
+impl core::fmt::Display for FeeDistributorError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			FeeDistributorError::ArithmeticError => write!(f, "ArithmeticError"),
+			FeeDistributorError::InvalidAmount => write!(f, "InvalidAmount"),
+			FeeDistributorError::InsufficientStake => write!(f, "InsufficientStake"),
+			FeeDistributorError::NothingToClaim => write!(f, "NothingToClaim"),
+			FeeDistributorError::TransferFailed => write!(f, "TransferFailed"),
+			FeeDistributorError::ReentrantCall => write!(f, "ReentrantCall"),
+			FeeDistributorError::StakingTokenError(e) => write!(f, "{:?}", e),
+		}
+	}
+}
+
+impl From<ReentrancyGuardError> for FeeDistributorError {
+	fn from(_: ReentrancyGuardError) -> Self {
+		FeeDistributorError::ReentrantCall
+	}
+}
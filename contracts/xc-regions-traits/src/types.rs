@@ -0,0 +1,141 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use openbrush::contracts::psp34::PSP34Error;
+use primitives::coretime::Region;
+
+#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum XcRegionsError {
+	/// The provided identifier is not a valid region id.
+	InvalidRegionId,
+	/// The metadata is either already initialized or the caller isn't the region owner.
+	CannotInitialize,
+	/// The region metadata cannot be removed as long as the underlying region continues to exist
+	/// on this chain.
+	CannotRemove,
+	/// No metadata was found for the region.
+	MetadataNotFound,
+	/// The provided metadata doesn't match with the metadata extracted from the region id.
+	InvalidMetadata,
+	/// The associated metadata version was not found.
+	VersionNotFound,
+	/// An error occured in the underlying runtime.
+	RuntimeError,
+	/// An psp34 error occured.
+	Psp34(PSP34Error),
+	/// The caller isn't the holder of the wrapped region.
+	CannotWithdraw,
+	/// The transferred value doesn't match the required deposit.
+	MissingDeposit,
+	/// Failed to return the deposit.
+	TransferFailed,
+	/// The XCM fee exceeds the maximum fee the contract pays for returning a region.
+	FeeTooHigh,
+	/// The contract isn't approved to transfer the underlying region.
+	ContractNotApproved,
+	/// The initialization of regions is paused.
+	InitializationPaused,
+	/// The region already ended.
+	RegionExpired,
+	/// The caller isn't approved for the region or the region is already locked.
+	CannotLock,
+	/// The caller isn't the account that locked the region.
+	CannotUnlock,
+	/// The region is locked.
+	RegionLocked,
+	/// The caller is neither the owner of the region nor approved for it.
+	NotAllowed,
+	/// The label key is reserved, empty or too long, or the label value is too long.
+	InvalidLabel,
+	/// The region already has the maximum number of labels.
+	TooManyLabels,
+	/// The note exceeds the maximum length.
+	InvalidNote,
+	/// The caller isn't the attestor.
+	NotAttestor,
+	/// The metadata proof is either missing or not signed by the oracle.
+	InvalidProof,
+	/// The storage proof of the region record is invalid.
+	InvalidStateProof,
+	/// The code of the contract couldn't be upgraded.
+	UpgradeFailed,
+	/// The storage is already migrated to the current version.
+	AlreadyMigrated,
+}
+
+impl core::fmt::Display for XcRegionsError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			XcRegionsError::InvalidRegionId => write!(f, "InvalidRegionId"),
+			XcRegionsError::CannotInitialize => write!(f, "CannotInitialize"),
+			XcRegionsError::CannotRemove => write!(f, "CannotRemove"),
+			XcRegionsError::MetadataNotFound => write!(f, "MetadataNotFound"),
+			XcRegionsError::InvalidMetadata => write!(f, "InvalidMetadata"),
+			XcRegionsError::VersionNotFound => write!(f, "VersionNotFound"),
+			XcRegionsError::RuntimeError => write!(f, "RuntimeError"),
+			XcRegionsError::Psp34(err) => write!(f, "{:?}", err),
+			XcRegionsError::CannotWithdraw => write!(f, "CannotWithdraw"),
+			XcRegionsError::MissingDeposit => write!(f, "MissingDeposit"),
+			XcRegionsError::TransferFailed => write!(f, "TransferFailed"),
+			XcRegionsError::FeeTooHigh => write!(f, "FeeTooHigh"),
+			XcRegionsError::ContractNotApproved => write!(f, "ContractNotApproved"),
+			XcRegionsError::InitializationPaused => write!(f, "InitializationPaused"),
+			XcRegionsError::RegionExpired => write!(f, "RegionExpired"),
+			XcRegionsError::CannotLock => write!(f, "CannotLock"),
+			XcRegionsError::CannotUnlock => write!(f, "CannotUnlock"),
+			XcRegionsError::RegionLocked => write!(f, "RegionLocked"),
+			XcRegionsError::NotAllowed => write!(f, "NotAllowed"),
+			XcRegionsError::InvalidLabel => write!(f, "InvalidLabel"),
+			XcRegionsError::TooManyLabels => write!(f, "TooManyLabels"),
+			XcRegionsError::InvalidNote => write!(f, "InvalidNote"),
+			XcRegionsError::NotAttestor => write!(f, "NotAttestor"),
+			XcRegionsError::InvalidProof => write!(f, "InvalidProof"),
+			XcRegionsError::InvalidStateProof => write!(f, "InvalidStateProof"),
+			XcRegionsError::UpgradeFailed => write!(f, "UpgradeFailed"),
+			XcRegionsError::AlreadyMigrated => write!(f, "AlreadyMigrated"),
+		}
+	}
+}
+
+/// The region metadata, versioned by its layout.
+///
+/// New variants get added whenever the layout of the region changes, so that consumers decoding
+/// older layouts keep working.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum VersionedRegion {
+	V1(Region),
+}
+
+impl VersionedRegion {
+	pub fn region(&self) -> &Region {
+		match self {
+			VersionedRegion::V1(region) => region,
+		}
+	}
+
+	pub fn into_region(self) -> Region {
+		match self {
+			VersionedRegion::V1(region) => region,
+		}
+	}
+}
+
+impl From<Region> for VersionedRegion {
+	fn from(region: Region) -> Self {
+		VersionedRegion::V1(region)
+	}
+}
@@ -16,6 +16,7 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 #![feature(min_specialization)]
 
+pub mod proof;
 pub mod traits;
 pub mod types;
 
@@ -24,213 +25,1956 @@ mod tests;
 
 pub use crate::xc_regions::PSP34Ref;
 
-// NOTE: This should be the collection ID of the underlying region collection.
-pub const REGIONS_COLLECTION_ID: u32 = 42;
-
-#[openbrush::implementation(PSP34, PSP34Enumerable)]
+// NOTE: This should be the parachain id of the Coretime chain.
+pub const CORETIME_CHAIN_PARA_ID: u32 = 1005;
+
+// NOTE: This should be the index of the pallet holding the regions on the Coretime chain.
+pub const CORETIME_REGIONS_PALLET_INDEX: u8 = 50;
+
+/// The name of the xcRegion collection exposed through `PSP34Metadata`.
+pub const COLLECTION_NAME: &str = "Cross-chain Regions";
+/// The symbol of the xcRegion collection exposed through `PSP34Metadata`.
+pub const COLLECTION_SYMBOL: &str = "xcREGION";
+
+/// The version of the storage layout of the contract.
+///
+/// Version 1 is the layout of the first upgradeable release of the contract. Contracts deployed
+/// from earlier code have no `upgrade` message and a different layout, so they can't be upgraded
+/// in place.
+///
+/// Must be incremented whenever a new version of the contract changes the storage layout in a way
+/// that requires a migration.
+pub const STORAGE_VERSION: u16 = 1;
+
+/// The maximum number of labels an xcRegion can have.
+pub const MAX_LABELS: usize = 8;
+/// The maximum length of a label key or value in bytes.
+pub const MAX_LABEL_LEN: usize = 64;
+/// The attribute keys exposed from the region metadata, which cannot be used as labels.
+pub const RESERVED_ATTRIBUTES: [&str; 4] = ["begin", "end", "core", "mask"];
+/// The maximum length of a note in bytes.
+pub const MAX_NOTE_LEN: usize = 128;
+
+#[openbrush::implementation(PSP34, PSP34Burnable, PSP34Enumerable, PSP34Metadata)]
 #[openbrush::contract(env = environment::ExtendedEnvironment)]
 pub mod xc_regions {
 	use crate::{
-		traits::{regionmetadata_external, RegionMetadata},
-		types::{VersionedRegion, XcRegionsError},
-		REGIONS_COLLECTION_ID,
+		proof,
+		traits::{regionmetadata_external, RegionMetadata, RegionReceiverRef},
+		types::{Provenance, RegionStatus, RegionsBackend, VersionedRegion, XcRegionsError},
+		COLLECTION_NAME, COLLECTION_SYMBOL, CORETIME_CHAIN_PARA_ID, CORETIME_REGIONS_PALLET_INDEX,
+		MAX_LABELS, MAX_LABEL_LEN, MAX_NOTE_LEN, RESERVED_ATTRIBUTES, STORAGE_VERSION,
 	};
+	use block_number_extension::BlockNumberProviderExtension;
 	use ink::{
 		codegen::{EmitEvent, Env},
+		env::{hash::Blake2x256, CallFlags},
+		prelude::{format, vec::Vec},
 		storage::Mapping,
 	};
-	use openbrush::traits::Storage;
+	use nfts_extension::NftsExtension;
+	use openbrush::traits::{Storage, String};
 	use primitives::{
-		coretime::{RawRegionId, Region, RegionId},
+		coretime::{
+			Finality, RawRegionId, Region, RegionId, TaskId, Timeslice, CORE_MASK_BIT_LEN,
+			TIMESLICE_PERIOD,
+		},
 		ensure,
-		uniques::{ItemDetails, UniquesCall},
-		RuntimeCall, Version,
+		nfts::NftsCall,
+		polkadot_xcm::PolkadotXcmCall,
+		uniques::{CollectionId, ItemDetails, UniquesCall},
+		Balance, PalletCall, RuntimeCall, Version, UNIQUES_PALLET_INDEX,
 	};
+	use regions_extension::RegionsExtension;
 	use uniques_extension::UniquesExtension;
 
-	#[cfg(test)]
-	use primitives::uniques::CollectionId;
+	#[openbrush::wrapper]
+	pub type PSP34Ref = dyn PSP34 + PSP34Burnable + PSP34Enumerable + PSP34Metadata;
+
+	#[ink(storage)]
+	#[derive(Default, Storage)]
+	pub struct XcRegions {
+		#[storage_field]
+		psp34: psp34::Data,
+		#[storage_field]
+		enumerable: enumerable::Data,
+		#[storage_field]
+		metadata: metadata::Data,
+		/// A mapping that links RawRegionId to its corresponding region metadata.
+		pub regions: Mapping<RawRegionId, Region>,
+		/// A mapping that keeps track of the metadata version for each region.
+		///
+		/// This version gets incremented for a region each time it gets re-initialized.
+		pub metadata_versions: Mapping<RawRegionId, Version>,
+		/// The blake2 hash of the encoded metadata of each region.
+		pub metadata_hashes: Mapping<RawRegionId, [u8; 32]>,
+		/// The number of regions that have a metadata version.
+		pub versioned_count: u32,
+		/// The regions that have a metadata version, in the order they were first initialized.
+		pub versioned_regions: Mapping<u32, RawRegionId>,
+		/// The metadata of each version of a region's metadata.
+		pub metadata_history: Mapping<(RawRegionId, Version), Region>,
+		/// The number of currently wrapped regions.
+		pub wrapped_count: u32,
+		/// The currently wrapped regions, stored by their index.
+		pub wrapped_regions: Mapping<u32, RawRegionId>,
+		/// A mapping of the currently wrapped regions to their index in `wrapped_regions`.
+		pub wrapped_region_indices: Mapping<RawRegionId, u32>,
+		/// The collection ID of the underlying region collection on this chain.
+		pub collection_id: CollectionId,
+		/// The pallet through which the underlying regions are represented on this chain.
+		pub backend: RegionsBackend,
+		/// The account trusted to set and confirm region metadata.
+		pub attestor: Option<AccountId>,
+		/// The regions whose metadata was set or confirmed by the attestor.
+		pub trusted_regions: Mapping<RawRegionId, ()>,
+		/// The compressed ECDSA public key of the oracle signing region metadata.
+		pub oracle_key: Option<[u8; 33]>,
+		/// A mapping of the locked regions to the account that locked them.
+		pub locks: Mapping<RawRegionId, AccountId>,
+		/// A mapping of regions to their user and the timeslice at which the user role expires.
+		pub users: Mapping<RawRegionId, (AccountId, Timeslice)>,
+		/// The contracts that opted out of the `on_region_received` callback.
+		pub receive_hook_opt_outs: Mapping<AccountId, ()>,
+		/// The labels attached to regions by their owners.
+		pub labels: Mapping<(RawRegionId, String), String>,
+		/// The keys of the labels attached to each region.
+		pub label_keys: Mapping<RawRegionId, Vec<String>>,
+		/// The notes attached to regions by their owners.
+		pub notes: Mapping<RawRegionId, String>,
+		/// The history of each region that was ever wrapped.
+		pub provenance: Mapping<RawRegionId, Provenance>,
+		/// The account allowed to upgrade the contract.
+		pub admin: AccountId,
+		/// The account allowed to pause the initialization of regions besides the admin.
+		pub guardian: Option<AccountId>,
+		/// Whether the initialization of regions is paused. While paused, xcRegions can still be
+		/// transferred and removed.
+		pub paused: bool,
+		/// The version of the storage layout.
+		pub storage_version: u16,
+		/// Whether regions that already ended can't be initialized.
+		pub reject_expired: bool,
+		/// Whether only the holder of an xcRegion can remove it through `remove_batch`, unless
+		/// the region already ended.
+		pub holder_only_removal: bool,
+		/// The deposit required for initializing a region. The deposit can be changed afterwards.
+		pub init_deposit: Balance,
+		/// The deposits paid for the wrapped regions, together with the account that paid them.
+		pub deposits: Mapping<RawRegionId, (AccountId, Balance)>,
+		/// The maximum amount of the relay chain asset the contract pays as the XCM fee for
+		/// returning a region to the Coretime chain.
+		pub max_return_fee: Balance,
+		/// The index of the uniques pallet in the runtime of this chain.
+		pub uniques_pallet_index: u8,
+		// Mock chain extension state only used for integration testing.
+		#[cfg(test)]
+		pub relay_state_root: [u8; 32],
+		// Mock chain extension state only used for integration testing.
+		#[cfg(test)]
+		pub items: Mapping<
+			(primitives::uniques::CollectionId, primitives::coretime::RawRegionId),
+			ItemDetails,
+		>,
+		// Mock chain extension state only used for integration testing.
+		#[cfg(test)]
+		pub account: Mapping<
+			AccountId,
+			Vec<(primitives::uniques::CollectionId, primitives::coretime::RawRegionId)>,
+		>,
+	}
+
+	/// Emitted whenever an xcRegion is minted, transferred or burned.
+	#[ink(event)]
+	pub struct Transfer {
+		/// The previous owner of the xcRegion. `None` when the xcRegion got minted.
+		#[ink(topic)]
+		pub(crate) from: Option<AccountId>,
+		/// The new owner of the xcRegion. `None` when the xcRegion got burned.
+		#[ink(topic)]
+		pub(crate) to: Option<AccountId>,
+		/// The identifier of the xcRegion.
+		#[ink(topic)]
+		pub(crate) id: Id,
+	}
+
+	/// Emitted whenever an account gets approved or disapproved for the xcRegions of an owner.
+	#[ink(event)]
+	pub struct Approval {
+		/// The owner of the xcRegions.
+		#[ink(topic)]
+		pub(crate) from: AccountId,
+		/// The approved account.
+		#[ink(topic)]
+		pub(crate) to: AccountId,
+		/// The identifier of the xcRegion. `None` if the approval is for all xcRegions of the
+		/// owner.
+		#[ink(topic)]
+		pub(crate) id: Option<Id>,
+		/// Whether the account got approved or disapproved.
+		pub(crate) approved: bool,
+	}
+
+	#[ink(event)]
+	pub struct RegionInitialized {
+		/// The identifier of the region that got initialized.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The associated metadata.
+		pub(crate) metadata: Region,
+		/// The version of the metadata. This is incremented by the contract each time the same
+		/// region is initialized.
+		pub(crate) version: Version,
+		/// The blake2 hash of the encoded metadata.
+		pub(crate) metadata_hash: [u8; 32],
+	}
+
+	#[ink(event)]
+	pub struct MetadataVersionBumped {
+		/// The identifier of the region that got re-initialized.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The version of the metadata before the re-initialization.
+		pub(crate) old: Version,
+		/// The new version of the metadata.
+		pub(crate) new: Version,
+	}
+
+	#[ink(event)]
+	pub struct RegionAttested {
+		/// The identifier of the region whose metadata got attested.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The attested metadata.
+		pub(crate) metadata: Region,
+		/// The version of the attested metadata.
+		pub(crate) version: Version,
+		/// The blake2 hash of the encoded attested metadata.
+		pub(crate) metadata_hash: [u8; 32],
+	}
+
+	#[ink(event)]
+	pub struct RegionLocked {
+		/// The identifier of the region that got locked.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that locked the region.
+		#[ink(topic)]
+		pub(crate) locker: AccountId,
+	}
+
+	#[ink(event)]
+	pub struct RegionUnlocked {
+		/// The identifier of the region that got unlocked.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+	}
+
+	#[ink(event)]
+	pub struct UserUpdated {
+		/// The identifier of the region whose user got updated.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The new user of the region.
+		#[ink(topic)]
+		pub(crate) user: Option<AccountId>,
+		/// The timeslice at which the user role expires.
+		pub(crate) expires: Timeslice,
+	}
+
+	#[ink(event)]
+	pub struct RegionAssigned {
+		/// The identifier of the region that got assigned.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The task the region got assigned to.
+		pub(crate) task: TaskId,
+		/// Whether the assignment can later be changed.
+		pub(crate) finality: Finality,
+	}
+
+	#[ink(event)]
+	pub struct LabelUpdated {
+		/// The identifier of the region whose label got updated.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The key of the label.
+		pub(crate) key: String,
+		/// The new value of the label. `None` if the label got removed.
+		pub(crate) value: Option<String>,
+	}
+
+	#[ink(event)]
+	pub struct NoteUpdated {
+		/// The identifier of the region whose note got updated.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The new note. `None` if the note got removed.
+		pub(crate) note: Option<String>,
+	}
+
+	#[ink(event)]
+	pub struct AdminChanged {
+		/// The new admin of the contract.
+		pub(crate) new_admin: AccountId,
+	}
+
+	#[ink(event)]
+	pub struct GuardianChanged {
+		/// The new guardian of the contract.
+		pub(crate) new_guardian: Option<AccountId>,
+	}
+
+	#[ink(event)]
+	pub struct PauseStatusUpdated {
+		/// Whether the initialization of regions is paused.
+		pub(crate) paused: bool,
+	}
+
+	#[ink(event)]
+	pub struct InitDepositUpdated {
+		/// The deposit required for initializing a region.
+		pub(crate) new_deposit: Balance,
+	}
+
+	#[ink(event)]
+	pub struct MaxReturnFeeUpdated {
+		/// The maximum XCM fee paid for returning a region.
+		pub(crate) new_max_fee: Balance,
+	}
+
+	#[ink(event)]
+	pub struct UniquesPalletIndexUpdated {
+		/// The index of the uniques pallet in the runtime.
+		pub(crate) index: u8,
+	}
+
+	#[ink(event)]
+	pub struct CodeUpgraded {
+		/// The hash of the new contract code.
+		pub(crate) code_hash: Hash,
+	}
+
+	#[ink(event)]
+	pub struct StorageMigrated {
+		/// The new version of the storage layout.
+		pub(crate) storage_version: u16,
+	}
+
+	#[ink(event)]
+	pub struct RegionRemoved {
+		/// The identifier of the region that got removed.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+	}
+
+	#[ink(event)]
+	pub struct RegionWithdrawn {
+		/// The identifier of the region that got withdrawn.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account that received the underlying region.
+		#[ink(topic)]
+		pub(crate) owner: AccountId,
+	}
+
+	#[ink(event)]
+	pub struct RegionReturned {
+		/// The identifier of the region that got sent back to the Coretime chain.
+		#[ink(topic)]
+		pub(crate) region_id: RawRegionId,
+		/// The account receiving the region on the Coretime chain.
+		#[ink(topic)]
+		pub(crate) beneficiary: AccountId,
+	}
+
+	#[overrider(PSP34)]
+	fn collection_id(&self) -> Id {
+		Id::U32(self.collection_id)
+	}
+
+	/// When the xcRegion is transferred to a contract, the `on_region_received` callback of the
+	/// `RegionReceiver` trait is invoked on it, allowing the contract to update its state
+	/// atomically. The transfer is reverted if the callback fails.
+	///
+	/// Contracts that don't implement the callback can still receive xcRegions, and contracts
+	/// can opt out of the callback through `set_receive_hook`. The callback is skipped when a
+	/// contract pulls the xcRegion into itself, as it initiated the transfer and can't be
+	/// reentered without allowing it.
+	#[overrider(PSP34)]
+	fn transfer(&mut self, to: AccountId, id: Id, data: Vec<u8>) -> Result<(), PSP34Error> {
+		let from =
+			psp34::PSP34Impl::owner_of(self, id.clone()).ok_or(PSP34Error::TokenNotExists)?;
+		psp34::PSP34Impl::transfer(self, to, id.clone(), data.clone())?;
+
+		let operator = self.env().caller();
+		if to == operator || !self.env().is_contract(&to) || self.receive_hook_opt_outs.contains(to)
+		{
+			return Ok(())
+		}
+
+		let result = RegionReceiverRef::on_region_received_builder(&to, operator, from, id, data)
+			.call_flags(CallFlags::default().set_allow_reentry(true))
+			.try_invoke();
+
+		match result {
+			Ok(Ok(result)) => result,
+			// The receiver doesn't implement the callback.
+			Ok(Err(ink::LangError::CouldNotReadInput)) => Ok(()),
+			_ => Err(PSP34Error::SafeTransferCheckFailed(String::from(
+				"The receiver failed to handle the xcRegion",
+			))),
+		}
+	}
+
+	#[overrider(psp34::Internal)]
+	fn _emit_transfer_event(&self, from: Option<AccountId>, to: Option<AccountId>, id: Id) {
+		self.env().emit_event(Transfer { from, to, id });
+	}
+
+	#[overrider(psp34::Internal)]
+	fn _emit_approval_event(&self, from: AccountId, to: AccountId, id: Option<Id>, approved: bool) {
+		self.env().emit_event(Approval { from, to, id, approved });
+	}
+
+	// Locked regions cannot be transferred nor burned.
+	//
+	// The user of a region is reset whenever the region changes hands, and the transfer is recorded
+	// in the provenance of the region.
+	#[overrider(psp34::Internal)]
+	fn _before_token_transfer(
+		&mut self,
+		from: Option<&AccountId>,
+		to: Option<&AccountId>,
+		id: &Id,
+	) -> Result<(), PSP34Error> {
+		if let Id::U128(region_id) = id {
+			ensure!(
+				!self.locks.contains(region_id),
+				PSP34Error::Custom(String::from("RegionLocked"))
+			);
+			if from != to {
+				self.users.remove(region_id);
+			}
+			if let (Some(from), Some(to)) = (from, to) {
+				if from != to {
+					if let Some(mut provenance) = self.provenance.get(region_id) {
+						provenance.transfer_count = provenance.transfer_count.saturating_add(1);
+						self.provenance.insert(region_id, &provenance);
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Burning an xcRegion withdraws it, i.e. its metadata gets removed and the underlying region
+	/// is transferred back to the owner. The metadata version is retained.
+	///
+	/// Only callable by the owner of the xcRegion or an account approved for it.
+	#[overrider(PSP34Burnable)]
+	fn burn(&mut self, account: AccountId, id: Id) -> Result<(), PSP34Error> {
+		let Id::U128(region_id) = id else { return Err(PSP34Error::TokenNotExists) };
+		let owner =
+			psp34::PSP34Impl::owner_of(self, id.clone()).ok_or(PSP34Error::TokenNotExists)?;
+		ensure!(owner == account, PSP34Error::NotApproved);
+
+		let caller = self.env().caller();
+		ensure!(
+			caller == owner || psp34::PSP34Impl::allowance(self, owner, caller, Some(id)),
+			PSP34Error::NotApproved
+		);
+
+		self._unwrap(region_id, owner).map_err(|err| match err {
+			XcRegionsError::Psp34(err) => err,
+			err => PSP34Error::Custom(format!("{}", err)),
+		})?;
+
+		self.env().emit_event(RegionWithdrawn { region_id, owner });
+		Ok(())
+	}
+
+	/// Besides the attributes stored in the contract (e.g. the collection `name` and `symbol`),
+	/// each xcRegion exposes the `begin`, `end`, `core` and `mask` attributes of its metadata as
+	/// well as the labels attached by its owner.
+	///
+	/// The `mask` is returned as a hex encoded string.
+	#[overrider(PSP34Metadata)]
+	fn get_attribute(&self, id: Id, key: String) -> Option<String> {
+		if let Id::U128(region_id) = id {
+			if let Some(region) = self.regions.get(region_id) {
+				return match key.as_str() {
+					"begin" => Some(format!("{}", region.begin)),
+					"end" => Some(format!("{}", region.end)),
+					"core" => Some(format!("{}", region.core)),
+					"mask" => Some(format!("{:#x}", u128::from(region.mask))),
+					_ => self.labels.get((region_id, key)),
+				}
+			}
+		}
+
+		metadata::PSP34MetadataImpl::get_attribute(self, id, key)
+	}
+
+	impl RegionMetadata for XcRegions {
+		/// A function for minting a wrapped xcRegion and initializing the metadata of it. It can
+		/// only be called if the specified region exists on this chain and the caller is either the
+		/// actual owner of the region or an account approved to transfer it.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `region` - The corresponding region metadata.
+		///
+		/// This function conducts a sanity check to verify that the metadata derived from the
+		/// `raw_region_id` aligns with the respective components of the metadata supplied through
+		/// the region argument.
+		///
+		/// If this is not the first time that this region is inititalized, the metadata version
+		/// will get incremented. If the region left the chain and came back while its previous
+		/// xcRegion still exists, the stale xcRegion and its metadata are removed as part of the
+		/// initialization.
+		///
+		/// The underlying region will be transferred to this contract, and in response, a wrapped
+		/// token will be minted for the owner of the region. When called by an approved operator,
+		/// the xcRegion is still minted to the owner.
+		///
+		/// The caller must pay the `init_deposit`, which is returned to the caller once the
+		/// xcRegion gets unwrapped.
+		///
+		/// NOTE: Prior to invoking this ink message, the owner must grant approval to the contract
+		/// for the region, enabling its transfer. Since the uniques pallet only allows a single
+		/// approved account per item, initializing through an operator is only possible with the
+		/// nfts backend.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionInitialized` event.
+		#[ink(message, payable)]
+		fn init(&mut self, id: Id, region: Region) -> Result<(), XcRegionsError> {
+			let trusted = Some(self.env().caller()) == self.attestor;
+			self._init(id, region, trusted)
+		}
+
+		/// A function to retrieve all metadata associated with a specific region.
+		///
+		/// The version of the metadata that is intended for client-side verification can be
+		/// retrieved through `metadata_version`.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		fn get_metadata(&self, id: Id) -> Result<VersionedRegion, XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let Some(region) = self.regions.get(region_id) else {
+				return Err(XcRegionsError::MetadataNotFound)
+			};
+
+			Ok(VersionedRegion::V1(region))
+		}
+
+		/// A function to retrieve the metadata of multiple regions at once.
+		///
+		/// The results are returned in the same order as the provided region identifiers.
+		///
+		/// ## Arguments:
+		/// - `region_ids` - The `u128` encoded region identifiers.
+		#[ink(message)]
+		fn get_metadata_batch(
+			&self,
+			region_ids: Vec<RawRegionId>,
+		) -> Vec<Result<VersionedRegion, XcRegionsError>> {
+			region_ids
+				.into_iter()
+				.map(|region_id| self.get_metadata(Id::U128(region_id)))
+				.collect()
+		}
+
+		/// A function to check whether the metadata of a specific region is initialized, without
+		/// retrieving the metadata itself.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		fn exists(&self, id: Id) -> bool {
+			let Id::U128(region_id) = id else { return false };
+			self.regions.contains(region_id)
+		}
+
+		/// A function to retrieve the version of the metadata of a specific region.
+		///
+		/// The version gets incremented each time the region is re-initialized, allowing clients
+		/// to detect that the metadata changed.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		fn metadata_version(&self, id: Id) -> Result<Version, XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			ensure!(self.regions.contains(region_id), XcRegionsError::MetadataNotFound);
+
+			// This should never really happen; if a region has its metadata stored, its version
+			// should be stored as well.
+			self.metadata_versions.get(region_id).ok_or(XcRegionsError::VersionNotFound)
+		}
+
+		/// A function to retrieve the blake2 hash of the encoded metadata of a specific region.
+		///
+		/// This allows light clients to verify the metadata against a hash commitment published
+		/// on the Coretime chain.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		fn metadata_hash(&self, id: Id) -> Result<[u8; 32], XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			self.metadata_hashes.get(region_id).ok_or(XcRegionsError::MetadataNotFound)
+		}
+
+		/// A function to retrieve the note attached to a specific xcRegion by its owner.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		fn note(&self, id: Id) -> Option<String> {
+			let Id::U128(region_id) = id else { return None };
+			self.notes.get(region_id)
+		}
+
+		/// A function to return the region to its owner.
+		///
+		/// This process involves burning the wrapped region and eliminating its associated
+		/// metadata.
+		///
+		/// Only the owner of the wrapped region can call this function.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionRemoved` event.
+		#[ink(message)]
+		fn remove(&mut self, id: Id) -> Result<(), XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let owner =
+				psp34::PSP34Impl::owner_of(self, id.clone()).ok_or(XcRegionsError::CannotRemove)?;
+
+			ensure!(owner == self.env().caller(), XcRegionsError::CannotRemove);
+			self._unwrap(region_id, owner)?;
+
+			self.env().emit_event(RegionRemoved { region_id });
+			Ok(())
+		}
+	}
+
+	impl XcRegions {
+		/// ## Arguments:
+		/// - `collection_id` - The collection ID of the underlying region collection on this chain.
+		#[ink(constructor)]
+		pub fn new(collection_id: CollectionId) -> Self {
+			Self::new_with_backend(collection_id, RegionsBackend::Uniques)
+		}
+
+		/// ## Arguments:
+		/// - `collection_id` - The collection ID of the underlying region collection on this chain.
+		/// - `backend` - The pallet through which the underlying regions are represented on this
+		///   chain.
+		#[ink(constructor)]
+		pub fn new_with_backend(collection_id: CollectionId, backend: RegionsBackend) -> Self {
+			Self::new_with_attestor(collection_id, backend, None)
+		}
+
+		/// ## Arguments:
+		/// - `collection_id` - The collection ID of the underlying region collection on this chain.
+		/// - `backend` - The pallet through which the underlying regions are represented on this
+		///   chain.
+		/// - `attestor` - The account trusted to set and confirm region metadata.
+		#[ink(constructor)]
+		pub fn new_with_attestor(
+			collection_id: CollectionId,
+			backend: RegionsBackend,
+			attestor: Option<AccountId>,
+		) -> Self {
+			Self::new_with_options(collection_id, backend, attestor, false, false)
+		}
+
+		/// ## Arguments:
+		/// - `collection_id` - The collection ID of the underlying region collection on this chain.
+		/// - `backend` - The pallet through which the underlying regions are represented on this
+		///   chain.
+		/// - `attestor` - The account trusted to set and confirm region metadata.
+		/// - `reject_expired` - Whether regions that already ended can't be initialized.
+		/// - `holder_only_removal` - Whether only the holder of an xcRegion can remove it through
+		///   `remove_batch`, unless the region already ended.
+		#[ink(constructor)]
+		pub fn new_with_options(
+			collection_id: CollectionId,
+			backend: RegionsBackend,
+			attestor: Option<AccountId>,
+			reject_expired: bool,
+			holder_only_removal: bool,
+		) -> Self {
+			let mut xc_regions = Self {
+				collection_id,
+				backend,
+				attestor,
+				reject_expired,
+				holder_only_removal,
+				admin: Self::env().caller(),
+				storage_version: STORAGE_VERSION,
+				uniques_pallet_index: UNIQUES_PALLET_INDEX,
+				..Default::default()
+			};
+
+			let collection_id = psp34::PSP34Impl::collection_id(&xc_regions);
+			metadata::InternalImpl::_set_attribute(
+				&mut xc_regions,
+				collection_id.clone(),
+				String::from("name"),
+				String::from(COLLECTION_NAME),
+			);
+			metadata::InternalImpl::_set_attribute(
+				&mut xc_regions,
+				collection_id,
+				String::from("symbol"),
+				String::from(COLLECTION_SYMBOL),
+			);
+
+			xc_regions
+		}
+
+		/// Instantiates the contract with the regions collection id the runtime is configured
+		/// with, rather than relying on a collection id provided by the deployer.
+		///
+		/// ## Arguments:
+		/// - `backend` - The pallet through which the underlying regions are represented.
+		#[ink(constructor)]
+		pub fn new_from_runtime(backend: RegionsBackend) -> Result<Self, XcRegionsError> {
+			let collection_id = Self::_runtime_collection_id()?;
+			Ok(Self::new_with_backend(collection_id, backend))
+		}
+
+		#[ink(message)]
+		pub fn regions_collection_id(&self) -> CollectionId {
+			self.collection_id
+		}
+
+		#[ink(message)]
+		pub fn backend(&self) -> RegionsBackend {
+			self.backend
+		}
+
+		#[ink(message)]
+		pub fn attestor(&self) -> Option<AccountId> {
+			self.attestor
+		}
+
+		#[ink(message)]
+		pub fn admin(&self) -> AccountId {
+			self.admin
+		}
+
+		#[ink(message)]
+		pub fn storage_version(&self) -> u16 {
+			self.storage_version
+		}
+
+		#[ink(message)]
+		pub fn reject_expired(&self) -> bool {
+			self.reject_expired
+		}
+
+		#[ink(message)]
+		pub fn holder_only_removal(&self) -> bool {
+			self.holder_only_removal
+		}
+
+		#[ink(message)]
+		pub fn init_deposit(&self) -> Balance {
+			self.init_deposit
+		}
+
+		#[ink(message)]
+		pub fn max_return_fee(&self) -> Balance {
+			self.max_return_fee
+		}
+
+		#[ink(message)]
+		pub fn uniques_pallet_index(&self) -> u8 {
+			self.uniques_pallet_index
+		}
+
+		#[ink(message)]
+		pub fn guardian(&self) -> Option<AccountId> {
+			self.guardian
+		}
+
+		#[ink(message)]
+		pub fn paused(&self) -> bool {
+			self.paused
+		}
+
+		#[ink(message)]
+		pub fn oracle_key(&self) -> Option<[u8; 33]> {
+			self.oracle_key
+		}
+
+		/// A function for setting the key of the oracle signing region metadata.
+		///
+		/// Only callable by the attestor.
+		///
+		/// ## Arguments:
+		/// - `oracle_key` - The compressed ECDSA public key of the oracle. Setting this to `None`
+		///   disables `init_with_proof`.
+		#[ink(message)]
+		pub fn set_oracle_key(
+			&mut self,
+			oracle_key: Option<[u8; 33]>,
+		) -> Result<(), XcRegionsError> {
+			ensure!(Some(self.env().caller()) == self.attestor, XcRegionsError::NotAttestor);
+			self.oracle_key = oracle_key;
+			Ok(())
+		}
+
+		/// Returns the hash the oracle has to sign for the metadata of a region to be initialized
+		/// through `init_with_proof`.
+		///
+		/// The hash commits to this contract and to the version the metadata will get, so that a
+		/// proof cannot be replayed on another deployment or after the region gets re-initialized.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `region` - The corresponding region metadata.
+		#[ink(message)]
+		pub fn metadata_proof_hash(
+			&self,
+			id: Id,
+			region: Region,
+		) -> Result<[u8; 32], XcRegionsError> {
+			let Id::U128(raw_region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let version = self.next_metadata_version(raw_region_id);
+
+			let mut hash = [0u8; 32];
+			ink::env::hash_encoded::<Blake2x256, _>(
+				&(self.env().account_id(), raw_region_id, region, version),
+				&mut hash,
+			);
+			Ok(hash)
+		}
+
+		/// A function for initializing a region, which first ensures that the contract is approved
+		/// to transfer the underlying region.
+		///
+		/// Other than that, this behaves the same as `init`.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `region` - The corresponding region metadata.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionInitialized` event.
+		#[ink(message, payable)]
+		pub fn init_if_approved(&mut self, id: Id, region: Region) -> Result<(), XcRegionsError> {
+			ensure!(self.contract_approved(id.clone()), XcRegionsError::ContractNotApproved);
+			RegionMetadata::init(self, id, region)
+		}
+
+		/// Returns whether the contract is approved to transfer the underlying region, which is
+		/// required for initializing it.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn contract_approved(&self, id: Id) -> bool {
+			let Id::U128(raw_region_id) = id else { return false };
+			self._uniques_approved(raw_region_id, self.env().account_id())
+		}
+
+		/// A function for initializing a region with metadata signed by the oracle.
+		///
+		/// Same as `init`, except that the metadata is verified against the signature of the
+		/// oracle, and is therefore marked as trusted.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `region` - The corresponding region metadata.
+		/// - `signature` - The recoverable ECDSA signature of the oracle over the
+		///   `metadata_proof_hash`.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionInitialized` event.
+		#[ink(message, payable)]
+		pub fn init_with_proof(
+			&mut self,
+			id: Id,
+			region: Region,
+			signature: [u8; 65],
+		) -> Result<(), XcRegionsError> {
+			let oracle_key = self.oracle_key.ok_or(XcRegionsError::InvalidProof)?;
+
+			let hash = self.metadata_proof_hash(id.clone(), region.clone())?;
+			let mut signer = [0u8; 33];
+			ink::env::ecdsa_recover(&signature, &hash, &mut signer)
+				.map_err(|_| XcRegionsError::InvalidProof)?;
+			ensure!(signer == oracle_key, XcRegionsError::InvalidProof);
+
+			self._init(id, region, true)
+		}
+
+		/// A function for initializing a region with metadata proven against the state of the
+		/// Coretime chain.
+		///
+		/// Same as `init`, except that the region record is verified through storage proofs
+		/// anchored in the relay chain state root, and the metadata is therefore marked as
+		/// trusted. Unlike `init`, the mask of the metadata must match the one in the region id.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `region` - The corresponding region metadata.
+		/// - `relay_proof` - The storage proof of the Coretime chain head in the relay chain block
+		///   this chain's current block is built upon.
+		/// - `coretime_proof` - The storage proof of the region record in `pallet-broker` against
+		///   the state root of the proven Coretime chain head.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionInitialized` event.
+		#[ink(message, payable)]
+		pub fn init_with_state_proof(
+			&mut self,
+			id: Id,
+			region: Region,
+			relay_proof: Vec<Vec<u8>>,
+			coretime_proof: Vec<Vec<u8>>,
+		) -> Result<(), XcRegionsError> {
+			let Id::U128(raw_region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let region_id = RegionId::from(raw_region_id);
+
+			let record = proof::verify_region_record(
+				self._relay_state_root()?,
+				relay_proof,
+				coretime_proof,
+				CORETIME_CHAIN_PARA_ID,
+				&region_id,
+			)?;
+			// The proof only covers the region with the full mask of the region id, so partial
+			// masks can't be trusted.
+			ensure!(
+				record.end == region.end && region.mask == region_id.mask,
+				XcRegionsError::InvalidMetadata
+			);
+
+			self._init(id, region, true)
+		}
+
+		/// A function for attesting the metadata of a wrapped region.
+		///
+		/// If the provided metadata matches the stored one, the attestor co-signs it. Otherwise,
+		/// the stored metadata is replaced and its version gets incremented. Either way, the
+		/// metadata of the region is marked as trusted.
+		///
+		/// Only callable by the attestor.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `region` - The corresponding region metadata.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionAttested` event.
+		#[ink(message)]
+		pub fn attest(&mut self, id: Id, region: Region) -> Result<(), XcRegionsError> {
+			ensure!(Some(self.env().caller()) == self.attestor, XcRegionsError::NotAttestor);
+
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let stored = self.regions.get(region_id).ok_or(XcRegionsError::MetadataNotFound)?;
+			Self::ensure_metadata_matches(region_id, &region)?;
+
+			let mut version =
+				self.metadata_versions.get(region_id).ok_or(XcRegionsError::VersionNotFound)?;
+			let metadata_hash = Self::hash_metadata(&region);
+			if stored != region {
+				version = version.saturating_add(1);
+				self.metadata_versions.insert(region_id, &version);
+				self.metadata_history.insert((region_id, version), &region);
+				self.regions.insert(region_id, &region);
+				self.metadata_hashes.insert(region_id, &metadata_hash);
+			}
+			self.trusted_regions.insert(region_id, &());
+
+			self.env().emit_event(RegionAttested {
+				region_id,
+				metadata: region,
+				version,
+				metadata_hash,
+			});
+			Ok(())
+		}
+
+		/// Returns the history of the region, allowing buyers to assess how many hands it went
+		/// through.
+		///
+		/// The provenance is retained when the region is unwrapped.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn provenance(&self, id: Id) -> Option<Provenance> {
+			let Id::U128(region_id) = id else { return None };
+			self.provenance.get(region_id)
+		}
+
+		/// Returns the number of regions currently wrapped by the contract.
+		#[ink(message)]
+		pub fn wrapped_region_count(&self) -> u32 {
+			self.wrapped_count
+		}
+
+		/// Returns the regions currently wrapped by the contract.
+		///
+		/// The order of the regions changes as regions get unwrapped.
+		///
+		/// ## Arguments:
+		/// - `offset`: The number of wrapped regions to skip.
+		/// - `limit`: The maximum number of wrapped regions to return.
+		#[ink(message)]
+		pub fn wrapped_regions(&self, offset: u32, limit: u32) -> Vec<RawRegionId> {
+			(offset..self.wrapped_count)
+				.take(limit as usize)
+				.filter_map(|index| self.wrapped_regions.get(index))
+				.collect()
+		}
+
+		/// Returns the number of regions that have been initialized at least once.
+		#[ink(message)]
+		pub fn versioned_region_count(&self) -> u32 {
+			self.versioned_count
+		}
+
+		/// Returns the regions that have been initialized at least once, together with the
+		/// current version of their metadata.
+		///
+		/// The regions are ordered by the time they were first initialized and remain listed
+		/// after being unwrapped.
+		///
+		/// ## Arguments:
+		/// - `offset`: The number of regions to skip.
+		/// - `limit`: The maximum number of regions to return.
+		#[ink(message)]
+		pub fn versioned_regions(&self, offset: u32, limit: u32) -> Vec<(RawRegionId, Version)> {
+			(offset..self.versioned_count)
+				.take(limit as usize)
+				.filter_map(|index| {
+					let region_id = self.versioned_regions.get(index)?;
+					Some((region_id, self.metadata_versions.get(region_id)?))
+				})
+				.collect()
+		}
+
+		/// Returns the metadata the region had at each of its versions, allowing the
+		/// re-initialization history of the region to be reconstructed.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `offset`: The first version to return.
+		/// - `limit`: The maximum number of versions to return.
+		#[ink(message)]
+		pub fn metadata_history(
+			&self,
+			id: Id,
+			offset: Version,
+			limit: u32,
+		) -> Vec<(Version, Region)> {
+			let Id::U128(region_id) = id else { return Default::default() };
+			let Some(latest) = self.metadata_versions.get(region_id) else {
+				return Default::default()
+			};
+
+			(offset..=latest)
+				.take(limit as usize)
+				.filter_map(|version| {
+					Some((version, self.metadata_history.get((region_id, version))?))
+				})
+				.collect()
+		}
+
+		/// Returns whether the metadata of the region was set or confirmed by the trusted attestor,
+		/// in which case clients can skip their own verification.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn is_trusted(&self, id: Id) -> bool {
+			let Id::U128(region_id) = id else { return false };
+			self.trusted_regions.contains(region_id)
+		}
+
+		/// A function to retrieve all the xcRegions held by an account together with their
+		/// metadata.
+		///
+		/// ## Arguments:
+		/// - `owner` - The account whose xcRegions are returned.
+		#[ink(message)]
+		pub fn owned_regions(&self, owner: AccountId) -> Vec<(RawRegionId, VersionedRegion)> {
+			let count = psp34::PSP34Impl::balance_of(self, owner);
+
+			(0..count as u128)
+				.filter_map(|index| {
+					let Ok(Id::U128(region_id)) =
+						enumerable::PSP34EnumerableImpl::owners_token_by_index(self, owner, index)
+					else {
+						return None
+					};
+					let metadata = self.get_metadata(Id::U128(region_id)).ok()?;
+					Some((region_id, metadata))
+				})
+				.collect()
+		}
+
+		/// Removes the xcRegions and metadata of multiple regions that are no longer held by the
+		/// contract, e.g. because they left the chain.
+		///
+		/// Regions that are still held by the contract or that have no metadata are skipped.
+		/// Anyone can call this function, unless the contract was instantiated with
+		/// `holder_only_removal`, in which case regions that didn't end yet are only removed for
+		/// the holder of their xcRegion.
+		///
+		/// Returns the number of removed regions.
+		///
+		/// ## Arguments:
+		/// - `region_ids` - The `u128` encoded region identifiers.
+		///
+		/// ## Events:
+		/// On success this ink message emits a `RegionRemoved` event for each removed region.
+		#[ink(message)]
+		pub fn remove_batch(
+			&mut self,
+			region_ids: Vec<RawRegionId>,
+		) -> Result<u32, XcRegionsError> {
+			let caller = self.env().caller();
+			let contract = self.env().account_id();
+			let current_timeslice = self.current_timeslice();
+			let mut removed = 0u32;
+			for region_id in region_ids {
+				let Some(region) = self.regions.get(region_id) else { continue };
+				if self._uniques_owner(region_id) == Some(contract) {
+					continue
+				}
+				if self.holder_only_removal &&
+					region.end > current_timeslice &&
+					psp34::PSP34Impl::owner_of(self, Id::U128(region_id)) != Some(caller)
+				{
+					continue
+				}
+				self._remove_stale_wrapper(region_id)?;
+				removed = removed.saturating_add(1);
+			}
+
+			Ok(removed)
+		}
+
+		/// Returns the details of the underlying region item.
+		///
+		/// With the nfts backend, only the first approved account is returned and the item is
+		/// never reported as frozen.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn underlying_item(&self, id: Id) -> Result<Option<ItemDetails>, XcRegionsError> {
+			let Id::U128(raw_region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			Ok(self._uniques_item(raw_region_id))
+		}
+
+		/// Returns the owner of the underlying region item.
+		///
+		/// While the region is wrapped this is the contract itself, so any other owner indicates
+		/// that the xcRegion got out of sync with the underlying region.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn underlying_owner(&self, id: Id) -> Option<AccountId> {
+			let Id::U128(raw_region_id) = id else { return None };
+			self._uniques_owner(raw_region_id)
+		}
+
+		/// Returns the holder of the xcRegion wrapping the underlying region item.
+		///
+		/// Returns `None` if the underlying region item isn't held by the contract.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn escrow_holder(&self, id: Id) -> Option<AccountId> {
+			let Id::U128(raw_region_id) = id else { return None };
+			if self._uniques_owner(raw_region_id) != Some(self.env().account_id()) {
+				return None
+			}
+			psp34::PSP34Impl::owner_of(self, Id::U128(raw_region_id))
+		}
+
+		/// Returns the status of the region based on its metadata and the current timeslice.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn region_status(&self, id: Id) -> RegionStatus {
+			let Id::U128(region_id) = id else { return RegionStatus::Unavailable };
+			let Some(region) = self.regions.get(region_id) else {
+				return RegionStatus::Unavailable
+			};
+
+			let current_timeslice = self.current_timeslice();
+			if current_timeslice < region.begin {
+				RegionStatus::Pending
+			} else if current_timeslice < region.end {
+				RegionStatus::Active { remaining: region.end - current_timeslice }
+			} else {
+				RegionStatus::Expired
+			}
+		}
+
+		/// Decodes a raw region id into its `begin`, `core` and `mask` components.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn decode_region_id(&self, raw_region_id: RawRegionId) -> RegionId {
+			RegionId::from(raw_region_id)
+		}
+
+		/// Returns the share of the core the region occupies, in basis points.
+		///
+		/// This is derived from the region id, so the region doesn't have to be initialized.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn core_occupancy(&self, id: Id) -> Result<u32, XcRegionsError> {
+			let count_ones = self.mask_count_ones(id)?;
+			Ok(count_ones.saturating_mul(10_000) / CORE_MASK_BIT_LEN as u32)
+		}
+
+		/// Returns the number of parts of the core the region is scheduled on.
+		///
+		/// This is derived from the region id, so the region doesn't have to be initialized.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn mask_count_ones(&self, id: Id) -> Result<u32, XcRegionsError> {
+			let Id::U128(raw_region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			Ok(RegionId::from(raw_region_id).mask.count_ones())
+		}
+
+		/// Returns whether two regions are scheduled on the same parts of a core.
+		///
+		/// Only the masks of the regions are compared; the regions don't have to be on the same
+		/// core or overlap in time.
+		///
+		/// ## Arguments:
+		/// - `a` - The `u128` encoded identifier of the first region.
+		/// - `b` - The `u128` encoded identifier of the second region.
+		#[ink(message)]
+		pub fn masks_overlap(&self, a: Id, b: Id) -> Result<bool, XcRegionsError> {
+			let (Id::U128(a), Id::U128(b)) = (a, b) else {
+				return Err(XcRegionsError::InvalidRegionId)
+			};
+			Ok(RegionId::from(a).mask.overlaps(&RegionId::from(b).mask))
+		}
+
+		/// A function for locking an xcRegion, e.g. when it is used as collateral.
+		///
+		/// While locked, the xcRegion cannot be transferred, withdrawn, removed or returned to the
+		/// Coretime chain. Custody of the xcRegion stays with its owner.
+		///
+		/// Only callable by an account approved by the owner for the xcRegion.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionLocked` event.
+		#[ink(message)]
+		pub fn lock(&mut self, id: Id) -> Result<(), XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let owner =
+				psp34::PSP34Impl::owner_of(self, id.clone()).ok_or(XcRegionsError::CannotLock)?;
+
+			let locker = self.env().caller();
+			ensure!(
+				psp34::PSP34Impl::allowance(self, owner, locker, Some(id)),
+				XcRegionsError::CannotLock
+			);
+			ensure!(!self.locks.contains(region_id), XcRegionsError::CannotLock);
+
+			self.locks.insert(region_id, &locker);
+
+			self.env().emit_event(RegionLocked { region_id, locker });
+			Ok(())
+		}
+
+		/// A function for unlocking a locked xcRegion.
+		///
+		/// Only callable by the account that locked the xcRegion.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionUnlocked` event.
+		#[ink(message)]
+		pub fn unlock(&mut self, id: Id) -> Result<(), XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			ensure!(
+				self.locks.get(region_id) == Some(self.env().caller()),
+				XcRegionsError::CannotUnlock
+			);
+
+			self.locks.remove(region_id);
+
+			self.env().emit_event(RegionUnlocked { region_id });
+			Ok(())
+		}
+
+		/// Returns the account that locked the xcRegion, if it is locked.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn locked_by(&self, id: Id) -> Option<AccountId> {
+			let Id::U128(region_id) = id else { return None };
+			self.locks.get(region_id)
+		}
+
+		/// A function for assigning a user to an xcRegion.
+		///
+		/// The user can exercise usage-related rights of the region until the expiry, but cannot
+		/// transfer it. The user is reset once the region changes hands.
+		///
+		/// Only callable by the owner of the xcRegion or an account approved for it.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `user` - The new user of the region. `None` removes the current user.
+		/// - `expires` - The timeslice at which the user role expires.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `UserUpdated` event.
+		#[ink(message)]
+		pub fn set_user(
+			&mut self,
+			id: Id,
+			user: Option<AccountId>,
+			expires: Timeslice,
+		) -> Result<(), XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let owner =
+				psp34::PSP34Impl::owner_of(self, id.clone()).ok_or(XcRegionsError::NotAllowed)?;
+
+			let caller = self.env().caller();
+			ensure!(
+				caller == owner || psp34::PSP34Impl::allowance(self, owner, caller, Some(id)),
+				XcRegionsError::NotAllowed
+			);
+
+			if let Some(user) = user {
+				self.users.insert(region_id, &(user, expires));
+			} else {
+				self.users.remove(region_id);
+			}
+
+			self.env().emit_event(UserUpdated { region_id, user, expires });
+			Ok(())
+		}
+
+		/// Returns the current user of the xcRegion, if the user role hasn't expired.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn user_of(&self, id: Id) -> Option<AccountId> {
+			let Id::U128(region_id) = id else { return None };
+			let (user, expires) = self.users.get(region_id)?;
+			(self.current_timeslice() < expires).then_some(user)
+		}
+
+		/// A function for assigning the underlying region of an xcRegion to a task on the Coretime
+		/// chain.
+		///
+		/// Only callable by the owner of the xcRegion or an account approved for it, such as the
+		/// assignments contract dispatching an assignment on behalf of the owner.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `task` - The task to which the region is assigned.
+		/// - `finality` - Whether the assignment can later be changed.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionAssigned` event.
+		#[ink(message)]
+		pub fn assign(
+			&mut self,
+			id: Id,
+			task: TaskId,
+			finality: Finality,
+		) -> Result<(), XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let owner =
+				psp34::PSP34Impl::owner_of(self, id.clone()).ok_or(XcRegionsError::NotAllowed)?;
+
+			let caller = self.env().caller();
+			ensure!(
+				caller == owner || psp34::PSP34Impl::allowance(self, owner, caller, Some(id)),
+				XcRegionsError::NotAllowed
+			);
+
+			self.env()
+				.extension()
+				.assign(region_id, task, finality)
+				.map_err(|_| XcRegionsError::RuntimeError)?;
+
+			self.env().emit_event(RegionAssigned { region_id, task, finality });
+			Ok(())
+		}
+
+		/// Returns the timeslice at which the user role of the xcRegion expires.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn user_expires(&self, id: Id) -> Option<Timeslice> {
+			let Id::U128(region_id) = id else { return None };
+			self.users.get(region_id).map(|(_, expires)| expires)
+		}
+
+		/// A function for attaching a label to an xcRegion, e.g. to describe its purpose to
+		/// marketplaces.
+		///
+		/// The labels are exposed through `get_attribute` and are kept when the xcRegion changes
+		/// hands. They are removed once the xcRegion is unwrapped.
+		///
+		/// Only callable by the owner of the xcRegion.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `key` - The key of the label. Cannot be one of the `RESERVED_ATTRIBUTES`.
+		/// - `value` - The value of the label. `None` removes the label.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `LabelUpdated` event.
+		#[ink(message)]
+		pub fn set_label(
+			&mut self,
+			id: Id,
+			key: String,
+			value: Option<String>,
+		) -> Result<(), XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let owner =
+				psp34::PSP34Impl::owner_of(self, id.clone()).ok_or(XcRegionsError::NotAllowed)?;
+			ensure!(owner == self.env().caller(), XcRegionsError::NotAllowed);
+
+			ensure!(
+				!key.is_empty() &&
+					key.len() <= MAX_LABEL_LEN &&
+					!RESERVED_ATTRIBUTES.contains(&key.as_str()),
+				XcRegionsError::InvalidLabel
+			);
+
+			let mut keys = self.label_keys.get(region_id).unwrap_or_default();
+			let position = keys.iter().position(|k| *k == key);
+
+			if let Some(value) = &value {
+				ensure!(value.len() <= MAX_LABEL_LEN, XcRegionsError::InvalidLabel);
+				if position.is_none() {
+					ensure!(keys.len() < MAX_LABELS, XcRegionsError::TooManyLabels);
+					keys.push(key.clone());
+				}
+				self.labels.insert((region_id, key.clone()), value);
+			} else if let Some(position) = position {
+				keys.remove(position);
+				self.labels.remove((region_id, key.clone()));
+			}
+			self.label_keys.insert(region_id, &keys);
+
+			self.env().emit_event(LabelUpdated { region_id, key, value });
+			Ok(())
+		}
+
+		/// A function for attaching a short note to an xcRegion, e.g. to describe what it is best
+		/// suited for to potential buyers.
+		///
+		/// The note is kept when the xcRegion changes hands and removed once the xcRegion is
+		/// unwrapped.
+		///
+		/// Only callable by the owner of the xcRegion.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `note` - The note, at most `MAX_NOTE_LEN` bytes long. `None` removes the note.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `NoteUpdated` event.
+		#[ink(message)]
+		pub fn set_note(&mut self, id: Id, note: Option<String>) -> Result<(), XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let owner =
+				psp34::PSP34Impl::owner_of(self, id.clone()).ok_or(XcRegionsError::NotAllowed)?;
+			ensure!(owner == self.env().caller(), XcRegionsError::NotAllowed);
+
+			if let Some(note) = &note {
+				ensure!(note.len() <= MAX_NOTE_LEN, XcRegionsError::InvalidNote);
+				self.notes.insert(region_id, note);
+			} else {
+				self.notes.remove(region_id);
+			}
+
+			self.env().emit_event(NoteUpdated { region_id, note });
+			Ok(())
+		}
+
+		/// Returns the keys of the labels attached to the xcRegion.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		#[ink(message)]
+		pub fn label_keys(&self, id: Id) -> Vec<String> {
+			let Id::U128(region_id) = id else { return Default::default() };
+			self.label_keys.get(region_id).unwrap_or_default()
+		}
+
+		/// A function for opting in or out of the `on_region_received` callback invoked when the
+		/// caller receives an xcRegion.
+		///
+		/// ## Arguments:
+		/// - `enabled` - Whether the callback should be invoked. It is enabled by default.
+		#[ink(message)]
+		pub fn set_receive_hook(&mut self, enabled: bool) {
+			let caller = self.env().caller();
+			if enabled {
+				self.receive_hook_opt_outs.remove(caller);
+			} else {
+				self.receive_hook_opt_outs.insert(caller, &());
+			}
+		}
+
+		#[ink(message)]
+		pub fn receive_hook_enabled(&self, account: AccountId) -> bool {
+			!self.receive_hook_opt_outs.contains(account)
+		}
+
+		/// A function for withdrawing the underlying region of an xcRegion.
+		///
+		/// The wrapped region is burned, its metadata gets removed, and the underlying uniques
+		/// item is transferred back to the caller.
+		///
+		/// Only the holder of the xcRegion can call this function.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionWithdrawn` event.
+		#[ink(message)]
+		pub fn withdraw(&mut self, id: Id) -> Result<(), XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let owner = psp34::PSP34Impl::owner_of(self, id.clone())
+				.ok_or(XcRegionsError::CannotWithdraw)?;
+
+			ensure!(owner == self.env().caller(), XcRegionsError::CannotWithdraw);
+			self._unwrap(region_id, owner)?;
+
+			self.env().emit_event(RegionWithdrawn { region_id, owner });
+			Ok(())
+		}
+
+		/// A function for sending the underlying region of an xcRegion back to the Coretime
+		/// chain.
+		///
+		/// The wrapped region is burned, its metadata gets removed, and the underlying region is
+		/// reserve-transferred to the beneficiary on the Coretime chain.
+		///
+		/// Only the holder of the xcRegion can call this function.
+		///
+		/// ## Arguments:
+		/// - `raw_region_id` - The `u128` encoded region identifier.
+		/// - `beneficiary` - The account receiving the region on the Coretime chain.
+		/// - `fee` - The amount of the relay chain asset used for paying the XCM execution fees.
+		///   Can't exceed `max_return_fee`.
+		///
+		/// NOTE: The fee is paid from the relay chain asset held by this contract, so the contract
+		/// must hold at least `fee` of it. Any unspent fee is deposited to the beneficiary, which
+		/// is why the admin caps the fee.
+		///
+		/// ## Events:
+		/// On success this ink message emits the `RegionReturned` event.
+		#[ink(message)]
+		pub fn return_to_coretime_chain(
+			&mut self,
+			id: Id,
+			beneficiary: AccountId,
+			fee: Balance,
+		) -> Result<(), XcRegionsError> {
+			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
+			let owner = psp34::PSP34Impl::owner_of(self, id.clone())
+				.ok_or(XcRegionsError::CannotWithdraw)?;
+
+			ensure!(owner == self.env().caller(), XcRegionsError::CannotWithdraw);
+			ensure!(fee <= self.max_return_fee, XcRegionsError::FeeTooHigh);
+			self._burn_wrapper(region_id, owner)?;
+			self._reserve_transfer(region_id, beneficiary, fee)?;
+
+			self.env().emit_event(RegionReturned { region_id, beneficiary });
+			Ok(())
+		}
+
+		/// A function for transferring the admin role to a different account.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_admin`: The account that will become the new admin.
+		#[ink(message)]
+		pub fn set_admin(&mut self, new_admin: AccountId) -> Result<(), XcRegionsError> {
+			ensure!(self.env().caller() == self.admin, XcRegionsError::NotAllowed);
+
+			self.admin = new_admin;
+
+			self.env().emit_event(AdminChanged { new_admin });
+			Ok(())
+		}
+
+		/// A function for assigning the guardian role, which allows pausing the initialization of
+		/// regions in case of an emergency.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_guardian`: The account that will become the new guardian. `None` removes the
+		///   current guardian.
+		#[ink(message)]
+		pub fn set_guardian(
+			&mut self,
+			new_guardian: Option<AccountId>,
+		) -> Result<(), XcRegionsError> {
+			ensure!(self.env().caller() == self.admin, XcRegionsError::NotAllowed);
+
+			self.guardian = new_guardian;
 
-	#[openbrush::wrapper]
-	pub type PSP34Ref = dyn PSP34 + PSP34Enumerable;
+			self.env().emit_event(GuardianChanged { new_guardian });
+			Ok(())
+		}
 
-	#[ink(storage)]
-	#[derive(Default, Storage)]
-	pub struct XcRegions {
-		#[storage_field]
-		psp34: psp34::Data,
-		#[storage_field]
-		enumerable: enumerable::Data,
-		/// A mapping that links RawRegionId to its corresponding region metadata.
-		pub regions: Mapping<RawRegionId, Region>,
-		/// A mapping that keeps track of the metadata version for each region.
+		/// A function for pausing or unpausing the initialization of regions, e.g. when the
+		/// chain extension or the underlying collection turns out to be misconfigured.
 		///
-		/// This version gets incremented for a region each time it gets re-initialized.
-		pub metadata_versions: Mapping<RawRegionId, Version>,
-		// Mock chain extension state only used for integration testing.
-		#[cfg(test)]
-		pub items: Mapping<
-			(primitives::uniques::CollectionId, primitives::coretime::RawRegionId),
-			ItemDetails,
-		>,
-		// Mock chain extension state only used for integration testing.
-		#[cfg(test)]
-		pub account: Mapping<
-			AccountId,
-			Vec<(primitives::uniques::CollectionId, primitives::coretime::RawRegionId)>,
-		>,
-	}
+		/// Only callable by the guardian or the admin.
+		///
+		/// ## Arguments:
+		/// - `paused`: Whether the initialization of regions should be paused. While paused,
+		///   xcRegions can still be transferred, withdrawn and removed.
+		#[ink(message)]
+		pub fn set_paused(&mut self, paused: bool) -> Result<(), XcRegionsError> {
+			let caller = self.env().caller();
+			ensure!(
+				caller == self.admin || Some(caller) == self.guardian,
+				XcRegionsError::NotAllowed
+			);
 
-	#[ink(event)]
-	pub struct RegionInitialized {
-		/// The identifier of the region that got initialized.
-		#[ink(topic)]
-		pub(crate) region_id: RawRegionId,
-		/// The associated metadata.
-		pub(crate) metadata: Region,
-		/// The version of the metadata. This is incremented by the contract each time the same
-		/// region is initialized.
-		pub(crate) version: Version,
-	}
+			self.paused = paused;
 
-	#[ink(event)]
-	pub struct RegionRemoved {
-		/// The identifier of the region that got removed.
-		#[ink(topic)]
-		pub(crate) region_id: RawRegionId,
-	}
+			self.env().emit_event(PauseStatusUpdated { paused });
+			Ok(())
+		}
 
-	#[overrider(PSP34)]
-	fn collection_id(&self) -> Id {
-		Id::U32(REGIONS_COLLECTION_ID)
-	}
+		/// A function for updating the deposit required for initializing a region.
+		///
+		/// Only callable by the admin.
+		///
+		/// ## Arguments:
+		/// - `new_deposit`: The deposit required for new initializations. Wrapped regions keep the
+		///   deposit they paid.
+		#[ink(message)]
+		pub fn set_init_deposit(&mut self, new_deposit: Balance) -> Result<(), XcRegionsError> {
+			ensure!(self.env().caller() == self.admin, XcRegionsError::NotAllowed);
 
-	impl RegionMetadata for XcRegions {
-		/// A function for minting a wrapped xcRegion and initializing the metadata of it. It can
-		/// only be called if the specified region exists on this chain and the caller is the actual
-		/// owner of the region.
+			self.init_deposit = new_deposit;
+
+			self.env().emit_event(InitDepositUpdated { new_deposit });
+			Ok(())
+		}
+
+		/// A function for updating the maximum XCM fee the contract pays for returning a region to
+		/// the Coretime chain.
+		///
+		/// Only callable by the admin.
 		///
 		/// ## Arguments:
-		/// - `raw_region_id` - The `u128` encoded region identifier.
-		/// - `region` - The corresponding region metadata.
+		/// - `new_max_fee`: The maximum amount of the relay chain asset paid per returned region.
+		#[ink(message)]
+		pub fn set_max_return_fee(&mut self, new_max_fee: Balance) -> Result<(), XcRegionsError> {
+			ensure!(self.env().caller() == self.admin, XcRegionsError::NotAllowed);
+
+			self.max_return_fee = new_max_fee;
+
+			self.env().emit_event(MaxReturnFeeUpdated { new_max_fee });
+			Ok(())
+		}
+
+		/// A function for updating the index of the uniques pallet, for runtimes that don't place
+		/// the pallet at `UNIQUES_PALLET_INDEX`.
 		///
-		/// This function conducts a sanity check to verify that the metadata derived from the
-		/// `raw_region_id` aligns with the respective components of the metadata supplied through
-		/// the region argument.
+		/// Only callable by the admin.
 		///
-		/// If this is not the first time that this region is inititalized, the metadata version
-		/// will get incremented.
+		/// ## Arguments:
+		/// - `index`: The index of the uniques pallet in the runtime of this chain.
+		#[ink(message)]
+		pub fn set_uniques_pallet_index(&mut self, index: u8) -> Result<(), XcRegionsError> {
+			ensure!(self.env().caller() == self.admin, XcRegionsError::NotAllowed);
+
+			self.uniques_pallet_index = index;
+
+			self.env().emit_event(UniquesPalletIndexUpdated { index });
+			Ok(())
+		}
+
+		/// A function for upgrading the code of the contract.
 		///
-		/// The underlying region will be transferred to this contract, and in response, a wrapped
-		/// token will be minted for the caller.
+		/// Only callable by the admin.
 		///
-		/// NOTE: Prior to invoking this ink message, the caller must grant approval to the contract
-		/// for the region, enabling its transfer.
+		/// ## Arguments:
+		/// - `code_hash`: The hash of the new contract code. The code must already be uploaded on
+		///   chain.
 		///
-		/// ## Events:
-		/// On success this ink message emits the `RegionInitialized` event.
+		/// The storage of the contract, including the metadata of all wrapped regions, is kept. If
+		/// the new code changes the storage layout, it must bump `STORAGE_VERSION` and implement
+		/// the migration in `migrate`, which must be called right after the upgrade.
 		#[ink(message)]
-		fn init(&mut self, id: Id, region: Region) -> Result<(), XcRegionsError> {
+		pub fn upgrade(&mut self, code_hash: Hash) -> Result<(), XcRegionsError> {
+			ensure!(self.env().caller() == self.admin, XcRegionsError::NotAllowed);
+
+			self.env()
+				.set_code_hash(&code_hash)
+				.map_err(|_| XcRegionsError::UpgradeFailed)?;
+
+			self.env().emit_event(CodeUpgraded { code_hash });
+			Ok(())
+		}
+
+		/// A function for migrating the storage of the contract to the layout expected by the
+		/// current code.
+		///
+		/// Only callable by the admin.
+		#[ink(message)]
+		pub fn migrate(&mut self) -> Result<(), XcRegionsError> {
+			ensure!(self.env().caller() == self.admin, XcRegionsError::NotAllowed);
+			ensure!(self.storage_version < STORAGE_VERSION, XcRegionsError::AlreadyMigrated);
+
+			// NOTE: Migrations between storage versions should be added here.
+
+			self.storage_version = STORAGE_VERSION;
+
+			self.env().emit_event(StorageMigrated { storage_version: STORAGE_VERSION });
+			Ok(())
+		}
+
+		// Initializes the metadata of a region and mints the xcRegion to the region owner.
+		fn _init(&mut self, id: Id, region: Region, trusted: bool) -> Result<(), XcRegionsError> {
+			ensure!(!self.paused, XcRegionsError::InitializationPaused);
 			let caller = self.env().caller();
 
 			let Id::U128(raw_region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
-			ensure!(
-				Some(caller) == self._uniques_owner(raw_region_id),
-				XcRegionsError::CannotInitialize
-			);
-
-			// Cannot initialize a region that already has metadata stored.
-			ensure!(self.regions.get(raw_region_id).is_none(), XcRegionsError::CannotInitialize);
+			let contract = self.env().account_id();
+			// The underlying item is only fetched once, since every extension call adds weight.
+			let (owner, [caller_approved, contract_approved]) = self
+				._uniques_owner_and_approvals(raw_region_id, [caller, contract])
+				.ok_or(XcRegionsError::CannotInitialize)?;
+			ensure!(caller == owner || caller_approved, XcRegionsError::CannotInitialize);
+
+			if self.regions.contains(raw_region_id) {
+				// Cannot initialize a region that is wrapped and already has metadata stored.
+				ensure!(owner != contract, XcRegionsError::CannotInitialize);
+
+				// If the region left the chain and came back, the contract no longer holds it, so
+				// the previous xcRegion is stale and gets removed.
+				self._remove_stale_wrapper(raw_region_id)?;
+			}
 
 			// Do a sanity check to ensure that the provided region metadata matches with the
 			// metadata extracted from the region id.
-			let region_id = RegionId::from(raw_region_id);
-			ensure!(region_id.begin == region.begin, XcRegionsError::InvalidMetadata);
-			ensure!(region_id.core == region.core, XcRegionsError::InvalidMetadata);
-			ensure!(region_id.mask == region.mask, XcRegionsError::InvalidMetadata);
+			Self::ensure_metadata_matches(raw_region_id, &region)?;
+
+			if self.reject_expired {
+				ensure!(region.end > self.current_timeslice(), XcRegionsError::RegionExpired);
+			}
+			ensure!(
+				self.env().transferred_value() == self.init_deposit,
+				XcRegionsError::MissingDeposit
+			);
 
 			// After passing all checks we will transfer the region to the contract and mint a
 			// wrapped xcRegion token.
-			let contract = self.env().account_id();
-			self._transfer(raw_region_id, contract)?;
-
-			let new_version = if let Some(version) = self.metadata_versions.get(raw_region_id) {
-				version.saturating_add(1)
-			} else {
-				Default::default()
-			};
-
+			self._transfer(raw_region_id, contract).map_err(|err| {
+				if contract_approved {
+					err
+				} else {
+					// The most common reason for the transfer failing.
+					XcRegionsError::ContractNotApproved
+				}
+			})?;
+
+			let old_version = self.metadata_versions.get(raw_region_id);
+			let new_version = self.next_metadata_version(raw_region_id);
 			self.metadata_versions.insert(raw_region_id, &new_version);
+			self.metadata_history.insert((raw_region_id, new_version), &region);
+			if old_version.is_none() {
+				self.versioned_regions.insert(self.versioned_count, &raw_region_id);
+				self.versioned_count = self.versioned_count.saturating_add(1);
+			}
+			let metadata_hash = Self::hash_metadata(&region);
 			self.regions.insert(raw_region_id, &region);
+			self.metadata_hashes.insert(raw_region_id, &metadata_hash);
+			self.add_wrapped_region(raw_region_id);
+			if !self.provenance.contains(raw_region_id) {
+				let provenance = Provenance { first_initializer: caller, transfer_count: 0 };
+				self.provenance.insert(raw_region_id, &provenance);
+			}
+			if trusted {
+				self.trusted_regions.insert(raw_region_id, &());
+			}
+			if self.init_deposit > 0 {
+				self.deposits.insert(raw_region_id, &(caller, self.init_deposit));
+			}
 
-			psp34::InternalImpl::_mint_to(self, caller, Id::U128(raw_region_id))
+			psp34::InternalImpl::_mint_to(self, owner, Id::U128(raw_region_id))
 				.map_err(XcRegionsError::Psp34)?;
 
+			if let Some(old) = old_version {
+				self.env().emit_event(MetadataVersionBumped {
+					region_id: raw_region_id,
+					old,
+					new: new_version,
+				});
+			}
 			self.env().emit_event(RegionInitialized {
 				region_id: raw_region_id,
 				metadata: region,
 				version: new_version,
+				metadata_hash,
 			});
 
 			Ok(())
 		}
 
-		/// A function to retrieve all metadata associated with a specific region.
-		///
-		/// The function returns a `VersionedRegion`, encompassing the version of the retrieved
-		/// metadata that is intended for client-side verification.
-		///
-		/// ## Arguments:
-		/// - `raw_region_id` - The `u128` encoded region identifier.
-		#[ink(message)]
-		fn get_metadata(&self, id: Id) -> Result<VersionedRegion, XcRegionsError> {
-			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
-			let Some(region) = self.regions.get(region_id) else {
-				return Err(XcRegionsError::MetadataNotFound)
-			};
+		pub(crate) fn current_timeslice(&self) -> Timeslice {
+			(self._relay_block_number() / TIMESLICE_PERIOD) as Timeslice
+		}
 
-			let Some(version) = self.metadata_versions.get(region_id) else {
-				// This should never really happen; if a region has its metadata stored, its version
-				// should be stored as well.
-				return Err(XcRegionsError::VersionNotFound)
-			};
+		fn add_wrapped_region(&mut self, region_id: RawRegionId) {
+			self.wrapped_regions.insert(self.wrapped_count, &region_id);
+			self.wrapped_region_indices.insert(region_id, &self.wrapped_count);
+			self.wrapped_count = self.wrapped_count.saturating_add(1);
+		}
+
+		fn remove_wrapped_region(&mut self, region_id: RawRegionId) {
+			let Some(index) = self.wrapped_region_indices.take(region_id) else { return };
+			self.wrapped_count = self.wrapped_count.saturating_sub(1);
 
-			Ok(VersionedRegion { version, region })
+			// The last region is moved to the position of the removed one.
+			let last_region = self.wrapped_regions.take(self.wrapped_count);
+			if let Some(last_region) = last_region.filter(|_| index != self.wrapped_count) {
+				self.wrapped_regions.insert(index, &last_region);
+				self.wrapped_region_indices.insert(last_region, &index);
+			}
 		}
 
-		/// A function to return the region to its owner.
-		///
-		/// This process involves burning the wrapped region and eliminating its associated
-		/// metadata.
-		///
-		/// Only the owner of the wrapped region can call this function.
-		///
-		/// ## Arguments:
-		/// - `raw_region_id` - The `u128` encoded region identifier.
-		///
-		/// ## Events:
-		/// On success this ink message emits the `RegionRemoved` event.
-		#[ink(message)]
-		fn remove(&mut self, id: Id) -> Result<(), XcRegionsError> {
-			let Id::U128(region_id) = id else { return Err(XcRegionsError::InvalidRegionId) };
-			let owner =
-				psp34::PSP34Impl::owner_of(self, id.clone()).ok_or(XcRegionsError::CannotRemove)?;
+		// Returns the version the metadata of a region gets when it is initialized.
+		fn next_metadata_version(&self, raw_region_id: RawRegionId) -> Version {
+			if let Some(version) = self.metadata_versions.get(raw_region_id) {
+				version.saturating_add(1)
+			} else {
+				Default::default()
+			}
+		}
 
-			ensure!(owner == self.env().caller(), XcRegionsError::CannotRemove);
-			self.regions.remove(region_id);
+		// Returns the blake2 hash of the encoded metadata.
+		fn hash_metadata(region: &Region) -> [u8; 32] {
+			let mut hash = [0u8; 32];
+			ink::env::hash_encoded::<Blake2x256, _>(region, &mut hash);
+			hash
+		}
+
+		// Ensures that the metadata matches with the metadata extracted from the region id.
+		//
+		// Interlacing a region on the Coretime chain splits its mask between the resulting regions,
+		// so the mask of the metadata is only required to be a subset of the mask encoded in the
+		// region id rather than being equal to it.
+		fn ensure_metadata_matches(
+			raw_region_id: RawRegionId,
+			region: &Region,
+		) -> Result<(), XcRegionsError> {
+			let region_id = RegionId::from(raw_region_id);
+			ensure!(region_id.begin == region.begin, XcRegionsError::InvalidMetadata);
+			ensure!(region_id.core == region.core, XcRegionsError::InvalidMetadata);
+			ensure!(region.mask.is_subset_of(&region_id.mask), XcRegionsError::InvalidMetadata);
+
+			Ok(())
+		}
+
+		// Burns the xcRegion, removes its metadata and returns the underlying region to `owner`.
+		fn _unwrap(
+			&mut self,
+			region_id: RawRegionId,
+			owner: AccountId,
+		) -> Result<(), XcRegionsError> {
+			self._burn_wrapper(region_id, owner)?;
+			self._transfer(region_id, owner)
+		}
 
-			psp34::InternalImpl::_burn_from(self, owner, id).map_err(XcRegionsError::Psp34)?;
-			self._transfer(region_id, owner)?;
+		// Removes the xcRegion of a region that is no longer held by the contract, together with
+		// its metadata. The xcRegion is removed even if it is locked, since it no longer
+		// represents the region.
+		fn _remove_stale_wrapper(&mut self, region_id: RawRegionId) -> Result<(), XcRegionsError> {
+			self.locks.remove(region_id);
+
+			if let Some(holder) = psp34::PSP34Impl::owner_of(self, Id::U128(region_id)) {
+				self._burn_wrapper(region_id, holder)?;
+			} else {
+				self.regions.remove(region_id);
+				self.metadata_hashes.remove(region_id);
+			}
 
 			self.env().emit_event(RegionRemoved { region_id });
 			Ok(())
 		}
-	}
 
-	impl XcRegions {
-		#[ink(constructor)]
-		pub fn new() -> Self {
-			Default::default()
+		// Burns the xcRegion, removes its metadata and returns the deposit paid for it.
+		//
+		// The metadata version is retained so that re-initializing the region increments it.
+		fn _burn_wrapper(
+			&mut self,
+			region_id: RawRegionId,
+			owner: AccountId,
+		) -> Result<(), XcRegionsError> {
+			ensure!(!self.locks.contains(region_id), XcRegionsError::RegionLocked);
+
+			self.regions.remove(region_id);
+			self.metadata_hashes.remove(region_id);
+			self.trusted_regions.remove(region_id);
+			self.remove_wrapped_region(region_id);
+			for key in self.label_keys.take(region_id).unwrap_or_default() {
+				self.labels.remove((region_id, key));
+			}
+			self.notes.remove(region_id);
+
+			psp34::InternalImpl::_burn_from(self, owner, Id::U128(region_id))
+				.map_err(XcRegionsError::Psp34)?;
+
+			if let Some((depositor, deposit)) = self.deposits.take(region_id) {
+				self.env()
+					.transfer(depositor, deposit)
+					.map_err(|_| XcRegionsError::TransferFailed)?;
+			}
+
+			Ok(())
 		}
 	}
 
@@ -238,12 +1982,61 @@ pub mod xc_regions {
 	#[cfg(not(test))]
 	impl XcRegions {
 		fn _transfer(&self, region_id: RawRegionId, dest: AccountId) -> Result<(), XcRegionsError> {
+			let (collection, item, dest) = (self.collection_id, region_id, dest.into());
+			match self.backend {
+				RegionsBackend::Uniques => self.env().call_runtime(&PalletCall {
+					pallet_index: self.uniques_pallet_index,
+					call: UniquesCall::Transfer { collection, item, dest },
+				}),
+				RegionsBackend::Nfts =>
+					self.env().call_runtime(&RuntimeCall::Nfts(NftsCall::Transfer {
+						collection,
+						item,
+						dest,
+					})),
+			}
+			.map_err(|_| XcRegionsError::RuntimeError)?;
+
+			Ok(())
+		}
+
+		/// The relay chain block number this chain's current block is built upon.
+		fn _relay_block_number(&self) -> BlockNumber {
+			self.env().extension().relay_chain_block_number().unwrap_or_default()
+		}
+
+		/// The state root of the relay chain block this chain's current block is built upon.
+		fn _relay_state_root(&self) -> Result<[u8; 32], XcRegionsError> {
+			self.env()
+				.extension()
+				.relay_chain_state_root()
+				.map_err(|_| XcRegionsError::RuntimeError)
+		}
+
+		/// The id of the collection in which the runtime represents the regions.
+		fn _runtime_collection_id() -> Result<CollectionId, XcRegionsError> {
+			Self::env()
+				.extension()
+				.regions_collection_id()
+				.map_err(|_| XcRegionsError::RuntimeError)
+		}
+
+		fn _reserve_transfer(
+			&self,
+			region_id: RawRegionId,
+			beneficiary: AccountId,
+			fee: Balance,
+		) -> Result<(), XcRegionsError> {
+			let call = PolkadotXcmCall::return_region(
+				CORETIME_CHAIN_PARA_ID,
+				CORETIME_REGIONS_PALLET_INDEX,
+				region_id,
+				beneficiary,
+				fee,
+			);
+
 			self.env()
-				.call_runtime(&RuntimeCall::Uniques(UniquesCall::Transfer {
-					collection: REGIONS_COLLECTION_ID,
-					item: region_id,
-					dest: dest.into(),
-				}))
+				.call_runtime(&RuntimeCall::PolkadotXcm(call))
 				.map_err(|_| XcRegionsError::RuntimeError)?;
 
 			Ok(())
@@ -256,12 +2049,68 @@ pub mod xc_regions {
 
 		/// Returns the details of an item within a collection.
 		fn _uniques_item(&self, item_id: RawRegionId) -> Option<ItemDetails> {
-			self.env().extension().item(REGIONS_COLLECTION_ID, item_id).ok()?
+			let extension = self.env().extension();
+			match self.backend {
+				RegionsBackend::Uniques =>
+					UniquesExtension::item(&extension, self.collection_id, item_id).ok()?,
+				RegionsBackend::Nfts =>
+					NftsExtension::item(&extension, self.collection_id, item_id)
+						.ok()?
+						.map(Into::into),
+			}
 		}
 
 		/// The owner of the specific item.
 		fn _uniques_owner(&self, region_id: RawRegionId) -> Option<AccountId> {
-			self.env().extension().owner(REGIONS_COLLECTION_ID, region_id).ok()?
+			let extension = self.env().extension();
+			match self.backend {
+				RegionsBackend::Uniques =>
+					UniquesExtension::owner(&extension, self.collection_id, region_id).ok()?,
+				RegionsBackend::Nfts =>
+					NftsExtension::owner(&extension, self.collection_id, region_id).ok()?,
+			}
+		}
+
+		/// Returns whether the account is approved to transfer the specific item.
+		fn _uniques_approved(&self, region_id: RawRegionId, account: AccountId) -> bool {
+			let extension = self.env().extension();
+			match self.backend {
+				RegionsBackend::Uniques =>
+					UniquesExtension::item(&extension, self.collection_id, region_id)
+						.ok()
+						.flatten()
+						.map_or(false, |item| item.approved == Some(account)),
+				RegionsBackend::Nfts =>
+					NftsExtension::item(&extension, self.collection_id, region_id)
+						.ok()
+						.flatten()
+						.map_or(false, |item| item.approvals.contains_key(&account)),
+			}
+		}
+
+		/// The owner of the specific item, together with whether each of the `accounts` is
+		/// approved to transfer it.
+		fn _uniques_owner_and_approvals<const N: usize>(
+			&self,
+			region_id: RawRegionId,
+			accounts: [AccountId; N],
+		) -> Option<(AccountId, [bool; N])> {
+			let extension = self.env().extension();
+			match self.backend {
+				RegionsBackend::Uniques => {
+					let item = UniquesExtension::item(&extension, self.collection_id, region_id)
+						.ok()??;
+					Some((item.owner, accounts.map(|account| item.approved == Some(account))))
+				},
+				RegionsBackend::Nfts => {
+					let item =
+						NftsExtension::item(&extension, self.collection_id, region_id).ok()??;
+					Some((
+						item.owner,
+						accounts.map(|account| item.approvals.contains_key(&account)),
+					))
+				},
+			}
 		}
 	}
 
@@ -273,8 +2122,37 @@ pub mod xc_regions {
 			region_id: RawRegionId,
 			dest: AccountId,
 		) -> Result<(), XcRegionsError> {
-			self.burn((REGIONS_COLLECTION_ID, region_id)).unwrap();
-			self.mint((REGIONS_COLLECTION_ID, region_id), dest).unwrap();
+			self.burn_item((self.collection_id, region_id)).unwrap();
+			self.mint((self.collection_id, region_id), dest).unwrap();
+			Ok(())
+		}
+
+		/// The relay chain block number this chain's current block is built upon.
+		pub fn _relay_block_number(&self) -> BlockNumber {
+			self.env().block_number()
+		}
+
+		/// The state root of the relay chain block this chain's current block is built upon.
+		pub fn _relay_state_root(&self) -> Result<[u8; 32], XcRegionsError> {
+			Ok(self.relay_state_root)
+		}
+
+		/// The collection id the mock runtime represents the regions in.
+		pub const RUNTIME_COLLECTION_ID: CollectionId = 7;
+
+		/// The id of the collection in which the runtime represents the regions.
+		pub fn _runtime_collection_id() -> Result<CollectionId, XcRegionsError> {
+			Ok(Self::RUNTIME_COLLECTION_ID)
+		}
+
+		// The region leaves this chain, so it is simply burned.
+		fn _reserve_transfer(
+			&mut self,
+			region_id: RawRegionId,
+			_beneficiary: AccountId,
+			_fee: Balance,
+		) -> Result<(), XcRegionsError> {
+			self.burn_item((self.collection_id, region_id)).unwrap();
 			Ok(())
 		}
 
@@ -285,12 +2163,42 @@ pub mod xc_regions {
 
 		/// Returns the details of an item within a collection.
 		pub fn _uniques_item(&self, item_id: RawRegionId) -> Option<ItemDetails> {
-			self.items.get((REGIONS_COLLECTION_ID, item_id))
+			self.items.get((self.collection_id, item_id))
 		}
 
 		/// The owner of the specific item.
 		pub fn _uniques_owner(&self, region_id: RawRegionId) -> Option<AccountId> {
-			self.items.get((REGIONS_COLLECTION_ID, region_id)).map(|a| a.owner)
+			self.items.get((self.collection_id, region_id)).map(|a| a.owner)
+		}
+
+		/// Returns whether the account is approved to transfer the specific item.
+		pub fn _uniques_approved(&self, region_id: RawRegionId, account: AccountId) -> bool {
+			self.items
+				.get((self.collection_id, region_id))
+				.map_or(false, |a| a.approved == Some(account))
+		}
+
+		/// The owner of the specific item, together with whether each of the `accounts` is
+		/// approved to transfer it.
+		pub fn _uniques_owner_and_approvals<const N: usize>(
+			&self,
+			region_id: RawRegionId,
+			accounts: [AccountId; N],
+		) -> Option<(AccountId, [bool; N])> {
+			let item = self.items.get((self.collection_id, region_id))?;
+			Some((item.owner, accounts.map(|account| item.approved == Some(account))))
+		}
+
+		pub fn approve_transfer(
+			&mut self,
+			id: (CollectionId, RawRegionId),
+			delegate: AccountId,
+		) -> Result<(), &'static str> {
+			let Some(mut item) = self.items.get((id.0, id.1)) else { return Err("Item not found") };
+			item.approved = Some(delegate);
+			self.items.insert((id.0, id.1), &item);
+
+			Ok(())
 		}
 
 		pub fn mint(
@@ -316,7 +2224,7 @@ pub mod xc_regions {
 			Ok(())
 		}
 
-		pub fn burn(&mut self, id: (CollectionId, RawRegionId)) -> Result<(), &'static str> {
+		pub fn burn_item(&mut self, id: (CollectionId, RawRegionId)) -> Result<(), &'static str> {
 			let Some(owner) = self.items.get((id.0, id.1)).map(|a| a.owner) else {
 				return Err("Item not found")
 			};
@@ -339,10 +2247,7 @@ pub mod xc_regions {
 	#[cfg(all(test, feature = "e2e-tests"))]
 	pub mod tests {
 		use super::*;
-		use crate::{
-			traits::regionmetadata_external::RegionMetadata, types::VersionedRegion,
-			REGIONS_COLLECTION_ID,
-		};
+		use crate::{traits::regionmetadata_external::RegionMetadata, types::VersionedRegion};
 		use environment::ExtendedEnvironment;
 		use ink_e2e::{subxt::dynamic::Value, MessageBuilder};
 		use openbrush::contracts::psp34::psp34_external::PSP34;
@@ -350,11 +2255,13 @@ pub mod xc_regions {
 
 		type E2EResult<T> = Result<T, Box<dyn std::error::Error>>;
 
+		const REGIONS_COLLECTION_ID: CollectionId = 42;
+
 		#[ink_e2e::test(environment = ExtendedEnvironment)]
 		async fn init_non_existing_region_fails(
 			mut client: ink_e2e::Client<C, E>,
 		) -> E2EResult<()> {
-			let constructor = XcRegionsRef::new();
+			let constructor = XcRegionsRef::new(REGIONS_COLLECTION_ID);
 			let contract_acc_id = client
 				.instantiate("xc-regions", &ink_e2e::alice(), constructor, 0, None)
 				.await
@@ -376,7 +2283,7 @@ pub mod xc_regions {
 
 		#[ink_e2e::test(environment = ExtendedEnvironment)]
 		async fn init_works(mut client: E2EBackend) -> E2EResult<()> {
-			let constructor = XcRegionsRef::new();
+			let constructor = XcRegionsRef::new(REGIONS_COLLECTION_ID);
 			let contract_acc_id = client
 				.instantiate("xc-regions", &ink_e2e::alice(), constructor, 0, None)
 				.await
@@ -451,14 +2358,14 @@ pub mod xc_regions {
 			let get_metadata_res =
 				client.call_dry_run(&ink_e2e::alice(), &get_metadata, 0, None).await;
 
-			assert_eq!(get_metadata_res.return_value(), Ok(VersionedRegion { version: 0, region }));
+			assert_eq!(get_metadata_res.return_value(), Ok(VersionedRegion::V1(region)));
 
 			Ok(())
 		}
 
 		#[ink_e2e::test(environment = ExtendedEnvironment)]
 		async fn remove_works(mut client: E2EBackend) -> E2EResult<()> {
-			let constructor = XcRegionsRef::new();
+			let constructor = XcRegionsRef::new(REGIONS_COLLECTION_ID);
 			let contract_acc_id = client
 				.instantiate("xc-regions", &ink_e2e::alice(), constructor, 0, None)
 				.await
@@ -0,0 +1,83 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Verification of Coretime chain region records through storage proofs.
+//!
+//! The region record is proven in two steps:
+//! 1. The head of the Coretime chain is proven against the relay chain state root.
+//! 2. The region record is proven against the state root contained in the Coretime chain head.
+
+use crate::types::XcRegionsError;
+use ink::prelude::vec::Vec;
+use primitives::coretime::{RegionId, RegionRecord};
+use scale::{Compact, Decode, Encode};
+use sp_core::{
+	hashing::{blake2_128, twox_128, twox_64},
+	Blake2Hasher, H256,
+};
+use sp_trie::{read_trie_value, LayoutV1, StorageProof};
+
+/// The storage key of the head of a parachain in `paras::Heads` on the relay chain.
+pub fn paras_heads_key(para_id: u32) -> Vec<u8> {
+	let para_id = para_id.encode();
+	[&twox_128(b"Paras")[..], &twox_128(b"Heads")[..], &twox_64(&para_id)[..], &para_id[..]]
+		.concat()
+}
+
+/// The storage key of a region record in `pallet-broker::Regions` on the Coretime chain.
+pub fn broker_regions_key(region_id: &RegionId) -> Vec<u8> {
+	let region_id = region_id.encode();
+	[
+		&twox_128(b"Broker")[..],
+		&twox_128(b"Regions")[..],
+		&blake2_128(&region_id)[..],
+		&region_id[..],
+	]
+	.concat()
+}
+
+/// Verifies the record of a region on the Coretime chain.
+///
+/// ## Arguments:
+/// - `relay_state_root` - The trusted relay chain state root.
+/// - `relay_proof` - The proof of the Coretime chain head against the relay chain state root.
+/// - `coretime_proof` - The proof of the region record against the Coretime chain state root.
+/// - `para_id` - The parachain id of the Coretime chain.
+/// - `region_id` - The identifier of the region.
+pub fn verify_region_record(
+	relay_state_root: [u8; 32],
+	relay_proof: Vec<Vec<u8>>,
+	coretime_proof: Vec<Vec<u8>>,
+	para_id: u32,
+	region_id: &RegionId,
+) -> Result<RegionRecord, XcRegionsError> {
+	let head = read_value(relay_state_root, relay_proof, &paras_heads_key(para_id))?;
+	// The head data is an encoded header wrapped into a `Vec<u8>`.
+	let head = Vec::<u8>::decode(&mut &head[..]).map_err(|_| XcRegionsError::InvalidStateProof)?;
+	let (_parent_hash, _number, state_root) =
+		<([u8; 32], Compact<u32>, [u8; 32])>::decode(&mut &head[..])
+			.map_err(|_| XcRegionsError::InvalidStateProof)?;
+
+	let record = read_value(state_root, coretime_proof, &broker_regions_key(region_id))?;
+	RegionRecord::decode(&mut &record[..]).map_err(|_| XcRegionsError::InvalidStateProof)
+}
+
+// Reads the value under `key` from the storage proof, ensuring the proof matches `root`.
+fn read_value(root: [u8; 32], proof: Vec<Vec<u8>>, key: &[u8]) -> Result<Vec<u8>, XcRegionsError> {
+	let db = StorageProof::new(proof).into_memory_db::<Blake2Hasher>();
+	read_trie_value::<LayoutV1<Blake2Hasher>, _>(&db, &H256(root), key, None, None)
+		.map_err(|_| XcRegionsError::InvalidStateProof)?
+		.ok_or(XcRegionsError::InvalidStateProof)
+}
@@ -14,29 +14,52 @@
 // along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+	proof::{broker_regions_key, paras_heads_key},
 	traits::RegionMetadata,
-	types::{VersionedRegion, XcRegionsError},
-	xc_regions::{RegionInitialized, RegionRemoved, XcRegions},
-	REGIONS_COLLECTION_ID,
+	types::{Provenance, RegionStatus, RegionsBackend, VersionedRegion, XcRegionsError},
+	xc_regions::{
+		Approval, MetadataVersionBumped, NoteUpdated, RegionAttested, RegionInitialized,
+		RegionRemoved, RegionReturned, RegionWithdrawn, Transfer, XcRegions,
+	},
+	COLLECTION_NAME, COLLECTION_SYMBOL, CORETIME_CHAIN_PARA_ID, MAX_LABELS, MAX_LABEL_LEN,
+	MAX_NOTE_LEN, STORAGE_VERSION,
 };
 use ink::env::{
-	test::{default_accounts, set_caller, DefaultAccounts},
+	hash::Blake2x256,
+	test::{
+		default_accounts, get_account_balance, set_account_balance, set_caller,
+		set_value_transferred, DefaultAccounts,
+	},
 	DefaultEnvironment,
 };
-use openbrush::contracts::psp34::{Id, PSP34};
+use openbrush::{
+	contracts::psp34::{
+		extensions::{
+			burnable::PSP34Burnable, enumerable::PSP34Enumerable, metadata::PSP34Metadata,
+		},
+		Id, PSP34Error, PSP34,
+	},
+	traits::{AccountId, String},
+};
 use primitives::{
 	assert_ok,
-	coretime::{RawRegionId, Region},
-	uniques::{CollectionId, ItemDetails},
-	Version,
+	coretime::{CoreMask, RawRegionId, Region, RegionId, RegionRecord, TIMESLICE_PERIOD},
+	uniques::{CollectionId, ItemDetails, UniquesCall},
+	PalletCall, RuntimeCall, Version, UNIQUES_PALLET_INDEX,
 };
+use scale::{Compact, Encode};
+use secp256k1::{Message, PublicKey, SecretKey, SECP256K1};
+use sp_core::Blake2Hasher;
+use sp_trie::{LayoutV1, MemoryDB, TrieDBMutBuilder, TrieMut};
 
 type Event = <XcRegions as ::ink::reflect::ContractEventBase>::Type;
 
+const REGIONS_COLLECTION_ID: CollectionId = 42;
+
 #[ink::test]
 fn mock_environment_helper_functions_work() {
 	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
-	let mut xc_regions = XcRegions::new();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
 
 	let region_id_0 = region_id(0);
 
@@ -67,21 +90,134 @@ fn mock_environment_helper_functions_work() {
 	let region_id_1 = region_id(1);
 	assert_ok!(xc_regions.mint(region_id_1, charlie));
 
-	assert_ok!(xc_regions.burn(region_id_0));
+	assert_ok!(xc_regions.burn_item(region_id_0));
 	assert!(xc_regions.items.get(region_id_0).is_none());
 	assert_eq!(xc_regions.account.get(charlie), Some(vec![region_id_1]));
 
-	assert_ok!(xc_regions.burn(region_id_1));
+	assert_ok!(xc_regions.burn_item(region_id_1));
 	assert!(xc_regions.items.get(region_id_1).is_none());
 	assert!(xc_regions.account.get(charlie).is_none());
 
-	assert!(xc_regions.burn(region_id_1).is_err());
+	assert!(xc_regions.burn_item(region_id_1).is_err());
+}
+
+#[ink::test]
+fn collection_id_is_configurable() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(7);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.regions_collection_id(), 7);
+	assert_eq!(xc_regions.collection_id(), Id::U32(7));
+
+	// Only regions from the configured collection can be initialized:
+	assert_ok!(xc_regions.mint((REGIONS_COLLECTION_ID, 0), charlie));
+	assert_eq!(
+		xc_regions.init(Id::U128(0), Region::default()),
+		Err(XcRegionsError::CannotInitialize)
+	);
+
+	let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+	assert_ok!(xc_regions.mint((7, 0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(xc_regions.items.get((7, 0)).map(|item| item.owner), Some(contract));
+}
+
+#[ink::test]
+fn collection_id_can_be_resolved_from_runtime() {
+	let xc_regions = XcRegions::new_from_runtime(RegionsBackend::Nfts).unwrap();
+	assert_eq!(xc_regions.regions_collection_id(), XcRegions::RUNTIME_COLLECTION_ID);
+	assert_eq!(xc_regions.backend(), RegionsBackend::Nfts);
+	assert_eq!(
+		xc_regions.get_attribute(xc_regions.collection_id(), String::from("name")),
+		Some(String::from(COLLECTION_NAME))
+	);
+}
+
+#[ink::test]
+fn backend_is_configurable() {
+	assert_eq!(XcRegions::new(REGIONS_COLLECTION_ID).backend(), RegionsBackend::Uniques);
+	assert_eq!(
+		XcRegions::new_with_backend(REGIONS_COLLECTION_ID, RegionsBackend::Nfts).backend(),
+		RegionsBackend::Nfts
+	);
+}
+
+#[ink::test]
+fn migrate_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	assert_eq!(xc_regions.admin(), alice);
+	assert_eq!(xc_regions.storage_version(), STORAGE_VERSION);
+	// A freshly deployed contract doesn't require any migrations.
+	assert_eq!(xc_regions.migrate(), Err(XcRegionsError::AlreadyMigrated));
+
+	xc_regions.storage_version = 0;
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(xc_regions.migrate(), Err(XcRegionsError::NotAllowed));
+	assert_eq!(xc_regions.upgrade([0u8; 32].into()), Err(XcRegionsError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(xc_regions.migrate());
+	assert_eq!(xc_regions.storage_version(), STORAGE_VERSION);
+
+	// Only the admin can transfer the admin role:
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(xc_regions.set_admin(bob), Err(XcRegionsError::NotAllowed));
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(xc_regions.set_admin(bob));
+	assert_eq!(xc_regions.admin(), bob);
+}
+
+#[ink::test]
+fn pausing_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, django, .. } =
+		get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.mint(region_id(1), charlie));
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+
+	// Only the admin can assign the guardian:
+	assert_eq!(xc_regions.set_guardian(Some(bob)), Err(XcRegionsError::NotAllowed));
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(xc_regions.set_guardian(Some(bob)));
+	assert_eq!(xc_regions.guardian(), Some(bob));
+
+	// Only the guardian or the admin can pause:
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_eq!(xc_regions.set_paused(true), Err(XcRegionsError::NotAllowed));
+	set_caller::<DefaultEnvironment>(bob);
+	assert_ok!(xc_regions.set_paused(true));
+	assert!(xc_regions.paused());
+
+	set_caller::<DefaultEnvironment>(charlie);
+	let region_1 = Region { mask: 1.into(), ..Default::default() };
+	assert_eq!(
+		xc_regions.init(Id::U128(1), region_1.clone()),
+		Err(XcRegionsError::InitializationPaused)
+	);
+
+	// Transfers and removals still work while paused:
+	assert_ok!(xc_regions.transfer(django, Id::U128(0), vec![]));
+	set_caller::<DefaultEnvironment>(django);
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(xc_regions.set_paused(false));
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.init(Id::U128(1), region_1));
 }
 
 #[ink::test]
 fn init_works() {
 	let DefaultAccounts::<DefaultEnvironment> { charlie, bob, .. } = get_default_accounts();
-	let mut xc_regions = XcRegions::new();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
 	let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
 
 	// 1. Cannot initialize a region that doesn't exist:
@@ -130,10 +266,448 @@ fn init_works() {
 	);
 }
 
+#[ink::test]
+fn init_works_for_interlaced_regions() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	// The two regions resulting from interlacing a region with a complete mask:
+	let first_half = RegionId { begin: 0, core: 0, mask: CoreMask::from_chunk(0, 40) };
+	let second_half = RegionId { begin: 0, core: 0, mask: CoreMask::from_chunk(40, 80) };
+	let first_raw = RawRegionId::from(first_half.clone());
+	let second_raw = RawRegionId::from(second_half.clone());
+
+	assert_ok!(xc_regions.mint(region_id(first_raw), charlie));
+	assert_ok!(xc_regions.mint(region_id(second_raw), charlie));
+
+	// 1. The complete mask of the region before interlacing is not valid metadata:
+	let complete = Region { begin: 0, end: 10, core: 0, mask: CoreMask::complete() };
+	assert_eq!(
+		xc_regions.init(Id::U128(first_raw), complete),
+		Err(XcRegionsError::InvalidMetadata)
+	);
+
+	// 2. Neither is a mask that is only partially covered by the region id:
+	let partially_covered =
+		Region { begin: 0, end: 10, core: 0, mask: CoreMask::from_chunk(30, 50) };
+	assert_eq!(
+		xc_regions.init(Id::U128(second_raw), partially_covered),
+		Err(XcRegionsError::InvalidMetadata)
+	);
+
+	// 3. The mask encoded in the region id is valid:
+	let first = Region { begin: 0, end: 10, core: 0, mask: first_half.mask };
+	assert_ok!(xc_regions.init(Id::U128(first_raw), first.clone()));
+	assert_eq!(xc_regions.regions.get(first_raw), Some(first));
+
+	// 4. So is a subset of it:
+	let subset = Region { begin: 0, end: 10, core: 0, mask: CoreMask::from_chunk(40, 60) };
+	assert_ok!(xc_regions.init(Id::U128(second_raw), subset.clone()));
+	assert_eq!(xc_regions.regions.get(second_raw), Some(subset));
+}
+
+#[ink::test]
+fn init_removes_stale_xc_region() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, django, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_ok!(xc_regions.set_label(Id::U128(0), String::from("team"), Some(String::new())));
+	assert_ok!(xc_regions.approve(django, Some(Id::U128(0)), true));
+	set_caller::<DefaultEnvironment>(django);
+	assert_ok!(xc_regions.lock(Id::U128(0)));
+
+	// The region leaves the chain and comes back to a different owner:
+	assert_ok!(xc_regions.burn_item(region_id(0)));
+	assert_ok!(xc_regions.mint(region_id(0), django));
+
+	set_caller::<DefaultEnvironment>(django);
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	let len = emitted_events.len();
+	assert_removed_event(&emitted_events[len - 4], 0);
+	assert_transfer_event(&emitted_events[len - 3], None, Some(django), 0);
+	assert_version_bumped_event(&emitted_events[len - 2], 0, 0, 1);
+	assert_init_event(&emitted_events[len - 1], 0, Region::default(), 1);
+
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), Some(django));
+	assert_eq!(xc_regions.balance_of(charlie), 0);
+	assert_eq!(xc_regions.metadata_version(Id::U128(0)), Ok(1));
+	assert_eq!(xc_regions.locked_by(Id::U128(0)), None);
+	assert!(xc_regions.label_keys(Id::U128(0)).is_empty());
+	assert_eq!(xc_regions.wrapped_region_count(), 1);
+}
+
+#[ink::test]
+fn expired_regions_can_be_rejected() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(charlie);
+
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	assert!(!xc_regions.reject_expired());
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+
+	let mut xc_regions = XcRegions::new_with_options(
+		REGIONS_COLLECTION_ID,
+		RegionsBackend::Uniques,
+		None,
+		true,
+		false,
+	);
+	assert!(xc_regions.reject_expired());
+	assert_ok!(xc_regions.mint(region_id(1), charlie));
+
+	advance_n_blocks(TIMESLICE_PERIOD);
+	let region = Region { end: 1, mask: 1.into(), ..Default::default() };
+	assert_eq!(xc_regions.init(Id::U128(1), region.clone()), Err(XcRegionsError::RegionExpired));
+	assert_ok!(xc_regions.init(Id::U128(1), Region { end: 2, ..region }));
+}
+
+#[ink::test]
+fn init_deposit_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, django, .. } =
+		get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	assert_eq!(xc_regions.init_deposit(), 0);
+
+	// Only the admin can update the deposit:
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(xc_regions.set_init_deposit(100), Err(XcRegionsError::NotAllowed));
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(xc_regions.set_init_deposit(100));
+	assert_eq!(xc_regions.init_deposit(), 100);
+
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_eq!(
+		xc_regions.init(Id::U128(0), Region::default()),
+		Err(XcRegionsError::MissingDeposit)
+	);
+	set_value_transferred::<DefaultEnvironment>(100);
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(xc_regions.deposits.get(0), Some((charlie, 100)));
+
+	// The deposit is returned to the depositor once the region is unwrapped, even if the
+	// xcRegion changed hands:
+	set_value_transferred::<DefaultEnvironment>(0);
+	assert_ok!(xc_regions.transfer(django, Id::U128(0), vec![]));
+
+	let contract = ink::env::account_id::<DefaultEnvironment>();
+	set_account_balance::<DefaultEnvironment>(contract, 100);
+	let charlie_balance = get_account_balance::<DefaultEnvironment>(charlie).unwrap();
+
+	set_caller::<DefaultEnvironment>(django);
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+	assert_eq!(xc_regions.deposits.get(0), None);
+	assert_eq!(get_account_balance::<DefaultEnvironment>(charlie), Ok(charlie_balance + 100));
+	assert_eq!(get_account_balance::<DefaultEnvironment>(contract), Ok(0));
+}
+
+#[ink::test]
+fn uniques_pallet_index_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	assert_eq!(xc_regions.uniques_pallet_index(), UNIQUES_PALLET_INDEX);
+
+	// Only the admin can update the pallet index:
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(xc_regions.set_uniques_pallet_index(12), Err(XcRegionsError::NotAllowed));
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(xc_regions.set_uniques_pallet_index(12));
+	assert_eq!(xc_regions.uniques_pallet_index(), 12);
+
+	// The call is encoded the same way as the statically indexed `RuntimeCall`:
+	let call = || UniquesCall::Transfer { collection: 0, item: 0, dest: charlie.into() };
+	assert_eq!(
+		PalletCall { pallet_index: UNIQUES_PALLET_INDEX, call: call() }.encode(),
+		RuntimeCall::Uniques(call()).encode()
+	);
+	assert_eq!(PalletCall { pallet_index: 12, call: call() }.encode()[0], 12);
+}
+
+#[ink::test]
+fn init_if_approved_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert!(!xc_regions.contract_approved(Id::U128(0)));
+	assert!(!xc_regions.contract_approved(Id::U8(0)));
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_eq!(
+		xc_regions.init_if_approved(Id::U128(0), Region::default()),
+		Err(XcRegionsError::ContractNotApproved)
+	);
+
+	let contract = ink::env::account_id::<DefaultEnvironment>();
+	assert_ok!(xc_regions.approve_transfer(region_id(0), contract));
+	assert!(xc_regions.contract_approved(Id::U128(0)));
+	assert_ok!(xc_regions.init_if_approved(Id::U128(0), Region::default()));
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), Some(charlie));
+}
+
+#[ink::test]
+fn operator_init_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, django, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+
+	// Bob cannot initialize the region without being approved:
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(
+		xc_regions.init(Id::U128(0), Region::default()),
+		Err(XcRegionsError::CannotInitialize)
+	);
+
+	// Only the approved account can initialize on behalf of the owner:
+	assert_ok!(xc_regions.approve_transfer(region_id(0), bob));
+	set_caller::<DefaultEnvironment>(django);
+	assert_eq!(
+		xc_regions.init(Id::U128(0), Region::default()),
+		Err(XcRegionsError::CannotInitialize)
+	);
+
+	set_caller::<DefaultEnvironment>(bob);
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+
+	// The region gets transferred to the contract and the xcRegion is minted to the owner:
+	assert_eq!(xc_regions._uniques_owner(0), Some(contract));
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), Some(charlie));
+	assert_eq!(xc_regions.balance_of(bob), 0);
+
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_init_event(&emitted_events.last().unwrap(), 0, Region::default(), 0);
+}
+
+#[ink::test]
+fn attestor_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	let mut xc_regions =
+		XcRegions::new_with_attestor(REGIONS_COLLECTION_ID, RegionsBackend::Uniques, Some(alice));
+	assert_eq!(xc_regions.attestor(), Some(alice));
+
+	let region = Region { begin: 0, end: 2, core: 0, mask: Default::default() };
+
+	// Regions initialized by the attestor are trusted:
+	assert_ok!(xc_regions.mint(region_id(0), alice));
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(xc_regions.init(Id::U128(0), region.clone()));
+	assert_eq!(xc_regions.get_metadata(Id::U128(0)), Ok(VersionedRegion::V1(region.clone())));
+	assert!(xc_regions.is_trusted(Id::U128(0)));
+
+	// Regions initialized by anyone else aren't:
+	let raw_region_id = 1;
+	let region_1 = Region { begin: 0, end: 2, core: 0, mask: 1.into() };
+	assert_ok!(xc_regions.mint(region_id(raw_region_id), charlie));
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.init(Id::U128(raw_region_id), Region { end: 1, ..region_1.clone() }));
+	assert!(!xc_regions.is_trusted(Id::U128(raw_region_id)));
+
+	// Only the attestor can attest metadata:
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(
+		xc_regions.attest(Id::U128(raw_region_id), region_1.clone()),
+		Err(XcRegionsError::NotAttestor)
+	);
+
+	set_caller::<DefaultEnvironment>(alice);
+	// Cannot attest a region that isn't initialized:
+	assert_eq!(
+		xc_regions.attest(Id::U128(2), Region::default()),
+		Err(XcRegionsError::MetadataNotFound)
+	);
+	// Cannot attest metadata that doesn't match the region id:
+	assert_eq!(
+		xc_regions.attest(Id::U128(raw_region_id), region.clone()),
+		Err(XcRegionsError::InvalidMetadata)
+	);
+
+	// The attestor corrects the metadata:
+	assert_ok!(xc_regions.attest(Id::U128(raw_region_id), region_1.clone()));
+	assert_eq!(
+		xc_regions.get_metadata(Id::U128(raw_region_id)),
+		Ok(VersionedRegion::V1(region_1.clone()))
+	);
+	assert_eq!(xc_regions.metadata_version(Id::U128(raw_region_id)), Ok(1));
+	assert!(xc_regions.is_trusted(Id::U128(raw_region_id)));
+
+	// Co-signing the metadata doesn't change the version:
+	assert_ok!(xc_regions.attest(Id::U128(raw_region_id), region_1.clone()));
+	assert_eq!(xc_regions.metadata_version(Id::U128(raw_region_id)), Ok(1));
+
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_attested_event(&emitted_events.last().unwrap(), raw_region_id, region_1.clone(), 1);
+
+	// The region is no longer trusted once it is re-initialized by anyone else:
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.remove(Id::U128(raw_region_id)));
+	assert_ok!(xc_regions.init(Id::U128(raw_region_id), region_1));
+	assert!(!xc_regions.is_trusted(Id::U128(raw_region_id)));
+}
+
+#[ink::test]
+fn init_with_proof_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, charlie, .. } = get_default_accounts();
+	let mut xc_regions =
+		XcRegions::new_with_attestor(REGIONS_COLLECTION_ID, RegionsBackend::Uniques, Some(alice));
+
+	let oracle = SecretKey::from_slice(&[1u8; 32]).unwrap();
+	let impostor = SecretKey::from_slice(&[2u8; 32]).unwrap();
+	let sign = |hash: [u8; 32], key: &SecretKey| {
+		let message = Message::from_slice(&hash).unwrap();
+		let (recovery_id, compact) =
+			SECP256K1.sign_ecdsa_recoverable(&message, key).serialize_compact();
+		let mut signature = [0u8; 65];
+		signature[..64].copy_from_slice(&compact);
+		signature[64] = recovery_id.to_i32() as u8;
+		signature
+	};
+
+	let region = Region { begin: 0, end: 2, core: 0, mask: Default::default() };
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	let hash = xc_regions.metadata_proof_hash(Id::U128(0), region.clone()).unwrap();
+
+	// Doesn't work without an oracle key being set:
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_eq!(
+		xc_regions.init_with_proof(Id::U128(0), region.clone(), sign(hash, &oracle)),
+		Err(XcRegionsError::InvalidProof)
+	);
+
+	// Only the attestor can set the oracle key:
+	let oracle_key = PublicKey::from_secret_key(SECP256K1, &oracle).serialize();
+	assert_eq!(xc_regions.set_oracle_key(Some(oracle_key)), Err(XcRegionsError::NotAttestor));
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(xc_regions.set_oracle_key(Some(oracle_key)));
+	assert_eq!(xc_regions.oracle_key(), Some(oracle_key));
+
+	set_caller::<DefaultEnvironment>(charlie);
+	// The proof must be signed by the oracle:
+	assert_eq!(
+		xc_regions.init_with_proof(Id::U128(0), region.clone(), sign(hash, &impostor)),
+		Err(XcRegionsError::InvalidProof)
+	);
+	// The proof must match the provided metadata:
+	assert_eq!(
+		xc_regions.init_with_proof(
+			Id::U128(0),
+			Region { end: 3, ..region.clone() },
+			sign(hash, &oracle)
+		),
+		Err(XcRegionsError::InvalidProof)
+	);
+
+	assert_ok!(xc_regions.init_with_proof(Id::U128(0), region.clone(), sign(hash, &oracle)));
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), Some(charlie));
+	assert_eq!(xc_regions.get_metadata(Id::U128(0)), Ok(VersionedRegion::V1(region.clone())));
+	assert!(xc_regions.is_trusted(Id::U128(0)));
+
+	// The proof cannot be replayed once the region gets re-initialized:
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+	assert_eq!(
+		xc_regions.init_with_proof(Id::U128(0), region, sign(hash, &oracle)),
+		Err(XcRegionsError::InvalidProof)
+	);
+}
+
+#[ink::test]
+fn init_with_state_proof_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	let raw_region_id = 1u128 << 96 | 0xff;
+	let region = Region { begin: 1, end: 3, core: 0, mask: 0xff.into() };
+	let record = RegionRecord { end: 3, owner: charlie, paid: None };
+
+	// The region record is stored on the Coretime chain:
+	let (coretime_root, coretime_proof) = build_trie(vec![
+		(broker_regions_key(&RegionId::from(raw_region_id)), record.encode()),
+		(broker_regions_key(&RegionId::from(0)), record.encode()),
+	]);
+	// The Coretime chain head is stored on the relay chain:
+	let header = ([0u8; 32], Compact(1u32), coretime_root, [0u8; 32], Vec::<u8>::new()).encode();
+	let (relay_root, relay_proof) = build_trie(vec![
+		(paras_heads_key(CORETIME_CHAIN_PARA_ID), header.encode()),
+		(paras_heads_key(1000), Vec::<u8>::new().encode()),
+	]);
+
+	assert_ok!(xc_regions.mint(region_id(raw_region_id), charlie));
+
+	// Fails when the proofs aren't anchored in the relay chain state root:
+	assert_eq!(
+		xc_regions.init_with_state_proof(
+			Id::U128(raw_region_id),
+			region.clone(),
+			relay_proof.clone(),
+			coretime_proof.clone()
+		),
+		Err(XcRegionsError::InvalidStateProof)
+	);
+	xc_regions.relay_state_root = relay_root;
+
+	// Fails when the region record isn't part of the proof:
+	let (_, unrelated_proof) =
+		build_trie(vec![(broker_regions_key(&RegionId::from(0)), record.encode())]);
+	assert_eq!(
+		xc_regions.init_with_state_proof(
+			Id::U128(raw_region_id),
+			region.clone(),
+			relay_proof.clone(),
+			unrelated_proof
+		),
+		Err(XcRegionsError::InvalidStateProof)
+	);
+
+	// Fails when the metadata doesn't match the region record:
+	assert_eq!(
+		xc_regions.init_with_state_proof(
+			Id::U128(raw_region_id),
+			Region { end: 4, ..region.clone() },
+			relay_proof.clone(),
+			coretime_proof.clone()
+		),
+		Err(XcRegionsError::InvalidMetadata)
+	);
+
+	// Fails when the mask is only a subset of the proven one:
+	assert_eq!(
+		xc_regions.init_with_state_proof(
+			Id::U128(raw_region_id),
+			Region { mask: 0x0f.into(), ..region.clone() },
+			relay_proof.clone(),
+			coretime_proof.clone()
+		),
+		Err(XcRegionsError::InvalidMetadata)
+	);
+
+	assert_ok!(xc_regions.init_with_state_proof(
+		Id::U128(raw_region_id),
+		region.clone(),
+		relay_proof,
+		coretime_proof
+	));
+	assert_eq!(xc_regions.owner_of(Id::U128(raw_region_id)), Some(charlie));
+	assert_eq!(xc_regions.get_metadata(Id::U128(raw_region_id)), Ok(VersionedRegion::V1(region)));
+	assert!(xc_regions.is_trusted(Id::U128(raw_region_id)));
+}
+
 #[ink::test]
 fn remove_works() {
 	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
-	let mut xc_regions = XcRegions::new();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
 	set_caller::<DefaultEnvironment>(charlie);
 
 	let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
@@ -178,47 +752,940 @@ fn remove_works() {
 }
 
 #[ink::test]
-fn get_metadata_works() {
-	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
-	let mut xc_regions = XcRegions::new();
+fn withdraw_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
 	set_caller::<DefaultEnvironment>(charlie);
 
-	// Cannot get the metadata of a region that doesn't exist:
-	assert_eq!(xc_regions.get_metadata(Id::U128(0)), Err(XcRegionsError::MetadataNotFound));
+	// Cannot withdraw a region that isn't wrapped.
+	assert_eq!(xc_regions.withdraw(Id::U128(0)), Err(XcRegionsError::CannotWithdraw));
+	assert_eq!(xc_regions.withdraw(Id::U32(0)), Err(XcRegionsError::InvalidRegionId));
 
-	// Minting a region without initializing it.
 	assert_ok!(xc_regions.mint(region_id(0), charlie));
-	assert_eq!(xc_regions.get_metadata(Id::U128(0)), Err(XcRegionsError::MetadataNotFound));
-
 	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
-	assert_eq!(
-		xc_regions.get_metadata(Id::U128(0)),
-		Ok(VersionedRegion { version: 0, region: Region::default() })
-	);
-}
 
-#[ink::test]
-fn metadata_version_gets_updated() {
-	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
-	let mut xc_regions = XcRegions::new();
+	// Only the holder of the xcRegion can withdraw it:
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(xc_regions.withdraw(Id::U128(0)), Err(XcRegionsError::CannotWithdraw));
+
+	// The holder changes once the xcRegion gets transferred:
 	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.transfer(bob, Id::U128(0), Default::default()));
+	assert_eq!(xc_regions.withdraw(Id::U128(0)), Err(XcRegionsError::CannotWithdraw));
 
-	assert_ok!(xc_regions.mint(region_id(0), charlie));
-	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
-	assert_eq!(
-		xc_regions.get_metadata(Id::U128(0)),
-		Ok(VersionedRegion { version: 0, region: Region::default() })
-	);
+	set_caller::<DefaultEnvironment>(bob);
+	assert_ok!(xc_regions.withdraw(Id::U128(0)));
 
-	assert_ok!(xc_regions.remove(Id::U128(0)));
+	// Bob receives the underlying region and the wrapped region gets burned.
+	assert_eq!(xc_regions._uniques_owner(0), Some(bob));
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), None);
+	assert_eq!(xc_regions.balance_of(bob), 0);
 
-	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
-	assert_eq!(
-		xc_regions.get_metadata(Id::U128(0)),
-		Ok(VersionedRegion { version: 1, region: Region::default() })
+	assert_eq!(xc_regions.regions.get(0), None);
+	assert_eq!(xc_regions.metadata_versions.get(0), Some(0));
+
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_withdrawn_event(&emitted_events.last().unwrap(), 0, bob);
+}
+
+#[ink::test]
+fn return_to_coretime_chain_works() {
+	let DefaultAccounts::<DefaultEnvironment> { alice, bob, charlie, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(alice);
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	assert_eq!(xc_regions.max_return_fee(), 0);
+
+	// Only the admin can update the maximum fee:
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_eq!(xc_regions.set_max_return_fee(100), Err(XcRegionsError::NotAllowed));
+	set_caller::<DefaultEnvironment>(alice);
+	assert_ok!(xc_regions.set_max_return_fee(100));
+	assert_eq!(xc_regions.max_return_fee(), 100);
+
+	set_caller::<DefaultEnvironment>(charlie);
+
+	// Cannot return a region that isn't wrapped.
+	assert_eq!(
+		xc_regions.return_to_coretime_chain(Id::U128(0), charlie, 0),
+		Err(XcRegionsError::CannotWithdraw)
+	);
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+
+	// Only the holder of the xcRegion can return it:
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(
+		xc_regions.return_to_coretime_chain(Id::U128(0), bob, 0),
+		Err(XcRegionsError::CannotWithdraw)
+	);
+
+	set_caller::<DefaultEnvironment>(charlie);
+	// The fee paid by the contract is capped:
+	assert_eq!(
+		xc_regions.return_to_coretime_chain(Id::U128(0), bob, 101),
+		Err(XcRegionsError::FeeTooHigh)
+	);
+	assert_ok!(xc_regions.return_to_coretime_chain(Id::U128(0), bob, 100));
+
+	// The region leaves this chain and the wrapped region gets burned.
+	assert!(!xc_regions._uniques_exists(0));
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), None);
+	assert_eq!(xc_regions.balance_of(charlie), 0);
+
+	assert_eq!(xc_regions.regions.get(0), None);
+	assert_eq!(xc_regions.metadata_versions.get(0), Some(0));
+
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_returned_event(&emitted_events.last().unwrap(), 0, bob);
+}
+
+#[ink::test]
+fn locking_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, django, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.lock(Id::U128(0)), Err(XcRegionsError::CannotLock));
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+
+	// Only approved accounts can lock the region:
+	set_caller::<DefaultEnvironment>(bob);
+	assert_eq!(xc_regions.lock(Id::U128(0)), Err(XcRegionsError::CannotLock));
+
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.approve(bob, Some(Id::U128(0)), true));
+	set_caller::<DefaultEnvironment>(bob);
+	assert_ok!(xc_regions.lock(Id::U128(0)));
+	assert_eq!(xc_regions.locked_by(Id::U128(0)), Some(bob));
+
+	// Cannot lock a region that is already locked:
+	assert_eq!(xc_regions.lock(Id::U128(0)), Err(XcRegionsError::CannotLock));
+
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_locked_event(&emitted_events.last().unwrap(), 0, bob);
+
+	// The locked region cannot be transferred nor unwrapped:
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_eq!(
+		xc_regions.transfer(django, Id::U128(0), Default::default()),
+		Err(PSP34Error::Custom(String::from("RegionLocked")))
+	);
+	assert_eq!(xc_regions.withdraw(Id::U128(0)), Err(XcRegionsError::RegionLocked));
+	assert_eq!(xc_regions.remove(Id::U128(0)), Err(XcRegionsError::RegionLocked));
+	assert_eq!(
+		xc_regions.return_to_coretime_chain(Id::U128(0), charlie, 0),
+		Err(XcRegionsError::RegionLocked)
+	);
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), Some(charlie));
+
+	// Only the locker can unlock the region:
+	assert_eq!(xc_regions.unlock(Id::U128(0)), Err(XcRegionsError::CannotUnlock));
+	set_caller::<DefaultEnvironment>(bob);
+	assert_ok!(xc_regions.unlock(Id::U128(0)));
+	assert_eq!(xc_regions.locked_by(Id::U128(0)), None);
+
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.transfer(django, Id::U128(0), Default::default()));
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), Some(django));
+}
+
+#[ink::test]
+fn user_role_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, django, eve, .. } =
+		get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.set_user(Id::U128(0), Some(bob), 1), Err(XcRegionsError::NotAllowed));
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+
+	// Only the owner or an approved account can set the user:
+	set_caller::<DefaultEnvironment>(django);
+	assert_eq!(xc_regions.set_user(Id::U128(0), Some(bob), 1), Err(XcRegionsError::NotAllowed));
+
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.set_user(Id::U128(0), Some(bob), 1));
+	assert_eq!(xc_regions.user_of(Id::U128(0)), Some(bob));
+	assert_eq!(xc_regions.user_expires(Id::U128(0)), Some(1));
+
+	assert_ok!(xc_regions.approve(django, Some(Id::U128(0)), true));
+	set_caller::<DefaultEnvironment>(django);
+	assert_ok!(xc_regions.set_user(Id::U128(0), Some(eve), 2));
+	assert_eq!(xc_regions.user_of(Id::U128(0)), Some(eve));
+
+	// The user role expires:
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(xc_regions.user_of(Id::U128(0)), Some(eve));
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(xc_regions.user_of(Id::U128(0)), None);
+	assert_eq!(xc_regions.user_expires(Id::U128(0)), Some(2));
+
+	// The user can be removed:
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.set_user(Id::U128(0), Some(bob), 5));
+	assert_ok!(xc_regions.set_user(Id::U128(0), None, 0));
+	assert_eq!(xc_regions.user_of(Id::U128(0)), None);
+	assert_eq!(xc_regions.user_expires(Id::U128(0)), None);
+
+	// The user gets reset once the region changes hands:
+	assert_ok!(xc_regions.set_user(Id::U128(0), Some(bob), 5));
+	assert_ok!(xc_regions.transfer(django, Id::U128(0), Default::default()));
+	assert_eq!(xc_regions.user_of(Id::U128(0)), None);
+	assert_eq!(xc_regions.user_expires(Id::U128(0)), None);
+}
+
+#[ink::test]
+fn burn_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, django, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.burn(charlie, Id::U128(0)), Err(PSP34Error::TokenNotExists));
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+
+	// The account must be the owner:
+	assert_eq!(xc_regions.burn(bob, Id::U128(0)), Err(PSP34Error::NotApproved));
+
+	// Only the owner or an approved account can burn the xcRegion:
+	set_caller::<DefaultEnvironment>(django);
+	assert_eq!(xc_regions.burn(charlie, Id::U128(0)), Err(PSP34Error::NotApproved));
+
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.approve(django, Some(Id::U128(0)), true));
+
+	// Locked regions cannot be burned:
+	set_caller::<DefaultEnvironment>(django);
+	assert_ok!(xc_regions.lock(Id::U128(0)));
+	assert_eq!(
+		xc_regions.burn(charlie, Id::U128(0)),
+		Err(PSP34Error::Custom(String::from("RegionLocked")))
+	);
+	assert_ok!(xc_regions.unlock(Id::U128(0)));
+
+	assert_ok!(xc_regions.burn(charlie, Id::U128(0)));
+
+	// The underlying region gets transferred back to the owner:
+	assert_eq!(xc_regions._uniques_owner(0), Some(charlie));
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), None);
+	assert_eq!(xc_regions.balance_of(charlie), 0);
+
+	assert_eq!(xc_regions.regions.get(0), None);
+	assert_eq!(xc_regions.metadata_versions.get(0), Some(0));
+
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_withdrawn_event(&emitted_events.last().unwrap(), 0, charlie);
+
+	// The region can be initialized again with an incremented version:
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(xc_regions.metadata_version(Id::U128(0)), Ok(1));
+}
+
+#[ink::test]
+fn receive_hook_opt_out_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+
+	assert!(xc_regions.receive_hook_enabled(bob));
+
+	set_caller::<DefaultEnvironment>(bob);
+	xc_regions.set_receive_hook(false);
+	assert!(!xc_regions.receive_hook_enabled(bob));
+	assert!(xc_regions.receive_hook_enabled(charlie));
+
+	xc_regions.set_receive_hook(true);
+	assert!(xc_regions.receive_hook_enabled(bob));
+
+	// Transfers to accounts which aren't contracts don't invoke the callback:
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_ok!(xc_regions.transfer(bob, Id::U128(0), vec![1, 2, 3]));
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), Some(bob));
+
+	assert_eq!(
+		xc_regions.transfer(charlie, Id::U128(1), Default::default()),
+		Err(PSP34Error::TokenNotExists)
+	);
+}
+
+#[ink::test]
+fn get_metadata_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	// Cannot get the metadata of a region that doesn't exist:
+	assert_eq!(xc_regions.get_metadata(Id::U128(0)), Err(XcRegionsError::MetadataNotFound));
+
+	// Minting a region without initializing it.
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_eq!(xc_regions.get_metadata(Id::U128(0)), Err(XcRegionsError::MetadataNotFound));
+
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(xc_regions.get_metadata(Id::U128(0)), Ok(VersionedRegion::V1(Region::default())));
+}
+
+#[ink::test]
+fn metadata_hash_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.metadata_hash(Id::U128(0)), Err(XcRegionsError::MetadataNotFound));
+	assert_eq!(xc_regions.metadata_hash(Id::U32(0)), Err(XcRegionsError::InvalidRegionId));
+
+	let region = Region { begin: 0, end: 4, core: 0, mask: Default::default() };
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), region.clone()));
+	assert_eq!(xc_regions.metadata_hash(Id::U128(0)), Ok(hash_metadata(&region)));
+
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_init_event(&emitted_events.last().unwrap(), 0, region, 0);
+
+	// The hash is removed together with the metadata:
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+	assert_eq!(xc_regions.metadata_hash(Id::U128(0)), Err(XcRegionsError::MetadataNotFound));
+}
+
+#[ink::test]
+fn psp34_metadata_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	let attribute =
+		|xc_regions: &XcRegions, id: Id, key: &str| xc_regions.get_attribute(id, String::from(key));
+
+	// The collection attributes are set on construction:
+	let collection_id = Id::U32(REGIONS_COLLECTION_ID);
+	assert_eq!(attribute(&xc_regions, collection_id.clone(), "name"), Some(COLLECTION_NAME.into()));
+	assert_eq!(attribute(&xc_regions, collection_id, "symbol"), Some(COLLECTION_SYMBOL.into()));
+
+	// An uninitialized region doesn't have any attributes:
+	let raw_region_id = 1u128 << 96 | 0xf0;
+	assert_eq!(attribute(&xc_regions, Id::U128(raw_region_id), "begin"), None);
+
+	let region = Region { begin: 1, end: 3, core: 0, mask: 0xf0.into() };
+	assert_ok!(xc_regions.mint(region_id(raw_region_id), charlie));
+	assert_ok!(xc_regions.init(Id::U128(raw_region_id), region));
+
+	assert_eq!(attribute(&xc_regions, Id::U128(raw_region_id), "begin"), Some("1".into()));
+	assert_eq!(attribute(&xc_regions, Id::U128(raw_region_id), "end"), Some("3".into()));
+	assert_eq!(attribute(&xc_regions, Id::U128(raw_region_id), "core"), Some("0".into()));
+	assert_eq!(attribute(&xc_regions, Id::U128(raw_region_id), "mask"), Some("0xf0".into()));
+	assert_eq!(attribute(&xc_regions, Id::U128(raw_region_id), "name"), None);
+
+	// The attributes are no longer available once the region is removed:
+	assert_ok!(xc_regions.remove(Id::U128(raw_region_id)));
+	assert_eq!(attribute(&xc_regions, Id::U128(raw_region_id), "begin"), None);
+}
+
+#[ink::test]
+fn labels_work() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, django, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	let team = || String::from("team");
+	assert_eq!(
+		xc_regions.set_label(Id::U128(0), team(), Some(String::from("RegionX"))),
+		Err(XcRegionsError::NotAllowed)
+	);
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+
+	assert_ok!(xc_regions.set_label(Id::U128(0), team(), Some(String::from("RegionX"))));
+	assert_eq!(xc_regions.get_attribute(Id::U128(0), team()), Some(String::from("RegionX")));
+	assert_eq!(xc_regions.label_keys(Id::U128(0)), vec![team()]);
+
+	// Updating a label doesn't add a new key:
+	assert_ok!(xc_regions.set_label(Id::U128(0), team(), Some(String::from("Parachain"))));
+	assert_eq!(xc_regions.get_attribute(Id::U128(0), team()), Some(String::from("Parachain")));
+	assert_eq!(xc_regions.label_keys(Id::U128(0)), vec![team()]);
+
+	// Invalid labels:
+	assert_eq!(
+		xc_regions.set_label(Id::U128(0), String::from("end"), Some(String::from("1"))),
+		Err(XcRegionsError::InvalidLabel)
+	);
+	assert_eq!(
+		xc_regions.set_label(Id::U128(0), String::new(), Some(String::from("1"))),
+		Err(XcRegionsError::InvalidLabel)
+	);
+	assert_eq!(
+		xc_regions.set_label(Id::U128(0), team(), Some("a".repeat(MAX_LABEL_LEN + 1))),
+		Err(XcRegionsError::InvalidLabel)
+	);
+
+	for i in 1..MAX_LABELS {
+		assert_ok!(xc_regions.set_label(Id::U128(0), format!("{}", i), Some(String::new())));
+	}
+	assert_eq!(
+		xc_regions.set_label(Id::U128(0), String::from("purpose"), Some(String::new())),
+		Err(XcRegionsError::TooManyLabels)
+	);
+
+	// Removing a label:
+	assert_ok!(xc_regions.set_label(Id::U128(0), String::from("1"), None));
+	assert_eq!(xc_regions.get_attribute(Id::U128(0), String::from("1")), None);
+	assert_eq!(xc_regions.label_keys(Id::U128(0)).len(), MAX_LABELS - 1);
+
+	// The labels are kept when the region changes hands, but only the owner can set them:
+	assert_ok!(xc_regions.transfer(django, Id::U128(0), vec![]));
+	assert_eq!(xc_regions.get_attribute(Id::U128(0), team()), Some(String::from("Parachain")));
+	assert_eq!(xc_regions.set_label(Id::U128(0), team(), None), Err(XcRegionsError::NotAllowed));
+
+	// The labels are removed once the region is unwrapped:
+	set_caller::<DefaultEnvironment>(django);
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+	assert!(xc_regions.label_keys(Id::U128(0)).is_empty());
+	assert_eq!(xc_regions.labels.get((0, team())), None);
+}
+
+#[ink::test]
+fn notes_work() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, django, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	let note = || String::from("ideal for 2-week testnet");
+	assert_eq!(xc_regions.set_note(Id::U128(0), Some(note())), Err(XcRegionsError::NotAllowed));
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(xc_regions.note(Id::U128(0)), None);
+
+	assert_ok!(xc_regions.set_note(Id::U128(0), Some(note())));
+	assert_eq!(xc_regions.note(Id::U128(0)), Some(note()));
+
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_note_event(&emitted_events.last().unwrap(), 0, Some(note()));
+
+	// The note is bounded:
+	assert_eq!(
+		xc_regions.set_note(Id::U128(0), Some("a".repeat(MAX_NOTE_LEN + 1))),
+		Err(XcRegionsError::InvalidNote)
+	);
+	assert_ok!(xc_regions.set_note(Id::U128(0), Some("a".repeat(MAX_NOTE_LEN))));
+
+	// Removing the note:
+	assert_ok!(xc_regions.set_note(Id::U128(0), None));
+	assert_eq!(xc_regions.note(Id::U128(0)), None);
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_note_event(&emitted_events.last().unwrap(), 0, None);
+
+	// The note is kept when the region changes hands, but only the owner can set it:
+	assert_ok!(xc_regions.set_note(Id::U128(0), Some(note())));
+	assert_ok!(xc_regions.transfer(django, Id::U128(0), vec![]));
+	assert_eq!(xc_regions.note(Id::U128(0)), Some(note()));
+	assert_eq!(xc_regions.set_note(Id::U128(0), None), Err(XcRegionsError::NotAllowed));
+
+	// The note is removed once the region is unwrapped:
+	set_caller::<DefaultEnvironment>(django);
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+	assert_eq!(xc_regions.note(Id::U128(0)), None);
+}
+
+#[ink::test]
+fn psp34_events_work() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, django, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_transfer_event(&emitted_events[0], None, Some(charlie), 0);
+
+	assert_ok!(xc_regions.approve(django, Some(Id::U128(0)), true));
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_approval_event(&emitted_events.last().unwrap(), charlie, django, Some(0), true);
+
+	assert_ok!(xc_regions.transfer(django, Id::U128(0), vec![]));
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_transfer_event(&emitted_events.last().unwrap(), Some(charlie), Some(django), 0);
+
+	// The xcRegion gets burned when it is removed:
+	set_caller::<DefaultEnvironment>(django);
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	let len = emitted_events.len();
+	assert_transfer_event(&emitted_events[len - 2], Some(django), None, 0);
+	assert_removed_event(&emitted_events[len - 1], 0);
+}
+
+#[ink::test]
+fn psp34_enumerable_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.token_by_index(0), Err(PSP34Error::TokenNotExists));
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.mint(region_id(1), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_ok!(xc_regions.init(Id::U128(1), Region { begin: 0, end: 0, core: 0, mask: 1.into() }));
+
+	// All wrapped regions can be walked:
+	assert_eq!(xc_regions.total_supply(), 2);
+	assert_eq!(xc_regions.token_by_index(0), Ok(Id::U128(0)));
+	assert_eq!(xc_regions.token_by_index(1), Ok(Id::U128(1)));
+	assert_eq!(xc_regions.token_by_index(2), Err(PSP34Error::TokenNotExists));
+
+	// As well as the holdings of an account:
+	assert_eq!(xc_regions.owners_token_by_index(charlie, 0), Ok(Id::U128(0)));
+	assert_eq!(xc_regions.owners_token_by_index(charlie, 1), Ok(Id::U128(1)));
+
+	// The holdings are updated on transfer:
+	assert_ok!(xc_regions.transfer(bob, Id::U128(0), Default::default()));
+	assert_eq!(xc_regions.owners_token_by_index(charlie, 0), Ok(Id::U128(1)));
+	assert_eq!(xc_regions.owners_token_by_index(charlie, 1), Err(PSP34Error::TokenNotExists));
+	assert_eq!(xc_regions.owners_token_by_index(bob, 0), Ok(Id::U128(0)));
+
+	// Removed regions are no longer enumerated:
+	assert_ok!(xc_regions.remove(Id::U128(1)));
+	assert_eq!(xc_regions.total_supply(), 1);
+	assert_eq!(xc_regions.token_by_index(0), Ok(Id::U128(0)));
+	assert_eq!(xc_regions.owners_token_by_index(charlie, 0), Err(PSP34Error::TokenNotExists));
+}
+
+#[ink::test]
+fn owned_regions_works() {
+	let DefaultAccounts::<DefaultEnvironment> { bob, charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert!(xc_regions.owned_regions(charlie).is_empty());
+
+	let region_1 = Region { begin: 0, end: 0, core: 0, mask: 1.into() };
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.mint(region_id(1), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_ok!(xc_regions.init(Id::U128(1), region_1.clone()));
+
+	assert_eq!(
+		xc_regions.owned_regions(charlie),
+		vec![
+			(0, VersionedRegion::V1(Region::default())),
+			(1, VersionedRegion::V1(region_1.clone()))
+		]
+	);
+	assert!(xc_regions.owned_regions(bob).is_empty());
+
+	assert_ok!(xc_regions.transfer(bob, Id::U128(0), Default::default()));
+	assert_eq!(xc_regions.owned_regions(charlie), vec![(1, VersionedRegion::V1(region_1))]);
+	assert_eq!(xc_regions.owned_regions(bob), vec![(0, VersionedRegion::V1(Region::default()))]);
+}
+
+#[ink::test]
+fn remove_batch_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, django, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	for raw_region_id in 0..3 {
+		assert_ok!(xc_regions.mint(region_id(raw_region_id), charlie));
+		assert_ok!(xc_regions.init(Id::U128(raw_region_id), Region::default()));
+	}
+
+	// Regions 0 and 1 leave the chain:
+	assert_ok!(xc_regions.burn_item(region_id(0)));
+	assert_ok!(xc_regions.burn_item(region_id(1)));
+
+	// Anyone can clean up the departed regions. Regions that are still wrapped or that have no
+	// metadata are skipped:
+	set_caller::<DefaultEnvironment>(django);
+	assert_eq!(xc_regions.remove_batch(vec![0, 1, 2, 3]), Ok(2));
+
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	let len = emitted_events.len();
+	assert_removed_event(&emitted_events[len - 3], 0);
+	assert_removed_event(&emitted_events[len - 1], 1);
+
+	assert!(!xc_regions.exists(Id::U128(0)));
+	assert!(!xc_regions.exists(Id::U128(1)));
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), None);
+	assert_eq!(xc_regions.owner_of(Id::U128(1)), None);
+
+	assert!(xc_regions.exists(Id::U128(2)));
+	assert_eq!(xc_regions.owner_of(Id::U128(2)), Some(charlie));
+	assert_eq!(xc_regions.balance_of(charlie), 1);
+	assert_eq!(xc_regions.wrapped_region_count(), 1);
+
+	// Nothing is left to remove:
+	assert_eq!(xc_regions.remove_batch(vec![0, 1, 2]), Ok(0));
+}
+
+#[ink::test]
+fn holder_only_removal_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, django, .. } = get_default_accounts();
+	set_caller::<DefaultEnvironment>(charlie);
+
+	let xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	assert!(!xc_regions.holder_only_removal());
+
+	let mut xc_regions = XcRegions::new_with_options(
+		REGIONS_COLLECTION_ID,
+		RegionsBackend::Uniques,
+		None,
+		false,
+		true,
+	);
+	assert!(xc_regions.holder_only_removal());
+
+	let region = Region { end: 2, ..Default::default() };
+	for raw_region_id in 0..3 {
+		assert_ok!(xc_regions.mint(region_id(raw_region_id), charlie));
+		assert_ok!(xc_regions.init(Id::U128(raw_region_id), region.clone()));
+		assert_ok!(xc_regions.burn_item(region_id(raw_region_id)));
+	}
+
+	// Only the holder can remove regions that didn't end yet:
+	set_caller::<DefaultEnvironment>(django);
+	assert_eq!(xc_regions.remove_batch(vec![0]), Ok(0));
+	assert!(xc_regions.exists(Id::U128(0)));
+
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_eq!(xc_regions.remove_batch(vec![0]), Ok(1));
+	assert!(!xc_regions.exists(Id::U128(0)));
+
+	// Once the regions end, anyone can remove them:
+	advance_n_blocks(2 * TIMESLICE_PERIOD);
+	set_caller::<DefaultEnvironment>(django);
+	assert_eq!(xc_regions.remove_batch(vec![1, 2]), Ok(2));
+	assert!(!xc_regions.exists(Id::U128(1)));
+	assert!(!xc_regions.exists(Id::U128(2)));
+}
+
+#[ink::test]
+fn underlying_item_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.underlying_item(Id::U128(0)), Ok(None));
+	assert_eq!(xc_regions.underlying_item(Id::U32(0)), Err(XcRegionsError::InvalidRegionId));
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_eq!(
+		xc_regions.underlying_item(Id::U128(0)),
+		Ok(Some(ItemDetails {
+			owner: charlie,
+			approved: None,
+			is_frozen: false,
+			deposit: Default::default()
+		}))
+	);
+
+	// Once initialized, the underlying region is held by the contract:
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(
+		xc_regions.underlying_item(Id::U128(0)).map(|item| item.map(|i| i.owner)),
+		Ok(Some(contract))
+	);
+}
+
+#[ink::test]
+fn underlying_owner_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, django, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.underlying_owner(Id::U128(0)), None);
+	assert_eq!(xc_regions.underlying_owner(Id::U32(0)), None);
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_eq!(xc_regions.underlying_owner(Id::U128(0)), Some(charlie));
+
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(xc_regions.underlying_owner(Id::U128(0)), Some(contract));
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), Some(charlie));
+
+	// The underlying region gets moved without going through the contract:
+	assert_ok!(xc_regions.burn_item(region_id(0)));
+	assert_ok!(xc_regions.mint(region_id(0), django));
+	assert_eq!(xc_regions.underlying_owner(Id::U128(0)), Some(django));
+	assert_eq!(xc_regions.owner_of(Id::U128(0)), Some(charlie));
+}
+
+#[ink::test]
+fn escrow_holder_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, django, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.escrow_holder(Id::U128(0)), None);
+	assert_eq!(xc_regions.escrow_holder(Id::U32(0)), None);
+
+	// The region isn't held by the contract before being initialized:
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_eq!(xc_regions.escrow_holder(Id::U128(0)), None);
+
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(xc_regions.escrow_holder(Id::U128(0)), Some(charlie));
+
+	assert_ok!(xc_regions.transfer(django, Id::U128(0), Default::default()));
+	assert_eq!(xc_regions.escrow_holder(Id::U128(0)), Some(django));
+
+	// Once unwrapped, the region is no longer escrowed:
+	set_caller::<DefaultEnvironment>(django);
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+	assert_eq!(xc_regions.escrow_holder(Id::U128(0)), None);
+}
+
+#[ink::test]
+fn coremask_queries_work() {
+	let xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+
+	let full = u128::from(CoreMask::complete());
+	let first_half = u128::from(CoreMask::from_chunk(0, 40));
+	let second_half = u128::from(CoreMask::from_chunk(40, 80));
+	let first_quarter = u128::from(CoreMask::from_chunk(0, 20)) | 2u128 << 80;
+
+	assert_eq!(xc_regions.mask_count_ones(Id::U128(full)), Ok(80));
+	assert_eq!(xc_regions.mask_count_ones(Id::U128(first_quarter)), Ok(20));
+	assert_eq!(xc_regions.mask_count_ones(Id::U128(0)), Ok(0));
+	assert_eq!(xc_regions.mask_count_ones(Id::U32(0)), Err(XcRegionsError::InvalidRegionId));
+
+	assert_eq!(xc_regions.core_occupancy(Id::U128(full)), Ok(10_000));
+	assert_eq!(xc_regions.core_occupancy(Id::U128(first_half)), Ok(5_000));
+	assert_eq!(xc_regions.core_occupancy(Id::U128(first_quarter)), Ok(2_500));
+	assert_eq!(xc_regions.core_occupancy(Id::U128(1)), Ok(125));
+
+	assert_eq!(xc_regions.masks_overlap(Id::U128(first_half), Id::U128(second_half)), Ok(false));
+	assert_eq!(xc_regions.masks_overlap(Id::U128(first_half), Id::U128(first_quarter)), Ok(true));
+	assert_eq!(xc_regions.masks_overlap(Id::U128(full), Id::U128(second_half)), Ok(true));
+	assert_eq!(
+		xc_regions.masks_overlap(Id::U128(full), Id::U32(0)),
+		Err(XcRegionsError::InvalidRegionId)
+	);
+}
+
+#[ink::test]
+fn decode_region_id_works() {
+	let xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+
+	assert_eq!(xc_regions.decode_region_id(0), RegionId::default());
+
+	let id = RegionId { begin: 42, core: 7, mask: CoreMask::from_chunk(0, 40) };
+	let raw_region_id = RawRegionId::from(id.clone());
+	assert_eq!(raw_region_id, 42 << 96 | 7 << 80 | 0xffffffffff << 40);
+	assert_eq!(xc_regions.decode_region_id(raw_region_id), id);
+}
+
+#[ink::test]
+fn region_status_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	let raw_region_id = 1 << 96;
+	assert_eq!(xc_regions.region_status(Id::U128(raw_region_id)), RegionStatus::Unavailable);
+	assert_eq!(xc_regions.region_status(Id::U8(0)), RegionStatus::Unavailable);
+
+	assert_ok!(xc_regions.mint(region_id(raw_region_id), charlie));
+	assert_ok!(xc_regions.init(
+		Id::U128(raw_region_id),
+		Region { begin: 1, end: 3, core: 0, mask: Default::default() }
+	));
+	assert_eq!(xc_regions.region_status(Id::U128(raw_region_id)), RegionStatus::Pending);
+
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(
+		xc_regions.region_status(Id::U128(raw_region_id)),
+		RegionStatus::Active { remaining: 2 }
+	);
+
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(
+		xc_regions.region_status(Id::U128(raw_region_id)),
+		RegionStatus::Active { remaining: 1 }
+	);
+
+	advance_n_blocks(TIMESLICE_PERIOD);
+	assert_eq!(xc_regions.region_status(Id::U128(raw_region_id)), RegionStatus::Expired);
+}
+
+#[ink::test]
+fn provenance_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, django, eve, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.provenance(Id::U128(0)), None);
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.approve_transfer(region_id(0), django));
+	set_caller::<DefaultEnvironment>(django);
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(
+		xc_regions.provenance(Id::U128(0)),
+		Some(Provenance { first_initializer: django, transfer_count: 0 })
+	);
+
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.transfer(eve, Id::U128(0), vec![]));
+	set_caller::<DefaultEnvironment>(eve);
+	assert_ok!(xc_regions.transfer(charlie, Id::U128(0), vec![]));
+	assert_eq!(
+		xc_regions.provenance(Id::U128(0)),
+		Some(Provenance { first_initializer: django, transfer_count: 2 })
+	);
+
+	// The provenance is retained across re-initializations:
+	set_caller::<DefaultEnvironment>(charlie);
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(
+		xc_regions.provenance(Id::U128(0)),
+		Some(Provenance { first_initializer: django, transfer_count: 2 })
+	);
+}
+
+#[ink::test]
+fn wrapped_regions_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.wrapped_region_count(), 0);
+	assert!(xc_regions.wrapped_regions(0, 10).is_empty());
+
+	for raw_region_id in 0..3 {
+		assert_ok!(xc_regions.mint(region_id(raw_region_id), charlie));
+		let region = Region { mask: raw_region_id.into(), ..Default::default() };
+		assert_ok!(xc_regions.init(Id::U128(raw_region_id), region));
+	}
+	assert_eq!(xc_regions.wrapped_region_count(), 3);
+	assert_eq!(xc_regions.wrapped_regions(0, 10), vec![0, 1, 2]);
+	assert_eq!(xc_regions.wrapped_regions(1, 1), vec![1]);
+	assert!(xc_regions.wrapped_regions(3, 10).is_empty());
+
+	// The last region takes the place of the removed one:
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+	assert_eq!(xc_regions.wrapped_region_count(), 2);
+	assert_eq!(xc_regions.wrapped_regions(0, 10), vec![2, 1]);
+
+	assert_ok!(xc_regions.remove(Id::U128(1)));
+	assert_eq!(xc_regions.wrapped_regions(0, 10), vec![2]);
+
+	assert_ok!(xc_regions.remove(Id::U128(2)));
+	assert_eq!(xc_regions.wrapped_region_count(), 0);
+	assert!(xc_regions.wrapped_regions(0, 10).is_empty());
+}
+
+#[ink::test]
+fn metadata_history_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.versioned_region_count(), 0);
+	assert!(xc_regions.versioned_regions(0, 10).is_empty());
+	assert!(xc_regions.metadata_history(Id::U128(0), 0, 10).is_empty());
+
+	let first = Region { begin: 0, end: 2, core: 0, mask: Default::default() };
+	let second = Region { begin: 0, end: 4, core: 0, mask: Default::default() };
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), first.clone()));
+	assert_ok!(xc_regions.mint(region_id(1), charlie));
+	assert_ok!(xc_regions.init(Id::U128(1), Region::default()));
+
+	// Re-initializing region 0 with different metadata:
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+	assert_ok!(xc_regions.init(Id::U128(0), second.clone()));
+
+	// Re-initialized regions are only listed once:
+	assert_eq!(xc_regions.versioned_region_count(), 2);
+	assert_eq!(xc_regions.versioned_regions(0, 10), vec![(0, 1), (1, 0)]);
+	assert_eq!(xc_regions.versioned_regions(1, 10), vec![(1, 0)]);
+	assert_eq!(xc_regions.versioned_regions(0, 1), vec![(0, 1)]);
+
+	assert_eq!(
+		xc_regions.metadata_history(Id::U128(0), 0, 10),
+		vec![(0, first.clone()), (1, second.clone())]
+	);
+	assert_eq!(xc_regions.metadata_history(Id::U128(0), 1, 10), vec![(1, second)]);
+	assert_eq!(xc_regions.metadata_history(Id::U128(0), 0, 1), vec![(0, first)]);
+	assert_eq!(xc_regions.metadata_history(Id::U128(1), 0, 10), vec![(0, Region::default())]);
+	assert!(xc_regions.metadata_history(Id::U32(0), 0, 10).is_empty());
+
+	// The history is retained once the region gets unwrapped:
+	assert_ok!(xc_regions.remove(Id::U128(1)));
+	assert_eq!(xc_regions.versioned_regions(0, 10), vec![(0, 1), (1, 0)]);
+	assert_eq!(xc_regions.metadata_history(Id::U128(1), 0, 10), vec![(0, Region::default())]);
+}
+
+#[ink::test]
+fn get_metadata_batch_works() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert!(xc_regions.get_metadata_batch(vec![]).is_empty());
+
+	let region_1 = Region { begin: 0, end: 0, core: 0, mask: 1.into() };
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.mint(region_id(1), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_ok!(xc_regions.init(Id::U128(1), region_1.clone()));
+
+	assert_eq!(
+		xc_regions.get_metadata_batch(vec![1, 2, 0]),
+		vec![
+			Ok(VersionedRegion::V1(region_1)),
+			Err(XcRegionsError::MetadataNotFound),
+			Ok(VersionedRegion::V1(Region::default())),
+		]
 	);
 }
 
+#[ink::test]
+fn metadata_version_gets_updated() {
+	let DefaultAccounts::<DefaultEnvironment> { charlie, .. } = get_default_accounts();
+	let mut xc_regions = XcRegions::new(REGIONS_COLLECTION_ID);
+	set_caller::<DefaultEnvironment>(charlie);
+
+	assert_eq!(xc_regions.metadata_version(Id::U128(0)), Err(XcRegionsError::MetadataNotFound));
+	assert_eq!(xc_regions.metadata_version(Id::U8(0)), Err(XcRegionsError::InvalidRegionId));
+	assert!(!xc_regions.exists(Id::U128(0)));
+	assert!(!xc_regions.exists(Id::U8(0)));
+
+	assert_ok!(xc_regions.mint(region_id(0), charlie));
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(xc_regions.get_metadata(Id::U128(0)), Ok(VersionedRegion::V1(Region::default())));
+	assert_eq!(xc_regions.metadata_version(Id::U128(0)), Ok(0));
+	assert!(xc_regions.exists(Id::U128(0)));
+	// No version bump is signaled on the first initialization:
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	assert_eq!(emitted_events.len(), 2);
+
+	assert_ok!(xc_regions.remove(Id::U128(0)));
+	assert_eq!(xc_regions.metadata_version(Id::U128(0)), Err(XcRegionsError::MetadataNotFound));
+	assert!(!xc_regions.exists(Id::U128(0)));
+
+	assert_ok!(xc_regions.init(Id::U128(0), Region::default()));
+	assert_eq!(xc_regions.get_metadata(Id::U128(0)), Ok(VersionedRegion::V1(Region::default())));
+	assert_eq!(xc_regions.metadata_version(Id::U128(0)), Ok(1));
+
+	let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+	let len = emitted_events.len();
+	assert_version_bumped_event(&emitted_events[len - 2], 0, 0, 1);
+	assert_init_event(&emitted_events[len - 1], 0, Region::default(), 1);
+}
+
 // Helper functions for test
 fn assert_init_event(
 	event: &ink::env::test::EmittedEvent,
@@ -228,8 +1695,12 @@ fn assert_init_event(
 ) {
 	let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
 		.expect("encountered invalid contract event data buffer");
-	if let Event::RegionInitialized(RegionInitialized { region_id, metadata, version }) =
-		decoded_event
+	if let Event::RegionInitialized(RegionInitialized {
+		region_id,
+		metadata,
+		version,
+		metadata_hash,
+	}) = decoded_event
 	{
 		assert_eq!(
 			region_id, expected_region_id,
@@ -237,11 +1708,128 @@ fn assert_init_event(
 		);
 		assert_eq!(metadata, expected_metadata, "encountered invalid RegionInitialized.metadata");
 		assert_eq!(version, expected_version, "encountered invalid RegionInitialized.version");
+		assert_eq!(
+			metadata_hash,
+			hash_metadata(&expected_metadata),
+			"encountered invalid RegionInitialized.metadata_hash"
+		);
 	} else {
 		panic!("encountered unexpected event kind: expected a RegionInitialized event")
 	}
 }
 
+fn assert_attested_event(
+	event: &ink::env::test::EmittedEvent,
+	expected_region_id: RawRegionId,
+	expected_metadata: Region,
+	expected_version: Version,
+) {
+	let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+		.expect("encountered invalid contract event data buffer");
+	if let Event::RegionAttested(RegionAttested { region_id, metadata, version, metadata_hash }) =
+		decoded_event
+	{
+		assert_eq!(region_id, expected_region_id, "encountered invalid RegionAttested.region_id");
+		assert_eq!(metadata, expected_metadata, "encountered invalid RegionAttested.metadata");
+		assert_eq!(version, expected_version, "encountered invalid RegionAttested.version");
+		assert_eq!(
+			metadata_hash,
+			hash_metadata(&expected_metadata),
+			"encountered invalid RegionAttested.metadata_hash"
+		);
+	} else {
+		panic!("encountered unexpected event kind: expected a RegionAttested event")
+	}
+}
+
+fn assert_note_event(
+	event: &ink::env::test::EmittedEvent,
+	expected_region_id: RawRegionId,
+	expected_note: Option<String>,
+) {
+	let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+		.expect("encountered invalid contract event data buffer");
+	if let Event::NoteUpdated(NoteUpdated { region_id, note }) = decoded_event {
+		assert_eq!(region_id, expected_region_id, "encountered invalid NoteUpdated.region_id");
+		assert_eq!(note, expected_note, "encountered invalid NoteUpdated.note");
+	} else {
+		panic!("encountered unexpected event kind: expected a NoteUpdated event")
+	}
+}
+
+fn assert_transfer_event(
+	event: &ink::env::test::EmittedEvent,
+	expected_from: Option<AccountId>,
+	expected_to: Option<AccountId>,
+	expected_region_id: RawRegionId,
+) {
+	let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+		.expect("encountered invalid contract event data buffer");
+	if let Event::Transfer(Transfer { from, to, id }) = decoded_event {
+		assert_eq!(from, expected_from, "encountered invalid Transfer.from");
+		assert_eq!(to, expected_to, "encountered invalid Transfer.to");
+		assert_eq!(id, Id::U128(expected_region_id), "encountered invalid Transfer.id");
+	} else {
+		panic!("encountered unexpected event kind: expected a Transfer event")
+	}
+}
+
+fn assert_approval_event(
+	event: &ink::env::test::EmittedEvent,
+	expected_from: AccountId,
+	expected_to: AccountId,
+	expected_region_id: Option<RawRegionId>,
+	expected_approved: bool,
+) {
+	let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+		.expect("encountered invalid contract event data buffer");
+	if let Event::Approval(Approval { from, to, id, approved }) = decoded_event {
+		assert_eq!(from, expected_from, "encountered invalid Approval.from");
+		assert_eq!(to, expected_to, "encountered invalid Approval.to");
+		assert_eq!(id, expected_region_id.map(Id::U128), "encountered invalid Approval.id");
+		assert_eq!(approved, expected_approved, "encountered invalid Approval.approved");
+	} else {
+		panic!("encountered unexpected event kind: expected an Approval event")
+	}
+}
+
+fn assert_version_bumped_event(
+	event: &ink::env::test::EmittedEvent,
+	expected_region_id: RawRegionId,
+	expected_old: Version,
+	expected_new: Version,
+) {
+	let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+		.expect("encountered invalid contract event data buffer");
+	if let Event::MetadataVersionBumped(MetadataVersionBumped { region_id, old, new }) =
+		decoded_event
+	{
+		assert_eq!(
+			region_id, expected_region_id,
+			"encountered invalid MetadataVersionBumped.region_id"
+		);
+		assert_eq!(old, expected_old, "encountered invalid MetadataVersionBumped.old");
+		assert_eq!(new, expected_new, "encountered invalid MetadataVersionBumped.new");
+	} else {
+		panic!("encountered unexpected event kind: expected a MetadataVersionBumped event")
+	}
+}
+
+fn assert_locked_event(
+	event: &ink::env::test::EmittedEvent,
+	expected_region_id: RawRegionId,
+	expected_locker: AccountId,
+) {
+	let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+		.expect("encountered invalid contract event data buffer");
+	if let Event::RegionLocked(RegionLocked { region_id, locker }) = decoded_event {
+		assert_eq!(region_id, expected_region_id, "encountered invalid RegionLocked.region_id");
+		assert_eq!(locker, expected_locker, "encountered invalid RegionLocked.locker");
+	} else {
+		panic!("encountered unexpected event kind: expected a RegionLocked event")
+	}
+}
+
 fn assert_removed_event(event: &ink::env::test::EmittedEvent, expected_region_id: RawRegionId) {
 	let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
 		.expect("encountered invalid contract event data buffer");
@@ -252,6 +1840,71 @@ fn assert_removed_event(event: &ink::env::test::EmittedEvent, expected_region_id
 	}
 }
 
+fn assert_withdrawn_event(
+	event: &ink::env::test::EmittedEvent,
+	expected_region_id: RawRegionId,
+	expected_owner: AccountId,
+) {
+	let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+		.expect("encountered invalid contract event data buffer");
+	if let Event::RegionWithdrawn(RegionWithdrawn { region_id, owner }) = decoded_event {
+		assert_eq!(region_id, expected_region_id, "encountered invalid RegionWithdrawn.region_id");
+		assert_eq!(owner, expected_owner, "encountered invalid RegionWithdrawn.owner");
+	} else {
+		panic!("encountered unexpected event kind: expected a RegionWithdrawn event")
+	}
+}
+
+fn assert_returned_event(
+	event: &ink::env::test::EmittedEvent,
+	expected_region_id: RawRegionId,
+	expected_beneficiary: AccountId,
+) {
+	let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+		.expect("encountered invalid contract event data buffer");
+	if let Event::RegionReturned(RegionReturned { region_id, beneficiary }) = decoded_event {
+		assert_eq!(region_id, expected_region_id, "encountered invalid RegionReturned.region_id");
+		assert_eq!(
+			beneficiary, expected_beneficiary,
+			"encountered invalid RegionReturned.beneficiary"
+		);
+	} else {
+		panic!("encountered unexpected event kind: expected a RegionReturned event")
+	}
+}
+
+// Builds a trie from the entries, returning its root together with a proof of all entries.
+fn build_trie(entries: Vec<(Vec<u8>, Vec<u8>)>) -> ([u8; 32], Vec<Vec<u8>>) {
+	let mut db = MemoryDB::<Blake2Hasher>::default();
+	let mut root = Default::default();
+	{
+		let mut trie = TrieDBMutBuilder::<LayoutV1<Blake2Hasher>>::new(&mut db, &mut root).build();
+		for (key, value) in entries {
+			trie.insert(&key, &value).unwrap();
+		}
+	}
+
+	let proof = db
+		.drain()
+		.into_values()
+		.filter(|(_, rc)| *rc > 0)
+		.map(|(node, _)| node)
+		.collect();
+	(root.into(), proof)
+}
+
+fn advance_n_blocks(n: u32) {
+	for _ in 0..n {
+		ink::env::test::advance_block::<DefaultEnvironment>();
+	}
+}
+
+fn hash_metadata(region: &Region) -> [u8; 32] {
+	let mut hash = [0u8; 32];
+	ink::env::hash_encoded::<Blake2x256, _>(region, &mut hash);
+	hash
+}
+
 pub fn region_id(region_id: RawRegionId) -> (CollectionId, RawRegionId) {
 	(REGIONS_COLLECTION_ID, region_id)
 }
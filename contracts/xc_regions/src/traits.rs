@@ -13,22 +13,33 @@
 // You should have received a copy of the GNU General Public License
 // along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::types::{VersionedRegion, XcRegionsError};
-use openbrush::contracts::traits::psp34::Id;
-use primitives::coretime::Region;
+use ink::prelude::vec::Vec;
+use openbrush::{
+	contracts::traits::psp34::{Id, PSP34Error},
+	traits::AccountId,
+};
+pub use xc_regions_traits::{regionmetadata_external, RegionMetadata, RegionMetadataRef};
 
 #[openbrush::wrapper]
-pub type RegionMetadataRef = dyn RegionMetadata;
+pub type RegionReceiverRef = dyn RegionReceiver;
 
-/// This is based on: `<https://hackmd.io/@Szegoo/rkryxwdIp>`
+/// The callback invoked on contracts receiving an xcRegion through a transfer.
 #[openbrush::trait_definition]
-pub trait RegionMetadata {
+pub trait RegionReceiver {
+	/// Called after the xcRegion got transferred to the contract. Returning an error reverts the
+	/// transfer.
+	///
+	/// ## Arguments:
+	/// - `operator` - The account that initiated the transfer.
+	/// - `from` - The previous owner of the xcRegion.
+	/// - `id` - The identifier of the received xcRegion.
+	/// - `data` - The data passed along with the transfer.
 	#[ink(message)]
-	fn init(&mut self, id: Id, metadata: Region) -> Result<(), XcRegionsError>;
-
-	#[ink(message)]
-	fn get_metadata(&self, id: Id) -> Result<VersionedRegion, XcRegionsError>;
-
-	#[ink(message)]
-	fn remove(&mut self, id: Id) -> Result<(), XcRegionsError>;
+	fn on_region_received(
+		&mut self,
+		operator: AccountId,
+		from: AccountId,
+		id: Id,
+		data: Vec<u8>,
+	) -> Result<(), PSP34Error>;
 }
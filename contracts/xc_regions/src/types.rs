@@ -13,49 +13,46 @@
 // You should have received a copy of the GNU General Public License
 // along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
 
-use openbrush::contracts::psp34::PSP34Error;
-use primitives::{coretime::Region, Version};
+use openbrush::traits::AccountId;
+use primitives::coretime::Timeslice;
+pub use xc_regions_traits::types::{VersionedRegion, XcRegionsError};
 
-#[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-pub enum XcRegionsError {
-	/// The provided identifier is not a valid region id.
-	InvalidRegionId,
-	/// The metadata is either already initialized or the caller isn't the region owner.
-	CannotInitialize,
-	/// The region metadata cannot be removed as long as the underlying region continues to exist
-	/// on this chain.
-	CannotRemove,
-	/// No metadata was found for the region.
-	MetadataNotFound,
-	/// The provided metadata doesn't match with the metadata extracted from the region id.
-	InvalidMetadata,
-	/// The associated metadata version was not found.
-	VersionNotFound,
-	/// An error occured in the underlying runtime.
-	RuntimeError,
-	/// An psp34 error occured.
-	Psp34(PSP34Error),
+/// The pallet through which the underlying regions are represented on this chain.
+#[derive(scale::Decode, scale::Encode, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum RegionsBackend {
+	/// Regions are items of a `pallet-uniques` collection.
+	#[default]
+	Uniques,
+	/// Regions are items of a `pallet-nfts` collection.
+	Nfts,
 }
 
-impl core::fmt::Display for XcRegionsError {
-	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-		match self {
-			XcRegionsError::InvalidRegionId => write!(f, "InvalidRegionId"),
-			XcRegionsError::CannotInitialize => write!(f, "CannotInitialize"),
-			XcRegionsError::CannotRemove => write!(f, "CannotRemove"),
-			XcRegionsError::MetadataNotFound => write!(f, "MetadataNotFound"),
-			XcRegionsError::InvalidMetadata => write!(f, "InvalidMetadata"),
-			XcRegionsError::VersionNotFound => write!(f, "VersionNotFound"),
-			XcRegionsError::RuntimeError => write!(f, "RuntimeError"),
-			XcRegionsError::Psp34(err) => write!(f, "{:?}", err),
-		}
-	}
+/// The status of a region relative to the current timeslice.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum RegionStatus {
+	/// The region metadata isn't initialized, so the status cannot be determined.
+	Unavailable,
+	/// The region hasn't started yet.
+	Pending,
+	/// The region is currently in use.
+	Active {
+		/// The number of timeslices until the region ends.
+		remaining: Timeslice,
+	},
+	/// The region has ended.
+	Expired,
 }
 
-#[derive(scale::Decode, scale::Encode, Default, Clone, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-pub struct VersionedRegion {
-	pub version: Version,
-	pub region: Region,
+/// The history of a wrapped region.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Provenance {
+	/// The account that initialized the region for the first time.
+	pub first_initializer: AccountId,
+	/// The number of times the xcRegion got transferred between accounts.
+	///
+	/// This includes the transfers of all the xcRegions that wrapped the region.
+	pub transfer_count: u32,
 }
@@ -26,6 +26,25 @@ pub trait BlockNumberProviderExtension {
 			.handle_error_code::<BlockNumberProviderError>()
 			.call(&())
 	}
+
+	/// The storage root of the relay chain block this chain's current block is built upon.
+	fn relay_chain_state_root(&self) -> Result<[u8; 32], BlockNumberProviderError> {
+		::ink::env::chain_extension::ChainExtensionMethod::build(0x50002)
+			.input::<()>()
+			.output::<Result<[u8; 32], BlockNumberProviderError>, true>()
+			.handle_error_code::<BlockNumberProviderError>()
+			.call(&())
+	}
+
+	/// The randomness of the current relay chain epoch, derived from the BABE VRF outputs of the
+	/// relay chain block authors.
+	fn relay_chain_randomness(&self) -> Result<[u8; 32], BlockNumberProviderError> {
+		::ink::env::chain_extension::ChainExtensionMethod::build(0x50003)
+			.input::<()>()
+			.output::<Result<[u8; 32], BlockNumberProviderError>, true>()
+			.handle_error_code::<BlockNumberProviderError>()
+			.call(&())
+	}
 }
 
 #[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, Debug)]
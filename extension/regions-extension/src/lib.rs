@@ -0,0 +1,77 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use primitives::{
+	coretime::{Finality, RawRegionId, TaskId},
+	uniques::CollectionId,
+};
+use scale::{Decode, Encode};
+
+/// Exposes the runtime configuration of the regions represented on this chain.
+pub trait RegionsExtension {
+	/// The id of the collection in which the runtime represents the regions.
+	fn regions_collection_id(&self) -> Result<CollectionId, RegionsError> {
+		::ink::env::chain_extension::ChainExtensionMethod::build(0x70001)
+			.input::<()>()
+			.output::<Result<CollectionId, RegionsError>, true>()
+			.handle_error_code::<RegionsError>()
+			.call(&())
+	}
+
+	/// Requests the runtime to assign the region to the task on the Coretime chain.
+	fn assign(
+		&self,
+		region_id: RawRegionId,
+		task: TaskId,
+		finality: Finality,
+	) -> Result<(), RegionsError> {
+		::ink::env::chain_extension::ChainExtensionMethod::build(0x70002)
+			.input::<(RawRegionId, TaskId, Finality)>()
+			.output::<Result<(), RegionsError>, true>()
+			.handle_error_code::<RegionsError>()
+			.call(&(region_id, task, finality))
+	}
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum RegionsError {
+	/// Origin Caller is not supported
+	OriginCannotBeCaller = 98,
+	/// Unknown error
+	RuntimeError = 99,
+	/// Unknow status code
+	UnknownStatusCode,
+	/// Encountered unexpected invalid SCALE encoding
+	InvalidScaleEncoding,
+}
+
+impl ink::env::chain_extension::FromStatusCode for RegionsError {
+	fn from_status_code(status_code: u32) -> Result<(), Self> {
+		match status_code {
+			0 => Ok(()),
+			98 => Err(Self::OriginCannotBeCaller),
+			99 => Err(Self::RuntimeError),
+			_ => Err(Self::UnknownStatusCode),
+		}
+	}
+}
+
+impl From<scale::Error> for RegionsError {
+	fn from(_: scale::Error) -> Self {
+		RegionsError::InvalidScaleEncoding
+	}
+}
@@ -21,4 +21,6 @@
 pub struct Extension;
 
 impl uniques_extension::UniquesExtension for Extension {}
+impl nfts_extension::NftsExtension for Extension {}
 impl block_number_extension::BlockNumberProviderExtension for Extension {}
+impl regions_extension::RegionsExtension for Extension {}
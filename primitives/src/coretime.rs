@@ -26,6 +26,9 @@ pub type Timeslice = u32;
 /// Index of a Polkadot Core.
 pub type CoreIndex = u16;
 
+/// The identifier of a task, i.e. the para id of the parachain a region is assigned to.
+pub type TaskId = u32;
+
 /// Duration of a timeslice in rc blocks.
 pub const TIMESLICE_PERIOD: BlockNumber = 80;
 
@@ -51,6 +54,12 @@ impl CoreMask {
 	pub fn count_ones(&self) -> u32 {
 		self.0.iter().map(|i| i.count_ones()).sum()
 	}
+	pub fn overlaps(&self, other: &Self) -> bool {
+		self.0.iter().zip(other.0.iter()).any(|(a, b)| a & b != 0)
+	}
+	pub fn is_subset_of(&self, other: &Self) -> bool {
+		self.0.iter().zip(other.0.iter()).all(|(a, b)| a & !b == 0)
+	}
 	pub fn from_chunk(from: u32, to: u32) -> Self {
 		let mut v = [0u8; 10];
 		for i in (from.min(80) as usize)..(to.min(80) as usize) {
@@ -112,3 +121,25 @@ pub struct Region {
 	/// The regularity parts in which this Region will be scheduled.
 	pub mask: CoreMask,
 }
+
+/// Whether a region assignment can later be changed, as defined in `pallet-broker`.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum Finality {
+	/// The region remains with the same owner, allowing the assignment to be altered.
+	Provisional,
+	/// The region is removed and the assignment cannot be altered.
+	Final,
+}
+
+/// The record of a region as stored in `pallet-broker` on the Coretime chain.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct RegionRecord {
+	/// The timeslice at which the region ends.
+	pub end: Timeslice,
+	/// The owner of the region.
+	pub owner: openbrush::traits::AccountId,
+	/// The payment for the region on the bulk market. Defined only for renewable regions.
+	pub paid: Option<crate::Balance>,
+}
@@ -17,6 +17,8 @@
 
 pub mod coretime;
 pub mod macros;
+pub mod nfts;
+pub mod polkadot_xcm;
 pub mod uniques;
 
 /// Balance of an account.
@@ -25,8 +27,35 @@ pub type Balance = u128;
 /// The type used for versioning metadata.
 pub type Version = u32;
 
+/// The index of the uniques pallet in the RegionX runtime.
+pub const UNIQUES_PALLET_INDEX: u8 = 37;
+
 #[derive(scale::Encode, scale::Decode)]
 pub enum RuntimeCall {
 	#[codec(index = 37)]
 	Uniques(uniques::UniquesCall),
+	#[codec(index = 52)]
+	Nfts(nfts::NftsCall),
+	#[codec(index = 31)]
+	PolkadotXcm(polkadot_xcm::PolkadotXcmCall),
+}
+
+/// A runtime call to a pallet whose index is configured at runtime rather than fixed in
+/// `RuntimeCall`.
+pub struct PalletCall<Call> {
+	/// The index of the pallet in the runtime.
+	pub pallet_index: u8,
+	/// The call to the pallet.
+	pub call: Call,
+}
+
+impl<Call: scale::Encode> scale::Encode for PalletCall<Call> {
+	fn size_hint(&self) -> usize {
+		1 + self.call.size_hint()
+	}
+
+	fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+		dest.push_byte(self.pallet_index);
+		self.call.encode_to(dest);
+	}
 }
@@ -0,0 +1,76 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{coretime::RawRegionId, uniques, Balance};
+use ink::prelude::collections::BTreeMap;
+use openbrush::traits::{AccountId, BlockNumber};
+use sp_runtime::MultiAddress;
+
+// The type used to identify collections in the underlying nfts pallet.
+pub type CollectionId = u32;
+
+#[derive(scale::Encode, scale::Decode)]
+pub enum NftsCall {
+	#[codec(index = 6)]
+	Transfer { collection: CollectionId, item: RawRegionId, dest: MultiAddress<AccountId, ()> },
+	#[codec(index = 15)]
+	ApproveTransfer {
+		collection: CollectionId,
+		item: RawRegionId,
+		delegate: MultiAddress<AccountId, ()>,
+		maybe_deadline: Option<BlockNumber>,
+	},
+	#[codec(index = 16)]
+	CancelApproval {
+		collection: CollectionId,
+		item: RawRegionId,
+		delegate: MultiAddress<AccountId, ()>,
+	},
+}
+
+/// Information concerning the ownership of a single unique item.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ItemDetails {
+	/// The owner of this item.
+	pub owner: AccountId,
+	/// The approved transferrers of this item together with the deadline of their approval.
+	pub approvals: BTreeMap<AccountId, Option<BlockNumber>>,
+	/// The amount held in the pallet's default account for this item.
+	pub deposit: ItemDeposit,
+}
+
+/// Information about the reserved item deposit.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ItemDeposit {
+	/// A depositor account.
+	pub account: AccountId,
+	/// An amount that gets reserved.
+	pub amount: Balance,
+}
+
+impl From<ItemDetails> for uniques::ItemDetails {
+	fn from(details: ItemDetails) -> Self {
+		// The nfts pallet keeps track of locked items in the item config, which isn't part of
+		// the item details. Only the first approved transferrer is retained.
+		uniques::ItemDetails {
+			owner: details.owner,
+			approved: details.approvals.into_keys().next(),
+			is_frozen: false,
+			deposit: details.deposit.amount,
+		}
+	}
+}
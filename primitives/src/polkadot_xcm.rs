@@ -0,0 +1,122 @@
+// This file is part of RegionX.
+//
+// RegionX is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// RegionX is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with RegionX.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{coretime::RawRegionId, Balance};
+use openbrush::traits::AccountId;
+use xcm::{
+	v3::{
+		AssetId, AssetInstance, Fungibility, Junction, Junctions, MultiAsset, MultiAssets,
+		MultiLocation, WeightLimit,
+	},
+	VersionedMultiAssets, VersionedMultiLocation,
+};
+
+#[derive(scale::Encode, scale::Decode)]
+pub enum PolkadotXcmCall {
+	#[codec(index = 8)]
+	LimitedReserveTransferAssets {
+		dest: VersionedMultiLocation,
+		beneficiary: VersionedMultiLocation,
+		assets: VersionedMultiAssets,
+		fee_asset_item: u32,
+		weight_limit: WeightLimit,
+	},
+}
+
+impl PolkadotXcmCall {
+	/// Constructs the call reserve-transferring an amount of the relay chain asset to the Coretime
+	/// chain.
+	///
+	/// The XCM execution fees are paid from the transferred amount.
+	///
+	/// ## Arguments:
+	/// - `para_id` - The parachain id of the Coretime chain.
+	/// - `beneficiary` - The account receiving the asset on the Coretime chain.
+	/// - `amount` - The amount of the relay chain asset to transfer.
+	pub fn transfer_to_coretime_chain(
+		para_id: u32,
+		beneficiary: AccountId,
+		amount: Balance,
+	) -> Self {
+		let dest = MultiLocation::new(1, Junctions::X1(Junction::Parachain(para_id)));
+		let beneficiary = MultiLocation::new(
+			0,
+			Junctions::X1(Junction::AccountId32 { network: None, id: *beneficiary.as_ref() }),
+		);
+
+		let asset = MultiAsset {
+			id: AssetId::Concrete(MultiLocation::parent()),
+			fun: Fungibility::Fungible(amount),
+		};
+
+		PolkadotXcmCall::LimitedReserveTransferAssets {
+			dest: VersionedMultiLocation::V3(dest),
+			beneficiary: VersionedMultiLocation::V3(beneficiary),
+			assets: VersionedMultiAssets::V3(MultiAssets::from(ink::prelude::vec![asset])),
+			fee_asset_item: 0,
+			weight_limit: WeightLimit::Unlimited,
+		}
+	}
+
+	/// Constructs the call reserve-transferring a region back to the Coretime chain.
+	///
+	/// ## Arguments:
+	/// - `para_id` - The parachain id of the Coretime chain.
+	/// - `regions_pallet_index` - The index of the pallet holding the regions on the Coretime
+	///   chain.
+	/// - `region_id` - The `u128` encoded region identifier.
+	/// - `beneficiary` - The account receiving the region on the Coretime chain.
+	/// - `fee` - The amount of the relay chain asset used for paying the XCM execution fees.
+	pub fn return_region(
+		para_id: u32,
+		regions_pallet_index: u8,
+		region_id: RawRegionId,
+		beneficiary: AccountId,
+		fee: Balance,
+	) -> Self {
+		let dest = MultiLocation::new(1, Junctions::X1(Junction::Parachain(para_id)));
+		let beneficiary = MultiLocation::new(
+			0,
+			Junctions::X1(Junction::AccountId32 { network: None, id: *beneficiary.as_ref() }),
+		);
+
+		let fee_asset = MultiAsset {
+			id: AssetId::Concrete(MultiLocation::parent()),
+			fun: Fungibility::Fungible(fee),
+		};
+		let region = MultiAsset {
+			id: AssetId::Concrete(MultiLocation::new(
+				1,
+				Junctions::X2(
+					Junction::Parachain(para_id),
+					Junction::PalletInstance(regions_pallet_index),
+				),
+			)),
+			fun: Fungibility::NonFungible(AssetInstance::Index(region_id)),
+		};
+
+		// The assets get sorted, and since the relay chain asset is located at the parent, the
+		// fee asset will always be the first one.
+		let assets = MultiAssets::from(ink::prelude::vec![fee_asset, region]);
+
+		PolkadotXcmCall::LimitedReserveTransferAssets {
+			dest: VersionedMultiLocation::V3(dest),
+			beneficiary: VersionedMultiLocation::V3(beneficiary),
+			assets: VersionedMultiAssets::V3(assets),
+			fee_asset_item: 0,
+			weight_limit: WeightLimit::Unlimited,
+		}
+	}
+}
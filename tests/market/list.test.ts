@@ -25,6 +25,7 @@ const REGION_COLLECTION_ID = 42;
 const LISTING_DEPOIST = 100;
 // In reality this is 80, however we use 8 for testing.
 const TIMESLICE_PERIOD = 8;
+const MARKET_FEE = 0;
 
 const wsProvider = new WsProvider('ws://127.0.0.1:9944');
 // Create a keyring instance
@@ -43,11 +44,23 @@ describe('Coretime market listing', () => {
     alice = keyring.addFromUri('//Alice');
 
     const xcRegionsFactory = new XcRegions_Factory(api, alice);
-    xcRegions = new XcRegions((await xcRegionsFactory.new()).address, alice, api);
+    xcRegions = new XcRegions(
+      (await xcRegionsFactory.new(REGION_COLLECTION_ID)).address,
+      alice,
+      api,
+    );
 
     const marketFactory = new Market_Factory(api, alice);
     market = new Market(
-      (await marketFactory.new(xcRegions.address, LISTING_DEPOIST, TIMESLICE_PERIOD)).address,
+      (
+        await marketFactory.new(
+          xcRegions.address,
+          LISTING_DEPOIST,
+          TIMESLICE_PERIOD,
+          MARKET_FEE,
+          alice.address,
+        )
+      ).address,
       alice,
       api,
     );
@@ -100,6 +113,47 @@ describe('Coretime market listing', () => {
     expect(await balanceOf(api, alice.address)).to.be.lessThan(aliceBalance - LISTING_DEPOIST);
   });
 
+  it('Listing works with the receive hook enabled for the market', async () => {
+    const regionId: RegionId = {
+      begin: 30,
+      core: 4,
+      mask: CoreMask.completeMask(),
+    };
+    const regionRecord: RegionRecord = {
+      end: 60,
+      owner: alice.address,
+      paid: null,
+    };
+    const region = new Region(regionId, regionRecord);
+    const id: any = api.createType('Id', { U128: region.getEncodedRegionId(api) });
+
+    await mintRegion(api, alice, region);
+    await approveTransfer(api, alice, region, xcRegions.address);
+
+    await initRegion(api, xcRegions, alice, region);
+
+    await xcRegions.withSigner(alice).tx.approve(market.address, id, true);
+
+    // The market never opted out of the `on_region_received` callback:
+    expect((await xcRegions.query.receiveHookEnabled(market.address)).value.unwrap()).to.be.true;
+
+    // The market pulls the region into itself, so no callback is invoked on it:
+    const timeslicePrice = 50;
+    const result = await market
+      .withSigner(alice)
+      .tx.listRegion(id, timeslicePrice, alice.address, { value: LISTING_DEPOIST });
+    expectEvent(result, 'RegionListed', {
+      regionId: id.toPrimitive().u128,
+      timeslicePrice: timeslicePrice.toString(),
+      seller: alice.address,
+      saleRecepient: alice.address.toString(),
+      metadataVersion: 0,
+    });
+
+    await expectOnSale(market, id, alice, timeslicePrice);
+    expect((await xcRegions.query.ownerOf(id)).value.unwrap()).to.deep.equal(market.address);
+  });
+
   it('Listing requires listing deposit', async () => {
     const regionId: RegionId = {
       begin: 30,
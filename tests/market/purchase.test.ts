@@ -24,6 +24,7 @@ const REGION_COLLECTION_ID = 42;
 const LISTING_DEPOIST = 0;
 // In reality this is 80, however we use 8 for testing.
 const TIMESLICE_PERIOD = 8;
+const MARKET_FEE = 0;
 
 const wsProvider = new WsProvider('ws://127.0.0.1:9944');
 // Create a keyring instance
@@ -46,11 +47,23 @@ describe('Coretime market purchases', () => {
     charlie = keyring.addFromUri('//Charlie');
 
     const xcRegionsFactory = new XcRegions_Factory(api, alice);
-    xcRegions = new XcRegions((await xcRegionsFactory.new()).address, alice, api);
+    xcRegions = new XcRegions(
+      (await xcRegionsFactory.new(REGION_COLLECTION_ID)).address,
+      alice,
+      api,
+    );
 
     const marketFactory = new Market_Factory(api, alice);
     market = new Market(
-      (await marketFactory.new(xcRegions.address, LISTING_DEPOIST, TIMESLICE_PERIOD)).address,
+      (
+        await marketFactory.new(
+          xcRegions.address,
+          LISTING_DEPOIST,
+          TIMESLICE_PERIOD,
+          MARKET_FEE,
+          alice.address,
+        )
+      ).address,
       alice,
       api,
     );
@@ -97,11 +110,17 @@ describe('Coretime market purchases', () => {
 
     const result = await market
       .withSigner(bob)
-      .tx.purchaseRegion(id, 0, { value: timeslicePrice * (region.getEnd() - region.getBegin()) });
+      .tx.purchaseRegion(id, 0, null, null, null, {
+        value: timeslicePrice * (region.getEnd() - region.getBegin()),
+      });
     expectEvent(result, 'RegionPurchased', {
       regionId: id.toPrimitive().u128,
       buyer: bob.address,
+      seller: alice.address,
+      recipient: bob.address,
       totalPrice: (timeslicePrice * (region.getEnd() - region.getBegin())).toString(),
+      metadataVersion: 0,
+      timeslice: settlementTimeslice(result),
     });
 
     // Bob receives the region:
@@ -119,6 +138,62 @@ describe('Coretime market purchases', () => {
     expect((await market.query.listedRegion(id)).value.unwrap().ok).to.be.equal(null);
   });
 
+  it('Purchasing an interlaced region works', async () => {
+    const regionId: RegionId = {
+      begin: 30,
+      core: 14,
+      mask: CoreMask.completeMask(),
+    };
+    const regionRecord: RegionRecord = {
+      end: 60,
+      owner: alice.address,
+      paid: null,
+    };
+    const region = new Region(regionId, regionRecord);
+
+    await mintRegion(api, alice, region);
+    await approveTransfer(api, alice, region, xcRegions.address);
+
+    // The mask of an interlaced region is only a subset of the mask encoded in its id:
+    await xcRegions.withSigner(alice).tx.init(region.getEncodedRegionId(api), {
+      begin: region.getBegin(),
+      core: region.getCore(),
+      end: region.getEnd(),
+      // @ts-ignore
+      mask: CoreMask.fromChunk(0, 40).getMask(),
+    });
+
+    const id: any = api.createType('Id', { U128: region.getEncodedRegionId(api) });
+    await xcRegions.withSigner(alice).tx.approve(market.address, id, true);
+
+    const timeslicePrice = 5 * Math.pow(10, 12);
+    await market
+      .withSigner(alice)
+      .tx.listRegion(id, timeslicePrice, alice.address, { value: LISTING_DEPOIST });
+
+    // Only half of the core is occupied by the region:
+    const price = (timeslicePrice / 2) * (region.getEnd() - region.getBegin());
+    expect((await market.query.regionPrice(id)).value.unwrap().unwrap().toNumber()).to.be.equal(
+      price,
+    );
+
+    const result = await market
+      .withSigner(bob)
+      .tx.purchaseRegion(id, 0, null, null, null, { value: price });
+    expectEvent(result, 'RegionPurchased', {
+      regionId: id.toPrimitive().u128,
+      buyer: bob.address,
+      seller: alice.address,
+      recipient: bob.address,
+      totalPrice: price.toString(),
+      metadataVersion: 0,
+      timeslice: settlementTimeslice(result),
+    });
+
+    // Bob receives the region:
+    expect((await xcRegions.query.ownerOf(id)).value.unwrap()).to.be.equal(bob.address);
+  });
+
   it('Purchasing fails when insufficient value is sent', async () => {
     const regionId: RegionId = {
       begin: 30,
@@ -152,7 +227,7 @@ describe('Coretime market purchases', () => {
     expect((await xcRegions.query.ownerOf(id)).value.unwrap()).to.deep.equal(market.address);
 
     // Sending less tokens than supposed:
-    const result = await market.withSigner(bob).query.purchaseRegion(id, 0, {
+    const result = await market.withSigner(bob).query.purchaseRegion(id, 0, null, null, null, {
       value: timeslicePrice * (region.getEnd() - region.getBegin() - 1),
     });
     expect(result.value.unwrap().err).to.deep.equal(MarketErrorBuilder.InsufficientFunds());
@@ -174,7 +249,7 @@ describe('Coretime market purchases', () => {
 
     const timeslicePrice = 5 * Math.pow(10, 12);
 
-    const result = await market.withSigner(bob).query.purchaseRegion(id, 0, {
+    const result = await market.withSigner(bob).query.purchaseRegion(id, 0, null, null, null, {
       value: timeslicePrice * (region.getEnd() - region.getBegin()),
     });
     expect(result.value.unwrap().err).to.deep.equal(MarketErrorBuilder.RegionNotListed());
@@ -213,11 +288,17 @@ describe('Coretime market purchases', () => {
 
     const result = await market
       .withSigner(bob)
-      .tx.purchaseRegion(id, 0, { value: timeslicePrice * (region.getEnd() - region.getBegin()) });
+      .tx.purchaseRegion(id, 0, null, null, null, {
+        value: timeslicePrice * (region.getEnd() - region.getBegin()),
+      });
     expectEvent(result, 'RegionPurchased', {
       regionId: id.toPrimitive().u128,
       buyer: bob.address,
+      seller: alice.address,
+      recipient: bob.address,
       totalPrice: (timeslicePrice * (region.getEnd() - region.getBegin())).toString(),
+      metadataVersion: 0,
+      timeslice: settlementTimeslice(result),
     });
 
     // Bob receives the region:
@@ -237,3 +318,7 @@ describe('Coretime market purchases', () => {
     expect((await market.query.listedRegion(id)).value.unwrap().ok).to.be.equal(null);
   });
 });
+
+// The settlement timeslice depends on the relay chain block at which the purchase got included.
+const settlementTimeslice = (result: { events?: any }): number =>
+  result.events.find((event: { name: string }) => event.name === 'RegionPurchased').args.timeslice;
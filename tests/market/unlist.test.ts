@@ -26,6 +26,7 @@ const REGION_COLLECTION_ID = 42;
 const LISTING_DEPOIST = 5 * Math.pow(10, 15);
 // In reality this is 80, however we use 8 for testing.
 const TIMESLICE_PERIOD = 8;
+const MARKET_FEE = 0;
 
 const wsProvider = new WsProvider('ws://127.0.0.1:9944');
 // Create a keyring instance
@@ -46,11 +47,23 @@ describe('Coretime market unlisting', () => {
     bob = keyring.addFromUri('//Bob');
 
     const xcRegionsFactory = new XcRegions_Factory(api, alice);
-    xcRegions = new XcRegions((await xcRegionsFactory.new()).address, alice, api);
+    xcRegions = new XcRegions(
+      (await xcRegionsFactory.new(REGION_COLLECTION_ID)).address,
+      alice,
+      api,
+    );
 
     const marketFactory = new Market_Factory(api, alice);
     market = new Market(
-      (await marketFactory.new(xcRegions.address, LISTING_DEPOIST, TIMESLICE_PERIOD)).address,
+      (
+        await marketFactory.new(
+          xcRegions.address,
+          LISTING_DEPOIST,
+          TIMESLICE_PERIOD,
+          MARKET_FEE,
+          alice.address,
+        )
+      ).address,
       alice,
       api,
     );
@@ -97,6 +110,7 @@ describe('Coretime market unlisting', () => {
     expectEvent(result, 'RegionUnlisted', {
       regionId: id.toPrimitive().u128,
       caller: alice.address,
+      seller: alice.address,
     });
 
     // Ensure the region is removed from sale:
@@ -168,6 +182,7 @@ describe('Coretime market unlisting', () => {
     expectEvent(aliceUnlistResult, 'RegionUnlisted', {
       regionId: id.toPrimitive().u128,
       caller: alice.address,
+      seller: alice.address,
     });
 
     // Ensure the region is removed from sale:
@@ -179,7 +194,7 @@ describe('Coretime market unlisting', () => {
   });
 
   /* TODO: Come up with a better way to test this.
-  it('Anyone can unlist an expired region', async () => {
+  it('Anyone can purge an expired region', async () => {
     const regionId: RegionId = {
       begin: 0,
       core: 23,
@@ -211,10 +226,15 @@ describe('Coretime market unlisting', () => {
     // Wait for the region to expire.
     await wait(2000 * region.getEnd() * TIMESLICE_PERIOD);
 
-    const result = await market.withSigner(bob).tx.unlistRegion(id);
-    expectEvent(result, 'RegionUnlisted', {
+    // Only the seller can unlist the region, even once it expired.
+    const bobUnlistResult = await market.withSigner(bob).query.unlistRegion(id);
+    expect(bobUnlistResult.value.unwrap().err).to.deep.equal(MarketErrorBuilder.NotAllowed());
+
+    const result = await market.withSigner(bob).tx.purgeExpired(id);
+    expectEvent(result, 'RegionPurged', {
       regionId: id.toPrimitive().u128,
       caller: bob.address,
+      seller: alice.address,
     });
 
     // Ensure the region is removed from sale:
@@ -224,7 +244,7 @@ describe('Coretime market unlisting', () => {
     // Alice receives the region back:
     expect((await xcRegions.query.ownerOf(id)).value.unwrap()).to.be.equal(alice.address);
 
-    // TODO: should ideally ensure that bob received the reward.
+    // TODO: should ideally ensure that bob received the purge reward.
   });
   */
 });
@@ -24,6 +24,7 @@ const REGION_COLLECTION_ID = 42;
 const LISTING_DEPOIST = 0;
 // In reality this is 80, however we use 8 for testing.
 const TIMESLICE_PERIOD = 8;
+const MARKET_FEE = 0;
 
 const wsProvider = new WsProvider('ws://127.0.0.1:9944');
 // Create a keyring instance
@@ -46,11 +47,23 @@ describe('Coretime market purchases', () => {
     charlie = keyring.addFromUri('//Charlie');
 
     const xcRegionsFactory = new XcRegions_Factory(api, alice);
-    xcRegions = new XcRegions((await xcRegionsFactory.new()).address, alice, api);
+    xcRegions = new XcRegions(
+      (await xcRegionsFactory.new(REGION_COLLECTION_ID)).address,
+      alice,
+      api,
+    );
 
     const marketFactory = new Market_Factory(api, alice);
     market = new Market(
-      (await marketFactory.new(xcRegions.address, LISTING_DEPOIST, TIMESLICE_PERIOD)).address,
+      (
+        await marketFactory.new(
+          xcRegions.address,
+          LISTING_DEPOIST,
+          TIMESLICE_PERIOD,
+          MARKET_FEE,
+          alice.address,
+        )
+      ).address,
       alice,
       api,
     );
@@ -97,6 +110,7 @@ describe('Coretime market purchases', () => {
     const result = await market.withSigner(alice).tx.updateRegionPrice(id, newTimeslicePrice);
     expectEvent(result, 'RegionPriceUpdated', {
       regionId: id.toPrimitive().u128,
+      seller: alice.address,
       newTimeslicePrice: newTimeslicePrice.toString(),
     });
     await expectOnSale(market, id, alice, newTimeslicePrice);